@@ -0,0 +1,302 @@
+//! Parsing of `#[ssh(...)]` attributes.
+
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote};
+use syn::{
+    parse_quote, Attribute, Data, DataEnum, DeriveInput, Field, Fields, Generics, Lit, LitInt,
+    LitStr, Path, Type, Variant,
+};
+
+/// Name of the attribute used by the derive macros.
+const ATTRIBUTE: &str = "ssh";
+
+/// Attributes of the type being derived for.
+pub(crate) struct ContainerAttrs {
+    /// Path to the `ssh-encoding` crate.
+    pub(crate) krate: Path,
+
+    /// Error type of the impls.
+    pub(crate) error: Type,
+}
+
+impl ContainerAttrs {
+    /// Parse the attributes of the given type.
+    pub(crate) fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut krate = None;
+        let mut error = None;
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident(ATTRIBUTE)) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("crate") {
+                    krate = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                } else if meta.path.is_ident("error") {
+                    error = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                } else {
+                    return Err(meta.error("unsupported `ssh` container attribute"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        let krate: Path = krate.unwrap_or_else(|| parse_quote!(::ssh_encoding));
+        let error = error.unwrap_or_else(|| parse_quote!(#krate::Error));
+        Ok(Self { krate, error })
+    }
+}
+
+/// How a field is encoded.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub(crate) enum FieldEncoding {
+    /// Using the `Encode`/`Decode` impls of its type.
+    Default,
+
+    /// Inside a length-prefixed `string`.
+    LengthPrefixed,
+
+    /// As a `byte[n]`, i.e. a `[u8; N]` without a length prefix.
+    FixedSize,
+}
+
+impl FieldEncoding {
+    /// Parse the attributes of the given field.
+    fn parse(field: &Field) -> syn::Result<Self> {
+        let mut encoding = Self::Default;
+
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident(ATTRIBUTE))
+        {
+            attr.parse_nested_meta(|meta| {
+                let value = if meta.path.is_ident("length_prefixed") {
+                    Self::LengthPrefixed
+                } else if meta.path.is_ident("fixed_size") {
+                    Self::FixedSize
+                } else {
+                    return Err(meta.error("unsupported `ssh` field attribute"));
+                };
+
+                if encoding != Self::Default {
+                    return Err(meta.error("conflicting `ssh` field attributes"));
+                }
+
+                encoding = value;
+                Ok(())
+            })?;
+        }
+
+        Ok(encoding)
+    }
+}
+
+/// Field of a struct or enum variant.
+pub(crate) struct FieldInfo<'a> {
+    /// Name of the field, or `None` for tuple fields.
+    pub(crate) name: Option<&'a Ident>,
+
+    /// Type of the field.
+    pub(crate) ty: &'a Type,
+
+    /// Local variable the field is bound to.
+    pub(crate) binding: Ident,
+
+    /// How the field is encoded.
+    pub(crate) encoding: FieldEncoding,
+}
+
+impl<'a> FieldInfo<'a> {
+    /// Collect the fields of a struct or enum variant, in order.
+    pub(crate) fn collect(fields: &'a Fields) -> syn::Result<Vec<Self>> {
+        fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                Ok(Self {
+                    name: field.ident.as_ref(),
+                    ty: &field.ty,
+                    binding: format_ident!("__field{}", i),
+                    encoding: FieldEncoding::parse(field)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Generics of the given type, with bounds requiring the types of its fields
+/// which use a type parameter to impl `trait_`, with errors convertible to
+/// `error`.
+pub(crate) fn bounded_generics(
+    input: &DeriveInput,
+    trait_: &Path,
+    error: &Type,
+) -> syn::Result<Generics> {
+    let mut generics = input.generics.clone();
+    let params = input
+        .generics
+        .type_params()
+        .map(|param| &param.ident)
+        .collect::<Vec<_>>();
+
+    if params.is_empty() {
+        return Ok(generics);
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => vec![&data.fields],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| &variant.fields)
+            .collect(),
+        Data::Union(data) => return Err(unsupported(data.union_token.span)),
+    };
+
+    let mut types = Vec::new();
+
+    for fields in fields {
+        for field in FieldInfo::collect(fields)? {
+            let ty = field.ty;
+            let tokens = quote!(#ty);
+
+            if field.encoding != FieldEncoding::FixedSize
+                && uses_params(tokens.clone(), &params)
+                && !types.iter().any(|(other, _)| *other == tokens.to_string())
+            {
+                types.push((tokens.to_string(), ty));
+            }
+        }
+    }
+
+    let where_clause = generics.make_where_clause();
+
+    for (_, ty) in types {
+        where_clause.predicates.push(parse_quote!(#ty: #trait_));
+        where_clause
+            .predicates
+            .push(parse_quote!(#error: ::core::convert::From<<#ty as #trait_>::Error>));
+    }
+
+    Ok(generics)
+}
+
+/// Do the given tokens (e.g. a type) refer to any of the given parameters?
+fn uses_params(tokens: TokenStream, params: &[&Ident]) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => params.contains(&&ident),
+        TokenTree::Group(group) => uses_params(group.stream(), params),
+        _ => false,
+    })
+}
+
+/// Pattern (or expression) binding the given fields to their local
+/// variables, e.g. `{ a: __field0, b: __field1 }` or `(__field0, __field1)`.
+pub(crate) fn bindings(fields: &Fields, infos: &[FieldInfo<'_>]) -> TokenStream {
+    let bindings = infos.iter().map(|info| &info.binding);
+
+    match fields {
+        Fields::Named(_) => {
+            let names = infos.iter().map(|info| info.name);
+            quote!({ #(#names: #bindings),* })
+        }
+        Fields::Unnamed(_) => quote!((#(#bindings),*)),
+        Fields::Unit => TokenStream::new(),
+    }
+}
+
+/// Leading discriminant of an enum variant.
+pub(crate) enum Tag {
+    /// `byte` discriminant.
+    Byte(LitInt),
+
+    /// `string` discriminant.
+    String(LitStr),
+}
+
+impl Tag {
+    /// Parse the tags of the variants of the given enum, ensuring they are
+    /// all of the same kind and distinct.
+    pub(crate) fn collect(data: &DataEnum) -> syn::Result<Vec<Self>> {
+        let mut tags: Vec<Self> = Vec::new();
+
+        for variant in &data.variants {
+            let tag = Self::parse(variant)?;
+
+            if let Some(first) = tags.first() {
+                if matches!(first, Self::Byte(_)) != matches!(tag, Self::Byte(_)) {
+                    return Err(syn::Error::new_spanned(
+                        variant,
+                        "all variants must have the same kind of `ssh(tag)`",
+                    ));
+                }
+            }
+
+            if tags.iter().any(|other| other.value() == tag.value()) {
+                return Err(syn::Error::new_spanned(variant, "duplicate `ssh(tag)`"));
+            }
+
+            tags.push(tag);
+        }
+
+        Ok(tags)
+    }
+
+    /// Parse the tag of the given variant.
+    fn parse(variant: &Variant) -> syn::Result<Self> {
+        let mut tag = None;
+
+        for attr in variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident(ATTRIBUTE))
+        {
+            attr.parse_nested_meta(|meta| {
+                if !meta.path.is_ident("tag") {
+                    return Err(meta.error("unsupported `ssh` variant attribute"));
+                }
+
+                tag = Some(match meta.value()?.parse::<Lit>()? {
+                    Lit::Int(lit) => {
+                        lit.base10_parse::<u8>()?;
+                        Self::Byte(LitInt::new(
+                            &format!("{}u8", lit.base10_digits()),
+                            lit.span(),
+                        ))
+                    }
+                    Lit::Str(lit) => Self::String(lit),
+                    lit => return Err(syn::Error::new_spanned(lit, "expected integer or string")),
+                });
+
+                Ok(())
+            })?;
+        }
+
+        tag.ok_or_else(|| syn::Error::new_spanned(variant, "missing `ssh(tag)` attribute"))
+    }
+
+    /// Value of the tag, for detecting duplicates.
+    fn value(&self) -> String {
+        match self {
+            Self::Byte(lit) => lit.base10_digits().to_owned(),
+            Self::String(lit) => lit.value(),
+        }
+    }
+
+    /// Literal for the tag, i.e. a `u8` or `&str`.
+    pub(crate) fn lit(&self) -> TokenStream {
+        match self {
+            Self::Byte(lit) => quote!(#lit),
+            Self::String(lit) => quote!(#lit),
+        }
+    }
+
+    /// Is this a `string` discriminant?
+    pub(crate) fn is_string(&self) -> bool {
+        matches!(self, Self::String(_))
+    }
+}
+
+/// Error for unsupported types, e.g. unions.
+pub(crate) fn unsupported(span: Span) -> syn::Error {
+    syn::Error::new(span, "only structs and enums are supported")
+}
@@ -0,0 +1,91 @@
+//! `Decode` derive.
+
+use crate::attributes::{
+    bounded_generics, unsupported, ContainerAttrs, FieldEncoding, FieldInfo, Tag,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, Data, DeriveInput, Fields, Path};
+
+/// Derive `Decode` for the given type.
+pub(crate) fn derive(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let ContainerAttrs { krate, error } = ContainerAttrs::parse(&input.attrs)?;
+
+    let decode = match &input.data {
+        Data::Struct(data) => {
+            let construct = construct(&krate, &data.fields)?;
+            quote!(Ok(Self #construct))
+        }
+        Data::Enum(data) => {
+            let tags = Tag::collect(data)?;
+            let mut arms = Vec::new();
+
+            for (variant, tag) in data.variants.iter().zip(&tags) {
+                let name = &variant.ident;
+                let construct = construct(&krate, &variant.fields)?;
+                let tag = tag.lit();
+                arms.push(quote!(#tag => Ok(Self::#name #construct),));
+            }
+
+            let tag = match tags.first() {
+                Some(tag) if tag.is_string() => quote! {
+                    <#krate::__private::String as #krate::Decode>::decode(reader)?.as_str()
+                },
+                _ => quote!(<u8 as #krate::Decode>::decode(reader)?),
+            };
+
+            quote! {
+                match #tag {
+                    #(#arms)*
+                    _ => Err(#krate::Error::Discriminant.into()),
+                }
+            }
+        }
+        Data::Union(data) => return Err(unsupported(data.union_token.span)),
+    };
+
+    let name = &input.ident;
+    let generics = bounded_generics(input, &parse_quote!(#krate::Decode), &error)?;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics #krate::Decode for #name #ty_generics #where_clause {
+            type Error = #error;
+
+            fn decode(
+                reader: &mut impl #krate::Reader,
+            ) -> ::core::result::Result<Self, Self::Error> {
+                #decode
+            }
+        }
+    })
+}
+
+/// Fields of a struct expression decoding the given fields in order, e.g.
+/// `{ a: ..., b: ... }` or `(..., ...)`.
+fn construct(krate: &Path, fields: &Fields) -> syn::Result<TokenStream> {
+    let infos = FieldInfo::collect(fields)?;
+    let values = infos.iter().map(|field| {
+        let ty = field.ty;
+
+        match field.encoding {
+            FieldEncoding::Default => quote!(<#ty as #krate::Decode>::decode(reader)?),
+            FieldEncoding::LengthPrefixed => quote! {
+                #krate::Reader::read_prefixed(reader, |reader| {
+                    <#ty as #krate::Decode>::decode(reader)
+                })?
+            },
+            FieldEncoding::FixedSize => quote!(#krate::__private::read_byten(reader)?),
+        }
+    });
+
+    Ok(match fields {
+        Fields::Named(_) => {
+            let names = infos.iter().map(|field| field.name);
+            quote!({ #(#names: #values),* })
+        }
+        Fields::Unnamed(_) => quote!((#(#values),*)),
+        Fields::Unit => TokenStream::new(),
+    })
+}
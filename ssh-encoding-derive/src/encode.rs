@@ -0,0 +1,124 @@
+//! `Encode` derive.
+
+use crate::attributes::{
+    bindings, bounded_generics, unsupported, ContainerAttrs, FieldEncoding, FieldInfo, Tag,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, Data, DeriveInput, Path};
+
+/// Derive `Encode` for the given type.
+pub(crate) fn derive(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let ContainerAttrs { krate, error } = ContainerAttrs::parse(&input.attrs)?;
+
+    let (encoded_len, encode) = match &input.data {
+        Data::Struct(data) => {
+            let fields = FieldInfo::collect(&data.fields)?;
+            let pattern = bindings(&data.fields, &fields);
+            let lens = fields.iter().map(|field| encoded_len(&krate, field));
+            let encodes = fields.iter().map(|field| encode(&krate, field));
+            let sum = checked_sum(&krate, lens.collect());
+
+            (
+                quote! {
+                    let Self #pattern = self;
+                    #sum
+                },
+                quote! {
+                    let Self #pattern = self;
+                    #(#encodes)*
+                },
+            )
+        }
+        Data::Enum(data) => {
+            let tags = Tag::collect(data)?;
+            let mut len_arms = Vec::new();
+            let mut encode_arms = Vec::new();
+
+            for (variant, tag) in data.variants.iter().zip(&tags) {
+                let name = &variant.ident;
+                let fields = FieldInfo::collect(&variant.fields)?;
+                let pattern = bindings(&variant.fields, &fields);
+                let tag = tag.lit();
+
+                let mut lens = vec![quote!(#krate::Encode::encoded_len(&#tag)?)];
+                lens.extend(fields.iter().map(|field| encoded_len(&krate, field)));
+                let sum = checked_sum(&krate, lens);
+                len_arms.push(quote!(Self::#name #pattern => #sum,));
+
+                let encodes = fields.iter().map(|field| encode(&krate, field));
+                encode_arms.push(quote! {
+                    Self::#name #pattern => {
+                        #krate::Encode::encode(&#tag, writer)?;
+                        #(#encodes)*
+                    }
+                });
+            }
+
+            (
+                quote!(match self { #(#len_arms)* }),
+                quote!(match self { #(#encode_arms)* }),
+            )
+        }
+        Data::Union(data) => return Err(unsupported(data.union_token.span)),
+    };
+
+    let name = &input.ident;
+    let generics = bounded_generics(input, &parse_quote!(#krate::Encode), &error)?;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl #impl_generics #krate::Encode for #name #ty_generics #where_clause {
+            type Error = #error;
+
+            fn encoded_len(&self) -> ::core::result::Result<usize, Self::Error> {
+                #encoded_len
+            }
+
+            fn encode(
+                &self,
+                writer: &mut impl #krate::Writer,
+            ) -> ::core::result::Result<(), Self::Error> {
+                #encode
+                Ok(())
+            }
+        }
+    })
+}
+
+/// Expression computing the encoded length of the given field.
+fn encoded_len(krate: &Path, field: &FieldInfo<'_>) -> TokenStream {
+    let binding = &field.binding;
+
+    match field.encoding {
+        FieldEncoding::Default => quote!(#krate::Encode::encoded_len(#binding)?),
+        FieldEncoding::LengthPrefixed => quote!(#krate::Encode::encoded_len_prefixed(#binding)?),
+        FieldEncoding::FixedSize => quote!(#binding.len()),
+    }
+}
+
+/// Statement encoding the given field.
+fn encode(krate: &Path, field: &FieldInfo<'_>) -> TokenStream {
+    let binding = &field.binding;
+
+    match field.encoding {
+        FieldEncoding::Default => quote!(#krate::Encode::encode(#binding, writer)?;),
+        FieldEncoding::LengthPrefixed => {
+            quote!(#krate::Encode::encode_prefixed(#binding, writer)?;)
+        }
+        FieldEncoding::FixedSize => quote!(#krate::Writer::write(writer, #binding)?;),
+    }
+}
+
+/// Expression computing the checked sum of the given lengths.
+fn checked_sum(krate: &Path, lens: Vec<TokenStream>) -> TokenStream {
+    if lens.is_empty() {
+        quote!(Ok(0))
+    } else {
+        quote!({
+            let len = #krate::CheckedSum::checked_sum([#(#lens),*])?;
+            Ok(len)
+        })
+    }
+}
@@ -0,0 +1,220 @@
+//! Asynchronous decoding/encoding over [`tokio::io`] streams.
+//!
+//! These adapters allow SSH protocol implementations to read and write the
+//! data types described in [RFC4251 § 5] directly from/to a socket, without
+//! buffering entire packets or blocking a runtime thread.
+//!
+//! [RFC4251 § 5]: https://datatracker.ietf.org/doc/html/rfc4251#section-5
+
+use crate::{decode::MAX_SIZE, Decode, Encode, Error, Reader, Result};
+use alloc::{string::String, vec::Vec};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Asynchronous reader which decodes SSH data types from an [`AsyncRead`]
+/// stream.
+///
+/// Only the value being decoded is buffered. Length prefixes are limited to
+/// the same maximum size as the [`Decode`] impl on `usize`.
+#[derive(Debug)]
+pub struct AsyncReader<R> {
+    /// Inner stream.
+    inner: R,
+}
+
+impl<R: AsyncRead + Unpin> AsyncReader<R> {
+    /// Create a new reader which reads from the given stream.
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    /// Get a reference to the inner stream.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner stream.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Get the inner stream.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Read exactly `out.len()` bytes, i.e. a `byte[n]`.
+    pub async fn read_byten(&mut self, out: &mut [u8]) -> Result<()> {
+        self.inner.read_exact(out).await?;
+        Ok(())
+    }
+
+    /// Decode a `boolean`.
+    pub async fn read_bool(&mut self) -> Result<bool> {
+        Ok(self.read_u8().await? != 0)
+    }
+
+    /// Decode a single `byte`.
+    pub async fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.inner.read_u8().await?)
+    }
+
+    /// Decode a `uint32`.
+    pub async fn read_u32(&mut self) -> Result<u32> {
+        Ok(self.inner.read_u32().await?)
+    }
+
+    /// Decode a `uint64`.
+    pub async fn read_u64(&mut self) -> Result<u64> {
+        Ok(self.inner.read_u64().await?)
+    }
+
+    /// Decode a value whose encoding is a single length-prefixed `string`,
+    /// e.g. a `Vec<u8>`, a `String` or an `mpint`.
+    pub async fn decode_string<T>(&mut self) -> core::result::Result<T, T::Error>
+    where
+        T: Decode,
+        T::Error: From<Error>,
+    {
+        let contents = self.read_string_contents().await?;
+
+        let mut encoded = Vec::with_capacity(contents.len().saturating_add(4));
+        encoded.extend_from_slice(
+            &u32::try_from(contents.len())
+                .map_err(Error::from)?
+                .to_be_bytes(),
+        );
+        encoded.extend_from_slice(&contents);
+
+        let reader = &mut encoded.as_slice();
+        let value = T::decode(reader)?;
+        Ok(reader.finish(value)?)
+    }
+
+    /// Decode a `name-list`, i.e. a `string` containing a comma-separated
+    /// list of non-empty US-ASCII names.
+    pub async fn read_name_list(&mut self) -> Result<Vec<String>> {
+        let contents = String::from_utf8(self.read_string_contents().await?)?;
+
+        if contents.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        if !contents.is_ascii() || contents.split(',').any(str::is_empty) {
+            return Err(Error::CharacterEncoding);
+        }
+
+        Ok(contents.split(',').map(String::from).collect())
+    }
+
+    /// Decode a value nested inside a length-prefixed `string`, e.g. a key
+    /// blob.
+    ///
+    /// The value must consume the entire `string`.
+    pub async fn read_prefixed<T>(&mut self) -> core::result::Result<T, T::Error>
+    where
+        T: Decode,
+        T::Error: From<Error>,
+    {
+        let contents = self.read_string_contents().await?;
+        let reader = &mut contents.as_slice();
+        let value = T::decode(reader)?;
+        Ok(reader.finish(value)?)
+    }
+
+    /// Read the contents of a length-prefixed `string`.
+    async fn read_string_contents(&mut self) -> Result<Vec<u8>> {
+        let len = usize::try_from(self.read_u32().await?)?;
+
+        if len > MAX_SIZE {
+            return Err(Error::Overflow);
+        }
+
+        let mut contents = vec![0u8; len];
+        self.read_byten(&mut contents).await?;
+        Ok(contents)
+    }
+}
+
+/// Asynchronous writer which encodes SSH data types to an [`AsyncWrite`]
+/// stream.
+///
+/// Each value is encoded into a buffer and then written in full.
+#[derive(Debug)]
+pub struct AsyncWriter<W> {
+    /// Inner stream.
+    inner: W,
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWriter<W> {
+    /// Create a new writer which writes to the given stream.
+    pub fn new(inner: W) -> Self {
+        Self { inner }
+    }
+
+    /// Get a reference to the inner stream.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the inner stream.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Get the inner stream.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Encode the given value.
+    pub async fn encode<T>(&mut self, value: &T) -> core::result::Result<(), T::Error>
+    where
+        T: Encode + ?Sized,
+        T::Error: From<Error>,
+    {
+        let mut buf = Vec::with_capacity(value.encoded_len()?);
+        value.encode(&mut buf)?;
+        self.inner.write_all(&buf).await.map_err(Error::from)?;
+        Ok(())
+    }
+
+    /// Encode the given value with a `uint32` length prefix.
+    pub async fn encode_prefixed<T>(&mut self, value: &T) -> core::result::Result<(), T::Error>
+    where
+        T: Encode + ?Sized,
+        T::Error: From<Error>,
+    {
+        let mut buf = Vec::with_capacity(value.encoded_len_prefixed()?);
+        value.encode_prefixed(&mut buf)?;
+        self.inner.write_all(&buf).await.map_err(Error::from)?;
+        Ok(())
+    }
+
+    /// Encode the given names as a `name-list`.
+    ///
+    /// Names must be non-empty US-ASCII strings which don't contain a comma.
+    pub async fn encode_name_list(&mut self, names: &[impl AsRef<str>]) -> Result<()> {
+        let mut list = String::new();
+
+        for name in names {
+            let name = name.as_ref();
+
+            if name.is_empty() || !name.is_ascii() || name.contains(',') {
+                return Err(Error::CharacterEncoding);
+            }
+
+            if !list.is_empty() {
+                list.push(',');
+            }
+
+            list.push_str(name);
+        }
+
+        self.encode(&list.as_str()).await
+    }
+
+    /// Flush the inner stream.
+    pub async fn flush(&mut self) -> Result<()> {
+        Ok(self.inner.flush().await?)
+    }
+}
@@ -12,7 +12,7 @@ use alloc::{string::String, vec::Vec};
 use {crate::PEM_LINE_WIDTH, pem::PemLabel};
 
 /// Maximum size of a `usize` this library will accept.
-const MAX_SIZE: usize = 0xFFFFF;
+pub(crate) const MAX_SIZE: usize = 0xFFFFF;
 
 /// Decoding trait.
 ///
@@ -48,6 +48,22 @@ impl<T: Decode + PemLabel + Sized> DecodePem for T {
     }
 }
 
+/// Decode a `boolean` as described in [RFC4251 § 5]:
+///
+/// > A boolean value is stored as a single byte.  The value 0
+/// > represents FALSE, and the value 1 represents TRUE.  All non-zero
+/// > values MUST be interpreted as TRUE; however, applications MUST NOT
+/// > store values other than 0 and 1.
+///
+/// [RFC4251 § 5]: https://datatracker.ietf.org/doc/html/rfc4251#section-5
+impl Decode for bool {
+    type Error = Error;
+
+    fn decode(reader: &mut impl Reader) -> Result<Self> {
+        Ok(u8::decode(reader)? != 0)
+    }
+}
+
 /// Decode a single `byte` from the input data.
 impl Decode for u8 {
     type Error = Error;
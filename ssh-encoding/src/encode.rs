@@ -95,6 +95,22 @@ impl<T: Encode + PemLabel> EncodePem for T {
     }
 }
 
+/// Encode a `boolean` as described in [RFC4251 § 5], i.e. as a single byte
+/// which is 0 for FALSE and 1 for TRUE.
+///
+/// [RFC4251 § 5]: https://datatracker.ietf.org/doc/html/rfc4251#section-5
+impl Encode for bool {
+    type Error = Error;
+
+    fn encoded_len(&self) -> Result<usize, Error> {
+        Ok(1)
+    }
+
+    fn encode(&self, writer: &mut impl Writer) -> Result<(), Error> {
+        u8::from(*self).encode(writer)
+    }
+}
+
 /// Encode a single `byte` to the writer.
 impl Encode for u8 {
     type Error = Error;
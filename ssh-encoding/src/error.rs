@@ -16,6 +16,13 @@ pub enum Error {
     /// Character encoding-related errors.
     CharacterEncoding,
 
+    /// Unknown enum discriminant, e.g. a message type.
+    Discriminant,
+
+    /// I/O errors.
+    #[cfg(feature = "std")]
+    Io(std::io::ErrorKind),
+
     /// Invalid length.
     Length,
 
@@ -39,6 +46,9 @@ impl fmt::Display for Error {
             #[cfg(feature = "base64")]
             Error::Base64(err) => write!(f, "Base64 encoding error: {err}"),
             Error::CharacterEncoding => write!(f, "character encoding invalid"),
+            Error::Discriminant => write!(f, "unknown discriminant"),
+            #[cfg(feature = "std")]
+            Error::Io(kind) => write!(f, "I/O error: {kind}"),
             Error::Length => write!(f, "length invalid"),
             Error::Overflow => write!(f, "internal overflow error"),
             #[cfg(feature = "pem")]
@@ -91,6 +101,13 @@ impl From<pem::Error> for Error {
     }
 }
 
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Error {
+        Error::Io(err.kind())
+    }
+}
+
 #[cfg(feature = "std")]
 impl std::error::Error for Error {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
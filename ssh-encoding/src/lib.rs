@@ -7,7 +7,7 @@
 )]
 #![forbid(unsafe_code)]
 #![warn(
-    clippy::integer_arithmetic,
+    clippy::arithmetic_side_effects,
     clippy::panic,
     clippy::panic_in_result_fn,
     clippy::unwrap_used,
@@ -23,6 +23,8 @@ extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
+#[cfg(feature = "async")]
+mod async_io;
 mod checked;
 mod decode;
 mod encode;
@@ -41,6 +43,12 @@ pub use crate::{
     writer::Writer,
 };
 
+#[cfg(feature = "async")]
+pub use {
+    crate::async_io::{AsyncReader, AsyncWriter},
+    tokio,
+};
+
 #[cfg(feature = "base64")]
 pub use {
     crate::{reader::Base64Reader, writer::Base64Writer},
@@ -53,6 +61,30 @@ pub use {
     pem::{self, LineEnding},
 };
 
+/// Derive macros for the [`Decode`] and [`Encode`] traits.
+///
+/// See the [`ssh-encoding-derive`](https://docs.rs/ssh-encoding-derive)
+/// crate for the supported attributes.
+#[cfg(feature = "derive")]
+pub use ssh_encoding_derive::{Decode, Encode};
+
+/// Support code for the derive macros. Not public API.
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    use crate::{Reader, Result};
+
+    #[cfg(feature = "alloc")]
+    pub use alloc::string::String;
+
+    /// Decode a `[u8; N]` from `byte[n]`, i.e. without a length prefix.
+    pub fn read_byten<const N: usize>(reader: &mut impl Reader) -> Result<[u8; N]> {
+        let mut result = [0u8; N];
+        reader.read(&mut result)?;
+        Ok(result)
+    }
+}
+
 /// Line width used by the PEM encoding of OpenSSH documents.
 #[cfg(feature = "pem")]
 const PEM_LINE_WIDTH: usize = 70;
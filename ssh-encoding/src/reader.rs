@@ -33,6 +33,9 @@ pub trait Reader: Sized {
     /// Decodes a `uint32` which identifies the length of some encapsulated
     /// data, then calls the given reader function with the length of the
     /// remaining data.
+    ///
+    /// Returns [`Error::TrailingData`] if the reader function does not
+    /// consume all of the length-prefixed data.
     fn read_prefixed<'r, T, E, F>(&'r mut self, f: F) -> core::result::Result<T, E>
     where
         E: From<Error>,
@@ -40,10 +43,13 @@ pub trait Reader: Sized {
     {
         let len = usize::decode(self)?;
 
-        f(&mut NestedReader {
+        let mut reader = NestedReader {
             inner: self,
             remaining_len: len,
-        })
+        };
+
+        let ret = f(&mut reader)?;
+        Ok(reader.finish(ret)?)
     }
 
     /// Decodes `[u8]` from `byte[n]` as described in [RFC4251 § 5]:
@@ -128,6 +134,10 @@ pub trait Reader: Sized {
     }
 }
 
+/// Reads from the front of the slice, advancing it past the data read.
+///
+/// On error the slice is left unchanged, so the remaining data can still be
+/// inspected.
 impl Reader for &[u8] {
     fn read<'o>(&mut self, out: &'o mut [u8]) -> Result<&'o [u8]> {
         if self.len() >= out.len() {
@@ -148,6 +158,11 @@ impl Reader for &[u8] {
 #[cfg(feature = "base64")]
 impl Reader for Base64Reader<'_> {
     fn read<'o>(&mut self, out: &'o mut [u8]) -> Result<&'o [u8]> {
+        // The decoder errors on empty reads once all input is consumed
+        if out.is_empty() {
+            return Ok(out);
+        }
+
         Ok(self.decode(out)?)
     }
 
@@ -159,6 +174,11 @@ impl Reader for Base64Reader<'_> {
 #[cfg(feature = "pem")]
 impl Reader for pem::Decoder<'_> {
     fn read<'o>(&mut self, out: &'o mut [u8]) -> Result<&'o [u8]> {
+        // The decoder errors on empty reads once all input is consumed
+        if out.is_empty() {
+            return Ok(out);
+        }
+
         Ok(self.decode(out)?)
     }
 
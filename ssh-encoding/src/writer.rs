@@ -6,7 +6,7 @@ use crate::Result;
 use alloc::vec::Vec;
 
 #[cfg(feature = "sha2")]
-use sha2::{Digest, Sha256, Sha512};
+use sha2::{Digest, Sha256, Sha384, Sha512};
 
 /// Constant-time Base64 writer implementation.
 #[cfg(feature = "base64")]
@@ -19,6 +19,7 @@ pub trait Writer: Sized {
     fn write(&mut self, bytes: &[u8]) -> Result<()>;
 }
 
+/// Appends to the end of the vector.
 #[cfg(feature = "alloc")]
 impl Writer for Vec<u8> {
     fn write(&mut self, bytes: &[u8]) -> Result<()> {
@@ -49,6 +50,14 @@ impl Writer for Sha256 {
     }
 }
 
+#[cfg(feature = "sha2")]
+impl Writer for Sha384 {
+    fn write(&mut self, bytes: &[u8]) -> Result<()> {
+        self.update(bytes);
+        Ok(())
+    }
+}
+
 #[cfg(feature = "sha2")]
 impl Writer for Sha512 {
     fn write(&mut self, bytes: &[u8]) -> Result<()> {
@@ -0,0 +1,101 @@
+//! Tests for the `AsyncReader` and `AsyncWriter` types.
+
+#![cfg(feature = "async")]
+
+use hex_literal::hex;
+use ssh_encoding::{tokio::io::AsyncWriteExt, AsyncReader, AsyncWriter, Error};
+
+/// `name-list` containing `zlib` and `none`.
+const NAME_LIST: [u8; 13] = hex!("000000097a6c69622c6e6f6e65");
+
+#[tokio::test]
+async fn read_primitives() {
+    let mut reader = AsyncReader::new(hex!("0142DEADBEEF0000DEADBEEFCAFE").as_slice());
+    assert!(reader.read_bool().await.unwrap());
+    assert_eq!(reader.read_u8().await.unwrap(), 0x42);
+    assert_eq!(reader.read_u32().await.unwrap(), 0xDEADBEEF);
+    assert_eq!(reader.read_u64().await.unwrap(), 0xDEADBEEFCAFE);
+    assert_eq!(
+        reader.read_u8().await,
+        Err(Error::Io(std::io::ErrorKind::UnexpectedEof))
+    );
+}
+
+#[tokio::test]
+async fn decode_string() {
+    let mut bytes = hex!("0000000568656c6c6f").to_vec();
+    bytes.extend_from_slice(&NAME_LIST);
+
+    let mut reader = AsyncReader::new(bytes.as_slice());
+    assert_eq!(reader.decode_string::<String>().await.unwrap(), "hello");
+    assert_eq!(
+        reader.decode_string::<Vec<u8>>().await.unwrap(),
+        b"zlib,none"
+    );
+}
+
+#[tokio::test]
+async fn read_name_list() {
+    let mut bytes = NAME_LIST.to_vec();
+    bytes.extend_from_slice(&hex!("00000000000000057a6c69622c"));
+
+    let mut reader = AsyncReader::new(bytes.as_slice());
+    assert_eq!(reader.read_name_list().await.unwrap(), ["zlib", "none"]);
+    assert!(reader.read_name_list().await.unwrap().is_empty());
+    assert_eq!(reader.read_name_list().await, Err(Error::CharacterEncoding));
+}
+
+#[tokio::test]
+async fn read_prefixed() {
+    let mut reader = AsyncReader::new(hex!("00000004DEADBEEF00000005DEADBEEF00").as_slice());
+    assert_eq!(reader.read_prefixed::<u32>().await.unwrap(), 0xDEADBEEF);
+    assert_eq!(
+        reader.read_prefixed::<u32>().await,
+        Err(Error::TrailingData { remaining: 1 })
+    );
+}
+
+#[tokio::test]
+async fn reject_oversized_length() {
+    let mut reader = AsyncReader::new(hex!("FFFFFFFF").as_slice());
+    assert_eq!(
+        reader.decode_string::<Vec<u8>>().await,
+        Err(Error::Overflow)
+    );
+}
+
+#[tokio::test]
+async fn read_from_stream() {
+    let (client, mut server) = tokio::io::duplex(4);
+
+    // Writes are split across the length prefix and contents
+    let writer = tokio::spawn(async move {
+        for chunk in NAME_LIST.chunks(3) {
+            server.write_all(chunk).await.unwrap();
+        }
+    });
+
+    let mut reader = AsyncReader::new(client);
+    assert_eq!(reader.read_name_list().await.unwrap(), ["zlib", "none"]);
+    writer.await.unwrap();
+}
+
+#[tokio::test]
+async fn write() {
+    let mut writer = AsyncWriter::new(Vec::new());
+    writer.encode(&0x42u8).await.unwrap();
+    writer.encode(&"hello").await.unwrap();
+    writer.encode_prefixed(&0xDEADBEEFu32).await.unwrap();
+    writer.encode_name_list(&["zlib", "none"]).await.unwrap();
+    writer.flush().await.unwrap();
+
+    let mut expected = hex!("420000000568656c6c6f00000004DEADBEEF").to_vec();
+    expected.extend_from_slice(&NAME_LIST);
+    assert_eq!(writer.into_inner(), expected);
+    assert_eq!(
+        AsyncWriter::new(Vec::new())
+            .encode_name_list(&["a,b"])
+            .await,
+        Err(Error::CharacterEncoding)
+    );
+}
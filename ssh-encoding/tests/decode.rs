@@ -1,7 +1,17 @@
 //! Tests for the `Decode` trait.
 
 use hex_literal::hex;
-use ssh_encoding::{Decode, Error};
+use ssh_encoding::{Decode, Error, Reader};
+
+#[test]
+fn decode_bool() {
+    let mut bytes = hex!("000102").as_slice();
+    assert!(!bool::decode(&mut bytes).unwrap());
+    assert!(bool::decode(&mut bytes).unwrap());
+
+    // Non-zero values are interpreted as `true`
+    assert!(bool::decode(&mut bytes).unwrap());
+}
 
 #[test]
 fn decode_u8() {
@@ -72,3 +82,27 @@ fn decode_string_vec() {
     assert_eq!(ret[1], "bar");
     assert_eq!(ret[2], "baz");
 }
+
+#[test]
+fn decode_prefixed() {
+    let mut bytes = hex!("00000004 DEADBEEF").as_slice();
+    let ret = bytes.read_prefixed(u32::decode).unwrap();
+    assert_eq!(ret, 0xDEADBEEFu32);
+}
+
+#[test]
+fn reject_prefixed_trailing_data() {
+    let mut bytes = hex!("00000005 DEADBEEF 42").as_slice();
+    let err = bytes.read_prefixed(u32::decode).unwrap_err();
+    assert_eq!(err, Error::TrailingData { remaining: 1 });
+}
+
+#[cfg(all(feature = "alloc", feature = "base64"))]
+#[test]
+fn decode_empty_string_at_end_of_base64() {
+    // `uint32` length prefix of zero with no trailing data
+    let mut reader = ssh_encoding::Base64Reader::new(b"AAAAAA==").unwrap();
+    let ret = String::decode(&mut reader).unwrap();
+    assert_eq!(&ret, "");
+    assert!(reader.is_finished());
+}
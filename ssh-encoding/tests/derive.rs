@@ -0,0 +1,197 @@
+//! Tests for the `Decode` and `Encode` derive macros.
+
+#![cfg(all(feature = "alloc", feature = "derive"))]
+
+use hex_literal::hex;
+use ssh_encoding::{Decode, Encode, Error};
+
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq)]
+struct Request {
+    id: u32,
+    #[ssh(fixed_size)]
+    cookie: [u8; 4],
+    #[ssh(length_prefixed)]
+    body: Body,
+    name: String,
+}
+
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq)]
+struct Body(bool, Vec<u8>);
+
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq)]
+struct Empty;
+
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq)]
+enum Message {
+    #[ssh(tag = 1)]
+    Disconnect { reason: u32, description: String },
+    #[ssh(tag = 2)]
+    Ignore(Vec<u8>),
+    #[ssh(tag = 0x15)]
+    NewKeys,
+}
+
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq)]
+enum Request2 {
+    #[ssh(tag = "exit-status")]
+    ExitStatus(u32),
+    #[ssh(tag = "env")]
+    Env { name: String, value: String },
+}
+
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq)]
+struct Generic<T, const N: usize> {
+    #[ssh(fixed_size)]
+    prefix: [u8; N],
+    #[ssh(length_prefixed)]
+    value: T,
+    values: Vec<T>,
+}
+
+#[derive(Clone, Debug, Decode, Encode, Eq, PartialEq)]
+enum GenericMessage<T> {
+    #[ssh(tag = 1)]
+    One(T),
+    #[ssh(tag = 2)]
+    Two(T, T),
+}
+
+/// Custom error type.
+#[derive(Debug, Eq, PartialEq)]
+struct CustomError(Error);
+
+impl From<Error> for CustomError {
+    fn from(err: Error) -> Self {
+        Self(err)
+    }
+}
+
+#[derive(Debug, Decode, Encode, Eq, PartialEq)]
+#[ssh(crate = "::ssh_encoding", error = "CustomError")]
+struct WithCustomError {
+    value: u8,
+}
+
+/// Encode the given value, checking its encoded length.
+fn encode<T: Encode>(value: &T) -> Vec<u8>
+where
+    T::Error: core::fmt::Debug,
+{
+    let mut out = Vec::new();
+    value.encode(&mut out).unwrap();
+    assert_eq!(value.encoded_len().unwrap(), out.len());
+    out
+}
+
+#[test]
+fn struct_round_trip() {
+    let request = Request {
+        id: 0xDEADBEEF,
+        cookie: *b"abcd",
+        body: Body(true, vec![0x42]),
+        name: "ssh".to_owned(),
+    };
+
+    let encoded = encode(&request);
+    assert_eq!(
+        encoded,
+        hex!("DEADBEEF 61626364 00000006 01 0000000142 00000003737368")
+    );
+    assert_eq!(Request::decode(&mut encoded.as_slice()).unwrap(), request);
+}
+
+#[test]
+fn length_prefixed_trailing_data() {
+    let encoded = hex!("DEADBEEF 61626364 00000007 01 0000000142 00 00000003737368");
+    assert_eq!(
+        Request::decode(&mut encoded.as_slice()),
+        Err(Error::TrailingData { remaining: 1 })
+    );
+}
+
+#[test]
+fn unit_struct() {
+    assert!(encode(&Empty).is_empty());
+    assert_eq!(Empty::decode(&mut [].as_slice()).unwrap(), Empty);
+}
+
+#[test]
+fn byte_tagged_enum() {
+    for (message, expected) in [
+        (
+            Message::Disconnect {
+                reason: 11,
+                description: "bye".to_owned(),
+            },
+            hex!("01 0000000B 00000003627965").to_vec(),
+        ),
+        (
+            Message::Ignore(vec![1, 2]),
+            hex!("02 000000020102").to_vec(),
+        ),
+        (Message::NewKeys, hex!("15").to_vec()),
+    ] {
+        let encoded = encode(&message);
+        assert_eq!(encoded, expected);
+        assert_eq!(Message::decode(&mut encoded.as_slice()).unwrap(), message);
+    }
+
+    assert_eq!(
+        Message::decode(&mut hex!("03").as_slice()),
+        Err(Error::Discriminant)
+    );
+}
+
+#[test]
+fn string_tagged_enum() {
+    let request = Request2::Env {
+        name: "LANG".to_owned(),
+        value: "C".to_owned(),
+    };
+    let encoded = encode(&request);
+    assert_eq!(encoded, hex!("00000003656E76 000000044C414E47 0000000143"));
+    assert_eq!(Request2::decode(&mut encoded.as_slice()).unwrap(), request);
+
+    let encoded = encode(&Request2::ExitStatus(1));
+    assert_eq!(encoded, hex!("0000000B657869742D737461747573 00000001"));
+
+    assert_eq!(
+        Request2::decode(&mut hex!("0000000165").as_slice()),
+        Err(Error::Discriminant)
+    );
+}
+
+#[test]
+fn custom_error() {
+    assert_eq!(
+        WithCustomError::decode(&mut [].as_slice()),
+        Err(CustomError(Error::Length))
+    );
+}
+
+#[test]
+fn generic_round_trip() {
+    let value = Generic {
+        prefix: [0xFF],
+        value: "ssh".to_owned(),
+        values: vec!["a".to_owned()],
+    };
+
+    let encoded = encode(&value);
+    assert_eq!(
+        encoded,
+        hex!("FF 00000007 00000003737368 00000005 0000000161")
+    );
+    assert_eq!(
+        Generic::<String, 1>::decode(&mut encoded.as_slice()).unwrap(),
+        value
+    );
+
+    let message = GenericMessage::Two(1u32, 2u32);
+    let encoded = encode(&message);
+    assert_eq!(encoded, hex!("02 00000001 00000002"));
+    assert_eq!(
+        GenericMessage::decode(&mut encoded.as_slice()).unwrap(),
+        message
+    );
+}
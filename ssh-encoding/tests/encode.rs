@@ -5,6 +5,14 @@
 use hex_literal::hex;
 use ssh_encoding::Encode;
 
+#[test]
+fn encode_bool() {
+    let mut out = Vec::new();
+    false.encode(&mut out).unwrap();
+    true.encode(&mut out).unwrap();
+    assert_eq!(out, hex!("0001"));
+}
+
 #[test]
 fn encode_u8() {
     let mut out = Vec::new();
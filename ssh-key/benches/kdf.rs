@@ -0,0 +1,37 @@
+//! Key derivation function benchmarks.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ssh_key::{Cipher, Kdf, PrivateKey};
+
+/// AES-256-CTR encrypted Ed25519 OpenSSH-formatted private key, using 16
+/// bcrypt-pbkdf rounds.
+const OPENSSH_AES_CTR_ED25519_EXAMPLE: &str =
+    include_str!("../tests/examples/id_ed25519.aes-ctr.enc");
+
+/// Password used to encrypt the example key.
+const PASSWORD: &[u8] = b"hunter42";
+
+fn bcrypt_derive_key_and_iv(c: &mut Criterion) {
+    let kdf = Kdf::Bcrypt {
+        salt: b"saltsaltsaltsalt".to_vec(),
+        rounds: 16,
+    };
+
+    c.bench_function("bcrypt derive_key_and_iv aes256-ctr", |b| {
+        b.iter(|| {
+            kdf.derive_key_and_iv(black_box(Cipher::Aes256Ctr), black_box(PASSWORD))
+                .unwrap()
+        })
+    });
+}
+
+fn decrypt_openssh(c: &mut Criterion) {
+    let key = PrivateKey::from_openssh(OPENSSH_AES_CTR_ED25519_EXAMPLE).unwrap();
+
+    c.bench_function("decrypt openssh aes256-ctr", |b| {
+        b.iter(|| black_box(&key).decrypt(black_box(PASSWORD)).unwrap())
+    });
+}
+
+criterion_group!(benches, bcrypt_derive_key_and_iv, decrypt_openssh);
+criterion_main!(benches);
@@ -0,0 +1,371 @@
+//! ssh-agent protocol client.
+//!
+//! Implements the client side of the [ssh-agent protocol], which allows
+//! applications to sign data (including SSH certificates) using keys held by
+//! an agent such as OpenSSH's `ssh-agent`, without loading the private key
+//! material into the process.
+//!
+//! [ssh-agent protocol]: https://datatracker.ietf.org/doc/html/draft-miller-ssh-agent
+//!
+//! ## Example
+//!
+//! ```no_run
+//! # #[cfg(unix)]
+//! # fn main() -> Result<(), ssh_key::Error> {
+//! use ssh_key::agent::Agent;
+//!
+//! // Connect to the agent given by the `SSH_AUTH_SOCK` environment variable
+//! let agent = Agent::connect_env()?;
+//!
+//! for identity in agent.identities()? {
+//!     let signature = agent.sign(identity.public_key().key_data(), b"example message")?;
+//!     println!("{}: {}", identity.comment(), signature.algorithm());
+//! }
+//! # Ok(())
+//! # }
+//! # #[cfg(not(unix))]
+//! # fn main() {}
+//! ```
+
+use crate::{
+    public::KeyData, Algorithm, Certificate, Error, Identity, PrivateKey, PublicKey, Result,
+    Signature, SigningKey,
+};
+use alloc::{string::String, vec::Vec};
+use encoding::{CheckedSum, Decode, Encode, Reader, Writer};
+use signature::Signer;
+use std::{
+    io::{Read, Write},
+    sync::{Mutex, PoisonError},
+};
+use zeroize::Zeroizing;
+
+#[cfg(unix)]
+use std::{env, os::unix::net::UnixStream, path::Path};
+
+/// Environment variable giving the path of the agent's socket.
+#[cfg(unix)]
+const SSH_AUTH_SOCK: &str = "SSH_AUTH_SOCK";
+
+/// Maximum length of a message, as enforced by OpenSSH (256 KiB).
+const MAX_MESSAGE_LEN: usize = 0x40000;
+
+/// Generic failure response.
+const SSH_AGENT_FAILURE: u8 = 5;
+
+/// Generic success response.
+const SSH_AGENT_SUCCESS: u8 = 6;
+
+/// Request for the list of identities held by the agent.
+const SSH_AGENTC_REQUEST_IDENTITIES: u8 = 11;
+
+/// Response listing the identities held by the agent.
+const SSH_AGENT_IDENTITIES_ANSWER: u8 = 12;
+
+/// Request for a signature.
+const SSH_AGENTC_SIGN_REQUEST: u8 = 13;
+
+/// Response containing a signature.
+const SSH_AGENT_SIGN_RESPONSE: u8 = 14;
+
+/// Request to add an identity.
+const SSH_AGENTC_ADD_IDENTITY: u8 = 17;
+
+/// Request to remove an identity.
+const SSH_AGENTC_REMOVE_IDENTITY: u8 = 18;
+
+/// Request to remove all identities.
+const SSH_AGENTC_REMOVE_ALL_IDENTITIES: u8 = 19;
+
+/// Request to add an identity with constraints.
+const SSH_AGENTC_ADD_ID_CONSTRAINED: u8 = 25;
+
+/// Failure responses used by legacy agents, which clients should treat as
+/// [`SSH_AGENT_FAILURE`].
+const LEGACY_FAILURES: [u8; 2] = [30, 102];
+
+/// Lifetime constraint identifier.
+const SSH_AGENT_CONSTRAIN_LIFETIME: u8 = 1;
+
+/// Confirmation constraint identifier.
+const SSH_AGENT_CONSTRAIN_CONFIRM: u8 = 2;
+
+/// Sign request flag for `rsa-sha2-512` signatures.
+const SSH_AGENT_RSA_SHA2_512: u32 = 4;
+
+/// Constraints on the use of a key added to an agent.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum Constraint {
+    /// Remove the key from the agent after the given number of seconds.
+    Lifetime(u32),
+
+    /// Have the agent ask the user to confirm each use of the key, e.g. via
+    /// `ssh-askpass`.
+    Confirm,
+}
+
+impl Encode for Constraint {
+    type Error = Error;
+
+    fn encoded_len(&self) -> Result<usize> {
+        match self {
+            Self::Lifetime(seconds) => Ok([1, seconds.encoded_len()?].checked_sum()?),
+            Self::Confirm => Ok(1),
+        }
+    }
+
+    fn encode(&self, writer: &mut impl Writer) -> Result<()> {
+        match self {
+            Self::Lifetime(seconds) => {
+                SSH_AGENT_CONSTRAIN_LIFETIME.encode(writer)?;
+                seconds.encode(writer)?;
+            }
+            Self::Confirm => SSH_AGENT_CONSTRAIN_CONFIRM.encode(writer)?,
+        }
+
+        Ok(())
+    }
+}
+
+/// ssh-agent client, which sends requests over the given stream.
+///
+/// Requests are serialized, so an [`Agent`] can be shared between threads.
+/// Returns [`Error::AgentFailure`] if the agent refuses a request.
+#[derive(Debug)]
+pub struct Agent<S> {
+    /// Stream connected to the agent.
+    stream: Mutex<S>,
+}
+
+#[cfg(unix)]
+impl Agent<UnixStream> {
+    /// Connect to the agent listening on the Unix domain socket at the given
+    /// path.
+    pub fn connect(path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self::new(UnixStream::connect(path)?))
+    }
+
+    /// Connect to the agent given by the `SSH_AUTH_SOCK` environment
+    /// variable.
+    ///
+    /// Returns [`Error::Io`] with [`io::ErrorKind::NotFound`][`std::io::ErrorKind::NotFound`]
+    /// if the variable isn't set.
+    pub fn connect_env() -> Result<Self> {
+        let path = env::var_os(SSH_AUTH_SOCK).ok_or(Error::Io(std::io::ErrorKind::NotFound))?;
+        Self::connect(path)
+    }
+}
+
+impl<S: Read + Write> Agent<S> {
+    /// Create a new client which communicates with an agent over the given
+    /// stream.
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream: Mutex::new(stream),
+        }
+    }
+
+    /// Get the inner stream.
+    pub fn into_inner(self) -> S {
+        self.stream
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// List the public keys and certificates held by the agent, with their
+    /// comments.
+    ///
+    /// Certificates can be used to sign via the [`KeyData`] of their subject
+    /// public key, i.e. [`Identity::public_key`].
+    pub fn identities(&self) -> Result<Vec<Identity>> {
+        let response = self.request(SSH_AGENTC_REQUEST_IDENTITIES, &[])?;
+        let reader = &mut response.as_slice();
+
+        if u8::decode(reader)? != SSH_AGENT_IDENTITIES_ANSWER {
+            return Err(Error::FormatEncoding);
+        }
+
+        let count = u32::decode(reader)?;
+        let mut identities = Vec::new();
+
+        for _ in 0..count {
+            let key_blob = Vec::decode(reader)?;
+            let comment = String::decode(reader)?;
+            identities.push(decode_identity(&key_blob, comment)?);
+        }
+
+        Ok(reader.finish(identities)?)
+    }
+
+    /// Ask the agent to sign the given data using the private key
+    /// corresponding to the given public key.
+    ///
+    /// RSA keys produce `rsa-sha2-512` signatures.
+    pub fn sign(&self, key: &KeyData, data: &[u8]) -> Result<Signature> {
+        let flags = if key.is_rsa() {
+            SSH_AGENT_RSA_SHA2_512
+        } else {
+            0
+        };
+
+        let mut request = Vec::new();
+        key.encode_prefixed(&mut request)?;
+        data.encode(&mut request)?;
+        flags.encode(&mut request)?;
+
+        let response = self.request(SSH_AGENTC_SIGN_REQUEST, &request)?;
+        let reader = &mut response.as_slice();
+
+        if u8::decode(reader)? != SSH_AGENT_SIGN_RESPONSE {
+            return Err(Error::FormatEncoding);
+        }
+
+        let signature = reader.read_prefixed(Signature::decode)?;
+        Ok(reader.finish(signature)?)
+    }
+
+    /// Get a [`SigningKey`] which signs using the given key held by this
+    /// agent, e.g. for signing certificates with
+    /// [`certificate::Builder::sign`][`crate::certificate::Builder::sign`].
+    pub fn signer(&self, key: KeyData) -> AgentSigner<'_, S> {
+        AgentSigner { agent: self, key }
+    }
+
+    /// Add the given private key to the agent, subject to the given
+    /// constraints (if any).
+    ///
+    /// The comment of the private key is used as the comment of the
+    /// identity. Returns [`Error::Encrypted`] if the key is encrypted.
+    pub fn add_identity(&self, key: &PrivateKey, constraints: &[Constraint]) -> Result<()> {
+        if key.is_encrypted() {
+            return Err(Error::Encrypted);
+        }
+
+        let mut request = Zeroizing::new(Vec::new());
+        key.key_data().encode(&mut *request)?;
+        key.comment().encode(&mut *request)?;
+
+        let message_type = if constraints.is_empty() {
+            SSH_AGENTC_ADD_IDENTITY
+        } else {
+            SSH_AGENTC_ADD_ID_CONSTRAINED
+        };
+
+        for constraint in constraints {
+            constraint.encode(&mut *request)?;
+        }
+
+        self.request_success(message_type, &request)
+    }
+
+    /// Remove the identity with the given public key from the agent.
+    pub fn remove_identity(&self, key: &KeyData) -> Result<()> {
+        let mut request = Vec::new();
+        key.encode_prefixed(&mut request)?;
+        self.request_success(SSH_AGENTC_REMOVE_IDENTITY, &request)
+    }
+
+    /// Remove all identities from the agent.
+    pub fn remove_all_identities(&self) -> Result<()> {
+        self.request_success(SSH_AGENTC_REMOVE_ALL_IDENTITIES, &[])
+    }
+
+    /// Send a request which is answered by the generic success response.
+    fn request_success(&self, message_type: u8, contents: &[u8]) -> Result<()> {
+        let response = self.request(message_type, contents)?;
+
+        if response != [SSH_AGENT_SUCCESS] {
+            return Err(Error::FormatEncoding);
+        }
+
+        Ok(())
+    }
+
+    /// Send a request with the given type and contents, returning the
+    /// response (starting with its type).
+    ///
+    /// Failure responses are returned as [`Error::AgentFailure`].
+    fn request(&self, message_type: u8, contents: &[u8]) -> Result<Vec<u8>> {
+        let len = contents
+            .len()
+            .checked_add(1)
+            .ok_or(encoding::Error::Length)?;
+
+        if len > MAX_MESSAGE_LEN {
+            return Err(encoding::Error::Length.into());
+        }
+
+        let mut message = Zeroizing::new(Vec::with_capacity(len.saturating_add(4)));
+        u32::try_from(len)
+            .map_err(|_| encoding::Error::Length)?
+            .encode(&mut *message)?;
+        message_type.encode(&mut *message)?;
+        message.extend_from_slice(contents);
+
+        let mut stream = self.stream.lock().unwrap_or_else(PoisonError::into_inner);
+        stream.write_all(&message)?;
+        stream.flush()?;
+
+        let mut len = [0u8; 4];
+        stream.read_exact(&mut len)?;
+        let len = usize::try_from(u32::from_be_bytes(len)).map_err(|_| encoding::Error::Length)?;
+
+        if len == 0 || len > MAX_MESSAGE_LEN {
+            return Err(encoding::Error::Length.into());
+        }
+
+        let mut response = vec![0u8; len];
+        stream.read_exact(&mut response)?;
+
+        match response.first() {
+            Some(&SSH_AGENT_FAILURE) => Err(Error::AgentFailure),
+            Some(response_type) if LEGACY_FAILURES.contains(response_type) => {
+                Err(Error::AgentFailure)
+            }
+            _ => Ok(response),
+        }
+    }
+}
+
+/// Decode an identity from the key blob and comment of an identities answer.
+///
+/// The blob's algorithm name determines whether it's a certificate or a
+/// public key.
+fn decode_identity(key_blob: &[u8], comment: String) -> Result<Identity> {
+    let algorithm = String::decode(&mut &*key_blob)?;
+
+    if Algorithm::new_certificate(&algorithm).is_ok() {
+        let mut certificate = Certificate::from_bytes(key_blob)?;
+        certificate.set_comment(comment);
+        Ok(certificate.into())
+    } else {
+        let reader = &mut &*key_blob;
+        let key_data = KeyData::decode(reader)?;
+        Ok(reader.finish(PublicKey::new(key_data, comment))?.into())
+    }
+}
+
+/// [`SigningKey`] which signs using a key held by an [`Agent`].
+///
+/// Created using [`Agent::signer`].
+#[derive(Debug)]
+pub struct AgentSigner<'a, S> {
+    /// Agent holding the private key.
+    agent: &'a Agent<S>,
+
+    /// Public key of the private key.
+    key: KeyData,
+}
+
+impl<S: Read + Write> Signer<Signature> for AgentSigner<'_, S> {
+    fn try_sign(&self, message: &[u8]) -> signature::Result<Signature> {
+        Ok(self.agent.sign(&self.key, message)?)
+    }
+}
+
+impl<S: Read + Write> SigningKey for AgentSigner<'_, S> {
+    fn public_key(&self) -> KeyData {
+        self.key.clone()
+    }
+}
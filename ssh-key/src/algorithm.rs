@@ -2,14 +2,17 @@
 
 use crate::{Error, Result};
 use core::{fmt, str};
-use encoding::Label;
+use encoding::{CheckedSum, Decode, Encode, Label, Reader, Writer};
 
 #[cfg(feature = "alloc")]
 use {
-    alloc::vec::Vec,
-    sha2::{Digest, Sha256, Sha512},
+    alloc::{string::String, vec::Vec},
+    sha2::{Digest, Sha256, Sha384, Sha512},
 };
 
+#[cfg(all(feature = "alloc", feature = "serde"))]
+use serde::{de, ser, Deserialize, Serialize};
+
 /// bcrypt-pbkdf
 const BCRYPT: &str = "bcrypt";
 
@@ -31,12 +34,22 @@ const CERT_ED25519: &str = "ssh-ed25519-cert-v01@openssh.com";
 /// OpenSSH certificate with RSA public key
 const CERT_RSA: &str = "ssh-rsa-cert-v01@openssh.com";
 
+/// OpenSSH certificate with RSA public key using SHA-256 signatures
+const CERT_RSA_SHA2_256: &str = "rsa-sha2-256-cert-v01@openssh.com";
+
+/// OpenSSH certificate with RSA public key using SHA-512 signatures
+const CERT_RSA_SHA2_512: &str = "rsa-sha2-512-cert-v01@openssh.com";
+
 /// OpenSSH certificate for ECDSA (NIST P-256) U2F/FIDO security key
 const CERT_SK_ECDSA_SHA2_P256: &str = "sk-ecdsa-sha2-nistp256-cert-v01@openssh.com";
 
 /// OpenSSH certificate for Ed25519 U2F/FIDO security key
 const CERT_SK_SSH_ED25519: &str = "sk-ssh-ed25519-cert-v01@openssh.com";
 
+/// OpenSSH certificate for XMSS public key
+#[cfg(feature = "xmss")]
+const CERT_XMSS: &str = "ssh-xmss-cert-v01@openssh.com";
+
 /// ECDSA with SHA-256 + NIST P-256
 const ECDSA_SHA2_P256: &str = "ecdsa-sha2-nistp256";
 
@@ -58,6 +71,9 @@ const RSA_SHA2_512: &str = "rsa-sha2-512";
 /// SHA-256 hash function
 const SHA256: &str = "sha256";
 
+/// SHA-384 hash function
+const SHA384: &str = "sha384";
+
 /// SHA-512 hash function
 const SHA512: &str = "sha512";
 
@@ -76,11 +92,29 @@ const SK_ECDSA_SHA2_P256: &str = "sk-ecdsa-sha2-nistp256@openssh.com";
 /// U2F/FIDO security key with Ed25519
 const SK_SSH_ED25519: &str = "sk-ssh-ed25519@openssh.com";
 
+/// eXtended Merkle Signature Scheme (experimental in OpenSSH)
+#[cfg(feature = "xmss")]
+const SSH_XMSS: &str = "ssh-xmss@openssh.com";
+
+/// Maximum length of an algorithm name, as specified in RFC4251 § 6.
+const MAX_ALGORITHM_NAME_LEN: usize = 64;
+
+/// Suffix of OpenSSH certificate algorithm names.
+#[cfg(feature = "alloc")]
+const CERT_SUFFIX: &str = "-cert-v01@openssh.com";
+
+/// Prefix of ECDSA algorithm names, followed by a curve identifier as
+/// described in [RFC5656 § 6.1].
+///
+/// [RFC5656 § 6.1]: https://datatracker.ietf.org/doc/html/rfc5656#section-6.1
+#[cfg(feature = "alloc")]
+const ECDSA_SHA2_PREFIX: &str = "ecdsa-sha2-";
+
 /// SSH key algorithms.
 ///
 /// This type provides a registry of supported digital signature algorithms
 /// used for SSH keys.
-#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
 #[non_exhaustive]
 pub enum Algorithm {
     /// Digital Signature Algorithm
@@ -102,7 +136,8 @@ pub enum Algorithm {
         /// using [RFC8332] algorithm identifiers.
         ///
         /// If `hash` is set to `None`, then `ssh-rsa` is used as the algorithm
-        /// name.
+        /// name. [`HashAlg::Sha384`] has no RFC8332 identifier, so it can't
+        /// be encoded, nor used to sign or verify.
         ///
         /// [RFC8332]: https://datatracker.ietf.org/doc/html/rfc8332
         hash: Option<HashAlg>,
@@ -113,6 +148,30 @@ pub enum Algorithm {
 
     /// FIDO/U2F key with Ed25519
     SkEd25519,
+
+    /// eXtended Merkle Signature Scheme (XMSS) as described in [RFC8391],
+    /// which OpenSSH supports experimentally as `ssh-xmss@openssh.com`.
+    ///
+    /// XMSS is a stateful hash-based signature scheme. Keys can be decoded,
+    /// encoded and fingerprinted, but signing and verification aren't
+    /// supported.
+    ///
+    /// [RFC8391]: https://datatracker.ietf.org/doc/html/rfc8391
+    #[cfg(feature = "xmss")]
+    Xmss,
+
+    /// Other algorithm with a domain-qualified name (e.g.
+    /// `sntrup761x25519-sha512@openssh.com`), or ECDSA with an unsupported
+    /// curve (e.g. `ecdsa-sha2-1.3.132.0.10`), which is not otherwise
+    /// supported by this crate.
+    ///
+    /// This allows such names, e.g. in algorithm negotiation lists, to be
+    /// parsed and re-encoded unchanged. Public keys, private keys, and
+    /// signatures using these algorithms are decoded as opaque blobs (see
+    /// [`KeyData::Other`][`crate::public::KeyData`]), which can be
+    /// fingerprinted and re-encoded but not used to sign or verify.
+    #[cfg(feature = "alloc")]
+    Other(AlgorithmName),
 }
 
 impl Algorithm {
@@ -127,6 +186,10 @@ impl Algorithm {
     /// - `ssh-rsa`
     /// - `sk-ecdsa-sha2-nistp256@openssh.com` (FIDO/U2F key)
     /// - `sk-ssh-ed25519@openssh.com` (FIDO/U2F key)
+    /// - `ssh-xmss@openssh.com` (with the `xmss` feature)
+    ///
+    /// Any other valid domain-qualified name (i.e. `name@domain`) is returned
+    /// as [`Algorithm::Other`] when the `alloc` feature is enabled.
     pub fn new(id: &str) -> Result<Self> {
         match id {
             SSH_DSA => Ok(Algorithm::Dsa),
@@ -149,10 +212,39 @@ impl Algorithm {
             SSH_RSA => Ok(Algorithm::Rsa { hash: None }),
             SK_ECDSA_SHA2_P256 => Ok(Algorithm::SkEcdsaSha2NistP256),
             SK_SSH_ED25519 => Ok(Algorithm::SkEd25519),
+            #[cfg(feature = "xmss")]
+            SSH_XMSS => Ok(Algorithm::Xmss),
+            #[cfg(feature = "alloc")]
+            _ => AlgorithmName::new(id).map(Algorithm::Other),
+            #[cfg(not(feature = "alloc"))]
             _ => Err(Error::AlgorithmUnknown),
         }
     }
 
+    /// Decode algorithm from the given string identifier, ignoring ASCII case.
+    ///
+    /// This is a lenient alternative to [`Algorithm::new`] for interop with
+    /// tools which emit algorithm identifiers with inconsistent casing.
+    pub fn from_str_lenient(id: &str) -> Result<Self> {
+        [
+            SSH_DSA,
+            ECDSA_SHA2_P256,
+            ECDSA_SHA2_P384,
+            ECDSA_SHA2_P521,
+            RSA_SHA2_256,
+            RSA_SHA2_512,
+            SSH_ED25519,
+            SSH_RSA,
+            SK_ECDSA_SHA2_P256,
+            SK_SSH_ED25519,
+            #[cfg(feature = "xmss")]
+            SSH_XMSS,
+        ]
+        .iter()
+        .find(|known_id| known_id.eq_ignore_ascii_case(id))
+        .map_or(Err(Error::AlgorithmUnknown), |known_id| Self::new(known_id))
+    }
+
     /// Decode algorithm from the given string identifier as used by
     /// the OpenSSH certificate format.
     ///
@@ -161,6 +253,8 @@ impl Algorithm {
     ///
     /// # Supported algorithms
     /// - `ssh-rsa-cert-v01@openssh.com`
+    /// - `rsa-sha2-256-cert-v01@openssh.com`
+    /// - `rsa-sha2-512-cert-v01@openssh.com`
     /// - `ssh-dss-cert-v01@openssh.com`
     /// - `ecdsa-sha2-nistp256-cert-v01@openssh.com`
     /// - `ecdsa-sha2-nistp384-cert-v01@openssh.com`
@@ -168,6 +262,7 @@ impl Algorithm {
     /// - `ssh-ed25519-cert-v01@openssh.com`
     /// - `sk-ecdsa-sha2-nistp256-cert-v01@openssh.com` (FIDO/U2F key)
     /// - `sk-ssh-ed25519-cert-v01@openssh.com` (FIDO/U2F key)
+    /// - `ssh-xmss-cert-v01@openssh.com` (with the `xmss` feature)
     ///
     /// [PROTOCOL.certkeys]: https://cvsweb.openbsd.org/src/usr.bin/ssh/PROTOCOL.certkeys?annotate=HEAD
     pub fn new_certificate(id: &str) -> Result<Self> {
@@ -184,14 +279,37 @@ impl Algorithm {
             }),
             CERT_ED25519 => Ok(Algorithm::Ed25519),
             CERT_RSA => Ok(Algorithm::Rsa { hash: None }),
+            CERT_RSA_SHA2_256 => Ok(Algorithm::Rsa {
+                hash: Some(HashAlg::Sha256),
+            }),
+            CERT_RSA_SHA2_512 => Ok(Algorithm::Rsa {
+                hash: Some(HashAlg::Sha512),
+            }),
             CERT_SK_ECDSA_SHA2_P256 => Ok(Algorithm::SkEcdsaSha2NistP256),
             CERT_SK_SSH_ED25519 => Ok(Algorithm::SkEd25519),
+            #[cfg(feature = "xmss")]
+            CERT_XMSS => Ok(Algorithm::Xmss),
             _ => Err(Error::AlgorithmUnknown),
         }
     }
 
+    /// Decode algorithm from either a plain or OpenSSH certificate string
+    /// identifier, returning the base algorithm and whether or not the
+    /// identifier was for a certificate (i.e. ends in `*-cert-v01@openssh.com`).
+    ///
+    /// Use [`Algorithm::as_certificate_str`] to go the other way.
+    pub fn new_maybe_certificate(id: &str) -> Result<(Self, bool)> {
+        match Self::new_certificate(id) {
+            Ok(algorithm) => Ok((algorithm, true)),
+            Err(_) => Self::new(id).map(|algorithm| (algorithm, false)),
+        }
+    }
+
     /// Get the string identifier which corresponds to this algorithm.
-    pub fn as_str(self) -> &'static str {
+    ///
+    /// `Algorithm::Rsa { hash: Some(HashAlg::Sha384) }` has no identifier, so
+    /// an empty string is returned, and encoding it returns an error.
+    pub fn as_str(&self) -> &str {
         match self {
             Algorithm::Dsa => SSH_DSA,
             Algorithm::Ecdsa { curve } => match curve {
@@ -203,10 +321,16 @@ impl Algorithm {
             Algorithm::Rsa { hash } => match hash {
                 None => SSH_RSA,
                 Some(HashAlg::Sha256) => RSA_SHA2_256,
+                // No such identifier: rejected when encoding
+                Some(HashAlg::Sha384) => "",
                 Some(HashAlg::Sha512) => RSA_SHA2_512,
             },
             Algorithm::SkEcdsaSha2NistP256 => SK_ECDSA_SHA2_P256,
             Algorithm::SkEd25519 => SK_SSH_ED25519,
+            #[cfg(feature = "xmss")]
+            Algorithm::Xmss => SSH_XMSS,
+            #[cfg(feature = "alloc")]
+            Algorithm::Other(name) => name.as_str(),
         }
     }
 
@@ -216,8 +340,12 @@ impl Algorithm {
     /// OpenSSH certificate algorithms end in `*-cert-v01@openssh.com`.
     /// See [PROTOCOL.certkeys] for more information.
     ///
+    /// [`Algorithm::Other`] has no certificate format, so its name is
+    /// returned unchanged. As with [`Algorithm::as_str`], an empty string is
+    /// returned for `Algorithm::Rsa { hash: Some(HashAlg::Sha384) }`.
+    ///
     /// [PROTOCOL.certkeys]: https://cvsweb.openbsd.org/src/usr.bin/ssh/PROTOCOL.certkeys?annotate=HEAD
-    pub fn as_certificate_str(self) -> &'static str {
+    pub fn as_certificate_str(&self) -> &str {
         match self {
             Algorithm::Dsa => CERT_DSA,
             Algorithm::Ecdsa { curve } => match curve {
@@ -226,36 +354,127 @@ impl Algorithm {
                 EcdsaCurve::NistP521 => CERT_ECDSA_SHA2_P521,
             },
             Algorithm::Ed25519 => CERT_ED25519,
-            Algorithm::Rsa { .. } => CERT_RSA,
+            Algorithm::Rsa { hash } => match hash {
+                None => CERT_RSA,
+                Some(HashAlg::Sha256) => CERT_RSA_SHA2_256,
+                Some(HashAlg::Sha384) => "",
+                Some(HashAlg::Sha512) => CERT_RSA_SHA2_512,
+            },
             Algorithm::SkEcdsaSha2NistP256 => CERT_SK_ECDSA_SHA2_P256,
             Algorithm::SkEd25519 => CERT_SK_SSH_ED25519,
+            #[cfg(feature = "xmss")]
+            Algorithm::Xmss => CERT_XMSS,
+            #[cfg(feature = "alloc")]
+            Algorithm::Other(name) => name.as_str(),
         }
     }
 
     /// Is the algorithm DSA?
-    pub fn is_dsa(self) -> bool {
-        self == Algorithm::Dsa
+    pub fn is_dsa(&self) -> bool {
+        *self == Algorithm::Dsa
     }
 
     /// Is the algorithm ECDSA?
-    pub fn is_ecdsa(self) -> bool {
+    pub fn is_ecdsa(&self) -> bool {
         matches!(self, Algorithm::Ecdsa { .. })
     }
 
     /// Is the algorithm Ed25519?
-    pub fn is_ed25519(self) -> bool {
-        self == Algorithm::Ed25519
+    pub fn is_ed25519(&self) -> bool {
+        *self == Algorithm::Ed25519
     }
 
     /// Is the algorithm RSA?
-    pub fn is_rsa(self) -> bool {
+    pub fn is_rsa(&self) -> bool {
         matches!(self, Algorithm::Rsa { .. })
     }
 
+    /// Is the algorithm XMSS?
+    #[cfg(feature = "xmss")]
+    pub fn is_xmss(&self) -> bool {
+        *self == Algorithm::Xmss
+    }
+
+    /// Is the algorithm a FIDO/U2F Security Key algorithm?
+    pub fn is_sk(&self) -> bool {
+        matches!(self, Algorithm::SkEcdsaSha2NistP256 | Algorithm::SkEd25519)
+    }
+
+    /// Get the [`HashAlg`] used to digest messages when signing with this
+    /// algorithm, if it's one supported by [`HashAlg`].
+    ///
+    /// Returns `None` for algorithms which don't use a separate hash function
+    /// (i.e. Ed25519) and for those whose hash function isn't a [`HashAlg`],
+    /// i.e. SHA-1 for `ssh-dss` and `ssh-rsa`.
+    pub fn hash_alg(&self) -> Option<HashAlg> {
+        match self {
+            Algorithm::Ecdsa {
+                curve: EcdsaCurve::NistP256,
+            }
+            | Algorithm::SkEcdsaSha2NistP256 => Some(HashAlg::Sha256),
+            Algorithm::Ecdsa {
+                curve: EcdsaCurve::NistP384,
+            } => Some(HashAlg::Sha384),
+            Algorithm::Ecdsa {
+                curve: EcdsaCurve::NistP521,
+            } => Some(HashAlg::Sha512),
+            Algorithm::Rsa { hash } => *hash,
+            Algorithm::Dsa | Algorithm::Ed25519 | Algorithm::SkEd25519 => None,
+            #[cfg(feature = "alloc")]
+            Algorithm::Other(_) => None,
+            #[cfg(feature = "xmss")]
+            Algorithm::Xmss => None,
+        }
+    }
+
+    /// Get the size of an encoded signature (i.e. including the algorithm
+    /// identifier) produced using this algorithm, if it has a bounded size.
+    ///
+    /// This is exact for DSA and Ed25519. ECDSA signatures encode `r` and `s`
+    /// as `mpint`s with leading zeroes stripped, so the returned size is an
+    /// upper bound. Returns `None` for RSA, where the size depends on the
+    /// size of the key, and for XMSS and [`Algorithm::Other`].
+    pub fn signature_size_hint(&self) -> Option<usize> {
+        let signature_len = match self {
+            Algorithm::Dsa => 40,
+            Algorithm::Ecdsa { curve } => curve.max_signature_len(),
+            Algorithm::Ed25519 => 64,
+            Algorithm::Rsa { .. } => return None,
+            Algorithm::SkEcdsaSha2NistP256 => EcdsaCurve::NistP256.max_signature_len(),
+            Algorithm::SkEd25519 => 64,
+            #[cfg(feature = "xmss")]
+            Algorithm::Xmss => return None,
+            #[cfg(feature = "alloc")]
+            Algorithm::Other(_) => return None,
+        };
+
+        [
+            4, // algorithm identifier length prefix
+            self.as_str().len(),
+            4, // signature length prefix
+            signature_len,
+            if self.is_sk() { 5 } else { 0 }, // flags(u8), counter(u32)
+        ]
+        .checked_sum()
+        .ok()
+    }
+
+    /// Ensure this algorithm has a string identifier, i.e. it isn't
+    /// `Algorithm::Rsa { hash: Some(HashAlg::Sha384) }`.
+    pub(crate) fn check_identifier(&self) -> Result<()> {
+        if self.as_str().is_empty() {
+            return Err(encoding::Error::Discriminant.into());
+        }
+
+        Ok(())
+    }
+
     /// Return an error indicating this algorithm is unsupported.
     #[allow(dead_code)]
-    pub(crate) fn unsupported_error(self) -> Error {
-        Error::AlgorithmUnsupported { algorithm: self }
+    pub(crate) fn unsupported_error(&self) -> Error {
+        Error::AlgorithmUnsupported {
+            algorithm: self.clone(),
+        }
     }
 }
 
@@ -265,8 +484,27 @@ impl AsRef<str> for Algorithm {
     }
 }
 
-impl Label for Algorithm {
+impl Decode for Algorithm {
+    type Error = Error;
+
+    fn decode(reader: &mut impl Reader) -> Result<Self> {
+        let mut buf = [0u8; MAX_ALGORITHM_NAME_LEN];
+        reader.read_string(buf.as_mut())?.parse()
+    }
+}
+
+impl Encode for Algorithm {
     type Error = Error;
+
+    fn encoded_len(&self) -> Result<usize> {
+        self.check_identifier()?;
+        Ok(self.as_str().encoded_len()?)
+    }
+
+    fn encode(&self, writer: &mut impl Writer) -> Result<()> {
+        self.check_identifier()?;
+        Ok(self.as_str().encode(writer)?)
+    }
 }
 
 impl fmt::Display for Algorithm {
@@ -283,6 +521,126 @@ impl str::FromStr for Algorithm {
     }
 }
 
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl<'de> Deserialize<'de> for Algorithm {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let string = String::deserialize(deserializer)?;
+        Self::new(&string).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+impl Serialize for Algorithm {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        self.as_str().serialize(serializer)
+    }
+}
+
+/// Parse either a plain or OpenSSH certificate string identifier, e.g. the
+/// first field of a public key or certificate line, returning the base
+/// algorithm.
+///
+/// Unlike [`FromStr`][`str::FromStr`], which only accepts plain identifiers,
+/// this accepts e.g. `ecdsa-sha2-nistp256-cert-v01@openssh.com`. Use
+/// [`Algorithm::new_maybe_certificate`] to also learn whether the identifier
+/// was for a certificate.
+impl TryFrom<&str> for Algorithm {
+    type Error = Error;
+
+    fn try_from(id: &str) -> Result<Self> {
+        Self::new_maybe_certificate(id).map(|(algorithm, _)| algorithm)
+    }
+}
+
+/// Domain-qualified algorithm name, i.e. `name@domain`, or ECDSA algorithm
+/// name with an unsupported curve, i.e. `ecdsa-sha2-[identifier]`, as used
+/// by [`Algorithm::Other`].
+///
+/// Names are at most 64 characters, as specified in [RFC4251 § 6], and may
+/// only contain ASCII alphanumerics and `+`, `-`, `.` and `_`, besides the
+/// single `@` of domain-qualified names. OpenSSH certificate names (i.e.
+/// `*-cert-v01@openssh.com`) and the ECDSA curves supported by
+/// [`EcdsaCurve`] are not allowed.
+///
+/// [RFC4251 § 6]: https://datatracker.ietf.org/doc/html/rfc4251#section-6
+#[cfg(feature = "alloc")]
+#[derive(Clone, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct AlgorithmName {
+    /// Name.
+    id: String,
+}
+
+#[cfg(feature = "alloc")]
+impl AlgorithmName {
+    /// Create a new algorithm name, returning [`Error::AlgorithmUnknown`] if
+    /// it isn't a valid domain-qualified name.
+    pub fn new(id: &str) -> Result<Self> {
+        let valid_part = |part: &str| {
+            !part.is_empty()
+                && part
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b"+-._".contains(&b))
+        };
+
+        let valid = match (id.split_once('@'), id.strip_prefix(ECDSA_SHA2_PREFIX)) {
+            (Some((name, domain)), _) => {
+                valid_part(name) && valid_part(domain) && !id.ends_with(CERT_SUFFIX)
+            }
+            (None, Some(curve)) => valid_part(curve) && EcdsaCurve::new(curve).is_err(),
+            (None, None) => false,
+        };
+
+        if !valid || id.len() > MAX_ALGORITHM_NAME_LEN {
+            return Err(Error::AlgorithmUnknown);
+        }
+
+        Ok(Self { id: id.into() })
+    }
+
+    /// Get the name as a string.
+    pub fn as_str(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl AsRef<str> for AlgorithmName {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Debug for AlgorithmName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AlgorithmName")
+            .field(&self.as_str())
+            .finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for AlgorithmName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl str::FromStr for AlgorithmName {
+    type Err = Error;
+
+    fn from_str(id: &str) -> Result<Self> {
+        Self::new(id)
+    }
+}
+
 /// Elliptic curves supported for use with ECDSA.
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
 pub enum EcdsaCurve {
@@ -331,6 +689,20 @@ impl EcdsaCurve {
             EcdsaCurve::NistP521 => 66,
         }
     }
+
+    /// Get the maximum size of the `mpint`-encoded `r` and `s` components of
+    /// a signature for this curve.
+    ///
+    /// Each component has a `uint32` length prefix and may need a leading
+    /// zero byte if its MSB is set, which is not possible for P-521 since its
+    /// field elements are 521 bits.
+    const fn max_signature_len(self) -> usize {
+        match self {
+            EcdsaCurve::NistP256 => 2 * (4 + 1 + 32),
+            EcdsaCurve::NistP384 => 2 * (4 + 1 + 48),
+            EcdsaCurve::NistP521 => 2 * (4 + 66),
+        }
+    }
 }
 
 impl AsRef<str> for EcdsaCurve {
@@ -365,20 +737,26 @@ pub enum HashAlg {
     #[default]
     Sha256,
 
+    /// SHA-384
+    Sha384,
+
     /// SHA-512
     Sha512,
 }
 
 impl HashAlg {
-    /// Decode elliptic curve from the given string identifier.
+    /// Decode hash algorithm from the given string identifier, as used in
+    /// e.g. `sshsig` signatures.
     ///
     /// # Supported hash algorithms
     ///
     /// - `sha256`
+    /// - `sha384`
     /// - `sha512`
     pub fn new(id: &str) -> Result<Self> {
         match id {
             SHA256 => Ok(HashAlg::Sha256),
+            SHA384 => Ok(HashAlg::Sha384),
             SHA512 => Ok(HashAlg::Sha512),
             _ => Err(Error::AlgorithmUnknown),
         }
@@ -388,6 +766,7 @@ impl HashAlg {
     pub fn as_str(self) -> &'static str {
         match self {
             HashAlg::Sha256 => SHA256,
+            HashAlg::Sha384 => SHA384,
             HashAlg::Sha512 => SHA512,
         }
     }
@@ -396,6 +775,7 @@ impl HashAlg {
     pub const fn digest_size(self) -> usize {
         match self {
             HashAlg::Sha256 => 32,
+            HashAlg::Sha384 => 48,
             HashAlg::Sha512 => 64,
         }
     }
@@ -405,9 +785,37 @@ impl HashAlg {
     pub fn digest(self, msg: &[u8]) -> Vec<u8> {
         match self {
             HashAlg::Sha256 => Sha256::digest(msg).to_vec(),
+            HashAlg::Sha384 => Sha384::digest(msg).to_vec(),
             HashAlg::Sha512 => Sha512::digest(msg).to_vec(),
         }
     }
+
+    /// Compute a digest of all of the data read from the given reader using
+    /// this hash function, without buffering it in memory.
+    #[cfg(feature = "std")]
+    pub fn digest_reader(self, reader: impl std::io::Read) -> Result<Vec<u8>> {
+        match self {
+            HashAlg::Sha256 => digest_reader::<Sha256>(reader),
+            HashAlg::Sha384 => digest_reader::<Sha384>(reader),
+            HashAlg::Sha512 => digest_reader::<Sha512>(reader),
+        }
+    }
+}
+
+/// Hash everything read from `reader` using the digest function `D`.
+#[cfg(feature = "std")]
+fn digest_reader<D: Digest>(mut reader: impl std::io::Read) -> Result<Vec<u8>> {
+    let mut digest = D::new();
+    let mut buffer = [0u8; 8192];
+
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => return Ok(digest.finalize().to_vec()),
+            Ok(n) => digest.update(buffer.get(..n).ok_or(encoding::Error::Length)?),
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => (),
+            Err(err) => return Err(err.into()),
+        }
+    }
 }
 
 impl Label for HashAlg {
@@ -435,7 +843,7 @@ impl str::FromStr for HashAlg {
 }
 
 /// Key Derivation Function (KDF) algorithms.
-#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
 #[non_exhaustive]
 pub enum KdfAlg {
     /// None.
@@ -444,12 +852,17 @@ pub enum KdfAlg {
     /// bcrypt-pbkdf.
     #[default]
     Bcrypt,
+
+    /// Unrecognized KDF algorithm, identified by its `kdfname`.
+    #[cfg(feature = "alloc")]
+    Other(String),
 }
 
 impl KdfAlg {
     /// Decode KDF algorithm from the given `kdfname`.
     ///
     /// # Supported KDF names
+    /// - `bcrypt`
     /// - `none`
     pub fn new(kdfname: &str) -> Result<Self> {
         match kdfname {
@@ -460,16 +873,18 @@ impl KdfAlg {
     }
 
     /// Get the string identifier which corresponds to this algorithm.
-    pub fn as_str(self) -> &'static str {
+    pub fn as_str(&self) -> &str {
         match self {
             Self::None => NONE,
             Self::Bcrypt => BCRYPT,
+            #[cfg(feature = "alloc")]
+            Self::Other(kdfname) => kdfname,
         }
     }
 
     /// Is the KDF algorithm "none"?
-    pub fn is_none(self) -> bool {
-        self == Self::None
+    pub fn is_none(&self) -> bool {
+        *self == Self::None
     }
 }
 
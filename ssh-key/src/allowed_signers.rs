@@ -0,0 +1,638 @@
+//! Parser for `AllowedSignersFile`-formatted data.
+
+use crate::{
+    authorized_keys::ConfigOpts, certificate::match_pattern, Algorithm, Error, HashAlg, PublicKey,
+    Result, SshSig,
+};
+use alloc::{
+    string::{String, ToString},
+    vec::{self, Vec},
+};
+use core::{fmt, slice, str};
+
+#[cfg(feature = "std")]
+use std::{
+    fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Character that begins a comment
+const COMMENT_DELIMITER: char = '#';
+
+/// Namespace used by git for `sshsig` signatures.
+#[cfg(feature = "std")]
+const GIT_NAMESPACE: &str = "git";
+
+/// Seconds per day.
+const SECS_PER_DAY: u64 = 86400;
+
+/// Parser for `AllowedSignersFile`-formatted data, as used by
+/// `ssh-keygen -Y verify` and git's `gpg.ssh.allowedSignersFile`.
+///
+/// For a full description of the format, see:
+/// <https://man.openbsd.org/ssh-keygen.1#ALLOWED_SIGNERS>
+///
+/// Each line of the file consists of a single public key tied to one or more
+/// principals. Blank lines and lines beginning with `#` are ignored.
+///
+/// Entries consist of the following space-separated fields:
+///
+/// ```text
+/// principals, options, keytype, base64-encoded key, comment
+/// ```
+///
+/// - The principals field is a comma-separated list of principal patterns
+///   (e.g. email addresses).
+/// - The options field is optional, and is a comma-separated list of options
+///   such as `cert-authority` or `namespaces="git,file"`.
+/// - The comment field is not used for anything.
+pub struct AllowedSigners<'a> {
+    /// Lines of the file being iterated over
+    lines: str::Lines<'a>,
+}
+
+impl<'a> AllowedSigners<'a> {
+    /// Create a new parser for the given input buffer.
+    pub fn new(input: &'a str) -> Self {
+        Self {
+            lines: input.lines(),
+        }
+    }
+
+    /// Read an [`AllowedSigners`] file from the filesystem, returning an
+    /// [`Entry`] vector on success.
+    #[cfg(feature = "std")]
+    pub fn read_file(path: impl AsRef<Path>) -> Result<Vec<Entry>> {
+        let input = fs::read_to_string(path)?;
+        AllowedSigners::new(&input).collect()
+    }
+
+    /// Get the next line, trimming any whitespace.
+    ///
+    /// Ignores empty lines and comments.
+    fn next_line_trimmed(&mut self) -> Option<&'a str> {
+        loop {
+            let line = self.lines.next()?.trim();
+
+            if !line.is_empty() && !line.starts_with(COMMENT_DELIMITER) {
+                return Some(line);
+            }
+        }
+    }
+}
+
+impl Iterator for AllowedSigners<'_> {
+    type Item = Result<Entry>;
+
+    fn next(&mut self) -> Option<Result<Entry>> {
+        self.next_line_trimmed().map(|line| line.parse())
+    }
+}
+
+/// Individual entry in an `allowed_signers` file containing a single public
+/// key.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Entry {
+    /// Principals
+    principals: Vec<String>,
+
+    /// Configuration options
+    config_opts: ConfigOpts,
+
+    /// Public key
+    public_key: PublicKey,
+}
+
+impl Entry {
+    /// Create a new entry from the given principal patterns, configuration
+    /// options and public key.
+    ///
+    /// Returns [`Error::FormatEncoding`] if there are no principals, or any
+    /// principal is empty or contains whitespace or commas.
+    pub fn new(
+        principals: Vec<String>,
+        config_opts: ConfigOpts,
+        public_key: PublicKey,
+    ) -> Result<Self> {
+        let valid = |principal: &String| {
+            !principal.is_empty()
+                && !principal
+                    .chars()
+                    .any(|c| c == ',' || c.is_ascii_whitespace())
+        };
+
+        if principals.is_empty() || !principals.iter().all(valid) {
+            return Err(Error::FormatEncoding);
+        }
+
+        Ok(Self {
+            principals,
+            config_opts,
+            public_key,
+        })
+    }
+
+    /// Get the principals for this entry.
+    pub fn principals(&self) -> &[String] {
+        &self.principals
+    }
+
+    /// Get the configuration options for this entry.
+    pub fn config_opts(&self) -> &ConfigOpts {
+        &self.config_opts
+    }
+
+    /// Get public key for this entry.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Is this entry's public key for a certificate authority, i.e. does it
+    /// have the `cert-authority` option?
+    pub fn is_cert_authority(&self) -> bool {
+        self.config_opts
+            .iter()
+            .any(|opt| opt.eq_ignore_ascii_case("cert-authority"))
+    }
+
+    /// Is this entry's key allowed to make signatures in the given namespace?
+    ///
+    /// Entries without a `namespaces` option are allowed to sign in any
+    /// namespace. Namespaces are matched against the option's
+    /// comma-separated pattern list, as with [`Entry::matches_principal`].
+    pub fn allows_namespace(&self, namespace: &str) -> bool {
+        match self.option("namespaces") {
+            Some(namespaces) => match_pattern_list(namespace, namespaces),
+            None => true,
+        }
+    }
+
+    /// Does the given principal match this entry's principal patterns?
+    ///
+    /// Patterns may contain the `*` and `?` wildcards, and patterns prefixed
+    /// with `!` are negated: a principal matching any negated pattern never
+    /// matches, regardless of the other patterns.
+    pub fn matches_principal(&self, principal: &str) -> bool {
+        match_patterns(principal, self.principals.iter().map(String::as_str))
+    }
+
+    /// Get the time (as a Unix timestamp) from which this entry's key is
+    /// valid, if it has a `valid-after` option.
+    ///
+    /// Timestamps are formatted as `YYYYMMDD[Z]` or `YYYYMMDDHHMM[SS][Z]`.
+    /// Unlike `ssh-keygen`, which uses the local time zone for timestamps
+    /// without a `Z` suffix, timestamps are always interpreted as UTC.
+    ///
+    /// Returns [`Error::Time`] if the timestamp is malformed.
+    pub fn valid_after(&self) -> Result<Option<u64>> {
+        self.option("valid-after").map(parse_time).transpose()
+    }
+
+    /// Get the time (as a Unix timestamp) until which this entry's key is
+    /// valid, if it has a `valid-before` option.
+    ///
+    /// See [`Entry::valid_after`] for the timestamp format.
+    pub fn valid_before(&self) -> Result<Option<u64>> {
+        self.option("valid-before").map(parse_time).transpose()
+    }
+
+    /// Is this entry's key valid at the given time, according to its
+    /// `valid-after` and `valid-before` options?
+    ///
+    /// As in `ssh-keygen`, both bounds are inclusive.
+    pub fn is_valid_at(&self, unix_timestamp: u64) -> Result<bool> {
+        let after = self
+            .valid_after()?
+            .map_or(true, |after| unix_timestamp >= after);
+        let before = self
+            .valid_before()?
+            .map_or(true, |before| unix_timestamp <= before);
+
+        Ok(after && before)
+    }
+
+    /// Get the (unquoted) value of the option with the given name.
+    fn option(&self, name: &str) -> Option<&str> {
+        self.config_opts.iter().find_map(|opt| {
+            let (opt_name, value) = opt.split_once('=')?;
+            opt_name
+                .eq_ignore_ascii_case(name)
+                .then(|| value.trim_matches('"'))
+        })
+    }
+
+    /// Is this entry's key (or CA key) authorized to have made the given
+    /// signature?
+    fn authorizes_key(&self, signature: &SshSig) -> bool {
+        match signature.certificate() {
+            Some(certificate) => {
+                self.is_cert_authority()
+                    && certificate.signature_key() == self.public_key.key_data()
+            }
+            None => {
+                !self.is_cert_authority() && self.public_key.key_data() == signature.public_key()
+            }
+        }
+    }
+}
+
+impl From<Entry> for PublicKey {
+    fn from(entry: Entry) -> PublicKey {
+        entry.public_key
+    }
+}
+
+impl str::FromStr for Entry {
+    type Err = Error;
+
+    fn from_str(line: &str) -> Result<Self> {
+        let (principals_str, rest) = line.split_once(' ').ok_or(Error::FormatEncoding)?;
+        let rest = rest.trim_start();
+
+        // Options are present if the next field isn't an algorithm identifier
+        let (config_opts_str, public_key_str) = match rest.split_once(' ') {
+            Some((field, public_key)) if Algorithm::new(field).is_err() => {
+                (field, public_key.trim_start())
+            }
+            _ => ("", rest),
+        };
+
+        let principals = principals_str
+            .split(',')
+            .map(|principal| match principal {
+                "" => Err(Error::FormatEncoding),
+                _ => Ok(principal.to_string()),
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            principals,
+            config_opts: config_opts_str.parse()?,
+            public_key: public_key_str.parse()?,
+        })
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.principals.join(","))?;
+
+        if !self.config_opts.is_empty() {
+            write!(f, " {}", self.config_opts)?;
+        }
+
+        write!(f, " {}", self.public_key)
+    }
+}
+
+/// Owned collection of [`Entry`] values which can be serialized as an
+/// `allowed_signers` file, and used to verify signatures.
+///
+/// The [`fmt::Display`] impl emits one entry per line (each terminated with
+/// a newline) in the order the entries were added.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AllowedSignersFile {
+    /// Entries in the file.
+    entries: Vec<Entry>,
+}
+
+impl AllowedSignersFile {
+    /// Create a new empty `allowed_signers` file.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read an `allowed_signers` file from the filesystem.
+    #[cfg(feature = "std")]
+    pub fn read_file(path: impl AsRef<Path>) -> Result<Self> {
+        AllowedSigners::read_file(path).map(Into::into)
+    }
+
+    /// Add an entry to the end of this file.
+    pub fn push(&mut self, entry: Entry) {
+        self.entries.push(entry);
+    }
+
+    /// Get the entries in this file.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Iterate over the entries in this file.
+    pub fn iter(&self) -> slice::Iter<'_, Entry> {
+        self.entries.iter()
+    }
+
+    /// Number of entries in this file.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Does this file contain no entries?
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Verify that the given signature over `msg` was made by the given
+    /// principal in the given namespace, as with `ssh-keygen -Y verify`,
+    /// using the system clock to check the validity of entries and
+    /// certificates.
+    ///
+    /// See [`AllowedSignersFile::verify_at`] for more information.
+    #[cfg(feature = "std")]
+    pub fn verify(
+        &self,
+        principal: &str,
+        namespace: &str,
+        signature: &SshSig,
+        msg: &[u8],
+    ) -> Result<&Entry> {
+        let unix_timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        self.verify_at(unix_timestamp, principal, namespace, signature, msg)
+    }
+
+    /// Verify that the given signature over `msg` was made by the given
+    /// principal in the given namespace at the given time, as with
+    /// `ssh-keygen -Y verify -Overify-time=...`.
+    ///
+    /// The first entry which authorizes the signature is returned. An entry
+    /// authorizes it if:
+    ///
+    /// - one of its principal patterns matches `principal` (see
+    ///   [`Entry::matches_principal`]),
+    /// - its key made the signature or, for `cert-authority` entries, its key
+    ///   issued the (user) certificate which made the signature, in which
+    ///   case the certificate must be valid at the given time and list
+    ///   `principal` as one of its valid principals,
+    /// - it allows signatures in `namespace`, and
+    /// - it is valid at the given time according to its `valid-after` and
+    ///   `valid-before` options.
+    ///
+    /// # Returns
+    /// - [`Error::PublicKey`] if no entry for `principal` matches the
+    ///   signature's key.
+    /// - [`Error::Namespace`] if the signature wasn't made in `namespace`, or
+    ///   the matching entry doesn't allow it.
+    /// - [`Error::Time`] if the matching entry isn't valid at the given time,
+    ///   or has a malformed `valid-after` or `valid-before` option.
+    /// - [`Error::CertificateValidation`] if the certificate failed to
+    ///   validate.
+    /// - [`Error::Crypto`] if the signature is invalid.
+    pub fn verify_at(
+        &self,
+        unix_timestamp: u64,
+        principal: &str,
+        namespace: &str,
+        signature: &SshSig,
+        msg: &[u8],
+    ) -> Result<&Entry> {
+        if namespace != signature.namespace() {
+            return Err(Error::Namespace);
+        }
+
+        let mut result = Err(Error::PublicKey);
+
+        for entry in &self.entries {
+            if !entry.matches_principal(principal) || !entry.authorizes_key(signature) {
+                continue;
+            }
+
+            if !entry.allows_namespace(namespace) {
+                result = Err(Error::Namespace);
+                continue;
+            }
+
+            if !entry.is_valid_at(unix_timestamp)? {
+                result = Err(Error::Time);
+                continue;
+            }
+
+            let fingerprint = entry.public_key.fingerprint(HashAlg::Sha256);
+
+            match signature.certificate() {
+                Some(certificate) => {
+                    signature.verify_with_ca_at(unix_timestamp, [&fingerprint], namespace, msg)?;
+
+                    if !certificate.cert_type().is_user()
+                        || certificate.check_principal(principal).is_err()
+                    {
+                        return Err(Error::CertificateValidation);
+                    }
+                }
+                None => signature.verify(msg)?,
+            }
+
+            return Ok(entry);
+        }
+
+        result
+    }
+}
+
+impl From<Vec<Entry>> for AllowedSignersFile {
+    fn from(entries: Vec<Entry>) -> AllowedSignersFile {
+        AllowedSignersFile { entries }
+    }
+}
+
+impl From<AllowedSignersFile> for Vec<Entry> {
+    fn from(file: AllowedSignersFile) -> Vec<Entry> {
+        file.entries
+    }
+}
+
+impl Extend<Entry> for AllowedSignersFile {
+    fn extend<I: IntoIterator<Item = Entry>>(&mut self, iter: I) {
+        self.entries.extend(iter);
+    }
+}
+
+impl FromIterator<Entry> for AllowedSignersFile {
+    fn from_iter<I: IntoIterator<Item = Entry>>(iter: I) -> Self {
+        Self {
+            entries: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl IntoIterator for AllowedSignersFile {
+    type Item = Entry;
+    type IntoIter = vec::IntoIter<Entry>;
+
+    fn into_iter(self) -> vec::IntoIter<Entry> {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a AllowedSignersFile {
+    type Item = &'a Entry;
+    type IntoIter = slice::Iter<'a, Entry>;
+
+    fn into_iter(self) -> slice::Iter<'a, Entry> {
+        self.iter()
+    }
+}
+
+impl str::FromStr for AllowedSignersFile {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        AllowedSigners::new(s)
+            .collect::<Result<Vec<_>>>()
+            .map(Into::into)
+    }
+}
+
+impl fmt::Display for AllowedSignersFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{entry}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Verify an SSH signature over a git commit or tag, as created by e.g.
+/// `git commit -S` with `gpg.format=ssh`, returning the principal of the
+/// signer.
+///
+/// - `allowed_signers`: contents of an `allowed_signers` file, i.e. git's
+///   `gpg.ssh.allowedSignersFile`.
+/// - `payload`: the signed data, i.e. the commit or tag object with the
+///   signature (e.g. the `gpgsig` header of a commit) removed.
+/// - `signature`: the signature, with namespace `git`.
+///
+/// Like git, this first finds the candidate principals for the signature
+/// (as with `ssh-keygen -Y find-principals`), then verifies the signature
+/// for each of them using [`AllowedSignersFile::verify`] in the `git`
+/// namespace, returning the first principal for which it succeeds. The
+/// candidates are the valid principals of the signer's certificate, if any,
+/// and otherwise the principals of the entries for the signer's key which
+/// aren't patterns.
+///
+/// See [`AllowedSignersFile::verify_at`] for the errors returned.
+#[cfg(feature = "std")]
+pub fn verify_git_signature(
+    allowed_signers: &str,
+    payload: &[u8],
+    signature: &SshSig,
+) -> Result<String> {
+    let allowed_signers = allowed_signers.parse::<AllowedSignersFile>()?;
+
+    let candidates: Vec<&str> = match signature.certificate() {
+        Some(certificate) => certificate
+            .valid_principals()
+            .iter()
+            .map(String::as_str)
+            .collect(),
+        None => allowed_signers
+            .iter()
+            .filter(|entry| entry.authorizes_key(signature))
+            .flat_map(|entry| entry.principals.iter())
+            .map(String::as_str)
+            .filter(|principal| !principal.contains(['*', '?', '!']))
+            .collect(),
+    };
+
+    let mut result = Err(Error::PublicKey);
+
+    for principal in candidates {
+        match allowed_signers.verify(principal, GIT_NAMESPACE, signature, payload) {
+            Ok(_) => return Ok(principal.to_string()),
+            // Keep the most specific error
+            Err(Error::PublicKey) => (),
+            Err(err) => result = Err(err),
+        }
+    }
+
+    result
+}
+
+/// Match a string against a comma-separated list of patterns, as in
+/// OpenSSH's `match_pattern_list`.
+fn match_pattern_list(s: &str, patterns: &str) -> bool {
+    match_patterns(s, patterns.split(','))
+}
+
+/// Match a string against the given patterns, any of which may be negated
+/// using a `!` prefix.
+fn match_patterns<'a>(s: &str, patterns: impl IntoIterator<Item = &'a str>) -> bool {
+    let mut matched = false;
+
+    for pattern in patterns {
+        match pattern.strip_prefix('!') {
+            Some(negated) if match_pattern(s, negated) => return false,
+            Some(_) => (),
+            None => matched |= match_pattern(s, pattern),
+        }
+    }
+
+    matched
+}
+
+/// Parse a `YYYYMMDD[Z]` or `YYYYMMDDHHMM[SS][Z]` timestamp (in UTC) as a
+/// Unix timestamp.
+fn parse_time(s: &str) -> Result<u64> {
+    let digits = s.strip_suffix(['Z', 'z']).unwrap_or(s);
+
+    if !matches!(digits.len(), 8 | 12 | 14) || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(Error::Time);
+    }
+
+    let field = |range: core::ops::Range<usize>| -> Result<u64> {
+        digits
+            .get(range)
+            .filter(|field| !field.is_empty())
+            .map_or(Ok(0), |field| field.parse().map_err(|_| Error::Time))
+    };
+
+    let (year, month, day) = (field(0..4)?, field(4..6)?, field(6..8)?);
+    let (hour, minute, second) = (field(8..10)?, field(10..12)?, field(12..14)?);
+
+    let is_leap = |year: u64| {
+        year.checked_rem(4) == Some(0)
+            && (year.checked_rem(100) != Some(0) || year.checked_rem(400) == Some(0))
+    };
+    let month_days = [
+        31,
+        if is_leap(year) { 29 } else { 28 },
+        31,
+        30,
+        31,
+        30,
+        31,
+        31,
+        30,
+        31,
+        30,
+        31,
+    ];
+
+    let (elapsed_months, days_in_month) = usize::try_from(month)
+        .ok()
+        .and_then(|month| month.checked_sub(1))
+        .and_then(|index| Some((month_days.get(..index)?, month_days.get(index)?)))
+        .ok_or(Error::Time)?;
+
+    if year < 1970 || day == 0 || day > *days_in_month || hour > 23 || minute > 59 || second > 59 {
+        return Err(Error::Time);
+    }
+
+    let mut days = day.saturating_sub(1);
+
+    for year in 1970..year {
+        days = days.saturating_add(if is_leap(year) { 366 } else { 365 });
+    }
+
+    for days_in_month in elapsed_months {
+        days = days.saturating_add(*days_in_month);
+    }
+
+    Ok(days
+        .saturating_mul(SECS_PER_DAY)
+        .saturating_add(hour.saturating_mul(3600))
+        .saturating_add(minute.saturating_mul(60))
+        .saturating_add(second))
+}
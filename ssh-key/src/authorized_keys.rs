@@ -1,16 +1,23 @@
 //! Parser for `AuthorizedKeysFile`-formatted data.
 
-use crate::{Error, PublicKey, Result};
+use crate::{Algorithm, Error, PublicKey, Result};
 use core::str;
 
 #[cfg(feature = "alloc")]
 use {
-    alloc::string::{String, ToString},
-    core::fmt,
+    crate::Certificate,
+    alloc::{
+        string::{String, ToString},
+        vec::{self, Vec},
+    },
+    core::{fmt, slice},
 };
 
 #[cfg(feature = "std")]
-use std::{fs, path::Path, vec::Vec};
+use {
+    crate::{lines::LineReader, Fingerprint, HashAlg},
+    std::{fs, io::BufRead, path::Path},
+};
 
 /// Character that begins a comment
 const COMMENT_DELIMITER: char = '#';
@@ -22,6 +29,8 @@ const COMMENT_DELIMITER: char = '#';
 /// <https://man7.org/linux/man-pages/man8/sshd.8.html#AUTHORIZED_KEYS_FILE_FORMAT>
 ///
 /// Each line of the file consists of a single public key. Blank lines are ignored.
+/// Lines may end in either `\n` or `\r\n`, and a trailing `\r` is never
+/// considered part of a key's comment.
 ///
 /// Public keys consist of the following space-separated fields:
 ///
@@ -36,7 +45,7 @@ const COMMENT_DELIMITER: char = '#';
 ///   the key).
 pub struct AuthorizedKeys<'a> {
     /// Lines of the file being iterated over
-    lines: core::str::Lines<'a>,
+    lines: str::Lines<'a>,
 }
 
 impl<'a> AuthorizedKeys<'a> {
@@ -61,15 +70,7 @@ impl<'a> AuthorizedKeys<'a> {
     /// Ignores empty lines.
     fn next_line_trimmed(&mut self) -> Option<&'a str> {
         loop {
-            let mut line = self.lines.next()?;
-
-            // Strip comment if present
-            if let Some((l, _)) = line.split_once(COMMENT_DELIMITER) {
-                line = l;
-            }
-
-            // Trim trailing whitespace
-            line = line.trim_end();
+            let line = trim_line(self.lines.next()?);
 
             if !line.is_empty() {
                 return Some(line);
@@ -86,6 +87,406 @@ impl Iterator for AuthorizedKeys<'_> {
     }
 }
 
+/// Compute the fingerprint of every key in an `authorized_keys` file, along
+/// with the key's comment (or `None` if it has no comment).
+///
+/// Returns [`Error::LineInvalid`] with the (1-based) number of the first line
+/// which fails to parse. To skip invalid lines instead, iterate over the
+/// entries with [`AuthorizedKeysReader`], which reports each of them.
+#[cfg(feature = "std")]
+pub fn fingerprints(
+    mut reader: impl std::io::Read,
+    hash_alg: HashAlg,
+) -> Result<Vec<(Option<String>, Fingerprint)>> {
+    let mut input = String::new();
+    reader.read_to_string(&mut input)?;
+
+    let mut fingerprints = Vec::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let line = trim_line(line);
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let entry = line.parse::<Entry>().map_err(|_| Error::LineInvalid {
+            number: index.saturating_add(1),
+        })?;
+
+        let public_key = entry.public_key();
+        let comment = Some(public_key.comment())
+            .filter(|comment| !comment.is_empty())
+            .map(ToString::to_string);
+
+        fingerprints.push((comment, public_key.fingerprint(hash_alg)));
+    }
+
+    Ok(fingerprints)
+}
+
+/// Canonicalize an `authorized_keys` file, producing a deterministic
+/// representation of it.
+///
+/// Comment lines and blank lines are removed, and each remaining entry is
+/// re-emitted with normalized whitespace on its own line. Duplicate options
+/// of each entry are removed, but the options are otherwise kept in their
+/// original order, since `sshd` applies them from left to right (e.g.
+/// `pty,restrict` disables PTY allocation while `restrict,pty` allows it).
+///
+/// Entries for the same key (or certificate) are merged into one, keeping the
+/// first non-empty comment. If their options differ, [`Error::LineInvalid`]
+/// is returned with the (1-based) number of the later line, since `sshd`
+/// would only ever use the first. Entries are then sorted by their encoded
+/// key.
+///
+/// Returns [`Error::LineInvalid`] with the number of the first line which
+/// fails to parse.
+#[cfg(feature = "alloc")]
+pub fn canonicalize(input: &str) -> Result<String> {
+    let mut entries: Vec<(Vec<u8>, Entry)> = Vec::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let line = trim_line(line);
+
+        if line.is_empty() {
+            continue;
+        }
+
+        let line_invalid = || Error::LineInvalid {
+            number: index.saturating_add(1),
+        };
+
+        let mut entry = line.parse::<Entry>().map_err(|_| line_invalid())?;
+        entry.config_opts = entry.config_opts.canonicalize()?;
+
+        let key_blob = match &entry.certificate {
+            Some(certificate) => certificate.to_bytes()?,
+            None => entry.public_key.to_bytes()?,
+        };
+
+        match entries.iter_mut().find(|(blob, _)| *blob == key_blob) {
+            Some((_, existing)) => {
+                if existing.config_opts != entry.config_opts {
+                    return Err(line_invalid());
+                }
+
+                if existing.public_key.comment().is_empty() {
+                    existing.set_comment(entry.public_key.comment());
+                }
+            }
+            None => entries.push((key_blob, entry)),
+        }
+    }
+
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut output = String::new();
+
+    for (_, entry) in entries {
+        output.push_str(&entry.to_string());
+        output.push('\n');
+    }
+
+    Ok(output)
+}
+
+/// Split a line into its (validated) configuration options and the rest of
+/// the line, which contains the key.
+fn split_entry(line: &str) -> Result<(&str, &str)> {
+    let is_key_type =
+        |field: &str| Algorithm::new(field).is_ok() || Algorithm::new_certificate(field).is_ok();
+
+    // Options are present if the first field isn't an algorithm identifier,
+    // or if it's followed by one: options like `name@example.com` are also
+    // valid (unknown) algorithm identifiers, but keys are followed by Base64
+    let (config_opts_str, key_str) = match line.split_once(' ') {
+        Some((field, rest))
+            if !is_key_type(field) || rest.split(' ').next().map_or(false, is_key_type) =>
+        {
+            split_config_opts(line)?
+        }
+        _ => ("", line),
+    };
+
+    ConfigOptsIter(config_opts_str).validate()?;
+    Ok((config_opts_str, key_str))
+}
+
+/// Trim surrounding whitespace from a line, returning an empty string for
+/// comment lines.
+///
+/// As in `sshd`, only lines whose first non-whitespace character is `#` are
+/// comments: elsewhere (e.g. in a quoted option) it's part of the entry.
+pub(crate) fn trim_line(line: &str) -> &str {
+    // Trim whitespace, including any `\r` from CRLF line endings
+    let line = line.trim();
+
+    if line.starts_with(COMMENT_DELIMITER) {
+        ""
+    } else {
+        line
+    }
+}
+
+/// Streaming parser for `authorized_keys` files read from an
+/// [`std::io::BufRead`], e.g. a [`std::io::BufReader`] wrapping a [`fs::File`].
+///
+/// Unlike [`AuthorizedKeys`], the file is never read into memory in full:
+/// lines are read one at a time into a reused buffer. Comments and blank
+/// lines are skipped.
+///
+/// Lines which fail to parse are reported as [`Error::LineInvalid`] with
+/// their (1-based) line number, and iteration continues with the next line.
+/// I/O errors are reported once, after which iteration ends.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct AuthorizedKeysReader<R> {
+    /// Lines of the file being iterated over
+    lines: LineReader<R>,
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> AuthorizedKeysReader<R> {
+    /// Create a new parser which reads from the given reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: LineReader::new(reader),
+        }
+    }
+
+    /// Get the inner reader.
+    pub fn into_inner(self) -> R {
+        self.lines.into_inner()
+    }
+
+    /// Number of the last line which was read, starting from 1.
+    pub fn line_number(&self) -> usize {
+        self.lines.line_number()
+    }
+
+    /// Read the next entry without allocating, as an [`EntryRef`] borrowing
+    /// from this reader's line buffer.
+    pub fn next_ref(&mut self) -> Option<Result<EntryRef<'_>>> {
+        Some(self.lines.next_line()?.and_then(|(number, line)| {
+            EntryRef::parse(line).map_err(|_| Error::LineInvalid { number })
+        }))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> Iterator for AuthorizedKeysReader<R> {
+    type Item = Result<Entry>;
+
+    fn next(&mut self) -> Option<Result<Entry>> {
+        Some(
+            self.lines
+                .next_line()?
+                .and_then(|(number, line)| line.parse().map_err(|_| Error::LineInvalid { number })),
+        )
+    }
+}
+
+/// Borrowed view of a single entry in an `authorized_keys` file, which splits
+/// a line into its fields without allocating.
+///
+/// The configuration options are validated and the key type must be a known
+/// algorithm, but the key itself isn't decoded until [`EntryRef::to_entry`]
+/// is called.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EntryRef<'a> {
+    /// Line containing the entry, with any comment and trailing whitespace
+    /// removed.
+    line: &'a str,
+
+    /// Configuration options field, if present.
+    config_opts: &'a str,
+
+    /// Key type, i.e. the algorithm identifier.
+    key_type: &'a str,
+
+    /// Base64-encoded key or certificate.
+    key_base64: &'a str,
+
+    /// Comment of the key, if present.
+    comment: &'a str,
+}
+
+impl<'a> EntryRef<'a> {
+    /// Parse the given line of an `authorized_keys` file.
+    pub fn parse(line: &'a str) -> Result<Self> {
+        let line = trim_line(line);
+        let (config_opts, key_str) = split_entry(line)?;
+
+        let (key_type, rest) = key_str.split_once(' ').ok_or(Error::FormatEncoding)?;
+        let (key_base64, comment) = rest.split_once(' ').unwrap_or((rest, ""));
+
+        if Algorithm::new(key_type).is_err() && Algorithm::new_certificate(key_type).is_err() {
+            return Err(Error::FormatEncoding);
+        }
+
+        if key_base64.is_empty() {
+            return Err(Error::FormatEncoding);
+        }
+
+        Ok(Self {
+            line,
+            config_opts,
+            key_type,
+            key_base64,
+            comment,
+        })
+    }
+
+    /// Get the line containing this entry, without any comment or trailing
+    /// whitespace.
+    pub fn line(&self) -> &'a str {
+        self.line
+    }
+
+    /// Get an iterator over the configuration options for this entry.
+    pub fn config_opts(&self) -> ConfigOptsIter<'a> {
+        ConfigOptsIter(self.config_opts)
+    }
+
+    /// Get the key type of this entry, e.g. `ssh-ed25519`.
+    pub fn key_type(&self) -> &'a str {
+        self.key_type
+    }
+
+    /// Get the Base64-encoded key (or certificate) of this entry.
+    pub fn key_base64(&self) -> &'a str {
+        self.key_base64
+    }
+
+    /// Get the comment of this entry's key, which may be empty.
+    pub fn comment(&self) -> &'a str {
+        self.comment
+    }
+
+    /// Decode this entry's key and options into an owned [`Entry`].
+    pub fn to_entry(&self) -> Result<Entry> {
+        self.line.parse()
+    }
+}
+
+/// Owned collection of [`Entry`] values which can be serialized as an
+/// `authorized_keys` file.
+///
+/// The [`fmt::Display`] impl emits one entry per line (each terminated with
+/// a newline) in the order the entries were added.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AuthorizedKeysFile {
+    /// Entries in the file.
+    entries: Vec<Entry>,
+}
+
+#[cfg(feature = "alloc")]
+impl AuthorizedKeysFile {
+    /// Create a new empty `authorized_keys` file.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an entry to the end of this file.
+    pub fn push(&mut self, entry: impl Into<Entry>) {
+        self.entries.push(entry.into());
+    }
+
+    /// Get the entries in this file.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Iterate over the entries in this file.
+    pub fn iter(&self) -> slice::Iter<'_, Entry> {
+        self.entries.iter()
+    }
+
+    /// Number of entries in this file.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Does this file contain no entries?
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Vec<Entry>> for AuthorizedKeysFile {
+    fn from(entries: Vec<Entry>) -> AuthorizedKeysFile {
+        AuthorizedKeysFile { entries }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<AuthorizedKeysFile> for Vec<Entry> {
+    fn from(file: AuthorizedKeysFile) -> Vec<Entry> {
+        file.entries
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Into<Entry>> Extend<T> for AuthorizedKeysFile {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.entries.extend(iter.into_iter().map(Into::into));
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: Into<Entry>> FromIterator<T> for AuthorizedKeysFile {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut file = Self::new();
+        file.extend(iter);
+        file
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl IntoIterator for AuthorizedKeysFile {
+    type Item = Entry;
+    type IntoIter = vec::IntoIter<Entry>;
+
+    fn into_iter(self) -> vec::IntoIter<Entry> {
+        self.entries.into_iter()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> IntoIterator for &'a AuthorizedKeysFile {
+    type Item = &'a Entry;
+    type IntoIter = slice::Iter<'a, Entry>;
+
+    fn into_iter(self) -> slice::Iter<'a, Entry> {
+        self.iter()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl str::FromStr for AuthorizedKeysFile {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        AuthorizedKeys::new(s)
+            .collect::<Result<Vec<_>>>()
+            .map(Into::into)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for AuthorizedKeysFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.entries {
+            writeln!(f, "{entry}")?;
+        }
+
+        Ok(())
+    }
+}
+
 /// Individual entry in an `authorized_keys` file containing a single public key.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Entry {
@@ -95,19 +496,64 @@ pub struct Entry {
 
     /// Public key
     public_key: PublicKey,
+
+    /// Certificate, if this entry contains one rather than a bare public key.
+    #[cfg(feature = "alloc")]
+    certificate: Option<Certificate>,
 }
 
 impl Entry {
+    /// Create a new entry from the given configuration options and public key.
+    #[cfg(feature = "alloc")]
+    pub fn new(config_opts: ConfigOpts, public_key: PublicKey) -> Self {
+        Self {
+            config_opts,
+            public_key,
+            certificate: None,
+        }
+    }
+
     /// Get configuration options for this entry.
     #[cfg(feature = "alloc")]
     pub fn config_opts(&self) -> &ConfigOpts {
         &self.config_opts
     }
 
+    /// Set the configuration options for this entry, e.g. to a modified
+    /// version of its [`Options`].
+    #[cfg(feature = "alloc")]
+    pub fn set_config_opts(&mut self, config_opts: ConfigOpts) {
+        self.config_opts = config_opts;
+    }
+
+    /// Parse the configuration options for this entry into [`Options`].
+    #[cfg(feature = "alloc")]
+    pub fn options(&self) -> Result<Options> {
+        self.config_opts.options()
+    }
+
     /// Get public key for this entry.
+    ///
+    /// For certificate entries, this is the public key the certificate is for.
     pub fn public_key(&self) -> &PublicKey {
         &self.public_key
     }
+
+    /// Get the certificate for this entry, if it contains one.
+    #[cfg(feature = "alloc")]
+    pub fn certificate(&self) -> Option<&Certificate> {
+        self.certificate.as_ref()
+    }
+
+    /// Set the comment on this entry's public key (or certificate).
+    #[cfg(feature = "alloc")]
+    fn set_comment(&mut self, comment: &str) {
+        self.public_key.set_comment(comment);
+
+        if let Some(certificate) = &mut self.certificate {
+            certificate.set_comment(comment);
+        }
+    }
 }
 
 #[cfg(feature = "alloc")]
@@ -129,6 +575,19 @@ impl From<PublicKey> for Entry {
             #[cfg(feature = "alloc")]
             config_opts: ConfigOpts::default(),
             public_key,
+            #[cfg(feature = "alloc")]
+            certificate: None,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Certificate> for Entry {
+    fn from(certificate: Certificate) -> Entry {
+        Entry {
+            config_opts: ConfigOpts::default(),
+            public_key: PublicKey::new(certificate.public_key().clone(), certificate.comment()),
+            certificate: Some(certificate),
         }
     }
 }
@@ -137,42 +596,48 @@ impl str::FromStr for Entry {
     type Err = Error;
 
     fn from_str(line: &str) -> Result<Self> {
-        // TODO(tarcieri): more liberal whitespace handling?
-        match line.matches(' ').count() {
-            1..=2 => Ok(Self {
-                #[cfg(feature = "alloc")]
-                config_opts: Default::default(),
-                public_key: line.parse()?,
-            }),
-            3 => line
-                .split_once(' ')
-                .map(|(config_opts_str, public_key_str)| {
-                    ConfigOptsIter(config_opts_str).validate()?;
-
-                    Ok(Self {
-                        #[cfg(feature = "alloc")]
-                        config_opts: ConfigOpts(config_opts_str.to_string()),
-                        public_key: public_key_str.parse()?,
-                    })
-                })
-                .ok_or(Error::FormatEncoding)?,
-            _ => Err(Error::FormatEncoding),
-        }
+        #[cfg_attr(not(feature = "alloc"), allow(unused_variables))]
+        let (config_opts_str, key_str) = split_entry(line)?;
+
+        // Certificates are identified by their `*-cert-v01@openssh.com` algorithm
+        #[cfg(feature = "alloc")]
+        let certificate = match key_str.split_once(' ') {
+            Some((algorithm_id, _)) if Algorithm::new_certificate(algorithm_id).is_ok() => {
+                Some(key_str.parse::<Certificate>()?)
+            }
+            _ => None,
+        };
+
+        #[cfg(feature = "alloc")]
+        let public_key = match &certificate {
+            Some(cert) => PublicKey::new(cert.public_key().clone(), cert.comment()),
+            None => key_str.parse()?,
+        };
+
+        #[cfg(not(feature = "alloc"))]
+        let public_key = key_str.parse()?;
+
+        Ok(Self {
+            #[cfg(feature = "alloc")]
+            config_opts: ConfigOpts(config_opts_str.to_string()),
+            public_key,
+            #[cfg(feature = "alloc")]
+            certificate,
+        })
     }
 }
 
 #[cfg(feature = "alloc")]
-impl ToString for Entry {
-    fn to_string(&self) -> String {
-        let mut s = String::new();
-
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if !self.config_opts.is_empty() {
-            s.push_str(self.config_opts.as_str());
-            s.push(' ');
+            write!(f, "{} ", self.config_opts.as_str())?;
         }
 
-        s.push_str(&self.public_key.to_string());
-        s
+        match &self.certificate {
+            Some(certificate) => write!(f, "{certificate}"),
+            None => write!(f, "{}", self.public_key),
+        }
     }
 }
 
@@ -210,6 +675,28 @@ impl ConfigOpts {
     pub fn iter(&self) -> ConfigOptsIter<'_> {
         ConfigOptsIter(self.as_str())
     }
+
+    /// Parse the options into their typed [`Options`] representation.
+    pub fn options(&self) -> Result<Options> {
+        self.as_str().parse()
+    }
+
+    /// Remove duplicate options, keeping the order of the remaining options.
+    ///
+    /// The last occurrence of each option is kept, since options are applied
+    /// from left to right and it's the one which takes effect: for example
+    /// `pty,restrict,pty` becomes `restrict,pty`.
+    fn canonicalize(&self) -> Result<Self> {
+        let opts = self.iter().collect::<Vec<_>>();
+        let deduped = opts
+            .iter()
+            .enumerate()
+            .filter(|(i, opt)| !opts[i.saturating_add(1)..].contains(opt))
+            .map(|(_, opt)| *opt)
+            .collect::<Vec<_>>();
+
+        Self::new(deduped.join(","))
+    }
 }
 
 #[cfg(feature = "alloc")]
@@ -235,6 +722,352 @@ impl fmt::Display for ConfigOpts {
     }
 }
 
+#[cfg(feature = "alloc")]
+impl TryFrom<&Options> for ConfigOpts {
+    type Error = Error;
+
+    fn try_from(options: &Options) -> Result<ConfigOpts> {
+        ConfigOpts::new(options.to_string())
+    }
+}
+
+/// Typed representation of the configuration options of an entry, as
+/// described in the `AUTHORIZED_KEYS FILE FORMAT` section of `sshd(8)`.
+///
+/// Quoted option values are unescaped when parsing (`\"` and `\\` stand for
+/// a quote and a backslash), and quoted (and escaped) again when serializing
+/// using the [`fmt::Display`] impl, or converting back to [`ConfigOpts`].
+/// Serialization is canonical: `restrict` comes first, followed by the
+/// remaining flags and then the options which take values, in the order of
+/// the fields below.
+///
+/// Flags which can be negated with a `no-` prefix are represented as
+/// `Some(true)` if enabled, `Some(false)` if disabled, or `None` if not
+/// given. Since `restrict` disables all of them, `restrict` discards any such
+/// flags which precede it, as `sshd` would.
+///
+/// ```
+/// # fn main() -> Result<(), ssh_key::Error> {
+/// use ssh_key::authorized_keys::{ConfigOpts, Options};
+///
+/// let mut options: Options = "command=\"echo \\\"hello\\\"\",no-pty".parse()?;
+/// assert_eq!(options.command.as_deref(), Some("echo \"hello\""));
+/// assert_eq!(options.pty, Some(false));
+///
+/// // Enforce a policy
+/// options.restrict = true;
+/// options.pty = None;
+/// assert_eq!(
+///     ConfigOpts::try_from(&options)?.as_str(),
+///     "restrict,command=\"echo \\\"hello\\\"\""
+/// );
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct Options {
+    /// `restrict`: disable all forwarding, PTY allocation and
+    /// `~/.ssh/rc`, unless re-enabled by other options.
+    pub restrict: bool,
+
+    /// `cert-authority`: the key is trusted as a certificate authority.
+    pub cert_authority: bool,
+
+    /// `agent-forwarding` or `no-agent-forwarding`.
+    pub agent_forwarding: Option<bool>,
+
+    /// `port-forwarding` or `no-port-forwarding`.
+    pub port_forwarding: Option<bool>,
+
+    /// `pty` or `no-pty`.
+    pub pty: Option<bool>,
+
+    /// `user-rc` or `no-user-rc`.
+    pub user_rc: Option<bool>,
+
+    /// `X11-forwarding` or `no-X11-forwarding`.
+    pub x11_forwarding: Option<bool>,
+
+    /// `no-touch-required`: don't require FIDO/U2F Security Key signatures
+    /// to assert user presence.
+    pub no_touch_required: bool,
+
+    /// `verify-required`: require FIDO/U2F Security Key signatures to
+    /// assert user verification (e.g. a PIN).
+    pub verify_required: bool,
+
+    /// `command="..."`: command executed whenever the key is used.
+    pub command: Option<String>,
+
+    /// `environment="NAME=value"`: environment variables to set, which may
+    /// be given multiple times.
+    pub environment: Vec<String>,
+
+    /// `expiry-time="timespec"`: time after which the key is no longer
+    /// accepted, formatted as `YYYYMMDD[Z]` or `YYYYMMDDHHMM[SS][Z]`.
+    pub expiry_time: Option<String>,
+
+    /// `from="pattern-list"`: patterns which the client's host name or
+    /// address must match.
+    pub from: Vec<String>,
+
+    /// `permitlisten="[host:]port"`: remote port forwarding destinations,
+    /// which may be given multiple times.
+    pub permitlisten: Vec<String>,
+
+    /// `permitopen="host:port"`: local port forwarding destinations, which
+    /// may be given multiple times.
+    pub permitopen: Vec<String>,
+
+    /// `principals="principals"`: principals accepted in certificates
+    /// signed by a `cert-authority`.
+    pub principals: Vec<String>,
+
+    /// `tunnel="n"`: `tun` device to use for tunnel forwarding.
+    pub tunnel: Option<String>,
+
+    /// Unrecognized options, which are preserved verbatim.
+    pub other: Vec<String>,
+}
+
+#[cfg(feature = "alloc")]
+impl Options {
+    /// Parse a single option into these options.
+    fn parse_opt(&mut self, opt: &str) -> Result<()> {
+        let (name, value) = match opt.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (opt, None),
+        };
+
+        let name = name.to_ascii_lowercase();
+
+        let value = match value {
+            Some(value) => value,
+            None => {
+                let (flag, enabled) = match name.strip_prefix("no-") {
+                    Some(flag) => (flag, false),
+                    None => (name.as_str(), true),
+                };
+
+                match (flag, enabled) {
+                    ("restrict", true) => {
+                        // `restrict` overrides any preceding flags
+                        self.restrict = true;
+                        self.agent_forwarding = None;
+                        self.port_forwarding = None;
+                        self.pty = None;
+                        self.user_rc = None;
+                        self.x11_forwarding = None;
+                    }
+                    ("cert-authority", true) => self.cert_authority = true,
+                    ("agent-forwarding", _) => self.agent_forwarding = Some(enabled),
+                    ("port-forwarding", _) => self.port_forwarding = Some(enabled),
+                    ("pty", _) => self.pty = Some(enabled),
+                    ("user-rc", _) => self.user_rc = Some(enabled),
+                    ("x11-forwarding", _) => self.x11_forwarding = Some(enabled),
+                    ("touch-required", _) => self.no_touch_required = !enabled,
+                    ("verify-required", true) => self.verify_required = true,
+                    _ => self.other.push(opt.to_string()),
+                }
+
+                return Ok(());
+            }
+        };
+
+        let value = unquote(value)?;
+
+        // Options which can only be given once
+        let single = match name.as_str() {
+            "command" => &mut self.command,
+            "expiry-time" => &mut self.expiry_time,
+            "tunnel" => &mut self.tunnel,
+            "environment" => {
+                self.environment.push(value);
+                return Ok(());
+            }
+            "permitlisten" => {
+                self.permitlisten.push(value);
+                return Ok(());
+            }
+            "permitopen" => {
+                self.permitopen.push(value);
+                return Ok(());
+            }
+            "from" | "principals" => {
+                let list = if name == "from" {
+                    &mut self.from
+                } else {
+                    &mut self.principals
+                };
+
+                if !list.is_empty() {
+                    return Err(Error::FormatEncoding);
+                }
+
+                *list = value.split(',').map(ToString::to_string).collect();
+                return Ok(());
+            }
+            _ => {
+                self.other.push(opt.to_string());
+                return Ok(());
+            }
+        };
+
+        if single.replace(value).is_some() {
+            return Err(Error::FormatEncoding);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl str::FromStr for Options {
+    type Err = Error;
+
+    /// Parse options from a comma-separated options string.
+    ///
+    /// Returns [`Error::FormatEncoding`] if an option which can only be given
+    /// once (e.g. `command`) is repeated, or a quoted value is malformed.
+    fn from_str(s: &str) -> Result<Self> {
+        let mut options = Self::default();
+
+        for opt in ConfigOptsIter::new(s)? {
+            options.parse_opt(opt)?;
+        }
+
+        Ok(options)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Display for Options {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut opts = Vec::new();
+
+        let flags = [
+            ("restrict", self.restrict.then_some(true)),
+            ("cert-authority", self.cert_authority.then_some(true)),
+            ("agent-forwarding", self.agent_forwarding),
+            ("port-forwarding", self.port_forwarding),
+            ("pty", self.pty),
+            ("user-rc", self.user_rc),
+            ("X11-forwarding", self.x11_forwarding),
+            ("touch-required", self.no_touch_required.then_some(false)),
+            ("verify-required", self.verify_required.then_some(true)),
+        ];
+
+        for (flag, enabled) in flags {
+            match enabled {
+                Some(true) => opts.push(flag.to_string()),
+                Some(false) => opts.push(["no-", flag].concat()),
+                None => (),
+            }
+        }
+
+        let values = [
+            (
+                "command",
+                self.command.as_ref().map_or(&[][..], slice::from_ref),
+            ),
+            ("environment", &self.environment),
+            (
+                "expiry-time",
+                self.expiry_time.as_ref().map_or(&[][..], slice::from_ref),
+            ),
+            ("permitlisten", &self.permitlisten),
+            ("permitopen", &self.permitopen),
+            (
+                "tunnel",
+                self.tunnel.as_ref().map_or(&[][..], slice::from_ref),
+            ),
+        ];
+
+        for (name, values) in values {
+            opts.extend(values.iter().map(|value| quote(name, value)));
+        }
+
+        for (name, list) in [("from", &self.from), ("principals", &self.principals)] {
+            if !list.is_empty() {
+                opts.push(quote(name, &list.join(",")));
+            }
+        }
+
+        opts.extend(self.other.iter().cloned());
+        f.write_str(&opts.join(","))
+    }
+}
+
+/// Format an option with the given quoted value, escaping any quotes and
+/// backslashes.
+#[cfg(feature = "alloc")]
+fn quote(name: &str, value: &str) -> String {
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+    [name, "=\"", &escaped, "\""].concat()
+}
+
+/// Unquote an option value (if quoted), unescaping any quotes and
+/// backslashes.
+///
+/// Backslashes which don't precede a quote or another backslash are kept.
+#[cfg(feature = "alloc")]
+fn unquote(value: &str) -> Result<String> {
+    if !value.starts_with('"') {
+        return Ok(value.to_string());
+    }
+
+    let inner = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .ok_or(Error::FormatEncoding)?;
+
+    let mut unquoted = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unquoted.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some(c @ ('"' | '\\')) => unquoted.push(c),
+            Some(c) => {
+                unquoted.push('\\');
+                unquoted.push(c);
+            }
+            // The closing quote is escaped
+            None => return Err(Error::FormatEncoding),
+        }
+    }
+
+    Ok(unquoted)
+}
+
+/// Split an entry into its options and the remainder of the line, i.e. at
+/// the first space outside of a quoted option value.
+fn split_config_opts(line: &str) -> Result<(&str, &str)> {
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for (index, byte) in line.bytes().enumerate() {
+        match byte {
+            b'"' if !escaped => quoted = !quoted,
+            b' ' if !quoted => {
+                let (config_opts, rest) = line.split_at(index);
+                return Ok((config_opts, rest.trim_start_matches(' ')));
+            }
+            _ => (),
+        }
+
+        escaped = quoted && !escaped && byte == b'\\';
+    }
+
+    Err(Error::FormatEncoding)
+}
+
 /// Iterator over configuration options.
 #[derive(Clone, Debug)]
 pub struct ConfigOptsIter<'a>(&'a str);
@@ -262,10 +1095,13 @@ impl<'a> ConfigOptsIter<'a> {
         }
 
         let mut quoted = false;
+        let mut escaped = false;
         let mut index = 0;
 
         while let Some(byte) = self.0.as_bytes().get(index).cloned() {
             match byte {
+                // Escaped quotes inside quoted text don't end it
+                b'"' if escaped => (),
                 b',' => {
                     // Commas inside quoted text are ignored
                     if !quoted {
@@ -279,6 +1115,8 @@ impl<'a> ConfigOptsIter<'a> {
                     // Toggle quoted mode on-off
                     quoted = !quoted;
                 }
+                // Spaces are only allowed inside quoted text
+                b' ' if quoted => (),
                 // Valid characters
                 b'A'..=b'Z'
                 | b'a'..=b'z'
@@ -293,6 +1131,7 @@ impl<'a> ConfigOptsIter<'a> {
                 _ => return Err(encoding::Error::CharacterEncoding.into()),
             }
 
+            escaped = quoted && !escaped && byte == b'\\';
             index = index.checked_add(1).ok_or(encoding::Error::Length)?;
         }
 
@@ -355,6 +1194,21 @@ mod tests {
         assert_eq!(opts.try_next(), Ok(None));
     }
 
+    #[test]
+    fn options_quoted_spaces_and_escapes() {
+        let mut opts = ConfigOptsIter("command=\"echo \\\"a, b\\\"\",no-pty");
+        assert_eq!(opts.try_next(), Ok(Some("command=\"echo \\\"a, b\\\"\"")));
+        assert_eq!(opts.try_next(), Ok(Some("no-pty")));
+        assert_eq!(opts.try_next(), Ok(None));
+
+        // Spaces outside quoted text are invalid
+        let mut opts = ConfigOptsIter("no-pty no-user-rc");
+        assert_eq!(
+            opts.try_next(),
+            Err(encoding::Error::CharacterEncoding.into())
+        );
+    }
+
     #[test]
     fn options_invalid_character() {
         let mut opts = ConfigOptsIter("❌");
@@ -4,21 +4,34 @@ mod builder;
 mod cert_type;
 mod field;
 mod options_map;
+mod principal_error;
+mod revocation_list;
 mod unix_time;
+mod validator;
 
-pub use self::{builder::Builder, cert_type::CertType, field::Field, options_map::OptionsMap};
+#[cfg(feature = "std")]
+mod source_address;
+
+pub use self::{
+    builder::Builder,
+    cert_type::CertType,
+    field::Field,
+    options_map::OptionsMap,
+    principal_error::PrincipalError,
+    revocation_list::RevocationList,
+    validator::{ValidationError, Validator},
+};
+
+#[cfg(feature = "std")]
+pub use self::source_address::SourceAddress;
 
 use self::unix_time::UnixTime;
 use crate::{
     public::{KeyData, SshFormat},
-    Algorithm, Error, Fingerprint, HashAlg, Result, Signature,
+    Algorithm, Error, Fingerprint, HashAlg, PublicKey, Result, Signature, VerificationConfig,
 };
-use alloc::{
-    borrow::ToOwned,
-    string::{String, ToString},
-    vec::Vec,
-};
-use core::str::FromStr;
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use core::{fmt, str::FromStr};
 use encoding::{Base64Reader, CheckedSum, Decode, Encode, Reader, Writer};
 use signature::Verifier;
 
@@ -57,8 +70,9 @@ use std::{fs, path::Path, time::SystemTime};
 /// - All critical extensions to the certificate are recognized and validate
 ///   successfully.
 ///
-/// The [`Certificate::validate`] and [`Certificate::validate_at`] methods can
-/// be used to validate a certificate.
+/// The [`Validator`] performs all of these checks.
+/// The lower-level [`Certificate::validate`] and [`Certificate::validate_at`]
+/// methods only check the CA, signature and validity window.
 ///
 /// ## Example
 ///
@@ -88,7 +102,7 @@ use std::{fs, path::Path, time::SystemTime};
 ///
 /// // Check that the certificate includes the expected principal name
 /// // (i.e. username or hostname)
-/// // if !certificate.principals().contains(expected_principal) { return Err(...) }
+/// // certificate.check_principal(expected_principal)?;
 ///
 /// // Check that all of the critical extensions are recognized
 /// // if !certificate.critical_options.iter().all(|critical| ...) { return Err(...) }
@@ -117,6 +131,9 @@ use std::{fs, path::Path, time::SystemTime};
 /// [PROTOCOL.certkeys]: https://cvsweb.openbsd.org/src/usr.bin/ssh/PROTOCOL.certkeys?annotate=HEAD
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 pub struct Certificate {
+    /// Certificate algorithm (i.e. the certificate type name).
+    algorithm: Algorithm,
+
     /// CA-provided random bitstring of arbitrary length
     /// (but typically 16 or 32 bytes).
     nonce: Vec<u8>,
@@ -162,6 +179,11 @@ pub struct Certificate {
 }
 
 impl Certificate {
+    /// Maximum number of certificates in a chain accepted by
+    /// [`Certificate::verify_chain_at`], including the end-entity
+    /// certificate.
+    pub const MAX_CHAIN_DEPTH: usize = 8;
+
     /// Parse an OpenSSH-formatted certificate.
     ///
     /// OpenSSH-formatted certificates look like the following
@@ -219,8 +241,14 @@ impl Certificate {
     }
 
     /// Get the public key algorithm for this certificate.
+    ///
+    /// For RSA certificates this includes the hash of the certificate type
+    /// name, i.e. `rsa-sha2-256-cert-v01@openssh.com` and
+    /// `rsa-sha2-512-cert-v01@openssh.com` are decoded as
+    /// [`Algorithm::Rsa`] with a [`HashAlg`] of SHA-256 and SHA-512
+    /// respectively.
     pub fn algorithm(&self) -> Algorithm {
-        self.public_key.algorithm()
+        self.algorithm.clone()
     }
 
     /// Get the comment on this certificate.
@@ -228,6 +256,11 @@ impl Certificate {
         self.comment.as_str()
     }
 
+    /// Set the comment on this certificate.
+    pub fn set_comment(&mut self, comment: impl Into<String>) {
+        self.comment = comment.into();
+    }
+
     /// Nonces are a CA-provided random bitstring of arbitrary length
     /// (but typically 16 or 32 bytes).
     ///
@@ -277,12 +310,66 @@ impl Certificate {
         &self.valid_principals
     }
 
+    /// Is this certificate valid for the given principal?
+    ///
+    /// A host certificate with an empty "valid principals" field is valid for
+    /// any hostname, but such user certificates are valid for no one, as
+    /// `sshd` refuses them. See [`Certificate::check_principal`] for the
+    /// reason a principal is rejected.
+    ///
+    /// For host certificates, principals may contain `*` and `?` wildcards
+    /// (e.g. `*.example.com`) which are matched against `principal` as in
+    /// OpenSSH. Hostnames are compared case-sensitively, so they should be
+    /// lowercased first. User certificate principals must match exactly.
+    pub fn is_valid_for_principal(&self, principal: &str) -> bool {
+        if self.valid_principals.is_empty() {
+            return self.cert_type.is_host();
+        }
+
+        self.valid_principals.iter().any(|valid| {
+            if self.cert_type.is_host() {
+                match_pattern(principal, valid)
+            } else {
+                valid == principal
+            }
+        })
+    }
+
+    /// Check this certificate authorizes the given principal (i.e. username
+    /// for user certificates, hostname for host certificates), following the
+    /// rules `sshd` and `ssh` apply when no other principal checks are
+    /// configured.
+    ///
+    /// User certificates with an empty "valid principals" field are
+    /// rejected, whereas such host certificates are valid for any hostname.
+    /// Host certificate principals may contain wildcards, and user
+    /// certificate principals must match exactly.
+    pub fn check_principal(&self, principal: &str) -> core::result::Result<(), PrincipalError> {
+        if self.valid_principals.is_empty() {
+            return if self.cert_type.is_host() {
+                Ok(())
+            } else {
+                Err(PrincipalError::Empty)
+            };
+        }
+
+        if self.is_valid_for_principal(principal) {
+            Ok(())
+        } else if self.cert_type.is_host() {
+            Err(PrincipalError::PatternMismatch)
+        } else {
+            Err(PrincipalError::Mismatch)
+        }
+    }
+
     /// Valid after (Unix time).
     pub fn valid_after(&self) -> u64 {
         self.valid_after.into()
     }
 
     /// Valid before (Unix time).
+    ///
+    /// This is `u64::MAX` for certificates which never expire.
     pub fn valid_before(&self) -> u64 {
         self.valid_before.into()
     }
@@ -294,6 +381,8 @@ impl Certificate {
     }
 
     /// Valid before (system time).
+    ///
+    /// For certificates which never expire, this is 9999-12-31 23:59:59 UTC.
     #[cfg(feature = "std")]
     pub fn valid_before_time(&self) -> SystemTime {
         self.valid_before.into()
@@ -311,6 +400,25 @@ impl Certificate {
         &self.critical_options
     }
 
+    /// Parse the `source-address` critical option, if present.
+    ///
+    /// Returns [`Error::CertificateFieldInvalid`] if the option is malformed.
+    #[cfg(feature = "std")]
+    pub fn source_address(&self) -> Result<Option<SourceAddress>> {
+        let data = match self.critical_options.get(SourceAddress::OPTION_NAME) {
+            Some(data) => data,
+            None => return Ok(None),
+        };
+
+        // OpenSSH encodes option data as a string nested within the data field
+        let mut reader = data.as_bytes();
+        let value = String::decode(&mut reader)
+            .and_then(|value| reader.finish(value))
+            .map_err(|_| Field::CriticalOptions.invalid_error())?;
+
+        value.parse().map(Some)
+    }
+
     /// The extensions section of the certificate specifies zero or more
     /// non-critical certificate extensions.
     ///
@@ -325,12 +433,54 @@ impl Certificate {
         &self.signature_key
     }
 
+    /// Public key of the signing CA, e.g. for building trust chains.
+    ///
+    /// Certificates don't record a comment for the CA key, so the returned
+    /// key's comment is empty.
+    pub fn ca_key(&self) -> PublicKey {
+        self.signature_key.clone().into()
+    }
+
     /// Signature computed over all preceding fields from the initial string up
     /// to, and including the signature key.
     pub fn signature(&self) -> &Signature {
         &self.signature
     }
 
+    /// Compute the fingerprint of the subject public key in this certificate.
+    ///
+    /// This identifies the key, and is the same for every certificate issued
+    /// for it.
+    pub fn key_fingerprint(&self, hash_alg: HashAlg) -> Fingerprint {
+        self.public_key.fingerprint(hash_alg)
+    }
+
+    /// Compute a fingerprint of the entire certificate, i.e. a hash of its
+    /// binary encoding.
+    ///
+    /// This identifies this specific certificate, e.g. for revocation.
+    pub fn fingerprint(&self, hash_alg: HashAlg) -> Fingerprint {
+        Fingerprint::digest(hash_alg, self)
+    }
+
+    /// Are these certificates equivalent aside from the fields which differ
+    /// each time a certificate is (re-)issued?
+    ///
+    /// Compares the subject public key, certificate type, valid principals,
+    /// validity window, critical options and extensions. The nonce, serial
+    /// number, key ID, CA key, signature and comment are ignored.
+    ///
+    /// This does not validate either certificate.
+    pub fn semantically_eq(&self, other: &Self) -> bool {
+        self.public_key == other.public_key
+            && self.cert_type == other.cert_type
+            && self.valid_principals == other.valid_principals
+            && self.valid_after == other.valid_after
+            && self.valid_before == other.valid_before
+            && self.critical_options == other.critical_options
+            && self.extensions == other.extensions
+    }
+
     /// Perform certificate validation using the system clock to check that
     /// the current time is within the certificate's validity window.
     ///
@@ -379,7 +529,123 @@ impl Certificate {
         I: IntoIterator<Item = &'a Fingerprint>,
     {
         self.verify_signature()?;
+        self.validate_ca_and_validity(unix_timestamp, ca_fingerprints)
+    }
+
+    /// Perform certificate validation using the system clock, verifying the
+    /// CA signature according to the given [`VerificationConfig`].
+    ///
+    /// See [`Certificate::validate_at_with`].
+    #[cfg(feature = "std")]
+    pub fn validate_with<'a, I>(
+        &self,
+        ca_fingerprints: I,
+        config: &VerificationConfig,
+    ) -> Result<()>
+    where
+        I: IntoIterator<Item = &'a Fingerprint>,
+    {
+        self.validate_at_with(UnixTime::now()?.into(), ca_fingerprints, config)
+    }
 
+    /// Perform certificate validation as in [`Certificate::validate_at`],
+    /// verifying the CA signature according to the given
+    /// [`VerificationConfig`], e.g. to reject CAs using SHA-1 or small RSA
+    /// keys.
+    ///
+    /// The configuration applies to the CA signature only, not to the
+    /// certificate's subject public key.
+    ///
+    /// ## Returns
+    /// - `Ok` if the certificate validated successfully
+    /// - `Error::CertificateValidation` if the certificate failed to validate,
+    ///   including if the CA signature is not allowed by `config`
+    pub fn validate_at_with<'a, I>(
+        &self,
+        unix_timestamp: u64,
+        ca_fingerprints: I,
+        config: &VerificationConfig,
+    ) -> Result<()>
+    where
+        I: IntoIterator<Item = &'a Fingerprint>,
+    {
+        let mut tbs_certificate = Vec::new();
+        self.encode_tbs(&mut tbs_certificate)?;
+        self.signature_key
+            .verify_with(&tbs_certificate, &self.signature, config)
+            .map_err(|_| Error::CertificateValidation)?;
+
+        self.validate_ca_and_validity(unix_timestamp, ca_fingerprints)
+    }
+
+    /// Validate a chain of certificates using the system clock.
+    ///
+    /// See [`Certificate::verify_chain_at`].
+    #[cfg(feature = "std")]
+    pub fn verify_chain(
+        &self,
+        intermediates: &[Certificate],
+        trusted_ca: &Fingerprint,
+    ) -> Result<()> {
+        self.verify_chain_at(UnixTime::now()?.into(), intermediates, trusted_ca)
+    }
+
+    /// Validate a chain of certificates, for deployments which use
+    /// intermediate certificate authorities (CAs).
+    ///
+    /// Starting with this certificate, each link's signature is verified and
+    /// the timestamp checked against its validity window. If the key which
+    /// signed a link matches `trusted_ca` validation succeeds, and otherwise
+    /// the link's issuer is looked up in `intermediates` by its subject public
+    /// key and validated in turn.
+    ///
+    /// Chains longer than [`Certificate::MAX_CHAIN_DEPTH`], which includes
+    /// circular chains, are rejected.
+    ///
+    /// NOTE: only SHA-256 fingerprints are supported at this time.
+    ///
+    /// # ⚠️ Security Warning: Some Assembly Required
+    ///
+    /// As with [`Certificate::validate_at`], the principals and critical
+    /// options of the certificates still need to be checked. OpenSSH
+    /// certificates have no notion of a CA constraint, so any certificate in
+    /// `intermediates` is trusted to issue certificates.
+    ///
+    /// ## Returns
+    /// - `Ok` if the chain validated successfully
+    /// - `Error::CertificateValidation` if any link failed to validate, an
+    ///   issuer couldn't be found, or the chain is too long
+    pub fn verify_chain_at(
+        &self,
+        unix_timestamp: u64,
+        intermediates: &[Certificate],
+        trusted_ca: &Fingerprint,
+    ) -> Result<()> {
+        let mut cert = self;
+
+        for _ in 0..Self::MAX_CHAIN_DEPTH {
+            cert.verify_signature()?;
+            cert.check_validity_at(unix_timestamp)?;
+
+            if &cert.signature_key.fingerprint(HashAlg::Sha256) == trusted_ca {
+                return Ok(());
+            }
+
+            cert = intermediates
+                .iter()
+                .find(|issuer| issuer.public_key == cert.signature_key)
+                .ok_or(Error::CertificateValidation)?;
+        }
+
+        Err(Error::CertificateValidation)
+    }
+
+    /// Check the CA is trusted and the timestamp is within the certificate's
+    /// validity window, after the signature has been verified.
+    fn validate_ca_and_validity<'a, I>(&self, unix_timestamp: u64, ca_fingerprints: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'a Fingerprint>,
+    {
         // TODO(tarcieri): support non SHA-256 public key fingerprints?
         let cert_fingerprint = self.signature_key.fingerprint(HashAlg::Sha256);
 
@@ -387,6 +653,11 @@ impl Certificate {
             return Err(Error::CertificateValidation);
         }
 
+        self.check_validity_at(unix_timestamp)
+    }
+
+    /// Check the timestamp is within the certificate's validity window.
+    fn check_validity_at(&self, unix_timestamp: u64) -> Result<()> {
         let unix_timestamp = UnixTime::new(unix_timestamp)?;
 
         // From PROTOCOL.certkeys:
@@ -451,7 +722,13 @@ impl Decode for Certificate {
     fn decode(reader: &mut impl Reader) -> Result<Self> {
         let algorithm = Algorithm::new_certificate(&String::decode(reader)?)?;
 
+        // Opaque key data would consume the rest of the certificate
+        if matches!(algorithm, Algorithm::Other(_)) {
+            return Err(Error::AlgorithmUnknown);
+        }
+
         Ok(Self {
+            algorithm: algorithm.clone(),
             nonce: Vec::decode(reader)?,
             public_key: KeyData::decode_as(reader, algorithm)?,
             serial: u64::decode(reader)?,
@@ -507,9 +784,9 @@ impl FromStr for Certificate {
     }
 }
 
-impl ToString for Certificate {
-    fn to_string(&self) -> String {
-        self.to_openssh().expect("SSH certificate encoding error")
+impl fmt::Display for Certificate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_openssh().map_err(|_| fmt::Error)?)
     }
 }
 
@@ -546,3 +823,39 @@ impl Serialize for Certificate {
         }
     }
 }
+
+/// Match a string against a glob-style pattern in which `*` matches zero or
+/// more characters and `?` matches exactly one, as in OpenSSH's
+/// `match_pattern`.
+pub(crate) fn match_pattern(s: &str, pattern: &str) -> bool {
+    let (s, pattern) = (s.as_bytes(), pattern.as_bytes());
+    let (mut s_pos, mut pattern_pos) = (0usize, 0usize);
+
+    // Position of the last `*` in the pattern and the input it was tried at
+    let mut backtrack = None;
+
+    while s_pos < s.len() {
+        match pattern.get(pattern_pos) {
+            Some(b'*') => {
+                backtrack = Some((pattern_pos, s_pos));
+                pattern_pos = pattern_pos.saturating_add(1);
+            }
+            Some(&c) if c == b'?' || c == s[s_pos] => {
+                s_pos = s_pos.saturating_add(1);
+                pattern_pos = pattern_pos.saturating_add(1);
+            }
+            _ => match backtrack {
+                // Let the last `*` consume one more character and retry
+                Some((star_pos, star_s_pos)) => {
+                    let star_s_pos = star_s_pos.saturating_add(1);
+                    backtrack = Some((star_pos, star_s_pos));
+                    pattern_pos = star_pos.saturating_add(1);
+                    s_pos = star_s_pos;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[pattern_pos..].iter().all(|&c| c == b'*')
+}
@@ -13,6 +13,14 @@ use std::time::SystemTime;
 #[cfg(doc)]
 use crate::PrivateKey;
 
+/// Critical options recognized by OpenSSH.
+///
+/// See [PROTOCOL.certkeys] for more information.
+///
+/// [PROTOCOL.certkeys]: https://cvsweb.openbsd.org/src/usr.bin/ssh/PROTOCOL.certkeys?annotate=HEAD
+pub(super) const RECOGNIZED_CRITICAL_OPTIONS: &[&str] =
+    &["force-command", "source-address", "verify-required"];
+
 /// OpenSSH certificate builder.
 ///
 /// This type provides the core functionality of an OpenSSH certificate
@@ -99,6 +107,10 @@ impl Builder {
     ///
     /// Also requires a nonce (random value typically 16 or 32 bytes long) and
     /// the validity window of the certificate as Unix seconds.
+    ///
+    /// The subject may be a FIDO/U2F Security Key, in which case the issued
+    /// certificate is an `sk-*-cert-v01@openssh.com` certificate embedding
+    /// the key's application.
     pub fn new(
         nonce: impl Into<Vec<u8>>,
         public_key: impl Into<public::KeyData>,
@@ -210,6 +222,12 @@ impl Builder {
 
     /// Mark this certificate as being valid for all principals.
     ///
+    /// The certificate is encoded with an empty "valid principals" field. For
+    /// [`CertType::Host`] this means it is valid for any hostname. OpenSSH
+    /// rejects [`CertType::User`] certificates with no principals unless the
+    /// server checks principals separately (e.g. via
+    /// `AuthorizedPrincipalsFile`); see [`Certificate::check_principal`].
+    ///
     /// # ⚠️ Security Warning
     ///
     /// Use this method with care! It generates "golden ticket" certificates
@@ -220,9 +238,42 @@ impl Builder {
         Ok(self)
     }
 
+    /// Add a critical option recognized by OpenSSH to this certificate.
+    ///
+    /// Returns an error if the option isn't one of `force-command`,
+    /// `source-address`, or `verify-required`. Use
+    /// [`Builder::critical_option`] to add other options as raw values.
+    pub fn add_critical_option(
+        &mut self,
+        name: impl Into<String>,
+        data: impl Into<String>,
+    ) -> Result<&mut Self> {
+        let name = name.into();
+
+        if !RECOGNIZED_CRITICAL_OPTIONS.contains(&name.as_str()) {
+            return Err(Field::CriticalOptions.invalid_error());
+        }
+
+        self.critical_option(name, data)
+    }
+
+    /// Remove a critical option from this certificate, if present.
+    pub fn remove_critical_option(&mut self, name: &str) -> Result<&mut Self> {
+        self.critical_options.remove(name);
+        Ok(self)
+    }
+
+    /// Remove all critical options from this certificate.
+    pub fn clear_critical_options(&mut self) -> Result<&mut Self> {
+        self.critical_options.clear();
+        Ok(self)
+    }
+
     /// Add a critical option to this certificate.
     ///
     /// Critical options must be recognized or the certificate must be rejected.
+    ///
+    /// The option name is not checked. See [`Builder::add_critical_option`].
     pub fn critical_option(
         &mut self,
         name: impl Into<String>,
@@ -258,6 +309,29 @@ impl Builder {
         Ok(self)
     }
 
+    /// Add an extension to this certificate.
+    ///
+    /// Returns an error if an extension with the same name is already present.
+    pub fn add_extension(
+        &mut self,
+        name: impl Into<String>,
+        data: impl Into<String>,
+    ) -> Result<&mut Self> {
+        self.extension(name, data)
+    }
+
+    /// Remove an extension from this certificate, if present.
+    pub fn remove_extension(&mut self, name: &str) -> Result<&mut Self> {
+        self.extensions.remove(name);
+        Ok(self)
+    }
+
+    /// Remove all extensions from this certificate (i.e. `ssh-keygen -O clear`).
+    pub fn clear_extensions(&mut self) -> Result<&mut Self> {
+        self.extensions.clear();
+        Ok(self)
+    }
+
     /// Add a comment to this certificate.
     ///
     /// Default `""`
@@ -282,6 +356,7 @@ impl Builder {
         };
 
         let mut cert = Certificate {
+            algorithm: self.public_key.algorithm(),
             nonce: self.nonce,
             public_key: self.public_key,
             serial: self.serial.unwrap_or_default(),
@@ -4,10 +4,11 @@ use crate::{Error, Result};
 use encoding::{Decode, Encode, Reader, Writer};
 
 /// Types of OpenSSH certificates: user or host.
-#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
 pub enum CertType {
     /// User certificate
+    #[default]
     User = 1,
 
     /// Host certificate
@@ -26,12 +27,6 @@ impl CertType {
     }
 }
 
-impl Default for CertType {
-    fn default() -> Self {
-        Self::User
-    }
-}
-
 impl Decode for CertType {
     type Error = Error;
 
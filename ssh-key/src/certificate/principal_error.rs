@@ -0,0 +1,49 @@
+//! Principal check errors.
+
+use super::Field;
+use crate::Error;
+use core::fmt;
+
+/// Reasons a certificate is not valid for a principal, as returned by
+/// [`Certificate::check_principal`][`super::Certificate::check_principal`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum PrincipalError {
+    /// User certificate has an empty "valid principals" field.
+    ///
+    /// Although [PROTOCOL.certkeys] says an empty list means the certificate
+    /// is valid for any principal, OpenSSH refuses such user certificates
+    /// unless principals are checked separately (e.g. via
+    /// `AuthorizedPrincipalsFile`).
+    ///
+    /// [PROTOCOL.certkeys]: https://cvsweb.openbsd.org/src/usr.bin/ssh/PROTOCOL.certkeys?annotate=HEAD
+    Empty,
+
+    /// User certificate principals don't include the given principal.
+    ///
+    /// User certificate principals must match exactly.
+    Mismatch,
+
+    /// None of the host certificate principal patterns match the given
+    /// hostname.
+    PatternMismatch,
+}
+
+impl fmt::Display for PrincipalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "user certificate has no valid principals"),
+            Self::Mismatch => write!(f, "principal not listed in certificate"),
+            Self::PatternMismatch => write!(f, "hostname matches no certificate principal"),
+        }
+    }
+}
+
+impl From<PrincipalError> for Error {
+    fn from(_: PrincipalError) -> Error {
+        Field::ValidPrincipals.invalid_error()
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PrincipalError {}
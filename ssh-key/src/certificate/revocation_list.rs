@@ -0,0 +1,76 @@
+//! Simple certificate revocation lists.
+
+use super::Certificate;
+use crate::{public::KeyData, Fingerprint};
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+/// List of revoked certificates, identified by serial number or by the
+/// certificate's subject public key.
+///
+/// This is a simplified, in-memory form of the revocations supported by
+/// OpenSSH's Key Revocation Lists (KRLs): ranges of serial numbers, explicit
+/// key blobs, and key fingerprints.
+///
+/// Note that serial number revocations apply to every certificate checked
+/// against the list regardless of which CA issued it, so a list containing
+/// them should only be used with certificates issued by a single CA.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RevocationList {
+    /// Revoked ranges of serial numbers.
+    serials: Vec<RangeInclusive<u64>>,
+
+    /// Revoked subject public keys.
+    keys: Vec<KeyData>,
+
+    /// Fingerprints of revoked subject public keys.
+    fingerprints: Vec<Fingerprint>,
+}
+
+impl RevocationList {
+    /// Create a new empty revocation list.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Revoke the certificate with the given serial number.
+    pub fn revoke_serial(&mut self, serial: u64) {
+        self.revoke_serial_range(serial..=serial);
+    }
+
+    /// Revoke all certificates with serial numbers in the given range.
+    pub fn revoke_serial_range(&mut self, serials: RangeInclusive<u64>) {
+        self.serials.push(serials);
+    }
+
+    /// Revoke all certificates for the given subject public key.
+    pub fn revoke_key(&mut self, key: impl Into<KeyData>) {
+        self.keys.push(key.into());
+    }
+
+    /// Revoke all certificates for the subject public key with the given
+    /// fingerprint.
+    pub fn revoke_fingerprint(&mut self, fingerprint: Fingerprint) {
+        self.fingerprints.push(fingerprint);
+    }
+
+    /// Is the given certificate revoked?
+    pub fn is_revoked(&self, certificate: &Certificate) -> bool {
+        self.is_serial_revoked(certificate.serial())
+            || self.is_key_revoked(certificate.public_key())
+    }
+
+    /// Is the given serial number revoked?
+    pub fn is_serial_revoked(&self, serial: u64) -> bool {
+        self.serials.iter().any(|range| range.contains(&serial))
+    }
+
+    /// Is the given public key revoked, either explicitly or by fingerprint?
+    pub fn is_key_revoked(&self, key: &KeyData) -> bool {
+        self.keys.iter().any(|revoked| revoked == key)
+            || self
+                .fingerprints
+                .iter()
+                .any(|revoked| *revoked == key.fingerprint(revoked.algorithm()))
+    }
+}
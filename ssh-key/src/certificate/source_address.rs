@@ -0,0 +1,115 @@
+//! Typed representation of the `source-address` critical option.
+
+use super::Field;
+use crate::{Error, Result};
+use alloc::vec::Vec;
+use core::{fmt, str::FromStr};
+use std::net::IpAddr;
+
+/// Source addresses from which a certificate may be used, i.e. the value of
+/// its `source-address` critical option.
+///
+/// This is a comma-separated list of addresses in CIDR notation, e.g.
+/// `192.0.2.0/24,2001:db8::/32`. Addresses without a prefix length match
+/// only that exact address.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SourceAddress {
+    /// Address blocks, as a network address and prefix length.
+    blocks: Vec<(IpAddr, u8)>,
+}
+
+impl SourceAddress {
+    /// Name of the critical option.
+    pub const OPTION_NAME: &'static str = "source-address";
+
+    /// Is the given address within any of the allowed address blocks?
+    pub fn matches(&self, addr: IpAddr) -> bool {
+        self.blocks
+            .iter()
+            .any(|(network, prefix_len)| match (network, addr) {
+                (IpAddr::V4(network), IpAddr::V4(addr)) => {
+                    prefix_matches(&network.octets(), &addr.octets(), *prefix_len)
+                }
+                (IpAddr::V6(network), IpAddr::V6(addr)) => {
+                    prefix_matches(&network.octets(), &addr.octets(), *prefix_len)
+                }
+                _ => false,
+            })
+    }
+}
+
+impl FromStr for SourceAddress {
+    type Err = Error;
+
+    /// Parse a comma-separated list of CIDR address blocks.
+    ///
+    /// Returns [`Error::CertificateFieldInvalid`] for the critical options
+    /// field if any block is malformed, or has bits set beyond its prefix.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || Field::CriticalOptions.invalid_error();
+
+        let blocks = s
+            .split(',')
+            .map(|block| {
+                let (addr, prefix_len) = match block.split_once('/') {
+                    Some((addr, prefix_len)) => (addr, Some(prefix_len)),
+                    None => (block, None),
+                };
+
+                let addr = addr.parse::<IpAddr>().map_err(|_| invalid())?;
+                let max_prefix_len = if addr.is_ipv4() { 32 } else { 128 };
+
+                let prefix_len = match prefix_len {
+                    Some(prefix_len) => prefix_len.parse::<u8>().map_err(|_| invalid())?,
+                    None => max_prefix_len,
+                };
+
+                let host_bits_clear = match addr {
+                    IpAddr::V4(addr) => mask(&addr.octets(), prefix_len) == addr.octets(),
+                    IpAddr::V6(addr) => mask(&addr.octets(), prefix_len) == addr.octets(),
+                };
+
+                if prefix_len > max_prefix_len || !host_bits_clear {
+                    return Err(invalid());
+                }
+
+                Ok((addr, prefix_len))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { blocks })
+    }
+}
+
+impl fmt::Display for SourceAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (addr, prefix_len)) in self.blocks.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+
+            write!(f, "{addr}/{prefix_len}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Do the first `prefix_len` bits of the two addresses match?
+fn prefix_matches<const N: usize>(network: &[u8; N], addr: &[u8; N], prefix_len: u8) -> bool {
+    mask(network, prefix_len) == mask(addr, prefix_len)
+}
+
+/// Clear all but the first `prefix_len` bits of the given address.
+fn mask<const N: usize>(addr: &[u8; N], prefix_len: u8) -> [u8; N] {
+    let mut masked = *addr;
+    let mut remaining = u32::from(prefix_len);
+
+    for byte in masked.iter_mut() {
+        let bits = remaining.min(8);
+        *byte &= !0xFFu8.checked_shr(bits).unwrap_or(0);
+        remaining = remaining.saturating_sub(bits);
+    }
+
+    masked
+}
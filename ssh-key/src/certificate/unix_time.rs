@@ -16,6 +16,12 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 /// excessively far in the future. Otherwise the limit is `i64::MAX`.
 pub const MAX_SECS: u64 = 253402300799;
 
+/// Timestamp used by OpenSSH for certificates which never expire, i.e. the
+/// `valid_before` time of certificates issued without a validity interval.
+///
+/// This is accepted in addition to timestamps up to [`MAX_SECS`].
+pub const FOREVER_SECS: u64 = u64::MAX;
+
 /// Unix timestamps as used in OpenSSH certificates.
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord)]
 pub(super) struct UnixTime {
@@ -31,10 +37,10 @@ impl UnixTime {
     /// Create a new Unix timestamp.
     ///
     /// `secs` is the number of seconds since the Unix epoch and must be less
-    /// than or equal to `i64::MAX`.
+    /// than or equal to [`MAX_SECS`], or [`FOREVER_SECS`].
     #[cfg(not(feature = "std"))]
     pub fn new(secs: u64) -> Result<Self> {
-        if secs <= MAX_SECS {
+        if secs <= MAX_SECS || secs == FOREVER_SECS {
             Ok(Self { secs })
         } else {
             Err(Error::Time)
@@ -46,20 +52,22 @@ impl UnixTime {
     /// This version requires `std` and ensures there's a valid `SystemTime`
     /// representation with an infallible conversion (which also improves the
     /// `Debug` output)
+    ///
+    /// [`FOREVER_SECS`] is represented as the `SystemTime` for [`MAX_SECS`].
     #[cfg(feature = "std")]
     pub fn new(secs: u64) -> Result<Self> {
-        if secs > MAX_SECS {
+        if secs > MAX_SECS && secs != FOREVER_SECS {
             return Err(Error::Time);
         }
 
-        match UNIX_EPOCH.checked_add(Duration::from_secs(secs)) {
+        match UNIX_EPOCH.checked_add(Duration::from_secs(secs.min(MAX_SECS))) {
             Some(time) => Ok(Self { secs, time }),
             None => Err(Error::Time),
         }
     }
 
     /// Get the current time as a Unix timestamp.
-    #[cfg(all(feature = "std"))]
+    #[cfg(feature = "std")]
     pub fn now() -> Result<Self> {
         SystemTime::now().try_into()
     }
@@ -124,7 +132,7 @@ impl fmt::Debug for UnixTime {
 
 #[cfg(test)]
 mod tests {
-    use super::{UnixTime, MAX_SECS};
+    use super::{UnixTime, FOREVER_SECS, MAX_SECS};
     use crate::Error;
 
     #[test]
@@ -136,4 +144,11 @@ mod tests {
     fn new_over_max_secs_returns_error() {
         assert_eq!(UnixTime::new(MAX_SECS + 1), Err(Error::Time));
     }
+
+    #[test]
+    fn new_with_forever_secs() {
+        let forever = UnixTime::new(FOREVER_SECS).unwrap();
+        assert_eq!(u64::from(forever), FOREVER_SECS);
+        assert!(forever > UnixTime::new(MAX_SECS).unwrap());
+    }
 }
@@ -0,0 +1,322 @@
+//! High-level certificate validation.
+
+use super::{builder::RECOGNIZED_CRITICAL_OPTIONS, CertType, Certificate, PrincipalError};
+use crate::{Algorithm, Error, Fingerprint, HashAlg, VerificationConfig};
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+#[cfg(feature = "std")]
+use {
+    super::{SourceAddress, UnixTime},
+    std::net::IpAddr,
+};
+
+/// Certificate validator which performs the checks `sshd` and `ssh` apply
+/// before accepting a certificate.
+///
+/// Checks, in order, that:
+///
+/// - the certificate is signed by one of the trusted certificate authorities
+///   (CAs), using a signature algorithm and key allowed by the
+///   [`VerificationConfig`], and the signature verifies
+/// - the certificate has the expected [`CertType`], if one is configured
+/// - the current time is within the certificate's validity window
+/// - the certificate authorizes the expected principal, if one is
+///   configured (see [`Certificate::check_principal`])
+/// - all critical options are recognized, and the `source-address` option
+///   (if any) is well-formed and permits the client address. Certificates
+///   with a `source-address` option are rejected if no client address is
+///   configured (or without the `std` feature), since `sshd` never ignores
+///   a critical option
+///
+/// As in OpenSSH, the critical options `force-command`, `source-address` and
+/// `verify-required` are recognized for user certificates, whereas host
+/// certificates must not have any critical options. Additional options can
+/// be recognized using [`Validator::recognize_critical_option`], in which
+/// case the caller is responsible for enforcing them.
+///
+/// ## Example
+///
+#[cfg_attr(all(feature = "ed25519", feature = "std"), doc = " ```")]
+#[cfg_attr(not(all(feature = "ed25519", feature = "std")), doc = " ```ignore")]
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use ssh_key::certificate::{CertType, Certificate, Validator};
+///
+/// let cert = Certificate::from_openssh(include_str!("../../tests/examples/id_ed25519-cert.pub"))?;
+///
+/// let mut validator = Validator::new();
+/// validator
+///     .trust_ca("SHA256:UCUiLr7Pjs9wFFJMDByLgc3NrtdU344OgUM45wZPcIQ".parse()?)
+///     .cert_type(CertType::Host)
+///     .principal("host.example.com")
+///     .unix_timestamp(1750000000);
+///
+/// validator.validate(&cert)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Validator {
+    /// Fingerprints of the trusted CAs.
+    trusted_cas: Vec<Fingerprint>,
+
+    /// Expected certificate type.
+    cert_type: Option<CertType>,
+
+    /// Expected principal.
+    principal: Option<String>,
+
+    /// Fixed time to validate at, instead of the system clock.
+    unix_timestamp: Option<u64>,
+
+    /// Additional recognized critical options.
+    critical_options: Vec<String>,
+
+    /// Policy for verifying the CA signature.
+    config: VerificationConfig,
+
+    /// Address of the client, checked against the `source-address` option.
+    #[cfg(feature = "std")]
+    client_address: Option<IpAddr>,
+}
+
+impl Validator {
+    /// Create a new validator which trusts no CAs.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Trust the CA with the given fingerprint.
+    ///
+    /// NOTE: only SHA-256 fingerprints are supported at this time.
+    pub fn trust_ca(&mut self, fingerprint: Fingerprint) -> &mut Self {
+        self.trusted_cas.push(fingerprint);
+        self
+    }
+
+    /// Require certificates of the given type.
+    pub fn cert_type(&mut self, cert_type: CertType) -> &mut Self {
+        self.cert_type = Some(cert_type);
+        self
+    }
+
+    /// Require certificates to authorize the given principal, i.e. username
+    /// for user certificates or hostname for host certificates.
+    ///
+    /// If no principal is configured, principals aren't checked and must be
+    /// checked separately (e.g. as with `AuthorizedPrincipalsFile`).
+    pub fn principal(&mut self, principal: impl Into<String>) -> &mut Self {
+        self.principal = Some(principal.into());
+        self
+    }
+
+    /// Validate certificates at the given Unix timestamp, rather than the
+    /// current time according to the system clock.
+    ///
+    /// Without the `std` feature, a timestamp must be configured.
+    pub fn unix_timestamp(&mut self, unix_timestamp: u64) -> &mut Self {
+        self.unix_timestamp = Some(unix_timestamp);
+        self
+    }
+
+    /// Accept certificates with the given critical option, which the caller
+    /// will enforce.
+    pub fn recognize_critical_option(&mut self, name: impl Into<String>) -> &mut Self {
+        self.critical_options.push(name.into());
+        self
+    }
+
+    /// Verify CA signatures according to the given [`VerificationConfig`],
+    /// e.g. to restrict the CA key types and signature algorithms which are
+    /// accepted.
+    ///
+    /// Default: [`VerificationConfig::default`].
+    pub fn verification_config(&mut self, config: VerificationConfig) -> &mut Self {
+        self.config = config;
+        self
+    }
+
+    /// Require the `source-address` critical option (if any) to permit the
+    /// given client address.
+    ///
+    /// Without a client address, certificates with a `source-address`
+    /// option are rejected.
+    #[cfg(feature = "std")]
+    pub fn client_address(&mut self, addr: IpAddr) -> &mut Self {
+        self.client_address = Some(addr);
+        self
+    }
+
+    /// Validate the given certificate, returning the first check which
+    /// failed.
+    pub fn validate(&self, cert: &Certificate) -> Result<(), ValidationError> {
+        let unix_timestamp = self.now()?;
+
+        // TODO(tarcieri): support non SHA-256 public key fingerprints?
+        let ca_fingerprint = cert.signature_key.fingerprint(HashAlg::Sha256);
+
+        if !self.trusted_cas.contains(&ca_fingerprint) {
+            return Err(ValidationError::UntrustedCa);
+        }
+
+        let mut tbs_certificate = Vec::new();
+        cert.encode_tbs(&mut tbs_certificate)
+            .map_err(|_| ValidationError::Signature)?;
+
+        cert.signature_key
+            .verify_with(&tbs_certificate, &cert.signature, &self.config)
+            .map_err(|err| match err {
+                Error::AlgorithmUnsupported { algorithm } => {
+                    ValidationError::SignatureAlgorithm(algorithm)
+                }
+                _ => ValidationError::Signature,
+            })?;
+
+        if let Some(expected) = self.cert_type {
+            if cert.cert_type != expected {
+                return Err(ValidationError::CertType(cert.cert_type));
+            }
+        }
+
+        if unix_timestamp < cert.valid_after() {
+            return Err(ValidationError::NotYetValid);
+        }
+
+        if unix_timestamp >= cert.valid_before() {
+            return Err(ValidationError::Expired);
+        }
+
+        if let Some(principal) = &self.principal {
+            cert.check_principal(principal)
+                .map_err(ValidationError::Principal)?;
+        }
+
+        for name in cert.critical_options.keys() {
+            let recognized = (cert.cert_type.is_user()
+                && RECOGNIZED_CRITICAL_OPTIONS.contains(&name.as_str()))
+                || self.critical_options.contains(name);
+
+            if !recognized {
+                return Err(ValidationError::UnrecognizedCriticalOption(name.clone()));
+            }
+        }
+
+        #[cfg(feature = "std")]
+        self.check_source_address(cert)?;
+
+        // The client address can't be configured, so fail closed
+        #[cfg(not(feature = "std"))]
+        if cert
+            .critical_options
+            .keys()
+            .any(|name| name == "source-address")
+        {
+            return Err(ValidationError::SourceAddress);
+        }
+
+        Ok(())
+    }
+
+    /// Get the time to validate at.
+    fn now(&self) -> Result<u64, ValidationError> {
+        if let Some(unix_timestamp) = self.unix_timestamp {
+            return Ok(unix_timestamp);
+        }
+
+        #[cfg(feature = "std")]
+        return UnixTime::now()
+            .map(Into::into)
+            .map_err(|_| ValidationError::Clock);
+
+        #[cfg(not(feature = "std"))]
+        Err(ValidationError::Clock)
+    }
+
+    /// Check the `source-address` critical option, if present.
+    #[cfg(feature = "std")]
+    fn check_source_address(&self, cert: &Certificate) -> Result<(), ValidationError> {
+        let source_address = cert.source_address().map_err(|_| {
+            ValidationError::InvalidCriticalOption(SourceAddress::OPTION_NAME.into())
+        })?;
+
+        match (source_address, self.client_address) {
+            (Some(source_address), Some(addr)) if source_address.matches(addr) => Ok(()),
+            (Some(_), _) => Err(ValidationError::SourceAddress),
+            (None, _) => Ok(()),
+        }
+    }
+}
+
+/// Reasons a certificate failed validation, as returned by
+/// [`Validator::validate`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ValidationError {
+    /// Certificate is not signed by a trusted CA.
+    UntrustedCa,
+
+    /// CA signature algorithm is not allowed by the [`VerificationConfig`],
+    /// or is unsupported.
+    SignatureAlgorithm(Algorithm),
+
+    /// CA signature is invalid, or the CA key is not allowed by the
+    /// [`VerificationConfig`] (e.g. an RSA key which is too small).
+    Signature,
+
+    /// Certificate has the given type, rather than the expected one.
+    CertType(CertType),
+
+    /// Certificate's validity window hasn't started yet.
+    NotYetValid,
+
+    /// Certificate has expired.
+    Expired,
+
+    /// Certificate does not authorize the expected principal.
+    Principal(PrincipalError),
+
+    /// Certificate has the given critical option, which is not recognized.
+    UnrecognizedCriticalOption(String),
+
+    /// The given critical option is malformed.
+    InvalidCriticalOption(String),
+
+    /// The `source-address` critical option does not permit the client
+    /// address, or no client address was configured.
+    SourceAddress,
+
+    /// The current time couldn't be determined, i.e. the system clock is
+    /// before the Unix epoch or unavailable, and no timestamp was given.
+    Clock,
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UntrustedCa => write!(f, "certificate not signed by a trusted CA"),
+            Self::SignatureAlgorithm(algorithm) => {
+                write!(f, "CA signature algorithm not allowed: {algorithm}")
+            }
+            Self::Signature => write!(f, "CA signature invalid or CA key not allowed"),
+            Self::CertType(cert_type) => write!(f, "unexpected {cert_type:?} certificate"),
+            Self::NotYetValid => write!(f, "certificate not yet valid"),
+            Self::Expired => write!(f, "certificate expired"),
+            Self::Principal(err) => write!(f, "{err}"),
+            Self::UnrecognizedCriticalOption(name) => {
+                write!(f, "unrecognized critical option: {name}")
+            }
+            Self::InvalidCriticalOption(name) => write!(f, "invalid critical option: {name}"),
+            Self::SourceAddress => write!(f, "source address not permitted"),
+            Self::Clock => write!(f, "current time unavailable"),
+        }
+    }
+}
+
+impl From<ValidationError> for Error {
+    fn from(_: ValidationError) -> Error {
+        Error::CertificateValidation
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ValidationError {}
@@ -15,12 +15,56 @@ use aes::{
 #[cfg(feature = "aes-gcm")]
 use aes_gcm::{aead::AeadInPlace, Aes256Gcm};
 
+#[cfg(any(
+    feature = "aes-cbc",
+    feature = "pkcs8",
+    feature = "ppk",
+    feature = "tdes"
+))]
+use aes::cipher::{BlockCipher, BlockDecryptMut, BlockEncryptMut};
+
+#[cfg(any(
+    feature = "aes-cbc",
+    feature = "chacha20poly1305",
+    feature = "pkcs8",
+    feature = "ppk",
+    feature = "tdes"
+))]
+use aes::cipher::KeyIvInit;
+
+#[cfg(feature = "aes-cbc")]
+use aes::Aes128;
+
+#[cfg(feature = "chacha20poly1305")]
+use {
+    aes::cipher::{StreamCipher, StreamCipherSeek},
+    chacha20::ChaCha20Legacy,
+    poly1305::Poly1305,
+    subtle::ConstantTimeEq,
+    zeroize::Zeroizing,
+};
+
+#[cfg(feature = "tdes")]
+use des::TdesEde3;
+
+/// AES-128 in cipher block chaining (CBC) mode.
+const AES128_CBC: &str = "aes128-cbc";
+
+/// AES-256 in cipher block chaining (CBC) mode.
+const AES256_CBC: &str = "aes256-cbc";
+
 /// AES-256 in counter (CTR) mode
 const AES256_CTR: &str = "aes256-ctr";
 
 /// AES-256 in Galois/Counter Mode (GCM).
 const AES256_GCM: &str = "aes256-gcm@openssh.com";
 
+/// ChaCha20 with a Poly1305 authenticator, as constructed by OpenSSH.
+const CHACHA20_POLY1305: &str = "chacha20-poly1305@openssh.com";
+
+/// Triple DES (EDE3) in cipher block chaining (CBC) mode.
+const TDES_CBC: &str = "3des-cbc";
+
 /// Nonces for AEAD modes.
 #[cfg(feature = "aes-gcm")]
 type AeadNonce = [u8; 12];
@@ -41,24 +85,48 @@ pub enum Cipher {
     /// No cipher (unencrypted key).
     None,
 
+    /// AES-128 in cipher block chaining (CBC) mode.
+    Aes128Cbc,
+
+    /// AES-256 in cipher block chaining (CBC) mode.
+    Aes256Cbc,
+
     /// AES-256 in counter (CTR) mode.
     #[default]
     Aes256Ctr,
 
     /// AES-256 in Galois/Counter Mode (GCM).
     Aes256Gcm,
+
+    /// ChaCha20 with a Poly1305 authenticator
+    /// (`chacha20-poly1305@openssh.com`).
+    ChaCha20Poly1305,
+
+    /// Triple DES (EDE3) in cipher block chaining (CBC) mode.
+    ///
+    /// Obsolete: supported for decrypting legacy keys.
+    TDesCbc,
 }
 
 impl Cipher {
     /// Decode cipher algorithm from the given `ciphername`.
     ///
     /// # Supported cipher names
+    /// - `3des-cbc`
+    /// - `aes128-cbc`
+    /// - `aes256-cbc`
     /// - `aes256-ctr`
+    /// - `aes256-gcm@openssh.com`
+    /// - `chacha20-poly1305@openssh.com`
     pub fn new(ciphername: &str) -> Result<Self> {
         match ciphername {
             "none" => Ok(Self::None),
+            AES128_CBC => Ok(Self::Aes128Cbc),
+            AES256_CBC => Ok(Self::Aes256Cbc),
             AES256_CTR => Ok(Self::Aes256Ctr),
             AES256_GCM => Ok(Self::Aes256Gcm),
+            CHACHA20_POLY1305 => Ok(Self::ChaCha20Poly1305),
+            TDES_CBC => Ok(Self::TDesCbc),
             _ => Err(Error::AlgorithmUnknown),
         }
     }
@@ -67,31 +135,41 @@ impl Cipher {
     pub fn as_str(self) -> &'static str {
         match self {
             Self::None => "none",
+            Self::Aes128Cbc => AES128_CBC,
+            Self::Aes256Cbc => AES256_CBC,
             Self::Aes256Ctr => AES256_CTR,
             Self::Aes256Gcm => AES256_GCM,
+            Self::ChaCha20Poly1305 => CHACHA20_POLY1305,
+            Self::TDesCbc => TDES_CBC,
         }
     }
 
     /// Get the key and IV size for this cipher in bytes.
+    ///
+    /// `chacha20-poly1305@openssh.com` takes a 64-byte key (which is split
+    /// into two ChaCha20 keys) and no IV.
     pub fn key_and_iv_size(self) -> Option<(usize, usize)> {
         match self {
             Self::None => None,
-            Self::Aes256Ctr => Some((32, 16)),
+            Self::Aes128Cbc => Some((16, 16)),
+            Self::Aes256Cbc | Self::Aes256Ctr => Some((32, 16)),
             Self::Aes256Gcm => Some((32, 12)),
+            Self::ChaCha20Poly1305 => Some((64, 0)),
+            Self::TDesCbc => Some((24, 8)),
         }
     }
 
     /// Get the block size for this cipher in bytes.
     pub fn block_size(self) -> usize {
         match self {
-            Self::None => 8,
-            Self::Aes256Ctr | Self::Aes256Gcm => 16,
+            Self::None | Self::ChaCha20Poly1305 | Self::TDesCbc => 8,
+            Self::Aes128Cbc | Self::Aes256Cbc | Self::Aes256Ctr | Self::Aes256Gcm => 16,
         }
     }
 
     /// Compute the length of padding necessary to pad the given input to
     /// the block size.
-    #[allow(clippy::integer_arithmetic)]
+    #[allow(clippy::arithmetic_side_effects)]
     pub fn padding_len(self, input_size: usize) -> usize {
         match input_size % self.block_size() {
             0 => 0,
@@ -101,7 +179,7 @@ impl Cipher {
 
     /// Does this cipher have an authentication tag? (i.e. is it an AEAD mode?)
     pub fn has_tag(self) -> bool {
-        matches!(self, Self::Aes256Gcm)
+        matches!(self, Self::Aes256Gcm | Self::ChaCha20Poly1305)
     }
 
     /// Is this cipher `none`?
@@ -117,12 +195,16 @@ impl Cipher {
     /// Decrypt the ciphertext in the `buffer` in-place using this cipher.
     #[cfg(feature = "encryption")]
     pub fn decrypt(self, key: &[u8], iv: &[u8], buffer: &mut [u8], tag: Option<Tag>) -> Result<()> {
+        if tag.is_some() && !self.has_tag() {
+            return Err(Error::Crypto);
+        }
+
         match self {
+            #[cfg(feature = "aes-cbc")]
+            Self::Aes128Cbc => cbc_decrypt::<Aes128>(key, iv, buffer),
+            #[cfg(feature = "aes-cbc")]
+            Self::Aes256Cbc => cbc_decrypt::<Aes256>(key, iv, buffer),
             Self::Aes256Ctr => {
-                if tag.is_some() {
-                    return Err(Error::Crypto);
-                }
-
                 // Counter mode encryption and decryption are the same operation
                 self.encrypt(key, iv, buffer)?;
                 Ok(())
@@ -134,10 +216,25 @@ impl Cipher {
                 let tag = tag.ok_or(Error::Crypto)?;
                 cipher
                     .decrypt_in_place_detached(&nonce.into(), &[], buffer, &tag.into())
-                    .map_err(|_| Error::Crypto)?;
+                    .map_err(|_| Error::WrongPassword)?;
 
                 Ok(())
             }
+            #[cfg(feature = "chacha20poly1305")]
+            Self::ChaCha20Poly1305 => {
+                let (mut cipher, mac) = chacha20_poly1305_init(key, iv)?;
+                let expected_tag = Tag::from(mac.compute_unpadded(buffer));
+                let tag = tag.ok_or(Error::Crypto)?;
+
+                if !bool::from(expected_tag.ct_eq(&tag)) {
+                    return Err(Error::WrongPassword);
+                }
+
+                cipher.apply_keystream(buffer);
+                Ok(())
+            }
+            #[cfg(feature = "tdes")]
+            Self::TDesCbc => cbc_decrypt::<TdesEde3>(key, iv, buffer),
             _ => Err(Error::Crypto),
         }
     }
@@ -146,6 +243,16 @@ impl Cipher {
     #[cfg(feature = "encryption")]
     pub fn encrypt(self, key: &[u8], iv: &[u8], buffer: &mut [u8]) -> Result<Option<Tag>> {
         match self {
+            #[cfg(feature = "aes-cbc")]
+            Self::Aes128Cbc => {
+                cbc_encrypt::<Aes128>(key, iv, buffer)?;
+                Ok(None)
+            }
+            #[cfg(feature = "aes-cbc")]
+            Self::Aes256Cbc => {
+                cbc_encrypt::<Aes256>(key, iv, buffer)?;
+                Ok(None)
+            }
             Self::Aes256Ctr => {
                 let cipher = Aes256::new_from_slice(key)
                     .and_then(|aes| Ctr128BE::inner_iv_slice_init(aes, iv))
@@ -167,11 +274,99 @@ impl Cipher {
 
                 Ok(Some(tag.into()))
             }
+            #[cfg(feature = "chacha20poly1305")]
+            Self::ChaCha20Poly1305 => {
+                let (mut cipher, mac) = chacha20_poly1305_init(key, iv)?;
+                cipher.apply_keystream(buffer);
+                Ok(Some(mac.compute_unpadded(buffer).into()))
+            }
+            #[cfg(feature = "tdes")]
+            Self::TDesCbc => {
+                cbc_encrypt::<TdesEde3>(key, iv, buffer)?;
+                Ok(None)
+            }
             _ => Err(Error::Crypto),
         }
     }
 }
 
+/// Decrypt the given buffer in-place using the given block cipher in CBC
+/// mode.
+#[cfg(any(
+    feature = "aes-cbc",
+    feature = "pkcs8",
+    feature = "ppk",
+    feature = "tdes"
+))]
+pub(crate) fn cbc_decrypt<C>(key: &[u8], iv: &[u8], buffer: &mut [u8]) -> Result<()>
+where
+    C: BlockCipher + BlockDecryptMut + KeyInit,
+{
+    let mut cipher = cbc::Decryptor::<C>::new_from_slices(key, iv).map_err(|_| Error::Crypto)?;
+    let block_size = C::block_size();
+
+    if buffer.len().checked_rem(block_size) != Some(0) {
+        return Err(Error::Crypto);
+    }
+
+    for block in buffer.chunks_exact_mut(block_size) {
+        cipher.decrypt_block_mut(block.into());
+    }
+
+    Ok(())
+}
+
+/// Encrypt the given buffer in-place using the given block cipher in CBC
+/// mode.
+#[cfg(any(
+    feature = "aes-cbc",
+    feature = "pkcs8",
+    feature = "ppk",
+    feature = "tdes"
+))]
+pub(crate) fn cbc_encrypt<C>(key: &[u8], iv: &[u8], buffer: &mut [u8]) -> Result<()>
+where
+    C: BlockCipher + BlockEncryptMut + KeyInit,
+{
+    let mut cipher = cbc::Encryptor::<C>::new_from_slices(key, iv).map_err(|_| Error::Crypto)?;
+    let block_size = C::block_size();
+
+    if buffer.len().checked_rem(block_size) != Some(0) {
+        return Err(Error::Crypto);
+    }
+
+    for block in buffer.chunks_exact_mut(block_size) {
+        cipher.encrypt_block_mut(block.into());
+    }
+
+    Ok(())
+}
+
+/// Initialize `chacha20-poly1305@openssh.com`, returning the ChaCha20
+/// cipher positioned to process the buffer, and the Poly1305 instance which
+/// authenticates the ciphertext.
+///
+/// Private keys are encrypted as a single packet with sequence number zero
+/// and no length header, so only the first half of the key is used.
+#[cfg(feature = "chacha20poly1305")]
+fn chacha20_poly1305_init(key: &[u8], iv: &[u8]) -> Result<(ChaCha20Legacy, Poly1305)> {
+    if key.len() != 64 || !iv.is_empty() {
+        return Err(Error::Crypto);
+    }
+
+    let nonce = [0u8; 8];
+    let mut cipher =
+        ChaCha20Legacy::new_from_slices(&key[..32], &nonce).map_err(|_| Error::Crypto)?;
+
+    // The Poly1305 key is the first half of keystream block 0, and the
+    // buffer is processed starting from block 1
+    let mut poly1305_key = Zeroizing::new([0u8; 32]);
+    cipher.apply_keystream(&mut *poly1305_key);
+    cipher.seek(64u64);
+
+    Ok((cipher, Poly1305::new((&*poly1305_key).into())))
+}
+
 impl AsRef<str> for Cipher {
     fn as_ref(&self) -> &str {
         self.as_str()
@@ -10,9 +10,13 @@ use crate::certificate;
 pub type Result<T> = core::result::Result<T, Error>;
 
 /// Error type.
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 #[non_exhaustive]
 pub enum Error {
+    /// ssh-agent refused a request, e.g. because it doesn't hold the
+    /// requested key or the user declined to confirm its use.
+    AgentFailure,
+
     /// Unknown algorithm.
     ///
     /// This is returned when an algorithm is completely unknown to this crate.
@@ -30,6 +34,17 @@ pub enum Error {
         algorithm: Algorithm,
     },
 
+    /// Algorithm of the private key doesn't match the algorithm of the
+    /// accompanying public key, e.g. the outer and inner structures of an
+    /// OpenSSH private key disagree.
+    AlgorithmMismatch {
+        /// Algorithm of the public key.
+        public: Algorithm,
+
+        /// Algorithm of the private key.
+        private: Algorithm,
+    },
+
     /// Certificate field is invalid or already set.
     #[cfg(feature = "alloc")]
     CertificateFieldInvalid(certificate::Field),
@@ -37,6 +52,10 @@ pub enum Error {
     /// Certificate validation failed.
     CertificateValidation,
 
+    /// Comment contains characters which can't be encoded, i.e. control
+    /// characters such as newlines.
+    CommentInvalid,
+
     /// Cryptographic errors.
     Crypto,
 
@@ -56,10 +75,23 @@ pub enum Error {
     /// Other format encoding errors.
     FormatEncoding,
 
+    /// Unsupported private key format version, i.e. the data doesn't begin
+    /// with the `openssh-key-v1\0` magic.
+    FormatVersion {
+        /// Magic bytes which were observed in place of `openssh-key-v1\0`.
+        magic: [u8; 15],
+    },
+
     /// Input/output errors.
     #[cfg(feature = "std")]
     Io(std::io::ErrorKind),
 
+    /// Invalid line in a line-oriented file, e.g. `authorized_keys`.
+    LineInvalid {
+        /// Line number, starting from 1.
+        number: usize,
+    },
+
     /// Namespace invalid.
     Namespace,
 
@@ -80,20 +112,32 @@ pub enum Error {
         /// Version number.
         number: u32,
     },
+
+    /// Incorrect password for an encrypted private key.
+    ///
+    /// Returned when the decrypted data fails an integrity check, i.e. an
+    /// authentication tag or `checkint` mismatch.
+    WrongPassword,
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Error::AgentFailure => write!(f, "ssh-agent request failed"),
             Error::AlgorithmUnknown => write!(f, "unknown algorithm"),
             Error::AlgorithmUnsupported { algorithm } => {
                 write!(f, "unsupported algorithm: {algorithm}")
             }
+            Error::AlgorithmMismatch { public, private } => write!(
+                f,
+                "algorithm mismatch: public key is {public}, private key is {private}"
+            ),
             #[cfg(feature = "alloc")]
             Error::CertificateFieldInvalid(field) => {
                 write!(f, "certificate field invalid: {field}")
             }
             Error::CertificateValidation => write!(f, "certificate validation failed"),
+            Error::CommentInvalid => write!(f, "comment contains control characters"),
             Error::Crypto => write!(f, "cryptographic error"),
             Error::Decrypted => write!(f, "private key is already decrypted"),
             #[cfg(feature = "ecdsa")]
@@ -101,8 +145,14 @@ impl fmt::Display for Error {
             Error::Encoding(err) => write!(f, "{err}"),
             Error::Encrypted => write!(f, "private key is encrypted"),
             Error::FormatEncoding => write!(f, "format encoding error"),
+            Error::FormatVersion { magic } => write!(
+                f,
+                "unsupported private key format: \"{}\"",
+                magic.escape_ascii()
+            ),
             #[cfg(feature = "std")]
             Error::Io(err) => write!(f, "I/O error: {}", std::io::Error::from(*err)),
+            Error::LineInvalid { number } => write!(f, "invalid line: {number}"),
             Error::Namespace => write!(f, "namespace invalid"),
             Error::PublicKey => write!(f, "public key is incorrect"),
             Error::Time => write!(f, "invalid time"),
@@ -111,6 +161,7 @@ impl fmt::Display for Error {
                 "unexpected trailing data at end of message ({remaining} bytes)",
             ),
             Error::Version { number: version } => write!(f, "version unsupported: {version}"),
+            Error::WrongPassword => write!(f, "incorrect password"),
         }
     }
 }
@@ -158,7 +209,7 @@ impl From<signature::Error> for Error {
         use std::error::Error as _;
 
         err.source()
-            .and_then(|source| source.downcast_ref().copied())
+            .and_then(|source| source.downcast_ref().cloned())
             .unwrap_or(Error::Crypto)
     }
 }
@@ -191,6 +242,34 @@ impl From<sec1::Error> for Error {
     }
 }
 
+#[cfg(feature = "pkcs8")]
+impl From<pkcs8::Error> for Error {
+    fn from(_: pkcs8::Error) -> Error {
+        Error::FormatEncoding
+    }
+}
+
+#[cfg(feature = "pkcs8")]
+impl From<pkcs8::der::Error> for Error {
+    fn from(_: pkcs8::der::Error) -> Error {
+        Error::FormatEncoding
+    }
+}
+
+#[cfg(feature = "pkcs8")]
+impl From<pkcs8::spki::Error> for Error {
+    fn from(_: pkcs8::spki::Error) -> Error {
+        Error::FormatEncoding
+    }
+}
+
+#[cfg(feature = "rsa")]
+impl From<rsa::pkcs1::Error> for Error {
+    fn from(_: rsa::pkcs1::Error) -> Error {
+        Error::FormatEncoding
+    }
+}
+
 #[cfg(feature = "rsa")]
 impl From<rsa::errors::Error> for Error {
     fn from(_: rsa::errors::Error) -> Error {
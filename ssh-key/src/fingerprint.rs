@@ -12,7 +12,7 @@ use encoding::{
     base64::{Base64Unpadded, Encoding},
     Encode,
 };
-use sha2::{Digest, Sha256, Sha512};
+use sha2::{Digest, Sha256, Sha384, Sha512};
 
 /// Fingerprint encoding error message.
 const FINGERPRINT_ERR_MSG: &str = "fingerprint encoding error";
@@ -39,6 +39,11 @@ use serde::{de, ser, Deserialize, Serialize};
 /// SHA256:Nh0Me49Zh9fDw/VYUfq43IJmI1T+XrjiYONPND8GzaM
 /// ```
 ///
+/// # Ordering
+///
+/// Fingerprints are ordered by hash algorithm (SHA-256 before SHA-512), then
+/// by digest bytes, so they can be stored in e.g. a `BTreeSet`.
+///
 /// # `serde` support
 ///
 /// When the `serde` feature of this crate is enabled, this type receives impls
@@ -49,6 +54,9 @@ pub enum Fingerprint {
     /// Fingerprints computed using SHA-256.
     Sha256([u8; HashAlg::Sha256.digest_size()]),
 
+    /// Fingerprints computed using SHA-384.
+    Sha384([u8; HashAlg::Sha384.digest_size()]),
+
     /// Fingerprints computed using SHA-512.
     Sha512([u8; HashAlg::Sha512.digest_size()]),
 }
@@ -57,27 +65,55 @@ impl Fingerprint {
     /// Size of a SHA-512 hash encoded as Base64.
     const SHA512_BASE64_SIZE: usize = 86;
 
+    /// Default number of Base64 characters retained at each end by
+    /// [`Fingerprint::masked`].
+    pub const DEFAULT_MASKED_CHARS: usize = 4;
+
     /// Create a fingerprint of the given public key data using the provided
     /// hash algorithm.
     pub fn new(algorithm: HashAlg, public_key: &public::KeyData) -> Self {
+        Self::digest(algorithm, public_key)
+    }
+
+    /// Create a fingerprint of the encoding of the given value.
+    pub(crate) fn digest<T>(algorithm: HashAlg, value: &T) -> Self
+    where
+        T: Encode<Error = Error>,
+    {
         match algorithm {
             HashAlg::Sha256 => {
                 let mut digest = Sha256::new();
-                public_key.encode(&mut digest).expect(FINGERPRINT_ERR_MSG);
+                value.encode(&mut digest).expect(FINGERPRINT_ERR_MSG);
                 Self::Sha256(digest.finalize().into())
             }
+            HashAlg::Sha384 => {
+                let mut digest = Sha384::new();
+                value.encode(&mut digest).expect(FINGERPRINT_ERR_MSG);
+                Self::Sha384(digest.finalize().into())
+            }
             HashAlg::Sha512 => {
                 let mut digest = Sha512::new();
-                public_key.encode(&mut digest).expect(FINGERPRINT_ERR_MSG);
+                value.encode(&mut digest).expect(FINGERPRINT_ERR_MSG);
                 Self::Sha512(digest.finalize().into())
             }
         }
     }
 
+    /// Create a fingerprint of an already-encoded public key blob.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn from_key_blob(algorithm: HashAlg, key_blob: &[u8]) -> Self {
+        match algorithm {
+            HashAlg::Sha256 => Self::Sha256(Sha256::digest(key_blob).into()),
+            HashAlg::Sha384 => Self::Sha384(Sha384::digest(key_blob).into()),
+            HashAlg::Sha512 => Self::Sha512(Sha512::digest(key_blob).into()),
+        }
+    }
+
     /// Get the hash algorithm used for this fingerprint.
     pub fn algorithm(self) -> HashAlg {
         match self {
             Self::Sha256(_) => HashAlg::Sha256,
+            Self::Sha384(_) => HashAlg::Sha384,
             Self::Sha512(_) => HashAlg::Sha512,
         }
     }
@@ -86,6 +122,7 @@ impl Fingerprint {
     pub fn prefix(self) -> &'static str {
         match self.algorithm() {
             HashAlg::Sha256 => "SHA256",
+            HashAlg::Sha384 => "SHA384",
             HashAlg::Sha512 => "SHA512",
         }
     }
@@ -94,6 +131,7 @@ impl Fingerprint {
     fn footer(self) -> &'static str {
         match self.algorithm() {
             HashAlg::Sha256 => "[SHA256]",
+            HashAlg::Sha384 => "[SHA384]",
             HashAlg::Sha512 => "[SHA512]",
         }
     }
@@ -102,6 +140,7 @@ impl Fingerprint {
     pub fn as_bytes(&self) -> &[u8] {
         match self {
             Self::Sha256(bytes) => bytes.as_slice(),
+            Self::Sha384(bytes) => bytes.as_slice(),
             Self::Sha512(bytes) => bytes.as_slice(),
         }
     }
@@ -114,6 +153,14 @@ impl Fingerprint {
         }
     }
 
+    /// Get the SHA-384 fingerprint, if this is one.
+    pub fn sha384(self) -> Option<[u8; HashAlg::Sha384.digest_size()]> {
+        match self {
+            Self::Sha384(fingerprint) => Some(fingerprint),
+            _ => None,
+        }
+    }
+
     /// Get the SHA-512 fingerprint, if this is one.
     pub fn sha512(self) -> Option<[u8; HashAlg::Sha512.digest_size()]> {
         match self {
@@ -127,6 +174,11 @@ impl Fingerprint {
         matches!(self, Self::Sha256(_))
     }
 
+    /// Is this fingerprint SHA-384?
+    pub fn is_sha384(self) -> bool {
+        matches!(self, Self::Sha384(_))
+    }
+
     /// Is this fingerprint SHA-512?
     pub fn is_sha512(self) -> bool {
         matches!(self, Self::Sha512(_))
@@ -137,7 +189,23 @@ impl Fingerprint {
         Randomart::new(header, self).fmt(f)
     }
 
-    /// Render "randomart" hash visualization for this fingerprint as a string.
+    /// Write "randomart" for this fingerprint to the provided writer, e.g. a
+    /// fixed-size buffer in `no_std` environments, without allocating.
+    ///
+    /// The output is identical to [`Fingerprint::to_randomart`], and has no
+    /// trailing newline.
+    pub fn write_randomart(self, header: &str, out: &mut impl fmt::Write) -> fmt::Result {
+        write!(out, "{}", Randomart::new(header, self))
+    }
+
+    /// Render "randomart" hash visualization for this fingerprint as a string,
+    /// as printed by `ssh-keygen -lv`.
+    ///
+    /// The header is conventionally the key type and size (e.g.
+    /// `[ED25519 256]`), and is truncated to 16 characters if it doesn't fit
+    /// in the top border. `ssh-keygen` avoids this by omitting the size when
+    /// it wouldn't fit, e.g. `[ED25519-CERT]`. The footer is the
+    /// fingerprint's hash algorithm.
     ///
     /// ```text
     /// +--[ED25519 256]--+
@@ -156,6 +224,33 @@ impl Fingerprint {
     pub fn to_randomart(self, header: &str) -> String {
         Randomart::new(header, self).to_string()
     }
+
+    /// Render this fingerprint with the middle of its Base64 encoding elided,
+    /// e.g. `SHA256:UCUi…cIQ`, for logging in contexts where the full
+    /// fingerprint shouldn't be recorded.
+    ///
+    /// Retains [`Fingerprint::DEFAULT_MASKED_CHARS`] characters at each end.
+    #[cfg(feature = "alloc")]
+    pub fn masked(self) -> String {
+        self.masked_with(Self::DEFAULT_MASKED_CHARS)
+    }
+
+    /// Render this fingerprint retaining `chars` Base64 characters at each
+    /// end of its encoding, with the middle elided.
+    ///
+    /// To ensure most of the fingerprint is always elided, at most a quarter
+    /// of the encoding is retained at each end regardless of `chars`.
+    #[cfg(feature = "alloc")]
+    pub fn masked_with(self, chars: usize) -> String {
+        let mut buf = [0u8; Self::SHA512_BASE64_SIZE];
+        let base64 = Base64Unpadded::encode(self.as_bytes(), &mut buf).expect(FINGERPRINT_ERR_MSG);
+        let chars = chars.min(base64.len() / 4);
+
+        // Base64 is ASCII, so these are always character boundaries
+        let head = &base64[..chars];
+        let tail = &base64[base64.len().saturating_sub(chars)..];
+        format!("{}:{head}\u{2026}{tail}", self.prefix())
+    }
 }
 
 impl AsRef<[u8]> for Fingerprint {
@@ -184,6 +279,7 @@ impl FromStr for Fingerprint {
         // Fingerprints use a special upper-case hash algorithm encoding.
         let algorithm = match alg_str {
             "SHA256" => HashAlg::Sha256,
+            "SHA384" => HashAlg::Sha384,
             "SHA512" => HashAlg::Sha512,
             _ => return Err(Error::AlgorithmUnknown),
         };
@@ -192,8 +288,13 @@ impl FromStr for Fingerprint {
         let mut buf = [0u8; HashAlg::Sha512.digest_size()];
         let decoded_bytes = Base64Unpadded::decode(base64, &mut buf)?;
 
+        if decoded_bytes.len() != algorithm.digest_size() {
+            return Err(encoding::Error::Length.into());
+        }
+
         match algorithm {
             HashAlg::Sha256 => Ok(Self::Sha256(decoded_bytes.try_into()?)),
+            HashAlg::Sha384 => Ok(Self::Sha384(decoded_bytes.try_into()?)),
             HashAlg::Sha512 => Ok(Self::Sha512(decoded_bytes.try_into()?)),
         }
     }
@@ -25,8 +25,17 @@ pub(super) struct Randomart<'a> {
 
 impl<'a> Randomart<'a> {
     /// Create new "randomart" from the given fingerprint.
-    #[allow(clippy::integer_arithmetic)]
-    pub(super) fn new(header: &'a str, fingerprint: Fingerprint) -> Self {
+    ///
+    /// Headers which don't fit in the top border are truncated to 16
+    /// characters, as by OpenSSH's fixed-size title buffer.
+    #[allow(clippy::arithmetic_side_effects)]
+    pub(super) fn new(mut header: &'a str, fingerprint: Fingerprint) -> Self {
+        while header.chars().count() >= WIDTH {
+            let mut chars = header.chars();
+            chars.next_back();
+            header = chars.as_str();
+        }
+
         let mut field = Field::default();
         let mut x = WIDTH / 2;
         let mut y = HEIGHT / 2;
@@ -103,10 +112,53 @@ mod tests {
 |  .o*            |
 +----[SHA256]-----+";
 
+    /// Output of `ssh-keygen -lv -E sha512 -f id_ed25519-cert.pub`.
+    const EXAMPLE_SHA512_FINGERPRINT: &str = "SHA512:8Hm4eNTiYk9l6WR85IqY+UK4AXajRf5auLD/QIxCZQLwGpDiEDiXZ0ZttPuhyG8IHsWbt2zu8G0+G6VIVXoHhA";
+    const EXAMPLE_SHA512_RANDOMART: &str = "\
++-[ED25519-CERT]--+
+|O+ =.o.  o+      |
+|B.* = o.Eo .     |
+|=+.B .o o . o    |
+|.+ooB  = * =     |
+|o..*oBo S @ o    |
+| .=o*o=@ # o     |
+| . ==@X.X o      |
+|  o =**Bo.       |
+|   ..**+*o       |
++----[SHA512]-----+";
+
     #[test]
     fn generation() {
         let fingerprint = EXAMPLE_FINGERPRINT.parse::<Fingerprint>().unwrap();
         let randomart = fingerprint.to_randomart("[ED25519 256]");
         assert_eq!(EXAMPLE_RANDOMART, randomart);
     }
+
+    #[test]
+    fn generation_sha512() {
+        let fingerprint = EXAMPLE_SHA512_FINGERPRINT.parse::<Fingerprint>().unwrap();
+        let randomart = fingerprint.to_randomart("[ED25519-CERT]");
+        assert_eq!(EXAMPLE_SHA512_RANDOMART, randomart);
+    }
+
+    #[test]
+    fn truncated_header() {
+        let fingerprint = EXAMPLE_SHA512_FINGERPRINT.parse::<Fingerprint>().unwrap();
+        let randomart = fingerprint.to_randomart("[ED25519-CERT 256]");
+        assert_eq!(randomart.lines().next(), Some("+[ED25519-CERT 25-+"));
+        assert!(randomart
+            .lines()
+            .skip(1)
+            .eq(EXAMPLE_SHA512_RANDOMART.lines().skip(1)));
+    }
+
+    #[test]
+    fn write_randomart() {
+        let fingerprint = EXAMPLE_FINGERPRINT.parse::<Fingerprint>().unwrap();
+        let mut randomart = alloc::string::String::new();
+        fingerprint
+            .write_randomart("[ED25519 256]", &mut randomart)
+            .unwrap();
+        assert_eq!(EXAMPLE_RANDOMART, randomart);
+    }
 }
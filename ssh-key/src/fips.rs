@@ -0,0 +1,130 @@
+//! Policy restricting keys and signatures to FIPS-approved algorithms.
+
+use crate::{
+    public::{mpint_bits, KeyData},
+    Algorithm, EcdsaCurve, HashAlg, Result, VerificationConfig,
+};
+use alloc::vec::Vec;
+
+#[cfg(feature = "rand_core")]
+use {crate::PrivateKey, rand_core::CryptoRngCore};
+
+/// Policy restricting key generation and signature verification to
+/// algorithms approved under FIPS 186-5, as with OpenSSH built against a
+/// FIPS-validated cryptography module.
+///
+/// Approved algorithms are:
+///
+/// - ECDSA using the NIST P-256, P-384 and P-521 curves
+/// - RSA with a modulus of at least 2048 bits, signing with SHA-256 or
+///   SHA-512 (i.e. not `ssh-rsa`, which uses SHA-1)
+/// - Ed25519, only if [`FipsPolicy::allow_ed25519`] is set
+///
+/// DSA and security key (FIDO/U2F) algorithms are never approved. Disallowed
+/// algorithms are rejected with [`Error::AlgorithmUnsupported`][`crate::Error::AlgorithmUnsupported`].
+///
+/// This only restricts which algorithms are used: it does not make this
+/// crate a FIPS-validated implementation.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct FipsPolicy {
+    /// Allow Ed25519, which is approved by FIPS 186-5 but not by all FIPS
+    /// modules.
+    ///
+    /// Default: `false`.
+    pub allow_ed25519: bool,
+}
+
+impl FipsPolicy {
+    /// Minimum RSA modulus size in bits.
+    pub const MIN_RSA_BITS: usize = 2048;
+
+    /// Is the given key algorithm approved by this policy?
+    ///
+    /// RSA keys are identified by [`Algorithm::Rsa`] with any hash, however
+    /// their size must also be checked using [`FipsPolicy::check_key`].
+    pub fn allows_key(&self, algorithm: Algorithm) -> bool {
+        match algorithm {
+            Algorithm::Ecdsa { .. } | Algorithm::Rsa { .. } => true,
+            Algorithm::Ed25519 => self.allow_ed25519,
+            _ => false,
+        }
+    }
+
+    /// Check the given public key is approved by this policy.
+    ///
+    /// # Returns
+    /// - [`Error::AlgorithmUnsupported`][`crate::Error::AlgorithmUnsupported`]
+    ///   if the key's algorithm isn't approved, or it's an RSA key smaller
+    ///   than [`FipsPolicy::MIN_RSA_BITS`].
+    pub fn check_key(&self, key: &KeyData) -> Result<()> {
+        let algorithm = key.algorithm();
+
+        let approved = match key {
+            KeyData::Rsa(rsa) => mpint_bits(&rsa.n) >= Self::MIN_RSA_BITS,
+            _ => self.allows_key(algorithm.clone()),
+        };
+
+        if approved {
+            Ok(())
+        } else {
+            Err(algorithm.unsupported_error())
+        }
+    }
+
+    /// Get the [`VerificationConfig`] which accepts only the signature
+    /// algorithms approved by this policy.
+    ///
+    /// Keys which aren't approved (see [`FipsPolicy::check_key`]) are also
+    /// rejected, by way of the minimum RSA key size and allowed algorithms.
+    pub fn verification_config(&self) -> VerificationConfig {
+        let mut algorithms: Vec<Algorithm> = [
+            EcdsaCurve::NistP256,
+            EcdsaCurve::NistP384,
+            EcdsaCurve::NistP521,
+        ]
+        .into_iter()
+        .map(|curve| Algorithm::Ecdsa { curve })
+        .chain(
+            [HashAlg::Sha256, HashAlg::Sha512]
+                .into_iter()
+                .map(|hash| Algorithm::Rsa { hash: Some(hash) }),
+        )
+        .collect();
+
+        if self.allow_ed25519 {
+            algorithms.push(Algorithm::Ed25519);
+        }
+
+        VerificationConfig {
+            min_rsa_bits: Self::MIN_RSA_BITS,
+            allowed_algorithms: Some(algorithms),
+            ..Default::default()
+        }
+    }
+
+    /// Generate a random key which uses the given algorithm, if it's approved
+    /// by this policy.
+    ///
+    /// RSA keys are generated with the same (approved) size as
+    /// [`PrivateKey::random`].
+    ///
+    /// # Returns
+    /// - [`Error::AlgorithmUnsupported`][`crate::Error::AlgorithmUnsupported`]
+    ///   if the algorithm isn't approved.
+    /// - See [`PrivateKey::random`] for other errors.
+    #[cfg(feature = "rand_core")]
+    pub fn random_private_key(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        algorithm: Algorithm,
+    ) -> Result<PrivateKey> {
+        if !self.allows_key(algorithm.clone()) {
+            return Err(algorithm.unsupported_error());
+        }
+
+        let key = PrivateKey::random(rng, algorithm)?;
+        self.check_key(key.public_key().key_data())?;
+        Ok(key)
+    }
+}
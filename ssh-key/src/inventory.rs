@@ -0,0 +1,169 @@
+//! Inventory of the identities of public keys and certificates.
+
+use crate::{
+    authorized_keys::{AuthorizedKeys, Entry},
+    detect_format, Certificate, Fingerprint, HashAlg, KeyFormat, PublicKey, Result,
+};
+use alloc::{string::String, vec::Vec};
+use core::ops::Range;
+
+#[cfg(feature = "std")]
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Identifying metadata of a public key or certificate, e.g. for keeping an
+/// inventory of the keys deployed across a fleet.
+///
+/// For certificates, the key ID, principals and validity window are surfaced
+/// in addition to the comment and fingerprint of the subject public key.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Identity {
+    /// Public key, or the subject public key of the certificate.
+    public_key: PublicKey,
+
+    /// Certificate, if any.
+    certificate: Option<Certificate>,
+}
+
+impl Identity {
+    /// Parse the identities of all of the OpenSSH-formatted public keys and
+    /// certificates in the given input, one per line, as found in e.g. `.pub`
+    /// or `authorized_keys` files.
+    ///
+    /// Blank lines and comment lines are ignored. An [RFC4716] public key is
+    /// also accepted, yielding a single identity.
+    ///
+    /// [RFC4716]: https://datatracker.ietf.org/doc/html/rfc4716
+    pub fn parse(input: &str) -> Result<Vec<Self>> {
+        if detect_format(input) == KeyFormat::Rfc4716PublicKey {
+            return Ok(vec![PublicKey::from_rfc4716(input)?.into()]);
+        }
+
+        AuthorizedKeys::new(input)
+            .map(|entry| entry.map(Self::from))
+            .collect()
+    }
+
+    /// Read the identities of all of the keys and certificates in the given
+    /// file. See [`Identity::parse`].
+    #[cfg(feature = "std")]
+    pub fn read_file(path: impl AsRef<Path>) -> Result<Vec<Self>> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// Read the identities of all of the keys and certificates in the `.pub`
+    /// files in the given directory, along with the path of the file each was
+    /// read from.
+    ///
+    /// Files are read in order of their paths, and subdirectories are not
+    /// searched.
+    #[cfg(feature = "std")]
+    pub fn read_dir(path: impl AsRef<Path>) -> Result<Vec<(PathBuf, Self)>> {
+        let mut paths = Vec::new();
+
+        for dir_entry in fs::read_dir(path)? {
+            let path = dir_entry?.path();
+
+            if path.is_file() && path.extension().map_or(false, |ext| ext == "pub") {
+                paths.push(path);
+            }
+        }
+
+        paths.sort();
+
+        let mut identities = Vec::new();
+
+        for path in paths {
+            for identity in Self::read_file(&path)? {
+                identities.push((path.clone(), identity));
+            }
+        }
+
+        Ok(identities)
+    }
+
+    /// Get the public key, i.e. the subject public key for certificates.
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Get the certificate, if this identity is for one.
+    pub fn certificate(&self) -> Option<&Certificate> {
+        self.certificate.as_ref()
+    }
+
+    /// Comment on the key or certificate (e.g. email address).
+    pub fn comment(&self) -> &str {
+        self.public_key.comment()
+    }
+
+    /// Name to display for this identity, as OpenSSH tools do: the key ID for
+    /// certificates, or the comment for bare public keys.
+    ///
+    /// The comment of a certificate is still available via
+    /// [`Identity::comment`], and is what [`Certificate::to_openssh`] writes
+    /// after the certificate data.
+    pub fn display_name(&self) -> &str {
+        self.key_id().unwrap_or_else(|| self.comment())
+    }
+
+    /// Compute the fingerprint of the public key.
+    ///
+    /// For certificates, this is the fingerprint of the subject public key.
+    pub fn fingerprint(&self, hash_alg: HashAlg) -> Fingerprint {
+        self.public_key.fingerprint(hash_alg)
+    }
+
+    /// Key ID of the certificate, if this identity is for one.
+    pub fn key_id(&self) -> Option<&str> {
+        self.certificate.as_ref().map(Certificate::key_id)
+    }
+
+    /// Valid principals of the certificate.
+    ///
+    /// Empty for bare public keys.
+    pub fn valid_principals(&self) -> &[String] {
+        self.certificate
+            .as_ref()
+            .map(Certificate::valid_principals)
+            .unwrap_or_default()
+    }
+
+    /// Validity window of the certificate as Unix times, i.e. `valid_after`
+    /// (inclusive) to `valid_before` (exclusive), if this identity is for
+    /// one.
+    pub fn validity(&self) -> Option<Range<u64>> {
+        self.certificate
+            .as_ref()
+            .map(|cert| cert.valid_after()..cert.valid_before())
+    }
+}
+
+impl From<Entry> for Identity {
+    fn from(entry: Entry) -> Identity {
+        Identity {
+            public_key: entry.public_key().clone(),
+            certificate: entry.certificate().cloned(),
+        }
+    }
+}
+
+impl From<PublicKey> for Identity {
+    fn from(public_key: PublicKey) -> Identity {
+        Identity {
+            public_key,
+            certificate: None,
+        }
+    }
+}
+
+impl From<Certificate> for Identity {
+    fn from(certificate: Certificate) -> Identity {
+        Identity {
+            public_key: PublicKey::new(certificate.public_key().clone(), certificate.comment()),
+            certificate: Some(certificate),
+        }
+    }
+}
@@ -6,24 +6,30 @@ use crate::{Error, KdfAlg, Result};
 use encoding::{CheckedSum, Decode, Encode, Reader, Writer};
 
 #[cfg(feature = "alloc")]
-use alloc::vec::Vec;
+use alloc::{string::String, vec::Vec};
 
 #[cfg(feature = "encryption")]
 use {crate::Cipher, bcrypt_pbkdf::bcrypt_pbkdf, rand_core::CryptoRngCore, zeroize::Zeroizing};
 
 /// Default number of rounds to use for bcrypt-pbkdf.
 #[cfg(feature = "encryption")]
-const DEFAULT_BCRYPT_ROUNDS: u32 = 16;
+pub(crate) const DEFAULT_BCRYPT_ROUNDS: u32 = 16;
 
 /// Default salt size. Matches OpenSSH.
 #[cfg(feature = "encryption")]
-const DEFAULT_SALT_SIZE: usize = 16;
+pub(crate) const DEFAULT_SALT_SIZE: usize = 16;
+
+/// Maximum combined key and IV size of any [`Cipher`], i.e. that of
+/// `chacha20-poly1305@openssh.com`.
+#[cfg(feature = "encryption")]
+const MAX_KEY_AND_IV_SIZE: usize = 64;
 
 /// Key Derivation Functions (KDF).
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 #[non_exhaustive]
 pub enum Kdf {
     /// No KDF.
+    #[default]
     None,
 
     /// bcrypt-pbkdf options.
@@ -35,6 +41,19 @@ pub enum Kdf {
         /// Rounds
         rounds: u32,
     },
+
+    /// Unrecognized KDF.
+    ///
+    /// The raw `kdfoptions` are retained so the key can be re-encoded
+    /// unchanged, however keys using this KDF can't be decrypted.
+    #[cfg(feature = "alloc")]
+    Other {
+        /// KDF name (i.e. `kdfname`)
+        name: String,
+
+        /// Raw KDF options (i.e. `kdfoptions`)
+        options: Vec<u8>,
+    },
 }
 
 impl Kdf {
@@ -53,6 +72,7 @@ impl Kdf {
                 salt,
                 rounds: DEFAULT_BCRYPT_ROUNDS,
             }),
+            KdfAlg::Other(_) => Err(Error::AlgorithmUnknown),
         }
     }
 
@@ -62,6 +82,19 @@ impl Kdf {
             Self::None => KdfAlg::None,
             #[cfg(feature = "alloc")]
             Self::Bcrypt { .. } => KdfAlg::Bcrypt,
+            #[cfg(feature = "alloc")]
+            Self::Other { name, .. } => KdfAlg::Other(name.clone()),
+        }
+    }
+
+    /// Get the KDF name (i.e. `kdfname`), including for unrecognized KDFs.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::None => KdfAlg::None.as_str(),
+            #[cfg(feature = "alloc")]
+            Self::Bcrypt { .. } => KdfAlg::Bcrypt.as_str(),
+            #[cfg(feature = "alloc")]
+            Self::Other { name, .. } => name,
         }
     }
 
@@ -74,9 +107,27 @@ impl Kdf {
                 bcrypt_pbkdf(password, salt, *rounds, output).map_err(|_| Error::Crypto)?;
                 Ok(())
             }
+            Kdf::Other { .. } => Err(Error::AlgorithmUnknown),
         }
     }
 
+    /// Derive `output_len` bytes of key material from the given password,
+    /// allocating a self-zeroizing buffer for the result.
+    ///
+    /// For use with a [`Cipher`], `output_len` should be the sum of its key
+    /// and IV sizes (see [`Cipher::key_and_iv_size`]), with the key followed
+    /// by the IV in the output.
+    #[cfg(feature = "encryption")]
+    pub fn derive_key(
+        &self,
+        password: impl AsRef<[u8]>,
+        output_len: usize,
+    ) -> Result<Zeroizing<Vec<u8>>> {
+        let mut okm = Zeroizing::new(vec![0u8; output_len]);
+        self.derive(password, &mut okm)?;
+        Ok(okm)
+    }
+
     /// Derive key and IV for the given [`Cipher`].
     ///
     /// Returns two byte vectors containing the key and IV respectively.
@@ -86,15 +137,32 @@ impl Kdf {
         cipher: Cipher,
         password: impl AsRef<[u8]>,
     ) -> Result<(Zeroizing<Vec<u8>>, Vec<u8>)> {
+        let okm = self.derive_cipher_key(cipher, password)?;
+        Ok((Zeroizing::new(okm.key().to_vec()), okm.iv().to_vec()))
+    }
+
+    /// Derive key and IV for the given [`Cipher`] into a stack buffer,
+    /// avoiding the allocations of [`Kdf::derive_key_and_iv`].
+    #[cfg(feature = "encryption")]
+    pub(crate) fn derive_cipher_key(
+        &self,
+        cipher: Cipher,
+        password: impl AsRef<[u8]>,
+    ) -> Result<CipherKey> {
         let (key_size, iv_size) = cipher.key_and_iv_size().ok_or(Error::Decrypted)?;
         let okm_size = key_size
             .checked_add(iv_size)
+            .filter(|&size| size <= MAX_KEY_AND_IV_SIZE)
             .ok_or(encoding::Error::Length)?;
 
-        let mut okm = Zeroizing::new(vec![0u8; okm_size]);
-        self.derive(password, &mut okm)?;
-        let iv = okm.split_off(key_size);
-        Ok((okm, iv))
+        let mut okm = CipherKey {
+            okm: Zeroizing::new([0u8; MAX_KEY_AND_IV_SIZE]),
+            key_size,
+            okm_size,
+        };
+
+        self.derive(password, &mut okm.okm[..okm_size])?;
+        Ok(okm)
     }
 
     /// Is the KDF configured as `none`?
@@ -114,9 +182,29 @@ impl Kdf {
     }
 }
 
-impl Default for Kdf {
-    fn default() -> Self {
-        Self::None
+/// Key and IV derived for a [`Cipher`] by [`Kdf::derive_cipher_key`].
+#[cfg(feature = "encryption")]
+pub(crate) struct CipherKey {
+    /// Derived key material: the key followed by the IV.
+    okm: Zeroizing<[u8; MAX_KEY_AND_IV_SIZE]>,
+
+    /// Size of the key in bytes.
+    key_size: usize,
+
+    /// Combined size of the key and IV in bytes.
+    okm_size: usize,
+}
+
+#[cfg(feature = "encryption")]
+impl CipherKey {
+    /// Get the key.
+    pub(crate) fn key(&self) -> &[u8] {
+        &self.okm[..self.key_size]
+    }
+
+    /// Get the IV.
+    pub(crate) fn iv(&self) -> &[u8] {
+        &self.okm[self.key_size..self.okm_size]
     }
 }
 
@@ -124,12 +212,32 @@ impl Decode for Kdf {
     type Error = Error;
 
     fn decode(reader: &mut impl Reader) -> Result<Self> {
-        match KdfAlg::decode(reader)? {
+        #[cfg(not(feature = "alloc"))]
+        let algorithm = KdfAlg::decode(reader)?;
+
+        #[cfg(feature = "alloc")]
+        let algorithm = {
+            let name = String::decode(reader)?;
+
+            match KdfAlg::new(&name) {
+                Ok(algorithm) => algorithm,
+                Err(_) => {
+                    // Retain the options of unrecognized KDFs for re-encoding
+                    return Ok(Self::Other {
+                        name,
+                        options: Vec::decode(reader)?,
+                    });
+                }
+            }
+        };
+
+        match algorithm {
             KdfAlg::None => {
+                // The `none` KDF must be followed by an empty `kdfoptions` string
                 if usize::decode(reader)? == 0 {
                     Ok(Self::None)
                 } else {
-                    Err(Error::AlgorithmUnknown)
+                    Err(Error::FormatEncoding)
                 }
             }
             KdfAlg::Bcrypt => {
@@ -144,6 +252,8 @@ impl Decode for Kdf {
                     })
                 })
             }
+            #[cfg(feature = "alloc")]
+            KdfAlg::Other(_) => Err(Error::AlgorithmUnknown),
         }
     }
 }
@@ -156,13 +266,15 @@ impl Encode for Kdf {
             Self::None => 4,
             #[cfg(feature = "alloc")]
             Self::Bcrypt { salt, .. } => [12, salt.len()].checked_sum()?,
+            #[cfg(feature = "alloc")]
+            Self::Other { options, .. } => options.encoded_len()?,
         };
 
-        Ok([self.algorithm().encoded_len()?, kdfopts_prefixed_len].checked_sum()?)
+        Ok([self.name().encoded_len()?, kdfopts_prefixed_len].checked_sum()?)
     }
 
     fn encode(&self, writer: &mut impl Writer) -> Result<()> {
-        self.algorithm().encode(writer)?;
+        self.name().encode(writer)?;
 
         match self {
             Self::None => 0usize.encode(writer)?,
@@ -172,6 +284,8 @@ impl Encode for Kdf {
                 salt.encode(writer)?;
                 rounds.encode(writer)?
             }
+            #[cfg(feature = "alloc")]
+            Self::Other { options, .. } => options.encode(writer)?,
         }
 
         Ok(())
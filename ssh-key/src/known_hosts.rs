@@ -1,6 +1,6 @@
 //! Parser for `KnownHostsFile`-formatted data.
 
-use crate::{Error, PublicKey, Result};
+use crate::{authorized_keys::trim_line, certificate::match_pattern, Error, PublicKey, Result};
 use core::str;
 use encoding::base64::{Base64, Encoding};
 
@@ -10,13 +10,29 @@ use {
     core::fmt,
 };
 
+#[cfg(feature = "hashed-hosts")]
+use {
+    hmac::{Hmac, Mac},
+    sha1::Sha1,
+};
+
+#[cfg(all(feature = "hashed-hosts", feature = "rand_core"))]
+use rand_core::CryptoRngCore;
+
 #[cfg(feature = "std")]
-use std::{fs, path::Path};
+use {
+    crate::lines::LineReader,
+    std::{fs, io::BufRead, path::Path},
+};
 
 /// Character that begins a comment
 const COMMENT_DELIMITER: char = '#';
 /// The magic string prefix of a hashed hostname
 const MAGIC_HASH_PREFIX: &str = "|1|";
+/// Default SSH port, for which hostnames are not bracketed
+const DEFAULT_PORT: u16 = 22;
+/// Size of the salt and hash of a hashed hostname
+const HASH_SIZE: usize = 20;
 
 /// Parser for `KnownHostsFile`-formatted data, typically found in
 /// `~/.ssh/known_hosts`.
@@ -46,7 +62,7 @@ const MAGIC_HASH_PREFIX: &str = "|1|";
 ///   the key).
 pub struct KnownHosts<'a> {
     /// Lines of the file being iterated over
-    lines: core::str::Lines<'a>,
+    lines: str::Lines<'a>,
 }
 
 impl<'a> KnownHosts<'a> {
@@ -96,6 +112,160 @@ impl Iterator for KnownHosts<'_> {
     }
 }
 
+/// Streaming parser for `known_hosts` files read from an
+/// [`std::io::BufRead`], e.g. a [`std::io::BufReader`] wrapping a [`fs::File`].
+///
+/// Unlike [`KnownHosts`], the file is never read into memory in full: lines
+/// are read one at a time into a reused buffer. Comments and blank lines are
+/// skipped.
+///
+/// Lines which fail to parse are reported as [`Error::LineInvalid`] with
+/// their (1-based) line number, and iteration continues with the next line.
+/// I/O errors are reported once, after which iteration ends.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct KnownHostsReader<R> {
+    /// Lines of the file being iterated over
+    lines: LineReader<R>,
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> KnownHostsReader<R> {
+    /// Create a new parser which reads from the given reader.
+    pub fn new(reader: R) -> Self {
+        Self {
+            lines: LineReader::new(reader),
+        }
+    }
+
+    /// Get the inner reader.
+    pub fn into_inner(self) -> R {
+        self.lines.into_inner()
+    }
+
+    /// Number of the last line which was read, starting from 1.
+    pub fn line_number(&self) -> usize {
+        self.lines.line_number()
+    }
+
+    /// Read the next entry without allocating, as an [`EntryRef`] borrowing
+    /// from this reader's line buffer.
+    pub fn next_ref(&mut self) -> Option<Result<EntryRef<'_>>> {
+        Some(self.lines.next_line()?.and_then(|(number, line)| {
+            EntryRef::parse(line).map_err(|_| Error::LineInvalid { number })
+        }))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: BufRead> Iterator for KnownHostsReader<R> {
+    type Item = Result<Entry>;
+
+    fn next(&mut self) -> Option<Result<Entry>> {
+        Some(
+            self.lines
+                .next_line()?
+                .and_then(|(number, line)| line.parse().map_err(|_| Error::LineInvalid { number })),
+        )
+    }
+}
+
+/// Borrowed view of a single entry in a `known_hosts` file, which splits a
+/// line into its fields without allocating.
+///
+/// The marker is parsed, but the host patterns and key aren't decoded until
+/// [`EntryRef::to_entry`] is called.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EntryRef<'a> {
+    /// Line containing the entry, with any comment and trailing whitespace
+    /// removed.
+    line: &'a str,
+
+    /// Marker field, if present.
+    marker: Option<Marker>,
+
+    /// Host patterns field.
+    host_patterns: &'a str,
+
+    /// Key type, i.e. the algorithm identifier.
+    key_type: &'a str,
+
+    /// Base64-encoded key.
+    key_base64: &'a str,
+
+    /// Comment of the key, if present.
+    comment: &'a str,
+}
+
+impl<'a> EntryRef<'a> {
+    /// Parse the given line of a `known_hosts` file.
+    pub fn parse(line: &'a str) -> Result<Self> {
+        let line = trim_line(line);
+
+        // See the `FromStr` impl on `Entry`
+        let (marker, rest) = if line.starts_with('@') {
+            let (marker, rest) = line.split_once(' ').ok_or(Error::FormatEncoding)?;
+            (Some(marker.parse()?), rest)
+        } else {
+            (None, line)
+        };
+
+        let (host_patterns, rest) = rest.split_once(' ').ok_or(Error::FormatEncoding)?;
+        let (key_type, rest) = rest.split_once(' ').ok_or(Error::FormatEncoding)?;
+        let (key_base64, comment) = rest.split_once(' ').unwrap_or((rest, ""));
+
+        if host_patterns.is_empty() || key_type.is_empty() || key_base64.is_empty() {
+            return Err(Error::FormatEncoding);
+        }
+
+        Ok(Self {
+            line,
+            marker,
+            host_patterns,
+            key_type,
+            key_base64,
+            comment,
+        })
+    }
+
+    /// Get the line containing this entry, without any comment or trailing
+    /// whitespace.
+    pub fn line(&self) -> &'a str {
+        self.line
+    }
+
+    /// Get the marker for this entry, if present.
+    pub fn marker(&self) -> Option<Marker> {
+        self.marker
+    }
+
+    /// Get the host patterns field of this entry, e.g. `example.com,*.example.org`
+    /// or a hashed hostname.
+    pub fn host_patterns(&self) -> &'a str {
+        self.host_patterns
+    }
+
+    /// Get the key type of this entry, e.g. `ssh-ed25519`.
+    pub fn key_type(&self) -> &'a str {
+        self.key_type
+    }
+
+    /// Get the Base64-encoded key of this entry.
+    pub fn key_base64(&self) -> &'a str {
+        self.key_base64
+    }
+
+    /// Get the comment of this entry's key, which may be empty.
+    pub fn comment(&self) -> &'a str {
+        self.comment
+    }
+
+    /// Decode this entry's host patterns and key into an owned [`Entry`].
+    pub fn to_entry(&self) -> Result<Entry> {
+        self.line.parse()
+    }
+}
+
 /// Individual entry in an `known_hosts` file containing a single public key.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Entry {
@@ -110,6 +280,17 @@ pub struct Entry {
 }
 
 impl Entry {
+    /// Create a new entry, e.g. to add a host key to a `known_hosts` file.
+    ///
+    /// Use [`HostPatterns::hash`] to create a hashed entry.
+    pub fn new(marker: Option<Marker>, host_patterns: HostPatterns, public_key: PublicKey) -> Self {
+        Self {
+            marker,
+            host_patterns,
+            public_key,
+        }
+    }
+
     /// Get the marker for this entry, if present.
     pub fn marker(&self) -> Option<&Marker> {
         self.marker.as_ref()
@@ -124,6 +305,13 @@ impl Entry {
     pub fn public_key(&self) -> &PublicKey {
         &self.public_key
     }
+
+    /// Does this entry apply to the given host and port?
+    ///
+    /// See [`HostPatterns::matches`].
+    pub fn matches(&self, host: &str, port: u16) -> bool {
+        self.host_patterns.matches(host, port)
+    }
 }
 impl From<Entry> for Option<Marker> {
     fn from(entry: Entry) -> Option<Marker> {
@@ -169,27 +357,20 @@ impl str::FromStr for Entry {
     }
 }
 
-impl ToString for Entry {
-    fn to_string(&self) -> String {
-        let mut s = String::new();
-
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if let Some(marker) = &self.marker {
-            s.push_str(marker.as_str());
-            s.push(' ');
+            write!(f, "{} ", marker.as_str())?;
         }
 
-        s.push_str(&self.host_patterns.to_string());
-        s.push(' ');
-
-        s.push_str(&self.public_key.to_string());
-        s
+        write!(f, "{} {}", self.host_patterns, self.public_key)
     }
 }
 
 /// Markers associated with this host key entry.
 ///
 /// There can only be one of these per host key entry.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Marker {
     /// This host entry's public key is for a certificate authority's private key
     CertAuthority,
@@ -247,16 +428,79 @@ pub enum HostPatterns {
         /// The salt used for the hash
         salt: Vec<u8>,
         /// An SHA-1 hash of the hostname along with the salt
-        hash: [u8; 20],
+        hash: [u8; HASH_SIZE],
     },
 }
 
+impl HostPatterns {
+    /// Hash the given host and port with a random salt, as with OpenSSH's
+    /// `HashKnownHosts` option.
+    #[cfg(all(feature = "hashed-hosts", feature = "rand_core"))]
+    pub fn hash(rng: &mut impl CryptoRngCore, host: &str, port: u16) -> Self {
+        let mut salt = vec![0; HASH_SIZE];
+        rng.fill_bytes(&mut salt);
+        Self::hash_with_salt(host, port, salt)
+    }
+
+    /// Hash the given host and port with the given salt, which should be
+    /// random and 20 bytes long (see [`HostPatterns::hash`]).
+    ///
+    /// The hash is an HMAC-SHA-1 of the hostname keyed with the salt. The
+    /// hostname is lowercased and, for ports other than 22, given as
+    /// `[host]:port`.
+    #[cfg(feature = "hashed-hosts")]
+    pub fn hash_with_salt(host: &str, port: u16, salt: impl Into<Vec<u8>>) -> Self {
+        let salt = salt.into();
+        let hash = hash_host_name(&salt, &host_name(host, port));
+        Self::HashedName { salt, hash }
+    }
+
+    /// Do these patterns match the given host and port?
+    ///
+    /// As in OpenSSH, the host is lowercased and, for ports other than 22,
+    /// matched as `[host]:port`. Patterns may contain `*` and `?` wildcards,
+    /// and the host is rejected if it matches any negated (`!`) pattern.
+    ///
+    /// Hashed hostnames can only be matched when the `hashed-hosts` feature
+    /// is enabled, and otherwise never match.
+    pub fn matches(&self, host: &str, port: u16) -> bool {
+        let name = host_name(host, port);
+
+        match self {
+            Self::Patterns(patterns) => {
+                let mut matched = false;
+
+                for pattern in patterns {
+                    let (negated, pattern) = match pattern.strip_prefix('!') {
+                        Some(pattern) => (true, pattern),
+                        None => (false, pattern.as_str()),
+                    };
+
+                    if match_pattern(&name, &pattern.to_ascii_lowercase()) {
+                        if negated {
+                            return false;
+                        }
+
+                        matched = true;
+                    }
+                }
+
+                matched
+            }
+            #[cfg(feature = "hashed-hosts")]
+            Self::HashedName { salt, hash } => &hash_host_name(salt, &name) == hash,
+            #[cfg(not(feature = "hashed-hosts"))]
+            Self::HashedName { .. } => false,
+        }
+    }
+}
+
 impl str::FromStr for HostPatterns {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
         if let Some(s) = s.strip_prefix(MAGIC_HASH_PREFIX) {
-            let mut hash = [0; 20];
+            let mut hash = [0; HASH_SIZE];
             let (salt, hash_str) = s.split_once('|').ok_or(Error::FormatEncoding)?;
 
             let salt = Base64::decode_vec(salt)?;
@@ -273,19 +517,38 @@ impl str::FromStr for HostPatterns {
     }
 }
 
-impl ToString for HostPatterns {
-    fn to_string(&self) -> String {
+impl fmt::Display for HostPatterns {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match &self {
-            HostPatterns::Patterns(patterns) => patterns.join(","),
+            HostPatterns::Patterns(patterns) => f.write_str(&patterns.join(",")),
             HostPatterns::HashedName { salt, hash } => {
                 let salt = Base64::encode_string(salt);
                 let hash = Base64::encode_string(hash);
-                format!("|1|{salt}|{hash}")
+                write!(f, "|1|{salt}|{hash}")
             }
         }
     }
 }
 
+/// Get the name under which the given host and port are recorded.
+fn host_name(host: &str, port: u16) -> String {
+    let host = host.to_ascii_lowercase();
+
+    if port == DEFAULT_PORT {
+        host
+    } else {
+        format!("[{host}]:{port}")
+    }
+}
+
+/// Compute the hash of a hashed hostname.
+#[cfg(feature = "hashed-hosts")]
+fn hash_host_name(salt: &[u8], name: &str) -> [u8; HASH_SIZE] {
+    let mut mac = <Hmac<Sha1> as Mac>::new_from_slice(salt).expect("HMAC accepts any key size");
+    mac.update(name.as_bytes());
+    mac.finalize().into_bytes().into()
+}
+
 #[cfg(test)]
 mod tests {
     use alloc::string::ToString;
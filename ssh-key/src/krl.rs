@@ -0,0 +1,689 @@
+//! OpenSSH Key Revocation Lists (KRLs).
+//!
+//! Described in [PROTOCOL.krl](https://cvsweb.openbsd.org/src/usr.bin/ssh/PROTOCOL.krl?annotate=HEAD).
+
+use crate::{
+    certificate::Field, public::KeyData, Certificate, Error, Fingerprint, HashAlg, Mpint,
+    PublicKey, Result,
+};
+use alloc::{string::String, vec::Vec};
+use core::ops::RangeInclusive;
+use encoding::{CheckedSum, Decode, Encode, Reader, Writer};
+
+use sha1::{Digest, Sha1};
+
+#[cfg(feature = "std")]
+use std::{fs, path::Path};
+
+/// Magic bytes which begin a KRL: `SSHKRL\n\0`.
+const KRL_MAGIC: [u8; 8] = *b"SSHKRL\n\0";
+
+/// Supported KRL format version.
+const KRL_FORMAT_VERSION: u32 = 1;
+
+/// Section revoking certificates issued by a particular CA.
+const SECTION_CERTIFICATES: u8 = 1;
+
+/// Section revoking explicit public key blobs.
+const SECTION_EXPLICIT_KEY: u8 = 2;
+
+/// Section revoking public keys by SHA-1 hash of their blobs.
+const SECTION_FINGERPRINT_SHA1: u8 = 3;
+
+/// Section containing a signature over the preceding KRL.
+const SECTION_SIGNATURE: u8 = 4;
+
+/// Section revoking public keys by SHA-256 hash of their blobs.
+const SECTION_FINGERPRINT_SHA256: u8 = 5;
+
+/// Certificate subsection listing individual serial numbers.
+const CERT_SECTION_SERIAL_LIST: u8 = 0x20;
+
+/// Certificate subsection containing a range of serial numbers.
+const CERT_SECTION_SERIAL_RANGE: u8 = 0x21;
+
+/// Certificate subsection containing a bitmap of serial numbers.
+const CERT_SECTION_SERIAL_BITMAP: u8 = 0x22;
+
+/// Certificate subsection listing key IDs.
+const CERT_SECTION_KEY_ID: u8 = 0x23;
+
+/// Size of a SHA-1 digest in bytes.
+const SHA1_DIGEST_SIZE: usize = 20;
+
+/// OpenSSH Key Revocation List (KRL), as generated by `ssh-keygen -k`.
+///
+/// KRLs revoke plain public keys, either by their key blob or its SHA-1 or
+/// SHA-256 hash, and certificates, by serial number or key ID, scoped to the
+/// CA which issued them.
+///
+/// Signatures on KRLs are not verified, nor preserved when re-encoding.
+///
+/// New KRLs are created using [`KrlBuilder`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Krl {
+    /// Version of this KRL, incremented each time it is modified.
+    version: u64,
+
+    /// Time the KRL was generated, in seconds since the Unix epoch.
+    generated_date: u64,
+
+    /// Comment.
+    comment: String,
+
+    /// Revoked certificates, by issuing CA.
+    certificates: Vec<CertificateRevocations>,
+
+    /// Revoked public key blobs.
+    keys: Vec<KeyData>,
+
+    /// SHA-1 hashes of revoked public key blobs.
+    sha1_hashes: Vec<[u8; SHA1_DIGEST_SIZE]>,
+
+    /// SHA-256 hashes of revoked public key blobs.
+    sha256_hashes: Vec<[u8; HashAlg::Sha256.digest_size()]>,
+}
+
+impl Krl {
+    /// Parse a binary KRL.
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self> {
+        let reader = &mut bytes;
+        let krl = Self::decode(reader)?;
+        Ok(reader.finish(krl)?)
+    }
+
+    /// Read a binary KRL from a file.
+    #[cfg(feature = "std")]
+    pub fn read_file(path: impl AsRef<Path>) -> Result<Self> {
+        let input = fs::read(path)?;
+        Self::from_bytes(&input)
+    }
+
+    /// Encode this KRL in the binary format read by OpenSSH, e.g. via the
+    /// `RevokedKeys` option of `sshd_config`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(self.encoded_len()?);
+        self.encode(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Write this binary KRL to a file.
+    #[cfg(feature = "std")]
+    pub fn write_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        Ok(fs::write(path, self.to_bytes()?)?)
+    }
+
+    /// Get the version of this KRL.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Get the time this KRL was generated, in seconds since the Unix epoch.
+    pub fn generated_date(&self) -> u64 {
+        self.generated_date
+    }
+
+    /// Get the comment on this KRL.
+    pub fn comment(&self) -> &str {
+        &self.comment
+    }
+
+    /// Is the given public key revoked, either explicitly or by hash?
+    ///
+    /// See [`Krl::is_revoked_cert`] for certificates.
+    pub fn is_revoked(&self, public_key: &PublicKey) -> bool {
+        self.is_key_revoked(public_key.key_data())
+    }
+
+    /// Is the given certificate revoked?
+    ///
+    /// Checks the certificate's serial number and key ID against revocations
+    /// for its signing CA, as well as whether either its subject public key
+    /// or the CA key itself is revoked.
+    pub fn is_revoked_cert(&self, certificate: &Certificate) -> bool {
+        let ca_key = certificate.signature_key();
+
+        self.is_key_revoked(certificate.public_key())
+            || self.is_key_revoked(ca_key)
+            || self.is_serial_revoked(ca_key, certificate.serial())
+            || self.is_key_id_revoked(ca_key, certificate.key_id())
+    }
+
+    /// Is the certificate with the given serial number, issued by the given
+    /// CA, revoked?
+    ///
+    /// Always `false` for serial number 0, which can't be revoked by serial
+    /// number, as in OpenSSH.
+    pub fn is_serial_revoked(&self, ca_key: &KeyData, serial: u64) -> bool {
+        serial != 0
+            && self
+                .certificates_for(ca_key)
+                .any(|revocations| revocations.serials.iter().any(|r| r.contains(&serial)))
+    }
+
+    /// Is the certificate with the given key ID, issued by the given CA,
+    /// revoked?
+    pub fn is_key_id_revoked(&self, ca_key: &KeyData, key_id: &str) -> bool {
+        self.certificates_for(ca_key)
+            .any(|revocations| revocations.key_ids.iter().any(|id| id == key_id))
+    }
+
+    /// Is the given plain public key revoked, either explicitly or by hash?
+    pub fn is_key_revoked(&self, key: &KeyData) -> bool {
+        if self.keys.iter().any(|revoked| revoked == key) {
+            return true;
+        }
+
+        if let Some(hash) = key.fingerprint(HashAlg::Sha256).sha256() {
+            if self.sha256_hashes.contains(&hash) {
+                return true;
+            }
+        }
+
+        if !self.sha1_hashes.is_empty() {
+            let mut blob = Vec::new();
+
+            if key.encode(&mut blob).is_ok() {
+                let hash: [u8; SHA1_DIGEST_SIZE] = Sha1::digest(blob).into();
+                return self.sha1_hashes.contains(&hash);
+            }
+        }
+
+        false
+    }
+
+    /// Iterate over the certificate revocations which apply to the given CA,
+    /// including those which apply to any CA.
+    fn certificates_for<'a>(
+        &'a self,
+        ca_key: &'a KeyData,
+    ) -> impl Iterator<Item = &'a CertificateRevocations> {
+        self.certificates
+            .iter()
+            .filter(move |revocations| match &revocations.ca_key {
+                Some(key) => key == ca_key,
+                None => true,
+            })
+    }
+
+    /// Encode the sections of this KRL, as pairs of their type and contents.
+    fn sections(&self) -> Result<Vec<(u8, Vec<u8>)>> {
+        let mut sections = Vec::new();
+
+        for revocations in &self.certificates {
+            sections.push((SECTION_CERTIFICATES, revocations.encode_section()?));
+        }
+
+        if !self.keys.is_empty() {
+            let mut section = Vec::new();
+
+            for key in &self.keys {
+                key.encode_prefixed(&mut section)?;
+            }
+
+            sections.push((SECTION_EXPLICIT_KEY, section));
+        }
+
+        if !self.sha1_hashes.is_empty() {
+            sections.push((SECTION_FINGERPRINT_SHA1, encode_hashes(&self.sha1_hashes)?));
+        }
+
+        if !self.sha256_hashes.is_empty() {
+            sections.push((
+                SECTION_FINGERPRINT_SHA256,
+                encode_hashes(&self.sha256_hashes)?,
+            ));
+        }
+
+        Ok(sections)
+    }
+
+    /// Get the certificate revocations for the given CA (or any CA, if
+    /// `None`), adding them if there are none yet.
+    fn certificates_mut(&mut self, ca_key: Option<&KeyData>) -> &mut CertificateRevocations {
+        let position = self
+            .certificates
+            .iter()
+            .position(|revocations| revocations.ca_key.as_ref() == ca_key);
+
+        let index = match position {
+            Some(index) => index,
+            None => {
+                self.certificates.push(CertificateRevocations {
+                    ca_key: ca_key.cloned(),
+                    serials: Vec::new(),
+                    key_ids: Vec::new(),
+                });
+                self.certificates.len().saturating_sub(1)
+            }
+        };
+
+        &mut self.certificates[index]
+    }
+}
+
+impl Decode for Krl {
+    type Error = Error;
+
+    fn decode(reader: &mut impl Reader) -> Result<Self> {
+        let mut magic = [0u8; KRL_MAGIC.len()];
+        reader.read(&mut magic)?;
+
+        if magic != KRL_MAGIC || u32::decode(reader)? != KRL_FORMAT_VERSION {
+            return Err(Error::FormatEncoding);
+        }
+
+        let mut krl = Self {
+            version: u64::decode(reader)?,
+            generated_date: u64::decode(reader)?,
+            comment: String::new(),
+            certificates: Vec::new(),
+            keys: Vec::new(),
+            sha1_hashes: Vec::new(),
+            sha256_hashes: Vec::new(),
+        };
+
+        let _flags = u64::decode(reader)?;
+        reader.drain_prefixed()?;
+        krl.comment = String::decode(reader)?;
+
+        while !reader.is_finished() {
+            let section_type = u8::decode(reader)?;
+
+            if section_type == SECTION_SIGNATURE {
+                // Signing key and signature, which aren't verified. Unlike
+                // other sections, they aren't enclosed in a section string
+                reader.drain_prefixed()?;
+                reader.drain_prefixed()?;
+                continue;
+            }
+
+            reader.read_prefixed(|reader| {
+                match section_type {
+                    SECTION_CERTIFICATES => krl
+                        .certificates
+                        .push(CertificateRevocations::decode(reader)?),
+                    SECTION_EXPLICIT_KEY => {
+                        while !reader.is_finished() {
+                            krl.keys.push(reader.read_prefixed(KeyData::decode)?);
+                        }
+                    }
+                    SECTION_FINGERPRINT_SHA1 => {
+                        while !reader.is_finished() {
+                            krl.sha1_hashes.push(decode_hash(reader)?);
+                        }
+                    }
+                    SECTION_FINGERPRINT_SHA256 => {
+                        while !reader.is_finished() {
+                            krl.sha256_hashes.push(decode_hash(reader)?);
+                        }
+                    }
+                    _ => return Err(Error::FormatEncoding),
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(krl)
+    }
+}
+
+impl Encode for Krl {
+    type Error = Error;
+
+    fn encoded_len(&self) -> Result<usize> {
+        let mut len = [
+            KRL_MAGIC.len(),
+            KRL_FORMAT_VERSION.encoded_len()?,
+            self.version.encoded_len()?,
+            self.generated_date.encoded_len()?,
+            0u64.encoded_len()?,
+            [0u8; 0].encoded_len()?,
+            self.comment.encoded_len()?,
+        ]
+        .checked_sum()?;
+
+        for (section_type, section) in self.sections()? {
+            len = [len, section_type.encoded_len()?, section.encoded_len()?].checked_sum()?;
+        }
+
+        Ok(len)
+    }
+
+    fn encode(&self, writer: &mut impl Writer) -> Result<()> {
+        writer.write(&KRL_MAGIC)?;
+        KRL_FORMAT_VERSION.encode(writer)?;
+        self.version.encode(writer)?;
+        self.generated_date.encode(writer)?;
+
+        // Flags and reserved string
+        0u64.encode(writer)?;
+        [0u8; 0].encode(writer)?;
+        self.comment.encode(writer)?;
+
+        for (section_type, section) in self.sections()? {
+            section_type.encode(writer)?;
+            section.encode(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Builder for creating new [`Krl`]s.
+///
+/// ```
+/// # fn main() -> Result<(), ssh_key::Error> {
+/// use ssh_key::{KrlBuilder, PublicKey};
+///
+/// let ca_key = PublicKey::from_openssh(
+///     "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILM+rvN+ot98qgEN796jTiQfZfG1KaT0PtFDJ/XFSqti",
+/// )?;
+///
+/// let mut builder = KrlBuilder::new(1, 1700000000);
+/// builder
+///     .comment("revoked keys")?
+///     .revoke_serials(Some(ca_key.key_data()), 100..=199)?
+///     .revoke_key_id(Some(ca_key.key_data()), "alice@example.com")?;
+///
+/// let krl = builder.build();
+/// assert!(krl.is_serial_revoked(ca_key.key_data(), 150));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct KrlBuilder {
+    /// KRL being built.
+    krl: Krl,
+}
+
+impl KrlBuilder {
+    /// Create a new builder for a KRL with the given version and time of
+    /// generation (in seconds since the Unix epoch).
+    ///
+    /// The version should be incremented each time the KRL is regenerated.
+    pub fn new(version: u64, generated_date: u64) -> Self {
+        Self {
+            krl: Krl {
+                version,
+                generated_date,
+                comment: String::new(),
+                certificates: Vec::new(),
+                keys: Vec::new(),
+                sha1_hashes: Vec::new(),
+                sha256_hashes: Vec::new(),
+            },
+        }
+    }
+
+    /// Set the comment on the KRL.
+    pub fn comment(&mut self, comment: impl Into<String>) -> Result<&mut Self> {
+        self.krl.comment = comment.into();
+        Ok(self)
+    }
+
+    /// Revoke the given plain public key explicitly.
+    ///
+    /// This also revokes certificates issued by it, if it's a CA key.
+    pub fn revoke_key(&mut self, key: impl Into<KeyData>) -> Result<&mut Self> {
+        self.krl.keys.push(key.into());
+        Ok(self)
+    }
+
+    /// Revoke public keys by the given SHA-256 fingerprint (`ssh-keygen`'s
+    /// `hash:` specification), which avoids disclosing the revoked keys.
+    ///
+    /// Returns [`Error::FormatEncoding`] for SHA-512 fingerprints, which
+    /// KRLs can't contain.
+    pub fn revoke_fingerprint(&mut self, fingerprint: Fingerprint) -> Result<&mut Self> {
+        let hash = fingerprint.sha256().ok_or(Error::FormatEncoding)?;
+
+        self.krl.sha256_hashes.push(hash);
+        Ok(self)
+    }
+
+    /// Revoke the certificate with the given serial number issued by the
+    /// given CA, or by any CA if `None`.
+    ///
+    /// Returns [`Error::CertificateFieldInvalid`] for serial number 0, which
+    /// can't be revoked.
+    pub fn revoke_serial(&mut self, ca_key: Option<&KeyData>, serial: u64) -> Result<&mut Self> {
+        self.revoke_serials(ca_key, serial..=serial)
+    }
+
+    /// Revoke the certificates with serial numbers in the given range, issued
+    /// by the given CA, or by any CA if `None`.
+    ///
+    /// Returns [`Error::CertificateFieldInvalid`] if the range is empty or
+    /// includes serial number 0, which can't be revoked.
+    pub fn revoke_serials(
+        &mut self,
+        ca_key: Option<&KeyData>,
+        serials: RangeInclusive<u64>,
+    ) -> Result<&mut Self> {
+        if serials.is_empty() || *serials.start() == 0 {
+            return Err(Field::Serial.invalid_error());
+        }
+
+        self.krl.certificates_mut(ca_key).serials.push(serials);
+        Ok(self)
+    }
+
+    /// Revoke certificates with the given key ID issued by the given CA, or
+    /// by any CA if `None`.
+    pub fn revoke_key_id(
+        &mut self,
+        ca_key: Option<&KeyData>,
+        key_id: impl Into<String>,
+    ) -> Result<&mut Self> {
+        self.krl
+            .certificates_mut(ca_key)
+            .key_ids
+            .push(key_id.into());
+        Ok(self)
+    }
+
+    /// Build the KRL.
+    pub fn build(self) -> Krl {
+        self.krl
+    }
+}
+
+/// Certificates revoked by a KRL for a particular CA.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct CertificateRevocations {
+    /// CA key which issued the certificates, or `None` if these revocations
+    /// apply to certificates issued by any CA.
+    ca_key: Option<KeyData>,
+
+    /// Revoked ranges of serial numbers.
+    serials: Vec<RangeInclusive<u64>>,
+
+    /// Revoked key IDs.
+    key_ids: Vec<String>,
+}
+
+impl Decode for CertificateRevocations {
+    type Error = Error;
+
+    fn decode(reader: &mut impl Reader) -> Result<Self> {
+        let ca_key = Vec::<u8>::decode(reader)?;
+
+        let mut revocations = Self {
+            ca_key: match ca_key.as_slice() {
+                [] => None,
+                mut bytes => {
+                    let key = KeyData::decode(&mut bytes)?;
+                    Some(bytes.finish(key)?)
+                }
+            },
+            serials: Vec::new(),
+            key_ids: Vec::new(),
+        };
+
+        reader.drain_prefixed()?;
+
+        while !reader.is_finished() {
+            let section_type = u8::decode(reader)?;
+
+            reader.read_prefixed(|reader| {
+                match section_type {
+                    // Serial number 0 is ignored, as in OpenSSH
+                    CERT_SECTION_SERIAL_LIST => {
+                        while !reader.is_finished() {
+                            let serial = u64::decode(reader)?;
+
+                            if serial != 0 {
+                                revocations.serials.push(serial..=serial);
+                            }
+                        }
+                    }
+                    CERT_SECTION_SERIAL_RANGE => {
+                        let min = u64::decode(reader)?.max(1);
+                        let max = u64::decode(reader)?;
+
+                        if min <= max {
+                            revocations.serials.push(min..=max);
+                        }
+                    }
+                    CERT_SECTION_SERIAL_BITMAP => {
+                        let offset = u64::decode(reader)?;
+                        let bitmap = Mpint::decode(reader)?;
+                        revocations.decode_bitmap(offset, bitmap.as_bytes())?;
+                    }
+                    CERT_SECTION_KEY_ID => {
+                        while !reader.is_finished() {
+                            revocations.key_ids.push(String::decode(reader)?);
+                        }
+                    }
+                    _ => return Err(Error::FormatEncoding),
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(revocations)
+    }
+}
+
+impl CertificateRevocations {
+    /// Encode the contents of the certificates section for these
+    /// revocations.
+    ///
+    /// Runs of individual serial numbers are written as lists, and other
+    /// ranges individually, preserving their order.
+    fn encode_section(&self) -> Result<Vec<u8>> {
+        let mut section = Vec::new();
+
+        match &self.ca_key {
+            Some(ca_key) => ca_key.encode_prefixed(&mut section)?,
+            None => [0u8; 0].encode(&mut section)?,
+        }
+
+        // Reserved
+        [0u8; 0].encode(&mut section)?;
+
+        let mut serial_list = Vec::new();
+
+        for serials in &self.serials {
+            if serials.start() == serials.end() {
+                serials.start().encode(&mut serial_list)?;
+                continue;
+            }
+
+            flush_serial_list(&mut section, &mut serial_list)?;
+
+            let mut range = Vec::new();
+            serials.start().encode(&mut range)?;
+            serials.end().encode(&mut range)?;
+
+            CERT_SECTION_SERIAL_RANGE.encode(&mut section)?;
+            range.encode(&mut section)?;
+        }
+
+        flush_serial_list(&mut section, &mut serial_list)?;
+
+        if !self.key_ids.is_empty() {
+            let mut key_ids = Vec::new();
+
+            for key_id in &self.key_ids {
+                key_id.encode(&mut key_ids)?;
+            }
+
+            CERT_SECTION_KEY_ID.encode(&mut section)?;
+            key_ids.encode(&mut section)?;
+        }
+
+        Ok(section)
+    }
+
+    /// Add the serial numbers in a big endian bitmap, where bit `n` (counting
+    /// from the least significant bit) revokes serial number `offset + n`.
+    fn decode_bitmap(&mut self, offset: u64, bitmap: &[u8]) -> Result<()> {
+        for (i, byte) in bitmap.iter().rev().enumerate() {
+            for bit in 0..8 {
+                if byte & (1 << bit) == 0 {
+                    continue;
+                }
+
+                let serial = u64::try_from(i)
+                    .ok()
+                    .and_then(|i| i.checked_mul(8))
+                    .and_then(|n| n.checked_add(bit))
+                    .and_then(|n| offset.checked_add(n))
+                    .ok_or(Error::FormatEncoding)?;
+
+                if serial == 0 {
+                    continue;
+                }
+
+                // Coalesce runs of consecutive serial numbers
+                match self.serials.last_mut() {
+                    Some(range) if range.end().checked_add(1) == Some(serial) => {
+                        *range = *range.start()..=serial;
+                    }
+                    _ => self.serials.push(serial..=serial),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Write a (non-empty) list of encoded serial numbers as a certificate
+/// subsection, and clear it.
+fn flush_serial_list(section: &mut Vec<u8>, serial_list: &mut Vec<u8>) -> Result<()> {
+    if !serial_list.is_empty() {
+        CERT_SECTION_SERIAL_LIST.encode(section)?;
+        serial_list.encode(section)?;
+        serial_list.clear();
+    }
+
+    Ok(())
+}
+
+/// Encode a list of length-prefixed hashes of public key blobs.
+fn encode_hashes<const N: usize>(hashes: &[[u8; N]]) -> Result<Vec<u8>> {
+    let mut section = Vec::new();
+
+    for hash in hashes {
+        hash.as_slice().encode(&mut section)?;
+    }
+
+    Ok(section)
+}
+
+/// Decode a length-prefixed hash of a public key blob.
+fn decode_hash<const N: usize>(reader: &mut impl Reader) -> Result<[u8; N]> {
+    let mut hash = [0u8; N];
+
+    reader.read_prefixed(|reader| {
+        reader.read(&mut hash)?;
+        Ok::<_, Error>(())
+    })?;
+
+    Ok(hash)
+}
@@ -7,7 +7,7 @@
 )]
 #![forbid(unsafe_code)]
 #![warn(
-    clippy::integer_arithmetic,
+    clippy::arithmetic_side_effects,
     clippy::mod_module_files,
     clippy::panic,
     clippy::panic_in_result_fn,
@@ -124,13 +124,14 @@
 //!
 //! ## `serde` support
 //!
-//! When the `serde` feature of this crate is enabled, the [`Certificate`],
-//! [`Fingerprint`], and [`PublicKey`] types receive impls of `serde`'s
-//! [`Deserialize`][`serde::Deserialize`] and [`Serialize`][`serde::Serialize`]
-//! traits.
+//! When the `serde` feature of this crate is enabled, the [`Algorithm`],
+//! [`Certificate`], [`Fingerprint`], and [`PublicKey`] types receive impls of
+//! `serde`'s [`Deserialize`][`serde::Deserialize`] and
+//! [`Serialize`][`serde::Serialize`] traits.
 //!
-//! Serializing/deserializing [`PrivateKey`] using `serde` is presently
-//! unsupported.
+//! [`PrivateKey`] doesn't impl these traits, so that private keys can't be
+//! serialized by accident. Serializing them can be opted into on a per-field
+//! basis using the [`private::serde`] module.
 
 #[cfg(feature = "alloc")]
 #[macro_use]
@@ -142,23 +143,39 @@ pub mod authorized_keys;
 pub mod private;
 pub mod public;
 
+#[cfg(feature = "std")]
+pub mod agent;
+#[cfg(feature = "alloc")]
+pub mod allowed_signers;
 #[cfg(feature = "alloc")]
 pub mod certificate;
 #[cfg(feature = "alloc")]
+pub mod inventory;
+#[cfg(feature = "alloc")]
 pub mod known_hosts;
 
 mod algorithm;
 mod cipher;
 mod error;
 mod fingerprint;
+mod format;
 mod kdf;
 
+#[cfg(feature = "alloc")]
+mod fips;
+#[cfg(feature = "alloc")]
+mod krl;
 #[cfg(feature = "alloc")]
 mod mpint;
 #[cfg(feature = "alloc")]
 mod signature;
 #[cfg(feature = "alloc")]
 mod sshsig;
+#[cfg(feature = "alloc")]
+mod verification;
+
+#[cfg(feature = "std")]
+mod lines;
 
 pub use crate::{
     algorithm::{Algorithm, EcdsaCurve, HashAlg, KdfAlg},
@@ -166,22 +183,43 @@ pub use crate::{
     cipher::Cipher,
     error::{Error, Result},
     fingerprint::Fingerprint,
+    format::{detect_format, KeyFormat},
     kdf::Kdf,
     private::PrivateKey,
     public::PublicKey,
 };
-pub use encoding::LineEnding;
+pub use encoding::{self, LineEnding};
 pub use sha2;
 
 #[cfg(feature = "alloc")]
 pub use crate::{
+    algorithm::AlgorithmName,
+    allowed_signers::AllowedSigners,
     certificate::Certificate,
+    fips::FipsPolicy,
+    inventory::Identity,
     known_hosts::KnownHosts,
+    krl::{Krl, KrlBuilder},
     mpint::Mpint,
     signature::{Signature, SigningKey},
     sshsig::SshSig,
+    verification::VerificationConfig,
 };
 
+#[cfg(feature = "std")]
+pub use crate::{
+    agent::Agent, authorized_keys::AuthorizedKeysReader, known_hosts::KnownHostsReader,
+};
+
+#[cfg(feature = "encryption")]
+pub use crate::private::EncryptionOptions;
+
+#[cfg(feature = "ppk")]
+pub use crate::private::PpkVersion;
+
+#[cfg(feature = "rsa")]
+pub use crate::signature::RsaPadding;
+
 #[cfg(feature = "ecdsa")]
 pub use sec1;
 
@@ -0,0 +1,87 @@
+//! Line-by-line reading of `authorized_keys`-style files.
+
+use crate::{authorized_keys::trim_line, Error, Result};
+use core::str;
+use std::{io::BufRead, vec::Vec};
+
+/// Reader which yields the non-blank lines of a file one at a time, reusing
+/// a single buffer.
+#[derive(Debug)]
+pub(crate) struct LineReader<R> {
+    /// Inner reader.
+    reader: R,
+
+    /// Contents of the current line.
+    buf: Vec<u8>,
+
+    /// Number of the current line, starting from 1.
+    number: usize,
+
+    /// Has an I/O error occurred?
+    failed: bool,
+}
+
+impl<R: BufRead> LineReader<R> {
+    /// Create a new line reader.
+    pub(crate) fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: Vec::new(),
+            number: 0,
+            failed: false,
+        }
+    }
+
+    /// Get the inner reader.
+    pub(crate) fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Number of the last line which was read, starting from 1.
+    pub(crate) fn line_number(&self) -> usize {
+        self.number
+    }
+
+    /// Read the next line, trimming any comments and trailing whitespace,
+    /// returning it along with its line number.
+    ///
+    /// Ignores empty lines. Lines which aren't valid UTF-8 are reported as
+    /// [`Error::LineInvalid`], while I/O errors end the iteration.
+    pub(crate) fn next_line(&mut self) -> Option<Result<(usize, &str)>> {
+        loop {
+            if self.failed {
+                return None;
+            }
+
+            self.buf.clear();
+
+            match self.reader.read_until(b'\n', &mut self.buf) {
+                Ok(0) => return None,
+                Ok(_) => self.number = self.number.saturating_add(1),
+                Err(err) => {
+                    self.failed = true;
+                    return Some(Err(err.into()));
+                }
+            }
+
+            match str::from_utf8(&self.buf) {
+                Ok(line) if trim_line(line).is_empty() => continue,
+                Ok(_) => break,
+                Err(_) => return Some(Err(self.line_invalid())),
+            }
+        }
+
+        Some(
+            str::from_utf8(&self.buf)
+                .map(|line| (self.number, trim_line(line)))
+                .map_err(|_| self.line_invalid()),
+        )
+    }
+
+    /// Error for the current line.
+    fn line_invalid(&self) -> Error {
+        Error::LineInvalid {
+            number: self.number,
+        }
+    }
+}
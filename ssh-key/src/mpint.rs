@@ -92,6 +92,18 @@ impl Mpint {
             _ => None,
         }
     }
+
+    /// Decode an mpint which must be positive, as is the case for the
+    /// components of keys.
+    pub(crate) fn decode_positive(reader: &mut impl Reader) -> Result<Self> {
+        let mpint = Self::decode(reader)?;
+
+        if mpint.as_positive_bytes().is_none() {
+            return Err(Error::FormatEncoding);
+        }
+
+        Ok(mpint)
+    }
 }
 
 impl AsRef<[u8]> for Mpint {
@@ -152,6 +164,8 @@ impl TryFrom<Vec<u8>> for Mpint {
             [0x00] => Err(Error::FormatEncoding),
             // Unnecessary leading 0
             [0x00, n, ..] if *n < 0x80 => Err(Error::FormatEncoding),
+            // Unnecessary leading 0xFF
+            [0xFF, n, ..] if *n >= 0x80 => Err(Error::FormatEncoding),
             _ => Ok(Self { inner: bytes }),
         }
     }
@@ -251,6 +265,13 @@ mod tests {
         assert!(Mpint::from_bytes(&hex!("00 01")).is_err());
     }
 
+    #[test]
+    fn reject_extra_leading_ffs() {
+        assert!(Mpint::from_bytes(&hex!("ff 80")).is_err());
+        assert!(Mpint::from_bytes(&hex!("ff ff 21")).is_err());
+        assert!(Mpint::from_bytes(&hex!("ff 7f")).is_ok());
+    }
+
     #[test]
     fn decode_9a378f9b2e332a7() {
         assert!(Mpint::from_bytes(&hex!("09 a3 78 f9 b2 e3 32 a7")).is_ok());
@@ -147,6 +147,38 @@ impl DsaKeypair {
         let components = dsa::Components::generate(rng, Self::KEY_SIZE);
         dsa::SigningKey::generate(rng, components).try_into()
     }
+
+    /// Replace the public key `y` with the one derived from the private key,
+    /// i.e. `y = gˣ mod p`.
+    #[cfg(feature = "dsa")]
+    pub(super) fn repair_public_key(&mut self) -> Result<()> {
+        self.public.y = self.derive_y()?.try_into()?;
+        Ok(())
+    }
+
+    /// Check that the public key corresponds to the private key, i.e.
+    /// `y = gˣ mod p`.
+    #[cfg(feature = "dsa")]
+    pub(super) fn validate(&self) -> Result<()> {
+        if self.derive_y()? == dsa::BigUint::try_from(&self.public.y)? {
+            Ok(())
+        } else {
+            Err(Error::PublicKey)
+        }
+    }
+
+    /// Compute the public key `y = gˣ mod p` from the private key.
+    #[cfg(feature = "dsa")]
+    fn derive_y(&self) -> Result<dsa::BigUint> {
+        let components = dsa::Components::from_components(
+            dsa::BigUint::try_from(&self.public.p)?,
+            dsa::BigUint::try_from(&self.public.q)?,
+            dsa::BigUint::try_from(&self.public.g)?,
+        )?;
+
+        let x = dsa::BigUint::try_from(&self.private)?;
+        Ok(components.g().modpow(&x, components.p()))
+    }
 }
 
 impl ConstantTimeEq for DsaKeypair {
@@ -28,6 +28,23 @@ impl<const SIZE: usize> EcdsaPrivateKey<SIZE> {
         self.bytes
     }
 
+    /// Create a private key from a big endian integer which may be shorter
+    /// than the field size, i.e. with its leading zeros stripped.
+    #[cfg(feature = "ppk")]
+    pub(super) fn from_unpadded_bytes(bytes: &[u8]) -> Result<Self> {
+        let offset = SIZE
+            .checked_sub(bytes.len())
+            .ok_or(encoding::Error::Length)?;
+
+        let mut padded = [0u8; SIZE];
+        padded
+            .get_mut(offset..)
+            .ok_or(encoding::Error::Length)?
+            .copy_from_slice(bytes);
+
+        Ok(Self { bytes: padded })
+    }
+
     /// Does this private key need to be prefixed with a leading zero?
     fn needs_leading_zero(&self) -> bool {
         self.bytes[0] >= 0x80
@@ -143,6 +160,15 @@ impl From<p384::SecretKey> for EcdsaPrivateKey<48> {
     }
 }
 
+#[cfg(feature = "p521")]
+impl From<p521::SecretKey> for EcdsaPrivateKey<66> {
+    fn from(sk: p521::SecretKey) -> EcdsaPrivateKey<66> {
+        let mut bytes = [0u8; 66];
+        bytes.copy_from_slice(&sk.to_bytes());
+        EcdsaPrivateKey { bytes }
+    }
+}
+
 /// Elliptic Curve Digital Signature Algorithm (ECDSA) private/public keypair.
 #[derive(Clone, Debug)]
 pub enum EcdsaKeypair {
@@ -198,6 +224,16 @@ impl EcdsaKeypair {
                     public: public.into(),
                 })
             }
+            #[cfg(feature = "p521")]
+            EcdsaCurve::NistP521 => {
+                let private = p521::SecretKey::random(rng);
+                let public = private.public_key();
+                Ok(EcdsaKeypair::NistP521 {
+                    private: private.into(),
+                    public: public.into(),
+                })
+            }
+            #[cfg(not(all(feature = "p256", feature = "p384", feature = "p521")))]
             _ => Err(Error::AlgorithmUnknown),
         }
     }
@@ -235,6 +271,77 @@ impl EcdsaKeypair {
             Self::NistP521 { private, .. } => private.as_ref(),
         }
     }
+
+    /// Check that the public key corresponds to the private key by deriving
+    /// it from the private scalar.
+    ///
+    /// Keys on curves whose crate features aren't enabled are not checked.
+    #[cfg(any(feature = "p256", feature = "p384", feature = "p521"))]
+    pub(super) fn validate(&self) -> Result<()> {
+        #[allow(unreachable_patterns)]
+        let valid = match self {
+            #[cfg(feature = "p256")]
+            Self::NistP256 { public, private } => {
+                use p256::elliptic_curve::sec1::ToEncodedPoint;
+                let secret =
+                    p256::SecretKey::from_slice(private.as_ref()).map_err(|_| Error::Crypto)?;
+                secret.public_key().to_encoded_point(false) == *public
+            }
+            #[cfg(feature = "p384")]
+            Self::NistP384 { public, private } => {
+                use p384::elliptic_curve::sec1::ToEncodedPoint;
+                let secret =
+                    p384::SecretKey::from_slice(private.as_ref()).map_err(|_| Error::Crypto)?;
+                secret.public_key().to_encoded_point(false) == *public
+            }
+            #[cfg(feature = "p521")]
+            Self::NistP521 { public, private } => {
+                use p521::elliptic_curve::sec1::ToEncodedPoint;
+                let secret =
+                    p521::SecretKey::from_slice(private.as_ref()).map_err(|_| Error::Crypto)?;
+                secret.public_key().to_encoded_point(false) == *public
+            }
+            _ => true,
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(Error::PublicKey)
+        }
+    }
+
+    /// Replace the public key with the one derived from the private scalar.
+    #[cfg(any(feature = "p256", feature = "p384", feature = "p521"))]
+    pub(super) fn repair_public_key(&mut self) -> Result<()> {
+        #[allow(unreachable_patterns)]
+        match self {
+            #[cfg(feature = "p256")]
+            Self::NistP256 { public, private } => {
+                use p256::elliptic_curve::sec1::ToEncodedPoint;
+                let secret =
+                    p256::SecretKey::from_slice(private.as_ref()).map_err(|_| Error::Crypto)?;
+                *public = secret.public_key().to_encoded_point(false);
+            }
+            #[cfg(feature = "p384")]
+            Self::NistP384 { public, private } => {
+                use p384::elliptic_curve::sec1::ToEncodedPoint;
+                let secret =
+                    p384::SecretKey::from_slice(private.as_ref()).map_err(|_| Error::Crypto)?;
+                *public = secret.public_key().to_encoded_point(false);
+            }
+            #[cfg(feature = "p521")]
+            Self::NistP521 { public, private } => {
+                use p521::elliptic_curve::sec1::ToEncodedPoint;
+                let secret =
+                    p521::SecretKey::from_slice(private.as_ref()).map_err(|_| Error::Crypto)?;
+                *public = secret.public_key().to_encoded_point(false);
+            }
+            _ => return Err(self.algorithm().unsupported_error()),
+        }
+
+        Ok(())
+    }
 }
 
 impl ConstantTimeEq for EcdsaKeypair {
@@ -0,0 +1,121 @@
+//! Private key encryption options.
+
+use crate::{kdf, Cipher, Kdf};
+use alloc::vec::Vec;
+use rand_core::CryptoRngCore;
+
+/// Options for encrypting a [`PrivateKey`][`crate::PrivateKey`], for use with
+/// [`PrivateKey::encrypt_with_options`][`crate::PrivateKey::encrypt_with_options`].
+///
+/// Keys are always encrypted using a key derived with [`Kdf::Bcrypt`]. The
+/// defaults match `ssh-keygen`:
+///
+/// - Cipher: [`Cipher::Aes256Ctr`]
+/// - Rounds: 16
+/// - Salt: 16 random bytes
+///
+/// The cipher IV is derived from the password along with the key, so
+/// supplying a fixed [`salt`][`EncryptionOptions::salt`] and
+/// [`checkint`][`EncryptionOptions::checkint`] produces reproducible output.
+/// This is intended **for testing only**.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EncryptionOptions {
+    /// Cipher.
+    cipher: Cipher,
+
+    /// Number of `bcrypt-pbkdf` rounds.
+    rounds: u32,
+
+    /// Size of the randomly generated salt.
+    salt_len: usize,
+
+    /// Fixed salt.
+    salt: Option<Vec<u8>>,
+
+    /// Fixed `checkint`.
+    checkint: Option<u32>,
+}
+
+impl EncryptionOptions {
+    /// Create new encryption options using the defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the cipher.
+    pub fn cipher(&mut self, cipher: Cipher) -> &mut Self {
+        self.cipher = cipher;
+        self
+    }
+
+    /// Set the number of `bcrypt-pbkdf` rounds.
+    ///
+    /// Higher values make password guessing slower, but also slow down
+    /// decrypting the key. Encryption fails with [`Error::Crypto`] if this
+    /// is zero.
+    ///
+    /// [`Error::Crypto`]: crate::Error::Crypto
+    pub fn rounds(&mut self, rounds: u32) -> &mut Self {
+        self.rounds = rounds;
+        self
+    }
+
+    /// Set the size of the randomly generated salt in bytes.
+    ///
+    /// Ignored if a fixed [`salt`][`EncryptionOptions::salt`] is supplied.
+    pub fn salt_len(&mut self, salt_len: usize) -> &mut Self {
+        self.salt_len = salt_len;
+        self
+    }
+
+    /// Use a fixed salt instead of a random one. **For testing only!**
+    pub fn salt(&mut self, salt: impl Into<Vec<u8>>) -> &mut Self {
+        self.salt = Some(salt.into());
+        self
+    }
+
+    /// Use a fixed `checkint` instead of a random one. **For testing only!**
+    pub fn checkint(&mut self, checkint: u32) -> &mut Self {
+        self.checkint = Some(checkint);
+        self
+    }
+
+    /// Get the configured cipher.
+    pub(super) fn get_cipher(&self) -> Cipher {
+        self.cipher
+    }
+
+    /// Get the `checkint` and KDF configuration, filling in whichever are
+    /// not fixed from the given RNG.
+    pub(super) fn checkint_and_kdf(&self, rng: &mut impl CryptoRngCore) -> (u32, Kdf) {
+        let checkint = self.checkint.unwrap_or_else(|| rng.next_u32());
+
+        let salt = match &self.salt {
+            Some(salt) => salt.clone(),
+            None => {
+                let mut salt = vec![0u8; self.salt_len];
+                rng.fill_bytes(&mut salt);
+                salt
+            }
+        };
+
+        let kdf = Kdf::Bcrypt {
+            salt,
+            rounds: self.rounds,
+        };
+
+        (checkint, kdf)
+    }
+}
+
+impl Default for EncryptionOptions {
+    fn default() -> Self {
+        Self {
+            cipher: Cipher::default(),
+            rounds: kdf::DEFAULT_BCRYPT_ROUNDS,
+            salt_len: kdf::DEFAULT_SALT_SIZE,
+            salt: None,
+            checkint: None,
+        }
+    }
+}
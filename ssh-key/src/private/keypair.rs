@@ -7,7 +7,7 @@ use subtle::{Choice, ConstantTimeEq};
 
 #[cfg(feature = "alloc")]
 use {
-    super::{DsaKeypair, RsaKeypair, SkEd25519},
+    super::{DsaKeypair, OpaqueKeypair, RsaKeypair, SkEd25519},
     alloc::vec::Vec,
 };
 
@@ -17,6 +17,9 @@ use super::EcdsaKeypair;
 #[cfg(all(feature = "alloc", feature = "ecdsa"))]
 use super::SkEcdsaSha2NistP256;
 
+#[cfg(feature = "xmss")]
+use super::XmssKeypair;
+
 /// Private key data: digital signature key pairs.
 ///
 /// SSH private keys contain pairs of public and private keys for various
@@ -55,6 +58,14 @@ pub enum KeypairData {
     /// [PROTOCOL.u2f]: https://cvsweb.openbsd.org/src/usr.bin/ssh/PROTOCOL.u2f?annotate=HEAD
     #[cfg(feature = "alloc")]
     SkEd25519(SkEd25519),
+
+    /// eXtended Merkle Signature Scheme (XMSS) keypair.
+    #[cfg(feature = "xmss")]
+    Xmss(XmssKeypair),
+
+    /// Opaque keypair for an [`Algorithm::Other`].
+    #[cfg(feature = "alloc")]
+    Other(OpaqueKeypair),
 }
 
 impl KeypairData {
@@ -74,9 +85,112 @@ impl KeypairData {
             Self::SkEcdsaSha2NistP256(_) => Algorithm::SkEcdsaSha2NistP256,
             #[cfg(feature = "alloc")]
             Self::SkEd25519(_) => Algorithm::SkEd25519,
+            #[cfg(feature = "xmss")]
+            Self::Xmss(_) => Algorithm::Xmss,
+            #[cfg(feature = "alloc")]
+            Self::Other(keypair) => Algorithm::Other(keypair.algorithm()),
         })
     }
 
+    /// Get the public portion of this keypair, i.e. the public key stored
+    /// alongside the private key.
+    ///
+    /// This is equivalent to converting with `public::KeyData::try_from`, and
+    /// like it never re-derives the public key from the private key. Use
+    /// [`KeypairData::validate`] to check that they correspond, or
+    /// [`KeypairData::repair_public_key`] to re-derive it.
+    pub fn public_key_data(&self) -> Result<public::KeyData> {
+        Ok(match self {
+            #[cfg(feature = "alloc")]
+            Self::Dsa(dsa) => public::KeyData::Dsa(dsa.into()),
+            #[cfg(feature = "ecdsa")]
+            Self::Ecdsa(ecdsa) => public::KeyData::Ecdsa(ecdsa.into()),
+            Self::Ed25519(ed25519) => public::KeyData::Ed25519(ed25519.into()),
+            #[cfg(feature = "alloc")]
+            Self::Encrypted(_) => return Err(Error::Encrypted),
+            #[cfg(feature = "alloc")]
+            Self::Rsa(rsa) => public::KeyData::Rsa(rsa.into()),
+            #[cfg(all(feature = "alloc", feature = "ecdsa"))]
+            Self::SkEcdsaSha2NistP256(sk) => {
+                public::KeyData::SkEcdsaSha2NistP256(sk.public().clone())
+            }
+            #[cfg(feature = "alloc")]
+            Self::SkEd25519(sk) => public::KeyData::SkEd25519(sk.public().clone()),
+            #[cfg(feature = "xmss")]
+            Self::Xmss(keypair) => public::KeyData::Xmss(keypair.into()),
+            #[cfg(feature = "alloc")]
+            Self::Other(keypair) => public::KeyData::Other(keypair.into()),
+        })
+    }
+
+    /// Validate that the public portion of this keypair corresponds to the
+    /// private portion.
+    ///
+    /// The following are checked when the relevant crate features are
+    /// enabled:
+    ///
+    /// - DSA: `y = gˣ mod p`
+    /// - ECDSA/NIST P-256, P-384 and P-521: the public point is re-derived
+    ///   from the private scalar
+    /// - Ed25519: the public key is re-derived from the private key
+    /// - RSA: `n = pq` and `ed ≡ 1 mod λ(n)`
+    ///
+    /// Other key types, including security keys (FIDO/U2F), which don't
+    /// contain a private key, are accepted as-is.
+    ///
+    /// # Returns
+    /// - [`Error::PublicKey`] if the public key doesn't match the private key.
+    /// - [`Error::Crypto`] if the private key is invalid.
+    /// - [`Error::Encrypted`] if the keypair is encrypted.
+    pub fn validate(&self) -> Result<()> {
+        #[allow(unreachable_patterns)]
+        match self {
+            #[cfg(feature = "dsa")]
+            Self::Dsa(keypair) => keypair.validate(),
+            #[cfg(any(feature = "p256", feature = "p384", feature = "p521"))]
+            Self::Ecdsa(keypair) => keypair.validate(),
+            #[cfg(feature = "ed25519")]
+            Self::Ed25519(keypair) => ed25519_dalek::SigningKey::try_from(keypair).map(drop),
+            #[cfg(feature = "alloc")]
+            Self::Encrypted(_) => Err(Error::Encrypted),
+            #[cfg(feature = "rsa")]
+            Self::Rsa(keypair) => keypair.validate(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Re-derive the public portion of this keypair from the private portion,
+    /// replacing it.
+    ///
+    /// DSA, ECDSA/NIST P-256, P-384 and P-521, Ed25519 and RSA keys are supported
+    /// when the relevant crate features are enabled. For RSA keys, `n` and
+    /// `e` are recomputed from `p`, `q` and `d`.
+    ///
+    /// # Returns
+    /// - [`Error::AlgorithmUnsupported`] for other key types, including
+    ///   security keys (FIDO/U2F), which don't contain a private key.
+    /// - [`Error::Crypto`] if the private key is invalid.
+    /// - [`Error::Encrypted`] if the keypair is encrypted.
+    pub fn repair_public_key(&mut self) -> Result<()> {
+        #[allow(unreachable_patterns)]
+        match self {
+            #[cfg(feature = "dsa")]
+            Self::Dsa(keypair) => keypair.repair_public_key(),
+            #[cfg(any(feature = "p256", feature = "p384", feature = "p521"))]
+            Self::Ecdsa(keypair) => keypair.repair_public_key(),
+            #[cfg(feature = "ed25519")]
+            Self::Ed25519(keypair) => {
+                keypair.public = (&keypair.private).into();
+                Ok(())
+            }
+            #[cfg(feature = "alloc")]
+            Self::Encrypted(_) => Err(Error::Encrypted),
+            #[cfg(feature = "rsa")]
+            Self::Rsa(keypair) => keypair.repair_public_key(),
+            _ => Err(self.algorithm()?.unsupported_error()),
+        }
+    }
+
     /// Get DSA keypair if this key is the correct type.
     #[cfg(feature = "alloc")]
     pub fn dsa(&self) -> Option<&DsaKeypair> {
@@ -140,6 +254,24 @@ impl KeypairData {
         }
     }
 
+    /// Get XMSS keypair if this key is the correct type.
+    #[cfg(feature = "xmss")]
+    pub fn xmss(&self) -> Option<&XmssKeypair> {
+        match self {
+            Self::Xmss(keypair) => Some(keypair),
+            _ => None,
+        }
+    }
+
+    /// Get opaque keypair if this key is for an [`Algorithm::Other`].
+    #[cfg(feature = "alloc")]
+    pub fn other(&self) -> Option<&OpaqueKeypair> {
+        match self {
+            Self::Other(keypair) => Some(keypair),
+            _ => None,
+        }
+    }
+
     /// Is this key a DSA key?
     #[cfg(feature = "alloc")]
     pub fn is_dsa(&self) -> bool {
@@ -187,6 +319,59 @@ impl KeypairData {
         matches!(self, Self::SkEd25519(_))
     }
 
+    /// Is this key an XMSS key?
+    #[cfg(feature = "xmss")]
+    pub fn is_xmss(&self) -> bool {
+        matches!(self, Self::Xmss(_))
+    }
+
+    /// Decode [`KeypairData`] for the given public key, e.g. the public key
+    /// preceding it in the OpenSSH private key format.
+    ///
+    /// Opaque keypairs for an [`Algorithm::Other`] can only be decoded this
+    /// way. Their key data, like that of XMSS keys, isn't self-delimiting, so
+    /// the rest of the reader's input is consumed.
+    pub(super) fn decode_for(
+        reader: &mut impl Reader,
+        public_key: &public::KeyData,
+    ) -> Result<Self> {
+        match (Algorithm::decode(reader)?, public_key) {
+            #[cfg(feature = "alloc")]
+            (Algorithm::Other(name), public::KeyData::Other(public))
+                if name == public.algorithm() =>
+            {
+                OpaqueKeypair::decode_as(reader, public).map(Self::Other)
+            }
+            (algorithm, _) => Self::decode_as(reader, algorithm),
+        }
+    }
+
+    /// Decode [`KeypairData`] for the specified algorithm.
+    fn decode_as(reader: &mut impl Reader, algorithm: Algorithm) -> Result<Self> {
+        match algorithm {
+            #[cfg(feature = "alloc")]
+            Algorithm::Dsa => DsaKeypair::decode(reader).map(Self::Dsa),
+            #[cfg(feature = "ecdsa")]
+            Algorithm::Ecdsa { curve } => match EcdsaKeypair::decode(reader)? {
+                keypair if keypair.curve() == curve => Ok(Self::Ecdsa(keypair)),
+                _ => Err(Error::AlgorithmUnknown),
+            },
+            Algorithm::Ed25519 => Ed25519Keypair::decode(reader).map(Self::Ed25519),
+            #[cfg(feature = "alloc")]
+            Algorithm::Rsa { .. } => RsaKeypair::decode(reader).map(Self::Rsa),
+            #[cfg(all(feature = "alloc", feature = "ecdsa"))]
+            Algorithm::SkEcdsaSha2NistP256 => {
+                SkEcdsaSha2NistP256::decode(reader).map(Self::SkEcdsaSha2NistP256)
+            }
+            #[cfg(feature = "alloc")]
+            Algorithm::SkEd25519 => SkEd25519::decode(reader).map(Self::SkEd25519),
+            #[cfg(feature = "xmss")]
+            Algorithm::Xmss => XmssKeypair::decode(reader).map(Self::Xmss),
+            #[allow(unreachable_patterns)]
+            _ => Err(Error::AlgorithmUnknown),
+        }
+    }
+
     /// Compute a deterministic "checkint" for this private key.
     ///
     /// This is a sort of primitive pseudo-MAC used by the OpenSSH key format.
@@ -206,6 +391,10 @@ impl KeypairData {
             Self::SkEcdsaSha2NistP256(sk) => sk.key_handle(),
             #[cfg(feature = "alloc")]
             Self::SkEd25519(sk) => sk.key_handle(),
+            #[cfg(feature = "xmss")]
+            Self::Xmss(keypair) => keypair.private_key_bytes(),
+            #[cfg(feature = "alloc")]
+            Self::Other(keypair) => keypair.private_key_bytes(),
         };
 
         let mut n = 0u32;
@@ -243,6 +432,10 @@ impl ConstantTimeEq for KeypairData {
                 // The key structs contain all public data.
                 Choice::from((a == b) as u8)
             }
+            #[cfg(feature = "xmss")]
+            (Self::Xmss(a), Self::Xmss(b)) => a.ct_eq(b),
+            #[cfg(feature = "alloc")]
+            (Self::Other(a), Self::Other(b)) => a.ct_eq(b),
             #[allow(unreachable_patterns)]
             _ => Choice::from(0),
         }
@@ -261,26 +454,8 @@ impl Decode for KeypairData {
     type Error = Error;
 
     fn decode(reader: &mut impl Reader) -> Result<Self> {
-        match Algorithm::decode(reader)? {
-            #[cfg(feature = "alloc")]
-            Algorithm::Dsa => DsaKeypair::decode(reader).map(Self::Dsa),
-            #[cfg(feature = "ecdsa")]
-            Algorithm::Ecdsa { curve } => match EcdsaKeypair::decode(reader)? {
-                keypair if keypair.curve() == curve => Ok(Self::Ecdsa(keypair)),
-                _ => Err(Error::AlgorithmUnknown),
-            },
-            Algorithm::Ed25519 => Ed25519Keypair::decode(reader).map(Self::Ed25519),
-            #[cfg(feature = "alloc")]
-            Algorithm::Rsa { .. } => RsaKeypair::decode(reader).map(Self::Rsa),
-            #[cfg(all(feature = "alloc", feature = "ecdsa"))]
-            Algorithm::SkEcdsaSha2NistP256 => {
-                SkEcdsaSha2NistP256::decode(reader).map(Self::SkEcdsaSha2NistP256)
-            }
-            #[cfg(feature = "alloc")]
-            Algorithm::SkEd25519 => SkEd25519::decode(reader).map(Self::SkEd25519),
-            #[allow(unreachable_patterns)]
-            _ => Err(Error::AlgorithmUnknown),
-        }
+        let algorithm = Algorithm::decode(reader)?;
+        Self::decode_as(reader, algorithm)
     }
 }
 
@@ -302,6 +477,10 @@ impl Encode for KeypairData {
             Self::SkEcdsaSha2NistP256(sk) => sk.encoded_len()?,
             #[cfg(feature = "alloc")]
             Self::SkEd25519(sk) => sk.encoded_len()?,
+            #[cfg(feature = "xmss")]
+            Self::Xmss(keypair) => keypair.encoded_len()?,
+            #[cfg(feature = "alloc")]
+            Self::Other(keypair) => keypair.encoded_len()?,
         };
 
         Ok([self.algorithm()?.encoded_len()?, key_len].checked_sum()?)
@@ -326,6 +505,10 @@ impl Encode for KeypairData {
             Self::SkEcdsaSha2NistP256(sk) => sk.encode(writer)?,
             #[cfg(feature = "alloc")]
             Self::SkEd25519(sk) => sk.encode(writer)?,
+            #[cfg(feature = "xmss")]
+            Self::Xmss(keypair) => keypair.encode(writer)?,
+            #[cfg(feature = "alloc")]
+            Self::Other(keypair) => keypair.encode(writer)?,
         }
 
         Ok(())
@@ -336,23 +519,7 @@ impl TryFrom<&KeypairData> for public::KeyData {
     type Error = Error;
 
     fn try_from(keypair_data: &KeypairData) -> Result<public::KeyData> {
-        Ok(match keypair_data {
-            #[cfg(feature = "alloc")]
-            KeypairData::Dsa(dsa) => public::KeyData::Dsa(dsa.into()),
-            #[cfg(feature = "ecdsa")]
-            KeypairData::Ecdsa(ecdsa) => public::KeyData::Ecdsa(ecdsa.into()),
-            KeypairData::Ed25519(ed25519) => public::KeyData::Ed25519(ed25519.into()),
-            #[cfg(feature = "alloc")]
-            KeypairData::Encrypted(_) => return Err(Error::Encrypted),
-            #[cfg(feature = "alloc")]
-            KeypairData::Rsa(rsa) => public::KeyData::Rsa(rsa.into()),
-            #[cfg(all(feature = "alloc", feature = "ecdsa"))]
-            KeypairData::SkEcdsaSha2NistP256(sk) => {
-                public::KeyData::SkEcdsaSha2NistP256(sk.public().clone())
-            }
-            #[cfg(feature = "alloc")]
-            KeypairData::SkEd25519(sk) => public::KeyData::SkEd25519(sk.public().clone()),
-        })
+        keypair_data.public_key_data()
     }
 }
 
@@ -396,3 +563,17 @@ impl From<SkEd25519> for KeypairData {
         Self::SkEd25519(keypair)
     }
 }
+
+#[cfg(feature = "xmss")]
+impl From<XmssKeypair> for KeypairData {
+    fn from(keypair: XmssKeypair) -> KeypairData {
+        Self::Xmss(keypair)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<OpaqueKeypair> for KeypairData {
+    fn from(keypair: OpaqueKeypair) -> KeypairData {
+        Self::Other(keypair)
+    }
+}
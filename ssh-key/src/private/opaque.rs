@@ -0,0 +1,105 @@
+//! Opaque private keys for algorithms not otherwise supported by this crate.
+
+use crate::{public::OpaquePublicKey, AlgorithmName, Error, Result};
+use alloc::vec::Vec;
+use core::fmt;
+use encoding::{CheckedSum, Encode, Reader, Writer};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroizing;
+
+/// Opaque keypair for an [`Algorithm::Other`][`crate::Algorithm::Other`].
+///
+/// In the OpenSSH private key format, the key data of every algorithm
+/// besides RSA begins with its public key data, followed by its private key
+/// data. The private key data is stored as raw bytes, allowing such keys to be
+/// passed through unchanged.
+#[derive(Clone)]
+pub struct OpaqueKeypair {
+    /// Public key.
+    public: OpaquePublicKey,
+
+    /// Raw private key data, i.e. the key data following the public key.
+    private: Zeroizing<Vec<u8>>,
+}
+
+impl OpaqueKeypair {
+    /// Create a new opaque keypair from the given public key and raw private
+    /// key data.
+    pub fn new(public: OpaquePublicKey, private: impl Into<Vec<u8>>) -> Self {
+        Self {
+            public,
+            private: Zeroizing::new(private.into()),
+        }
+    }
+
+    /// Get the algorithm name.
+    pub fn algorithm(&self) -> AlgorithmName {
+        self.public.algorithm()
+    }
+
+    /// Get the public key.
+    pub fn public(&self) -> &OpaquePublicKey {
+        &self.public
+    }
+
+    /// Get the raw private key data.
+    pub fn private_key_bytes(&self) -> &[u8] {
+        &self.private
+    }
+
+    /// Decode the key data for the given public key, consuming the rest of
+    /// the reader's input.
+    ///
+    /// Returns [`Error::PublicKey`] if the key data doesn't begin with the
+    /// given public key.
+    pub(super) fn decode_as(reader: &mut impl Reader, public: &OpaquePublicKey) -> Result<Self> {
+        let mut key = Zeroizing::new(vec![0u8; reader.remaining_len()]);
+        reader.read(&mut key)?;
+
+        match key.strip_prefix(public.as_bytes()) {
+            Some(private) => Ok(Self::new(public.clone(), private)),
+            None => Err(Error::PublicKey),
+        }
+    }
+}
+
+impl ConstantTimeEq for OpaqueKeypair {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        Choice::from((self.public == other.public) as u8) & self.private.ct_eq(&other.private)
+    }
+}
+
+impl Eq for OpaqueKeypair {}
+
+impl PartialEq for OpaqueKeypair {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Encode for OpaqueKeypair {
+    type Error = Error;
+
+    fn encoded_len(&self) -> Result<usize> {
+        Ok([self.public.encoded_len()?, self.private.len()].checked_sum()?)
+    }
+
+    fn encode(&self, writer: &mut impl Writer) -> Result<()> {
+        self.public.encode(writer)?;
+        Ok(writer.write(&self.private)?)
+    }
+}
+
+impl From<&OpaqueKeypair> for OpaquePublicKey {
+    fn from(keypair: &OpaqueKeypair) -> OpaquePublicKey {
+        keypair.public.clone()
+    }
+}
+
+impl fmt::Debug for OpaqueKeypair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OpaqueKeypair")
+            .field("public", &self.public)
+            .finish_non_exhaustive()
+    }
+}
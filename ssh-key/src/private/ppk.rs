@@ -0,0 +1,726 @@
+//! PuTTY private key support, i.e. the `.ppk` format used by PuTTY and
+//! related tools such as WinSCP and Pageant.
+//!
+//! Described in the [PuTTY documentation]:
+//!
+//! ```text
+//! PuTTY-User-Key-File-3: ssh-ed25519
+//! Encryption: none
+//! Comment: user@example.com
+//! Public-Lines: 2
+//! AAAAC3NzaC1lZDI1NTE5AAAAILM+rvN+ot98qgEN796jTiQfZfG1KaT0PtFDJ/XF
+//! Sqti
+//! Private-Lines: 1
+//! AAAAIGhh...
+//! Private-MAC: 5a0b...
+//! ```
+//!
+//! [PuTTY documentation]: https://the.earth.li/~sgtatham/putty/0.78/htmldoc/AppendixC.html
+
+use super::{DsaKeypair, DsaPrivateKey, KeypairData, PrivateKey, RsaKeypair, RsaPrivateKey};
+use crate::{
+    cipher::{cbc_decrypt, cbc_encrypt},
+    public::{sshfp::decode_hex, KeyData},
+    Error, LineEnding, Mpint, Result,
+};
+use aes::Aes256;
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use argon2::Argon2;
+use core::str;
+use encoding::{
+    base64::{Base64, Encoding},
+    Decode, Encode, Reader,
+};
+use hmac::{Hmac, Mac};
+use rand_core::CryptoRngCore;
+use sha1::{Digest, Sha1};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+use zeroize::Zeroizing;
+
+#[cfg(feature = "ecdsa")]
+use {
+    super::{EcdsaKeypair, EcdsaPrivateKey},
+    crate::public::EcdsaPublicKey,
+};
+
+use super::{Ed25519Keypair, Ed25519PrivateKey};
+
+/// Prefix of the first header, which is followed by the format version.
+const FILE_HEADER_PREFIX: &str = "PuTTY-User-Key-File-";
+
+/// `Encryption` value for unencrypted keys.
+const ENCRYPTION_NONE: &str = "none";
+
+/// `Encryption` value for keys encrypted with AES-256 in CBC mode.
+const ENCRYPTION_AES256_CBC: &str = "aes256-cbc";
+
+/// Header which is only present on encrypted version 3 keys, giving the
+/// Argon2 variant used to derive the encryption key.
+const KEY_DERIVATION_HEADER: &str = "Key-Derivation";
+
+/// Prefix of the passphrase when deriving the MAC key of version 2 keys.
+const V2_MAC_KEY_PREFIX: &[u8] = b"putty-private-key-file-mac-key";
+
+/// AES block size in bytes.
+const AES_BLOCK_SIZE: usize = 16;
+
+/// AES-256 key size in bytes.
+const AES256_KEY_SIZE: usize = 32;
+
+/// Size of the MAC key of encrypted version 3 keys.
+const V3_MAC_KEY_SIZE: usize = 32;
+
+/// Argon2 memory cost in KiB used when encrypting version 3 keys. Matches
+/// PuTTYgen.
+const ARGON2_MEMORY: u32 = 8192;
+
+/// Number of Argon2 passes used when encrypting version 3 keys.
+///
+/// PuTTYgen calibrates this so that deriving the key takes 100ms, so it
+/// varies between machines.
+const ARGON2_PASSES: u32 = 21;
+
+/// Argon2 parallelism used when encrypting version 3 keys. Matches PuTTYgen.
+const ARGON2_PARALLELISM: u32 = 1;
+
+/// Size of the random Argon2 salt used when encrypting version 3 keys.
+/// Matches PuTTYgen.
+const ARGON2_SALT_SIZE: usize = 16;
+
+/// Maximum Argon2 memory cost in KiB (i.e. 1 GiB) accepted when decrypting,
+/// so that a small malicious file can't exhaust memory.
+const MAX_ARGON2_MEMORY: u32 = 1 << 20;
+
+/// Maximum number of Argon2 passes accepted when decrypting.
+const MAX_ARGON2_PASSES: u32 = 1 << 10;
+
+/// Maximum Argon2 parallelism accepted when decrypting.
+const MAX_ARGON2_PARALLELISM: u32 = 64;
+
+/// Line width used for Base64-encoded data. Matches PuTTYgen.
+const BASE64_LINE_WIDTH: usize = 64;
+
+/// PuTTY private key (`.ppk`) format versions.
+#[derive(Copy, Clone, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum PpkVersion {
+    /// Version 2, which authenticates keys with HMAC-SHA-1 and derives
+    /// encryption keys using SHA-1.
+    ///
+    /// Supported by all versions of PuTTY.
+    V2,
+
+    /// Version 3, which authenticates keys with HMAC-SHA-256 and derives
+    /// encryption keys using Argon2.
+    ///
+    /// Written by default since PuTTY 0.75.
+    #[default]
+    V3,
+}
+
+impl PpkVersion {
+    /// Parse the version number given in the first header.
+    fn new(version: &str) -> Result<Self> {
+        match version {
+            "2" => Ok(Self::V2),
+            "3" => Ok(Self::V3),
+            _ => Err(Error::FormatEncoding),
+        }
+    }
+
+    /// Get the version number given in the first header.
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::V2 => "2",
+            Self::V3 => "3",
+        }
+    }
+
+    /// Derive the keys used to encrypt and authenticate a key.
+    ///
+    /// Version 3 keys are only encrypted when `argon2` is given: otherwise
+    /// their MAC key is empty, and the passphrase unused.
+    fn keys(self, passphrase: &[u8], argon2: Option<&Argon2Params>) -> Result<PpkKeys> {
+        let mut keys = PpkKeys {
+            cipher_key: Zeroizing::new([0u8; AES256_KEY_SIZE]),
+            iv: [0u8; AES_BLOCK_SIZE],
+            mac_key: Zeroizing::new(Vec::new()),
+        };
+
+        match (self, argon2) {
+            (Self::V2, None) => {
+                for (counter, chunk) in [0u32, 1].into_iter().zip(keys.cipher_key.chunks_mut(20)) {
+                    let digest = Sha1::new()
+                        .chain_update(counter.to_be_bytes())
+                        .chain_update(passphrase)
+                        .finalize();
+
+                    chunk.copy_from_slice(&digest[..chunk.len()]);
+                }
+
+                let mac_key = Sha1::new()
+                    .chain_update(V2_MAC_KEY_PREFIX)
+                    .chain_update(passphrase)
+                    .finalize();
+
+                keys.mac_key.extend_from_slice(&mac_key);
+            }
+            (Self::V3, None) => (),
+            (Self::V3, Some(argon2)) => {
+                let mut output =
+                    Zeroizing::new([0u8; AES256_KEY_SIZE + AES_BLOCK_SIZE + V3_MAC_KEY_SIZE]);
+                argon2.derive(passphrase, &mut *output)?;
+
+                let (cipher_key, rest) = output.split_at(AES256_KEY_SIZE);
+                let (iv, mac_key) = rest.split_at(AES_BLOCK_SIZE);
+                keys.cipher_key.copy_from_slice(cipher_key);
+                keys.iv.copy_from_slice(iv);
+                keys.mac_key.extend_from_slice(mac_key);
+            }
+            (Self::V2, Some(_)) => return Err(Error::FormatEncoding),
+        }
+
+        Ok(keys)
+    }
+
+    /// Compute the MAC over the given data.
+    fn mac(self, mac_key: &[u8], data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Self::V2 => {
+                let mut mac =
+                    <Hmac<Sha1> as Mac>::new_from_slice(mac_key).map_err(|_| Error::Crypto)?;
+                mac.update(data);
+                Ok(mac.finalize().into_bytes().to_vec())
+            }
+            Self::V3 => {
+                let mut mac =
+                    <Hmac<Sha256> as Mac>::new_from_slice(mac_key).map_err(|_| Error::Crypto)?;
+                mac.update(data);
+                Ok(mac.finalize().into_bytes().to_vec())
+            }
+        }
+    }
+}
+
+/// Keys used to encrypt and authenticate a PuTTY private key.
+struct PpkKeys {
+    /// AES-256 key.
+    cipher_key: Zeroizing<[u8; AES256_KEY_SIZE]>,
+
+    /// CBC initialization vector, which is zero for version 2 keys.
+    iv: [u8; AES_BLOCK_SIZE],
+
+    /// HMAC key.
+    mac_key: Zeroizing<Vec<u8>>,
+}
+
+/// Argon2 parameters used to derive the keys of encrypted version 3 keys.
+struct Argon2Params {
+    /// Argon2 variant, i.e. the value of the `Key-Derivation` header.
+    algorithm: argon2::Algorithm,
+
+    /// Memory cost in KiB.
+    memory: u32,
+
+    /// Number of passes.
+    passes: u32,
+
+    /// Degree of parallelism.
+    parallelism: u32,
+
+    /// Salt.
+    salt: Vec<u8>,
+}
+
+impl Argon2Params {
+    /// Generate parameters with a random salt for encrypting a key.
+    fn generate(rng: &mut impl CryptoRngCore) -> Self {
+        let mut salt = vec![0u8; ARGON2_SALT_SIZE];
+        rng.fill_bytes(&mut salt);
+
+        Self {
+            algorithm: argon2::Algorithm::Argon2id,
+            memory: ARGON2_MEMORY,
+            passes: ARGON2_PASSES,
+            parallelism: ARGON2_PARALLELISM,
+            salt,
+        }
+    }
+
+    /// Read the parameters following a `Key-Derivation` header with the
+    /// given value.
+    fn decode<'a>(algorithm: &str, lines: &mut impl Iterator<Item = &'a str>) -> Result<Self> {
+        let algorithm = match algorithm {
+            "Argon2d" => argon2::Algorithm::Argon2d,
+            "Argon2i" => argon2::Algorithm::Argon2i,
+            "Argon2id" => argon2::Algorithm::Argon2id,
+            _ => return Err(Error::AlgorithmUnknown),
+        };
+
+        let mut parse = |name, max| {
+            header_value(lines, name)?
+                .parse::<u32>()
+                .ok()
+                .filter(|&value| value <= max)
+                .ok_or(Error::FormatEncoding)
+        };
+
+        let memory = parse("Argon2-Memory", MAX_ARGON2_MEMORY)?;
+        let passes = parse("Argon2-Passes", MAX_ARGON2_PASSES)?;
+        let parallelism = parse("Argon2-Parallelism", MAX_ARGON2_PARALLELISM)?;
+        let salt = decode_hex(header_value(lines, "Argon2-Salt")?)?;
+
+        Ok(Self {
+            algorithm,
+            memory,
+            passes,
+            parallelism,
+            salt,
+        })
+    }
+
+    /// Write the `Key-Derivation` header and the parameters which follow.
+    fn encode(&self, out: &mut String, eol: &str) {
+        let algorithm = match self.algorithm {
+            argon2::Algorithm::Argon2d => "Argon2d",
+            argon2::Algorithm::Argon2i => "Argon2i",
+            argon2::Algorithm::Argon2id => "Argon2id",
+        };
+
+        write_header(out, KEY_DERIVATION_HEADER, algorithm, eol);
+        write_header(out, "Argon2-Memory", &self.memory.to_string(), eol);
+        write_header(out, "Argon2-Passes", &self.passes.to_string(), eol);
+        write_header(
+            out,
+            "Argon2-Parallelism",
+            &self.parallelism.to_string(),
+            eol,
+        );
+        out.push_str("Argon2-Salt: ");
+        push_hex(out, &self.salt);
+        out.push_str(eol);
+    }
+
+    /// Derive key material from the passphrase, filling `output`.
+    fn derive(&self, passphrase: &[u8], output: &mut [u8]) -> Result<()> {
+        let params = argon2::Params::new(
+            self.memory,
+            self.passes,
+            self.parallelism,
+            Some(output.len()),
+        )
+        .map_err(|_| Error::Crypto)?;
+
+        Argon2::new(self.algorithm, argon2::Version::V0x13, params)
+            .hash_password_into(passphrase, &self.salt, output)
+            .map_err(|_| Error::Crypto)
+    }
+}
+
+impl PrivateKey {
+    /// Parse a PuTTY private key (`.ppk`), i.e. one which begins with
+    /// `PuTTY-User-Key-File-2` or `PuTTY-User-Key-File-3` as written by
+    /// PuTTYgen.
+    ///
+    /// The passphrase is used to decrypt keys encrypted with `aes256-cbc`,
+    /// and is ignored for unencrypted keys. The MAC of the key is always
+    /// checked.
+    ///
+    /// # Returns
+    /// - [`Error::WrongPassword`] if the passphrase is incorrect.
+    /// - [`Error::Crypto`] if the MAC of an unencrypted key is invalid, i.e.
+    ///   the file has been modified, or the Argon2 parameters of an encrypted
+    ///   version 3 key are invalid.
+    /// - [`Error::AlgorithmUnknown`] if the key uses an unknown encryption
+    ///   algorithm or key derivation function.
+    pub fn from_ppk(input: &str, passphrase: impl AsRef<[u8]>) -> Result<Self> {
+        let mut lines = input
+            .strip_prefix('\u{feff}')
+            .unwrap_or(input)
+            .lines()
+            .skip_while(|line| line.trim().is_empty());
+
+        let (name, algorithm) = next_header(&mut lines)?;
+        let version = PpkVersion::new(
+            name.strip_prefix(FILE_HEADER_PREFIX)
+                .ok_or(Error::FormatEncoding)?,
+        )?;
+
+        let encryption = header_value(&mut lines, "Encryption")?;
+        let encrypted = match encryption {
+            ENCRYPTION_NONE => false,
+            ENCRYPTION_AES256_CBC => true,
+            _ => return Err(Error::AlgorithmUnknown),
+        };
+
+        let comment = header_value(&mut lines, "Comment")?;
+        let public_blob = base64_lines(&mut lines, "Public-Lines")?;
+
+        let (mut name, mut value) = next_header(&mut lines)?;
+        let mut argon2 = None;
+
+        if name == KEY_DERIVATION_HEADER {
+            argon2 = Some(Argon2Params::decode(value, &mut lines)?);
+            (name, value) = next_header(&mut lines)?;
+        }
+
+        // Only encrypted version 3 keys have key derivation parameters
+        if argon2.is_some() != (encrypted && version == PpkVersion::V3) {
+            return Err(Error::FormatEncoding);
+        }
+
+        if name != "Private-Lines" {
+            return Err(Error::FormatEncoding);
+        }
+
+        let mut private_blob = Zeroizing::new(base64_lines_after(&mut lines, value)?);
+        (name, value) = next_header(&mut lines)?;
+
+        if name != "Private-MAC" {
+            return Err(Error::FormatEncoding);
+        }
+
+        let expected_mac = decode_hex(value)?;
+
+        if lines.any(|line| !line.trim().is_empty()) {
+            return Err(Error::FormatEncoding);
+        }
+
+        let passphrase = if encrypted { passphrase.as_ref() } else { b"" };
+        let keys = version.keys(passphrase, argon2.as_ref())?;
+
+        if encrypted {
+            if private_blob.is_empty() {
+                return Err(Error::Crypto);
+            }
+
+            cbc_decrypt::<Aes256>(&*keys.cipher_key, &keys.iv, &mut private_blob)?;
+        }
+
+        let data = mac_data(algorithm, encryption, comment, &public_blob, &private_blob)?;
+        let mac = version.mac(&keys.mac_key, &data)?;
+
+        if !bool::from(mac.as_slice().ct_eq(&expected_mac)) {
+            return Err(if encrypted {
+                Error::WrongPassword
+            } else {
+                Error::Crypto
+            });
+        }
+
+        let reader = &mut public_blob.as_slice();
+        let public = KeyData::decode(reader)?;
+        reader.finish(())?;
+
+        if public.algorithm().as_str() != algorithm {
+            return Err(Error::FormatEncoding);
+        }
+
+        // Any trailing data is padding, which is covered by the MAC
+        let key_data = decode_private_blob(public, &mut &**private_blob)?;
+        Self::new(key_data, comment)
+    }
+
+    /// Encode a PuTTY private key (`.ppk`) using the given format version.
+    ///
+    /// A non-empty passphrase encrypts the key with `aes256-cbc`. Encrypting
+    /// a [`PpkVersion::V3`] key requires a random salt, so is only supported
+    /// by [`PrivateKey::to_ppk_with_rng`].
+    ///
+    /// # Returns
+    /// - [`Error::Encrypted`] if this private key is encrypted.
+    /// - [`Error::AlgorithmUnknown`] if a passphrase is given for a
+    ///   [`PpkVersion::V3`] key.
+    /// - [`Error::AlgorithmUnsupported`] if the key's algorithm can't be
+    ///   represented as a PuTTY private key, e.g. security keys.
+    pub fn to_ppk(
+        &self,
+        version: PpkVersion,
+        passphrase: impl AsRef<[u8]>,
+        line_ending: LineEnding,
+    ) -> Result<Zeroizing<String>> {
+        let passphrase = passphrase.as_ref();
+
+        if !passphrase.is_empty() && version == PpkVersion::V3 {
+            return Err(Error::AlgorithmUnknown);
+        }
+
+        self.encode_ppk(version, passphrase, None, line_ending)
+    }
+
+    /// Encode a PuTTY private key (`.ppk`) using the given format version,
+    /// using the given RNG to generate the Argon2 salt of encrypted
+    /// [`PpkVersion::V3`] keys.
+    ///
+    /// See [`PrivateKey::to_ppk`] for the errors returned, aside from
+    /// [`Error::AlgorithmUnknown`].
+    pub fn to_ppk_with_rng(
+        &self,
+        rng: &mut impl CryptoRngCore,
+        version: PpkVersion,
+        passphrase: impl AsRef<[u8]>,
+        line_ending: LineEnding,
+    ) -> Result<Zeroizing<String>> {
+        let passphrase = passphrase.as_ref();
+        let argon2 = (!passphrase.is_empty() && version == PpkVersion::V3)
+            .then(|| Argon2Params::generate(rng));
+
+        self.encode_ppk(version, passphrase, argon2.as_ref(), line_ending)
+    }
+
+    /// Encode a PuTTY private key, encrypting it if the passphrase is
+    /// non-empty.
+    fn encode_ppk(
+        &self,
+        version: PpkVersion,
+        passphrase: &[u8],
+        argon2: Option<&Argon2Params>,
+        line_ending: LineEnding,
+    ) -> Result<Zeroizing<String>> {
+        let encrypted = !passphrase.is_empty();
+        let algorithm = self.algorithm();
+        let algorithm = algorithm.as_str();
+        let encryption = if encrypted {
+            ENCRYPTION_AES256_CBC
+        } else {
+            ENCRYPTION_NONE
+        };
+
+        let public_blob = self.public_key().to_bytes()?;
+        let mut private_blob = encode_private_blob(self.key_data())?;
+
+        if encrypted {
+            // Pad using the hash of the private key data, as PuTTY does
+            let padding = Sha1::digest(private_blob.as_slice());
+            let padding_len =
+                AES_BLOCK_SIZE.saturating_sub(private_blob.len() % AES_BLOCK_SIZE) % AES_BLOCK_SIZE;
+
+            private_blob.extend_from_slice(padding.get(..padding_len).ok_or(Error::Crypto)?);
+        }
+
+        let keys = version.keys(passphrase, argon2)?;
+        let data = mac_data(
+            algorithm,
+            encryption,
+            self.comment(),
+            &public_blob,
+            &private_blob,
+        )?;
+        let mac = version.mac(&keys.mac_key, &data)?;
+
+        if encrypted {
+            cbc_encrypt::<Aes256>(&*keys.cipher_key, &keys.iv, &mut private_blob)?;
+        }
+
+        let eol = str::from_utf8(line_ending.as_bytes())?;
+        let mut out = Zeroizing::new(String::new());
+
+        write_header(
+            &mut out,
+            &[FILE_HEADER_PREFIX, version.as_str()].concat(),
+            algorithm,
+            eol,
+        );
+        write_header(&mut out, "Encryption", encryption, eol);
+        write_header(&mut out, "Comment", self.comment(), eol);
+        write_base64_lines(&mut out, "Public-Lines", &public_blob, eol)?;
+
+        if let Some(argon2) = argon2 {
+            argon2.encode(&mut out, eol);
+        }
+
+        write_base64_lines(&mut out, "Private-Lines", &private_blob, eol)?;
+
+        out.push_str("Private-MAC: ");
+        push_hex(&mut out, &mac);
+        out.push_str(eol);
+        Ok(out)
+    }
+}
+
+/// Read the next header line, returning its name and value.
+fn next_header<'a>(lines: &mut impl Iterator<Item = &'a str>) -> Result<(&'a str, &'a str)> {
+    lines
+        .next()
+        .and_then(|line| line.split_once(": "))
+        .ok_or(Error::FormatEncoding)
+}
+
+/// Read the next header line, which must have the given name, returning its
+/// value.
+fn header_value<'a>(lines: &mut impl Iterator<Item = &'a str>, name: &str) -> Result<&'a str> {
+    match next_header(lines)? {
+        (header, value) if header == name => Ok(value),
+        _ => Err(Error::FormatEncoding),
+    }
+}
+
+/// Read a header with the given name giving the number of lines of Base64
+/// which follow, and decode them.
+fn base64_lines<'a>(lines: &mut impl Iterator<Item = &'a str>, name: &str) -> Result<Vec<u8>> {
+    let count = header_value(lines, name)?;
+    base64_lines_after(lines, count)
+}
+
+/// Decode the given number of lines of Base64.
+fn base64_lines_after<'a>(
+    lines: &mut impl Iterator<Item = &'a str>,
+    count: &str,
+) -> Result<Vec<u8>> {
+    let count = count.parse::<usize>().map_err(|_| Error::FormatEncoding)?;
+    let mut base64 = Zeroizing::new(String::new());
+
+    for _ in 0..count {
+        base64.push_str(lines.next().ok_or(Error::FormatEncoding)?.trim());
+    }
+
+    Ok(Base64::decode_vec(&base64)?)
+}
+
+/// Write a header line with the given name and value.
+fn write_header(out: &mut String, name: &str, value: &str, eol: &str) {
+    out.push_str(name);
+    out.push_str(": ");
+    out.push_str(value);
+    out.push_str(eol);
+}
+
+/// Write a header with the given name giving the number of lines of Base64
+/// which follow, and the Base64-encoded data.
+fn write_base64_lines(out: &mut String, name: &str, data: &[u8], eol: &str) -> Result<()> {
+    let base64 = Zeroizing::new(Base64::encode_string(data));
+    let lines = base64.as_bytes().chunks(BASE64_LINE_WIDTH);
+
+    write_header(out, name, &lines.len().to_string(), eol);
+
+    // Base64 is ASCII, so every chunk is valid UTF-8
+    for line in lines {
+        out.push_str(str::from_utf8(line)?);
+        out.push_str(eol);
+    }
+
+    Ok(())
+}
+
+/// Serialize the data covered by the MAC.
+fn mac_data(
+    algorithm: &str,
+    encryption: &str,
+    comment: &str,
+    public_blob: &[u8],
+    private_blob: &[u8],
+) -> Result<Zeroizing<Vec<u8>>> {
+    let mut data = Zeroizing::new(Vec::new());
+    algorithm.encode(&mut *data)?;
+    encryption.encode(&mut *data)?;
+    comment.encode(&mut *data)?;
+    public_blob.encode(&mut *data)?;
+    private_blob.encode(&mut *data)?;
+    Ok(data)
+}
+
+/// Append the given bytes to `out` as lower case hexadecimal.
+fn push_hex(out: &mut String, bytes: &[u8]) {
+    for byte in bytes {
+        for digit in [byte >> 4, byte & 0xf] {
+            out.extend(char::from_digit(digit.into(), 16));
+        }
+    }
+}
+
+/// Decode the private key data of the given public key.
+///
+/// PuTTY's private key data differs from OpenSSH's: RSA keys store the CRT
+/// coefficient last, ECDSA scalars are `mpint`s, and Ed25519 keys store
+/// only the seed as a little endian integer.
+fn decode_private_blob(public: KeyData, reader: &mut impl Reader) -> Result<KeypairData> {
+    match public {
+        KeyData::Dsa(public) => Ok(KeypairData::Dsa(DsaKeypair {
+            public,
+            private: DsaPrivateKey::decode(reader)?,
+        })),
+        #[cfg(feature = "ecdsa")]
+        KeyData::Ecdsa(public) => {
+            let scalar = Mpint::decode(reader)?;
+            let bytes = scalar.as_positive_bytes().ok_or(Error::FormatEncoding)?;
+
+            Ok(KeypairData::Ecdsa(match public {
+                EcdsaPublicKey::NistP256(public) => EcdsaKeypair::NistP256 {
+                    public,
+                    private: EcdsaPrivateKey::from_unpadded_bytes(bytes)?,
+                },
+                EcdsaPublicKey::NistP384(public) => EcdsaKeypair::NistP384 {
+                    public,
+                    private: EcdsaPrivateKey::from_unpadded_bytes(bytes)?,
+                },
+                EcdsaPublicKey::NistP521(public) => EcdsaKeypair::NistP521 {
+                    public,
+                    private: EcdsaPrivateKey::from_unpadded_bytes(bytes)?,
+                },
+            }))
+        }
+        KeyData::Ed25519(public) => {
+            let private = reader.read_prefixed(|reader| {
+                let mut seed = Zeroizing::new([0u8; Ed25519PrivateKey::BYTE_SIZE]);
+                let len = reader.remaining_len();
+                reader.read(seed.get_mut(..len).ok_or(encoding::Error::Length)?)?;
+                Ok::<_, Error>(Ed25519PrivateKey::from_bytes(&seed))
+            })?;
+
+            Ok(KeypairData::Ed25519(Ed25519Keypair { public, private }))
+        }
+        KeyData::Rsa(public) => {
+            let d = Mpint::decode(reader)?;
+            let p = Mpint::decode(reader)?;
+            let q = Mpint::decode(reader)?;
+            let iqmp = Mpint::decode(reader)?;
+
+            Ok(KeypairData::Rsa(RsaKeypair {
+                public,
+                private: RsaPrivateKey { d, iqmp, p, q },
+            }))
+        }
+        other => Err(other.algorithm().unsupported_error()),
+    }
+}
+
+/// Encode the private key data of the given keypair.
+///
+/// See [`decode_private_blob`].
+fn encode_private_blob(key_data: &KeypairData) -> Result<Zeroizing<Vec<u8>>> {
+    let mut blob = Zeroizing::new(Vec::new());
+
+    match key_data {
+        KeypairData::Dsa(keypair) => keypair.private.encode(&mut *blob)?,
+        #[cfg(feature = "ecdsa")]
+        KeypairData::Ecdsa(keypair) => {
+            let bytes = keypair.private_key_bytes();
+            let leading_zeros = bytes.iter().take_while(|&&byte| byte == 0).count();
+            let scalar = bytes.get(leading_zeros..).ok_or(Error::Crypto)?;
+            Mpint::from_positive_bytes(scalar)?.encode(&mut *blob)?;
+        }
+        KeypairData::Ed25519(keypair) => {
+            let seed = keypair.private.as_ref();
+            let len = seed
+                .iter()
+                .rposition(|&byte| byte != 0)
+                .map_or(0, |i| i.saturating_add(1));
+
+            seed.get(..len).ok_or(Error::Crypto)?.encode(&mut *blob)?;
+        }
+        KeypairData::Rsa(keypair) => {
+            keypair.private.d.encode(&mut *blob)?;
+            keypair.private.p.encode(&mut *blob)?;
+            keypair.private.q.encode(&mut *blob)?;
+            keypair.private.iqmp.encode(&mut *blob)?;
+        }
+        KeypairData::Encrypted(_) => return Err(Error::Encrypted),
+        other => return Err(other.algorithm()?.unsupported_error()),
+    }
+
+    Ok(blob)
+}
@@ -17,6 +17,14 @@ use {
 };
 
 /// RSA private key.
+///
+/// Contains the components stored in OpenSSH private keys, i.e. the private
+/// exponent and the Chinese Remainder Theorem (CRT) parameters `p`, `q` and
+/// `iqmp`, e.g. for exporting to other formats. They can be accessed from a
+/// decoded key via [`KeypairData::rsa`][`crate::private::KeypairData::rsa`].
+///
+/// The CRT exponents `d mod (p-1)` and `d mod (q-1)` are not stored by
+/// OpenSSH, and can be computed from these components if needed.
 #[derive(Clone)]
 pub struct RsaPrivateKey {
     /// RSA private exponent.
@@ -53,10 +61,10 @@ impl Decode for RsaPrivateKey {
     type Error = Error;
 
     fn decode(reader: &mut impl Reader) -> Result<Self> {
-        let d = Mpint::decode(reader)?;
-        let iqmp = Mpint::decode(reader)?;
-        let p = Mpint::decode(reader)?;
-        let q = Mpint::decode(reader)?;
+        let d = Mpint::decode_positive(reader)?;
+        let iqmp = Mpint::decode_positive(reader)?;
+        let p = Mpint::decode_positive(reader)?;
+        let q = Mpint::decode_positive(reader)?;
         Ok(Self { d, iqmp, p, q })
     }
 }
@@ -116,6 +124,68 @@ impl RsaKeypair {
             Err(Error::Crypto)
         }
     }
+
+    /// Replace the public key with the one derived from the private key,
+    /// i.e. `n = pq` and `e = d⁻¹ mod λ(n)`.
+    #[cfg(feature = "rsa")]
+    #[allow(clippy::arithmetic_side_effects)]
+    pub(super) fn repair_public_key(&mut self) -> Result<()> {
+        use bigint::{ExtendedGcd, ModInverse, ToBigInt};
+
+        let d = rsa::BigUint::try_from(&self.private.d)?;
+        let p = rsa::BigUint::try_from(&self.private.p)?;
+        let q = rsa::BigUint::try_from(&self.private.q)?;
+        let one = rsa::BigUint::from(1u8);
+
+        if p <= one || q <= one {
+            return Err(Error::Crypto);
+        }
+
+        // λ(n) = lcm(p - 1, q - 1)
+        let (p1, q1) = (&p - &one, &q - &one);
+        let gcd = p1.to_bigint().ok_or(Error::Crypto)?.extended_gcd(&q1).0;
+        let lambda = (&p1 * &q1) / gcd.to_biguint().ok_or(Error::Crypto)?;
+
+        let e = d
+            .clone()
+            .mod_inverse(&lambda)
+            .and_then(|e| e.to_biguint())
+            .ok_or(Error::Crypto)?;
+
+        let key = rsa::RsaPrivateKey::from_components(&p * &q, e, d, vec![p, q])?;
+        key.validate()?;
+        self.public = key.to_public_key().try_into()?;
+        Ok(())
+    }
+
+    /// Check that the public key corresponds to the private key, i.e.
+    /// `n = pq` and `ed ≡ 1 mod λ(n)`.
+    #[cfg(feature = "rsa")]
+    #[allow(clippy::arithmetic_side_effects)]
+    pub(super) fn validate(&self) -> Result<()> {
+        let n = rsa::BigUint::try_from(&self.public.n)?;
+        let e = rsa::BigUint::try_from(&self.public.e)?;
+        let d = rsa::BigUint::try_from(&self.private.d)?;
+        let p = rsa::BigUint::try_from(&self.private.p)?;
+        let q = rsa::BigUint::try_from(&self.private.q)?;
+        let one = rsa::BigUint::from(1u8);
+
+        if p <= one || q <= one {
+            return Err(Error::Crypto);
+        }
+
+        if &p * &q != n {
+            return Err(Error::PublicKey);
+        }
+
+        // λ(n) = lcm(p - 1, q - 1), so check `ed ≡ 1` modulo each of them
+        let ed = e * d;
+        if [&p - &one, &q - &one].iter().any(|m| &ed % m != one) {
+            return Err(Error::PublicKey);
+        }
+
+        Ok(())
+    }
 }
 
 impl ConstantTimeEq for RsaKeypair {
@@ -136,8 +206,8 @@ impl Decode for RsaKeypair {
     type Error = Error;
 
     fn decode(reader: &mut impl Reader) -> Result<Self> {
-        let n = Mpint::decode(reader)?;
-        let e = Mpint::decode(reader)?;
+        let n = Mpint::decode_positive(reader)?;
+        let e = Mpint::decode_positive(reader)?;
         let public = RsaPublicKey { n, e };
         let private = RsaPrivateKey::decode(reader)?;
         Ok(RsaKeypair { public, private })
@@ -203,7 +273,7 @@ impl TryFrom<&RsaKeypair> for rsa::RsaPrivateKey {
             rsa::BigUint::try_from(&key.private.d)?,
             vec![
                 rsa::BigUint::try_from(&key.private.p)?,
-                rsa::BigUint::try_from(&key.private.p)?,
+                rsa::BigUint::try_from(&key.private.q)?,
             ],
         )?;
 
@@ -262,3 +332,45 @@ where
         Ok(pkcs1v15::SigningKey::new(keypair.try_into()?))
     }
 }
+
+#[cfg(all(test, feature = "rsa"))]
+mod tests {
+    use super::RsaKeypair;
+    use crate::PrivateKey;
+    use bigint::BigUint;
+    use rsa::traits::PrivateKeyParts;
+
+    #[test]
+    fn rsa_private_key_round_trip() {
+        let key =
+            PrivateKey::from_openssh(include_str!("../../tests/examples/id_rsa_3072")).unwrap();
+        let keypair = key.key_data().rsa().unwrap();
+
+        let rsa_key = rsa::RsaPrivateKey::try_from(keypair).unwrap();
+        assert_eq!(
+            rsa_key.primes(),
+            [
+                BigUint::try_from(&keypair.private.p).unwrap(),
+                BigUint::try_from(&keypair.private.q).unwrap(),
+            ]
+        );
+        assert!(rsa_key.validate().is_ok());
+        assert_eq!(&RsaKeypair::try_from(&rsa_key).unwrap(), keypair);
+    }
+
+    #[test]
+    fn crt_parameters() {
+        let key =
+            PrivateKey::from_openssh(include_str!("../../tests/examples/id_rsa_3072")).unwrap();
+        let keypair = key.key_data().rsa().unwrap();
+        let uint = |mpint| BigUint::try_from(mpint).unwrap();
+
+        let n = uint(&keypair.public.n);
+        let p = uint(&keypair.private.p);
+        let q = uint(&keypair.private.q);
+        let iqmp = uint(&keypair.private.iqmp);
+
+        assert_eq!(&p * &q, n);
+        assert_eq!((q * iqmp) % p, BigUint::from(1u8));
+    }
+}
@@ -0,0 +1,61 @@
+//! Opt-in `serde` support for [`PrivateKey`], for use with
+//! `#[serde(with = "ssh_key::private::serde")]`.
+//!
+//! Like the `serde` impls on [`PublicKey`][`crate::PublicKey`], keys are
+//! serialized in the OpenSSH format (with LF line endings) for human-readable
+//! formats, and as the binary form of that format otherwise. Encrypted keys
+//! remain encrypted.
+//!
+//! NOTE: this crate zeroizes the intermediate buffers it creates, but the
+//! buffers of the serializer or deserializer (e.g. the serialized JSON) are
+//! outside of its control and will generally not be zeroized.
+//!
+//! ## Example
+//!
+//! ```
+//! use ssh_key::PrivateKey;
+//!
+//! #[derive(serde::Deserialize, serde::Serialize)]
+//! struct HostKey {
+//!     name: String,
+//!
+//!     #[serde(with = "ssh_key::private::serde")]
+//!     key: PrivateKey,
+//! }
+//! ```
+
+use super::PrivateKey;
+use crate::LineEnding;
+use alloc::{string::String, vec::Vec};
+use serde::{de, ser, Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+/// Serialize the given [`PrivateKey`].
+pub fn serialize<S>(key: &PrivateKey, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: ser::Serializer,
+{
+    if serializer.is_human_readable() {
+        key.to_openssh(LineEnding::LF)
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    } else {
+        key.to_bytes()
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+/// Deserialize a [`PrivateKey`].
+pub fn deserialize<'de, D>(deserializer: D) -> Result<PrivateKey, D::Error>
+where
+    D: de::Deserializer<'de>,
+{
+    if deserializer.is_human_readable() {
+        let string = Zeroizing::new(String::deserialize(deserializer)?);
+        PrivateKey::from_openssh(&*string).map_err(de::Error::custom)
+    } else {
+        let bytes = Zeroizing::new(Vec::<u8>::deserialize(deserializer)?);
+        PrivateKey::from_bytes(&bytes).map_err(de::Error::custom)
+    }
+}
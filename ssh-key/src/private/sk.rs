@@ -1,10 +1,148 @@
 //! Security Key (FIDO/U2F) private keys as described in [PROTOCOL.u2f].
 //!
+//! The private key material of these keys is held by the authenticator, so
+//! signing is delegated to an [`SkAuthenticator`] (e.g. a CTAP2 backend
+//! talking to a USB HID device) using an [`SkSigner`].
+//!
 //! [PROTOCOL.u2f]: https://cvsweb.openbsd.org/src/usr.bin/ssh/PROTOCOL.u2f?annotate=HEAD
 
-use crate::{public, Error, Result};
+use super::{KeypairData, PrivateKey};
+use crate::{
+    public::{self, KeyData, SK_USER_PRESENCE_FLAG, SK_USER_VERIFICATION_FLAG},
+    Algorithm, Error, Result, Signature, SigningKey,
+};
 use alloc::vec::Vec;
 use encoding::{CheckedSum, Decode, Encode, Reader, Writer};
+use signature::Signer;
+
+#[cfg(feature = "ecdsa")]
+use crate::EcdsaCurve;
+
+/// FIDO/U2F authenticator which holds the private keys of Security Keys.
+///
+/// This is the extension point for hardware backends, e.g. a CTAP2
+/// implementation performing an `authenticatorGetAssertion` request over USB
+/// HID, analogous to the `sk_sign` function of OpenSSH's `SecurityKeyProvider`
+/// middleware.
+pub trait SkAuthenticator {
+    /// Sign the given message using the Security Key with the given
+    /// application and key handle.
+    ///
+    /// Authenticators sign SHA-256(application) || flags || counter ||
+    /// SHA-256(message), i.e. the client data hash is SHA-256 of the
+    /// message. The given flags are those of the key, i.e. whether user
+    /// presence and/or verification are required.
+    ///
+    /// This may block, e.g. until the user touches the Security Key.
+    fn sign(
+        &self,
+        application: &str,
+        key_handle: &[u8],
+        flags: u8,
+        message: &[u8],
+    ) -> Result<SkAssertion>;
+}
+
+/// Assertion produced by an [`SkAuthenticator`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SkAssertion {
+    /// Flags reported by the authenticator, e.g. whether the user was
+    /// present.
+    pub flags: u8,
+
+    /// Signature counter reported by the authenticator.
+    pub counter: u32,
+
+    /// Signature over the authenticator data and message hash, i.e. an
+    /// `ssh-ed25519` signature for `sk-ssh-ed25519@openssh.com` keys or an
+    /// `ecdsa-sha2-nistp256` signature for `sk-ecdsa-sha2-nistp256@openssh.com`
+    /// keys.
+    pub signature: Signature,
+}
+
+/// [`SigningKey`] which signs using a Security Key held by an
+/// [`SkAuthenticator`].
+///
+/// Created using [`PrivateKey::sk_signer`].
+#[derive(Debug)]
+pub struct SkSigner<'a, A> {
+    /// Authenticator holding the private key.
+    authenticator: &'a A,
+
+    /// Security Key to sign with.
+    keypair: &'a KeypairData,
+
+    /// Public key of the Security Key.
+    public_key: KeyData,
+}
+
+impl<A: SkAuthenticator> Signer<Signature> for SkSigner<'_, A> {
+    fn try_sign(&self, message: &[u8]) -> signature::Result<Signature> {
+        let (algorithm, application, key_handle, flags) = match self.keypair {
+            #[cfg(feature = "ecdsa")]
+            KeypairData::SkEcdsaSha2NistP256(sk) => (
+                Algorithm::Ecdsa {
+                    curve: EcdsaCurve::NistP256,
+                },
+                sk.public().application(),
+                sk.key_handle(),
+                sk.flags(),
+            ),
+            KeypairData::SkEd25519(sk) => (
+                Algorithm::Ed25519,
+                sk.public().application(),
+                sk.key_handle(),
+                sk.flags(),
+            ),
+            _ => return Err(self.keypair.algorithm()?.unsupported_error().into()),
+        };
+
+        let assertion = self
+            .authenticator
+            .sign(application, key_handle, flags, message)?;
+
+        if assertion.signature.algorithm() != algorithm {
+            return Err(Error::Crypto.into());
+        }
+
+        let mut data = assertion.signature.as_bytes().to_vec();
+        data.push(assertion.flags);
+        data.extend_from_slice(&assertion.counter.to_be_bytes());
+        Ok(Signature::new(self.keypair.algorithm()?, data)?)
+    }
+}
+
+impl<A: SkAuthenticator> SigningKey for SkSigner<'_, A> {
+    fn public_key(&self) -> KeyData {
+        self.public_key.clone()
+    }
+}
+
+impl PrivateKey {
+    /// Get a [`SigningKey`] which signs using this Security Key, which is
+    /// held by the given authenticator.
+    ///
+    /// Returns [`Error::AlgorithmUnsupported`] if this isn't a Security Key,
+    /// or [`Error::Encrypted`] if the key is encrypted.
+    pub fn sk_signer<'a, A: SkAuthenticator>(
+        &'a self,
+        authenticator: &'a A,
+    ) -> Result<SkSigner<'a, A>> {
+        if self.is_encrypted() {
+            return Err(Error::Encrypted);
+        }
+
+        if !self.algorithm().is_sk() {
+            return Err(self.algorithm().unsupported_error());
+        }
+
+        Ok(SkSigner {
+            authenticator,
+            keypair: self.key_data(),
+            public_key: self.public_key().key_data().clone(),
+        })
+    }
+}
 
 /// Security Key (FIDO/U2F) ECDSA/NIST P-256 private key as specified in
 /// [PROTOCOL.u2f](https://cvsweb.openbsd.org/src/usr.bin/ssh/PROTOCOL.u2f?annotate=HEAD).
@@ -26,6 +164,21 @@ pub struct SkEcdsaSha2NistP256 {
 
 #[cfg(feature = "ecdsa")]
 impl SkEcdsaSha2NistP256 {
+    /// Create a new Security Key private key from the public key, flags and
+    /// key handle returned when the key was enrolled.
+    pub fn new(
+        public: public::SkEcdsaSha2NistP256,
+        flags: u8,
+        key_handle: impl Into<Vec<u8>>,
+    ) -> Self {
+        Self {
+            public,
+            flags,
+            key_handle: key_handle.into(),
+            reserved: Vec::new(),
+        }
+    }
+
     /// Get the ECDSA/NIST P-256 public key.
     pub fn public(&self) -> &public::SkEcdsaSha2NistP256 {
         &self.public
@@ -36,6 +189,16 @@ impl SkEcdsaSha2NistP256 {
         self.flags
     }
 
+    /// Is user presence (i.e. a touch) required when signing?
+    pub fn user_presence_required(&self) -> bool {
+        self.flags & SK_USER_PRESENCE_FLAG != 0
+    }
+
+    /// Is user verification (e.g. a PIN) required when signing?
+    pub fn user_verification_required(&self) -> bool {
+        self.flags & SK_USER_VERIFICATION_FLAG != 0
+    }
+
     /// Get FIDO/U2F key handle.
     pub fn key_handle(&self) -> &[u8] {
         &self.key_handle
@@ -98,6 +261,17 @@ pub struct SkEd25519 {
 }
 
 impl SkEd25519 {
+    /// Create a new Security Key private key from the public key, flags and
+    /// key handle returned when the key was enrolled.
+    pub fn new(public: public::SkEd25519, flags: u8, key_handle: impl Into<Vec<u8>>) -> Self {
+        Self {
+            public,
+            flags,
+            key_handle: key_handle.into(),
+            reserved: Vec::new(),
+        }
+    }
+
     /// Get the Ed25519 public key.
     pub fn public(&self) -> &public::SkEd25519 {
         &self.public
@@ -108,6 +282,16 @@ impl SkEd25519 {
         self.flags
     }
 
+    /// Is user presence (i.e. a touch) required when signing?
+    pub fn user_presence_required(&self) -> bool {
+        self.flags & SK_USER_PRESENCE_FLAG != 0
+    }
+
+    /// Is user verification (e.g. a PIN) required when signing?
+    pub fn user_verification_required(&self) -> bool {
+        self.flags & SK_USER_VERIFICATION_FLAG != 0
+    }
+
     /// Get FIDO/U2F key handle.
     pub fn key_handle(&self) -> &[u8] {
         &self.key_handle
@@ -0,0 +1,211 @@
+//! eXtended Merkle Signature Scheme (XMSS) private keys.
+
+use crate::{public::XmssPublicKey, Error, Result};
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+use encoding::{CheckedSum, Decode, Encode, Reader, Writer};
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::Zeroizing;
+
+/// Signer state format: no state.
+const STATE_NONE: u8 = 0;
+
+/// Signer state format: the state alone.
+const STATE_ONLY: u8 = 1;
+
+/// Signer state format: the key used to encrypt the state file, followed by
+/// the state.
+const STATE_FULL: u8 = 2;
+
+/// Magic strings beginning a serialized signer state.
+const STATE_MAGIC: [&str; 2] = ["XMSS-K1", "XMSS-K2"];
+
+/// XMSS keypair.
+///
+/// The private key and the state of the XMSS signer (e.g. the index of the
+/// next one-time key) are encoded as serialized by OpenSSH's
+/// `sshkey-xmss.c`: the private key is a `string`, followed by a byte giving
+/// the format of the state, and the state itself. The state is kept as raw
+/// bytes.
+///
+/// Since XMSS is stateful, re-encoding an older copy of a key after it has
+/// been used to sign messages can lead to one-time keys being reused.
+#[derive(Clone)]
+pub struct XmssKeypair {
+    /// Public key.
+    public: XmssPublicKey,
+
+    /// Private key.
+    private: Zeroizing<Vec<u8>>,
+
+    /// Signer state, beginning with the byte giving its format.
+    state: Zeroizing<Vec<u8>>,
+
+    /// Index of the next unused one-time key, if the state is present.
+    index: Option<u32>,
+}
+
+impl XmssKeypair {
+    /// Create a new XMSS keypair from the given public key, private key, and
+    /// signer state as serialized by OpenSSH (i.e. beginning with the byte
+    /// giving its format).
+    ///
+    /// Returns [`Error::FormatEncoding`] if the state is in an unknown format,
+    /// or an encoding error if it's malformed.
+    pub fn new(
+        public: XmssPublicKey,
+        private: impl Into<Vec<u8>>,
+        state: impl AsRef<[u8]>,
+    ) -> Result<Self> {
+        let reader = &mut state.as_ref();
+        let (state, index) = decode_state(reader)?;
+
+        Ok(reader.finish(Self {
+            public,
+            private: Zeroizing::new(private.into()),
+            state,
+            index,
+        })?)
+    }
+
+    /// Get the public key.
+    pub fn public(&self) -> &XmssPublicKey {
+        &self.public
+    }
+
+    /// Get the private key as bytes.
+    pub fn private_key_bytes(&self) -> &[u8] {
+        &self.private
+    }
+
+    /// Get the signer state as serialized by OpenSSH, beginning with the
+    /// byte giving its format.
+    pub fn state_bytes(&self) -> &[u8] {
+        &self.state
+    }
+
+    /// Get the index of the next unused one-time key, if the signer state
+    /// is present.
+    pub fn index(&self) -> Option<u32> {
+        self.index
+    }
+}
+
+impl Decode for XmssKeypair {
+    type Error = Error;
+
+    fn decode(reader: &mut impl Reader) -> Result<Self> {
+        let public = XmssPublicKey::decode(reader)?;
+        let private = Zeroizing::new(Vec::decode(reader)?);
+        let (state, index) = decode_state(reader)?;
+
+        Ok(Self {
+            public,
+            private,
+            state,
+            index,
+        })
+    }
+}
+
+impl ConstantTimeEq for XmssKeypair {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        Choice::from((self.public == other.public) as u8)
+            & self.private.ct_eq(&other.private)
+            & self.state.ct_eq(&other.state)
+    }
+}
+
+impl Eq for XmssKeypair {}
+
+impl PartialEq for XmssKeypair {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Encode for XmssKeypair {
+    type Error = Error;
+
+    fn encoded_len(&self) -> Result<usize> {
+        Ok([
+            self.public.encoded_len()?,
+            self.private.encoded_len()?,
+            self.state.len(),
+        ]
+        .checked_sum()?)
+    }
+
+    fn encode(&self, writer: &mut impl Writer) -> Result<()> {
+        self.public.encode(writer)?;
+        self.private.encode(writer)?;
+        Ok(writer.write(&self.state)?)
+    }
+}
+
+impl From<&XmssKeypair> for XmssPublicKey {
+    fn from(keypair: &XmssKeypair) -> XmssPublicKey {
+        keypair.public.clone()
+    }
+}
+
+impl fmt::Debug for XmssKeypair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("XmssKeypair")
+            .field("public", &self.public)
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Decode the signer state following the private key, as serialized by
+/// `sshkey_xmss_serialize_state_opt`, returning its raw bytes and the index
+/// of the next unused one-time key.
+fn decode_state(reader: &mut impl Reader) -> Result<(Zeroizing<Vec<u8>>, Option<u32>)> {
+    let mut state = Zeroizing::new(Vec::new());
+    let format = u8::decode(reader)?;
+    format.encode(&mut *state)?;
+
+    match format {
+        STATE_NONE => return Ok((state, None)),
+        STATE_ONLY => (),
+        STATE_FULL => {
+            // Sequence number and key/IV used to encrypt the state file
+            u32::decode(reader)?.encode(&mut *state)?;
+            Zeroizing::new(Vec::<u8>::decode(reader)?).encode(&mut *state)?;
+        }
+        _ => return Err(Error::FormatEncoding),
+    }
+
+    let magic = String::decode(reader)?;
+
+    if !STATE_MAGIC.contains(&magic.as_str()) {
+        return Err(Error::FormatEncoding);
+    }
+
+    magic.encode(&mut *state)?;
+    let index = u32::decode(reader)?;
+    index.encode(&mut *state)?;
+
+    // `stack`, `stackoffset`, then `stacklevels`, `auth`, `keep`,
+    // `th_nodes` and `retain`
+    Zeroizing::new(Vec::<u8>::decode(reader)?).encode(&mut *state)?;
+    u32::decode(reader)?.encode(&mut *state)?;
+
+    for _ in 0..5 {
+        Zeroizing::new(Vec::<u8>::decode(reader)?).encode(&mut *state)?;
+    }
+
+    // Treehash instances, each a `uint32` height, next index and stack
+    // usage, a completion flag, and a `uint32` node offset
+    let treehash_len = u32::decode(reader)?;
+    treehash_len.encode(&mut *state)?;
+
+    for _ in 0..treehash_len {
+        let mut treehash = [0u8; 17];
+        reader.read(&mut treehash)?;
+        state.extend_from_slice(&treehash);
+    }
+
+    Ok((state, Some(index)))
+}
@@ -9,18 +9,42 @@ mod ecdsa;
 mod ed25519;
 mod key_data;
 #[cfg(feature = "alloc")]
+mod key_set;
+#[cfg(feature = "alloc")]
+mod metadata;
+#[cfg(feature = "alloc")]
+mod opaque;
+#[cfg(feature = "alloc")]
+mod rfc4716;
+#[cfg(feature = "alloc")]
 mod rsa;
 mod sk;
+#[cfg(feature = "pkcs8")]
+pub(crate) mod spki;
 mod ssh_format;
+#[cfg(feature = "alloc")]
+pub(crate) mod sshfp;
+#[cfg(feature = "alloc")]
+mod with_comment;
+#[cfg(feature = "xmss")]
+mod xmss;
 
+#[cfg(feature = "alloc")]
+pub(crate) use self::sk::{SK_USER_PRESENCE_FLAG, SK_USER_VERIFICATION_FLAG};
 pub use self::{ed25519::Ed25519PublicKey, key_data::KeyData, sk::SkEd25519};
 
 #[cfg(feature = "alloc")]
-pub use self::{dsa::DsaPublicKey, rsa::RsaPublicKey};
+pub use self::{
+    dsa::DsaPublicKey, key_set::KeySet, metadata::KeyMetadata, opaque::OpaquePublicKey,
+    rsa::RsaPublicKey, sshfp::SshfpRecord, with_comment::WithComment,
+};
 
 #[cfg(feature = "ecdsa")]
 pub use self::{ecdsa::EcdsaPublicKey, sk::SkEcdsaSha2NistP256};
 
+#[cfg(feature = "xmss")]
+pub use self::xmss::XmssPublicKey;
+
 pub(crate) use self::ssh_format::SshFormat;
 
 use crate::{Algorithm, Error, Fingerprint, HashAlg, Result};
@@ -29,12 +53,9 @@ use encoding::{Base64Reader, Decode, Reader};
 
 #[cfg(feature = "alloc")]
 use {
-    crate::SshSig,
-    alloc::{
-        borrow::ToOwned,
-        string::{String, ToString},
-        vec::Vec,
-    },
+    crate::{EcdsaCurve, Mpint, SshSig, VerificationConfig},
+    alloc::{borrow::ToOwned, string::String, vec::Vec},
+    core::fmt,
     encoding::Encode,
 };
 
@@ -64,6 +85,9 @@ use std::{fs, path::Path};
 /// used to decode/encode public keys, or alternatively, the [`FromStr`] and
 /// [`ToString`] impls.
 ///
+/// The RFC4716 encoding output by `ssh-keygen -e` is also supported, via
+/// [`PublicKey::from_rfc4716`] and [`PublicKey::to_rfc4716`].
+///
 /// # `serde` support
 ///
 /// When the `serde` feature of this crate is enabled, this type receives impls
@@ -101,7 +125,11 @@ impl PublicKey {
     /// ```text
     /// ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILM+rvN+ot98qgEN796jTiQfZfG1KaT0PtFDJ/XFSqti foo@bar.com
     /// ```
+    ///
+    /// A leading UTF-8 byte order mark (as written by e.g. Notepad) is
+    /// ignored.
     pub fn from_openssh(public_key: &str) -> Result<Self> {
+        let public_key = public_key.strip_prefix('\u{feff}').unwrap_or(public_key);
         let encapsulation = SshFormat::decode(public_key.trim_end().as_bytes())?;
         let mut reader = Base64Reader::new(encapsulation.base64_data)?;
         let key_data = KeyData::decode(&mut reader)?;
@@ -120,6 +148,65 @@ impl PublicKey {
         Ok(reader.finish(public_key)?)
     }
 
+    /// Parse an OpenSSH-formatted public key whose leading algorithm
+    /// identifier may have been omitted, i.e. just the Base64-encoded key
+    /// blob optionally followed by a comment, as pasted by some tools:
+    ///
+    /// ```text
+    /// AAAAC3NzaC1lZDI1NTE5AAAAILM+rvN+ot98qgEN796jTiQfZfG1KaT0PtFDJ/XFSqti foo@bar.com
+    /// ```
+    ///
+    /// The algorithm is inferred from the identifier inside the blob. Input
+    /// whose first field is an algorithm identifier is parsed as in
+    /// [`PublicKey::from_openssh`] instead. Otherwise, the first field is
+    /// always the blob and the remainder of the line the comment, so a
+    /// comment is never mistaken for key data.
+    pub fn infer_from_blob(public_key: &str) -> Result<Self> {
+        let public_key = public_key.strip_prefix('\u{feff}').unwrap_or(public_key);
+        let public_key = public_key.trim();
+
+        #[allow(unused_variables)]
+        let (blob, comment) = public_key
+            .split_once(char::is_whitespace)
+            .unwrap_or((public_key, ""));
+
+        if Algorithm::new(blob).is_ok() {
+            return Self::from_openssh(public_key);
+        }
+
+        let mut reader = Base64Reader::new(blob.as_bytes())?;
+        let key_data = KeyData::decode(&mut reader)?;
+
+        let public_key = Self {
+            key_data,
+            #[cfg(feature = "alloc")]
+            comment: comment.trim_start().to_owned(),
+        };
+
+        Ok(reader.finish(public_key)?)
+    }
+
+    /// Parse several OpenSSH-formatted public keys, one per line.
+    ///
+    /// Blank lines and lines beginning with `#` are skipped.
+    ///
+    /// Returns [`Error::LineInvalid`] with the (1-based) number of the first
+    /// line which fails to parse.
+    #[cfg(feature = "alloc")]
+    pub fn from_openssh_multi(public_keys: &str) -> Result<Vec<Self>> {
+        public_keys
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(index, line)| {
+                Self::from_openssh(line).map_err(|_| Error::LineInvalid {
+                    number: index.saturating_add(1),
+                })
+            })
+            .collect()
+    }
+
     /// Parse a raw binary SSH public key.
     pub fn from_bytes(mut bytes: &[u8]) -> Result<Self> {
         let reader = &mut bytes;
@@ -144,6 +231,37 @@ impl PublicKey {
         SshFormat::encode_string(self.algorithm().as_str(), &self.key_data, self.comment())
     }
 
+    /// Parse a cloud metadata `ssh-keys` entry, i.e. an OpenSSH-formatted
+    /// public key prefixed with a username and `:` as used by e.g. GCP
+    /// instance metadata:
+    ///
+    /// ```text
+    /// username:ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILM+rvN+ot98qgEN796jTiQfZfG1KaT0PtFDJ/XFSqti foo@bar.com
+    /// ```
+    ///
+    /// Returns the username along with the public key.
+    #[cfg(feature = "alloc")]
+    pub fn from_cloud_metadata(line: &str) -> Result<(String, Self)> {
+        let (username, public_key) = line.split_once(':').ok_or(Error::FormatEncoding)?;
+        validate_cloud_metadata_username(username)?;
+        Ok((username.to_owned(), Self::from_openssh(public_key)?))
+    }
+
+    /// Encode this key as a cloud metadata `ssh-keys` entry for the given
+    /// username, i.e. `username:` followed by the OpenSSH-formatted key.
+    ///
+    /// Returns [`Error::FormatEncoding`] if the username is empty or contains
+    /// `:` or whitespace.
+    #[cfg(feature = "alloc")]
+    pub fn to_cloud_metadata(&self, username: &str) -> Result<String> {
+        validate_cloud_metadata_username(username)?;
+
+        let mut line = username.to_owned();
+        line.push(':');
+        line.push_str(&self.to_openssh()?);
+        Ok(line)
+    }
+
     /// Serialize SSH public key as raw bytes.
     #[cfg(feature = "alloc")]
     pub fn to_bytes(&self) -> Result<Vec<u8>> {
@@ -152,6 +270,63 @@ impl PublicKey {
         Ok(public_key_bytes)
     }
 
+    /// Get the canonical encoding of this key, i.e. the algorithm identifier
+    /// and key blob without the comment.
+    ///
+    /// These are the bytes hashed to compute the key's [`Fingerprint`], and
+    /// the same as [`PublicKey::to_bytes`].
+    #[cfg(feature = "alloc")]
+    pub fn canonical_bytes(&self) -> Result<Vec<u8>> {
+        self.to_bytes()
+    }
+
+    /// Serialize this key's identity as a compact leaf for key transparency
+    /// logs (e.g. Merkle trees).
+    ///
+    /// The format is stable: a single algorithm identifier byte, followed by
+    /// the raw binary public key (i.e. [`PublicKey::to_bytes`]). The comment
+    /// is not included. Identifier bytes are:
+    ///
+    /// | Byte   | Algorithm                            |
+    /// |--------|--------------------------------------|
+    /// | `0x01` | `ssh-dss`                            |
+    /// | `0x02` | `ecdsa-sha2-nistp256`                |
+    /// | `0x03` | `ecdsa-sha2-nistp384`                |
+    /// | `0x04` | `ecdsa-sha2-nistp521`                |
+    /// | `0x05` | `ssh-ed25519`                        |
+    /// | `0x06` | `ssh-rsa`                            |
+    /// | `0x07` | `sk-ecdsa-sha2-nistp256@openssh.com` |
+    /// | `0x08` | `sk-ssh-ed25519@openssh.com`         |
+    ///
+    /// Returns [`Error::AlgorithmUnsupported`] for other algorithms.
+    #[cfg(feature = "alloc")]
+    pub fn to_transparency_leaf(&self) -> Result<Vec<u8>> {
+        let algorithm = self.algorithm();
+        let id = transparency_leaf_id(&algorithm).ok_or_else(|| algorithm.unsupported_error())?;
+
+        let mut leaf = vec![id];
+        self.key_data.encode(&mut leaf)?;
+        Ok(leaf)
+    }
+
+    /// Parse a key transparency leaf produced by
+    /// [`PublicKey::to_transparency_leaf`].
+    ///
+    /// Returns [`Error::FormatEncoding`] if the identifier byte doesn't match
+    /// the algorithm of the public key which follows it. The resulting key's
+    /// comment is empty.
+    #[cfg(feature = "alloc")]
+    pub fn from_transparency_leaf(leaf: &[u8]) -> Result<Self> {
+        let (id, bytes) = leaf.split_first().ok_or(encoding::Error::Length)?;
+        let public_key = Self::from_bytes(bytes)?;
+
+        if transparency_leaf_id(&public_key.algorithm()) != Some(*id) {
+            return Err(Error::FormatEncoding);
+        }
+
+        Ok(public_key)
+    }
+
     /// Verify the [`SshSig`] signature over the given message using this
     /// public key.
     ///
@@ -178,6 +353,56 @@ impl PublicKey {
         signature.verify(msg)
     }
 
+    /// Verify the [`SshSig`] signature over the given message using this
+    /// public key as in [`PublicKey::verify`], rejecting signature algorithms
+    /// and keys which aren't allowed by the given [`VerificationConfig`].
+    ///
+    /// See [`KeyData::verify_with`] for the errors returned when `config`
+    /// rejects the signature.
+    #[cfg(feature = "alloc")]
+    pub fn verify_with(
+        &self,
+        namespace: &str,
+        msg: &[u8],
+        signature: &SshSig,
+        config: &VerificationConfig,
+    ) -> Result<()> {
+        if self.key_data() != signature.public_key() {
+            return Err(Error::PublicKey);
+        }
+
+        if namespace != signature.namespace() {
+            return Err(Error::Namespace);
+        }
+
+        signature.verify_with(msg, config)
+    }
+
+    /// Verify the [`SshSig`] signature over the data read from the given
+    /// reader using this public key, as in [`PublicKey::verify`].
+    ///
+    /// The data is hashed as it's read (using the signature's hash
+    /// algorithm) rather than being loaded into memory, making this suitable
+    /// for verifying signatures over large files, as with
+    /// `ssh-keygen -Y verify`.
+    #[cfg(feature = "std")]
+    pub fn verify_reader(
+        &self,
+        namespace: &str,
+        reader: impl std::io::Read,
+        signature: &SshSig,
+    ) -> Result<()> {
+        if self.key_data() != signature.public_key() {
+            return Err(Error::PublicKey);
+        }
+
+        if namespace != signature.namespace() {
+            return Err(Error::Namespace);
+        }
+
+        signature.verify_reader(reader)
+    }
+
     /// Read public key from an OpenSSH-formatted file.
     #[cfg(feature = "std")]
     pub fn read_openssh_file(path: &Path) -> Result<Self> {
@@ -215,6 +440,24 @@ impl PublicKey {
         &self.key_data
     }
 
+    /// Perform checks on the key which are skipped when decoding.
+    ///
+    /// See [`KeyData::validate`].
+    pub fn validate(&self) -> Result<()> {
+        self.key_data.validate()
+    }
+
+    /// Is this a FIDO/U2F Security Key?
+    pub fn is_sk(&self) -> bool {
+        self.key_data.is_sk()
+    }
+
+    /// Get the FIDO/U2F application (typically `ssh:`) if this is a
+    /// Security Key.
+    pub fn application(&self) -> Option<&str> {
+        self.key_data.application()
+    }
+
     /// Compute key fingerprint.
     ///
     /// Use [`Default::default()`] to use the default hash function (SHA-256).
@@ -222,12 +465,74 @@ impl PublicKey {
         self.key_data.fingerprint(hash_alg)
     }
 
+    /// Summarize this key like `ssh-keygen -l`, i.e. its size in bits,
+    /// fingerprint, comment and type:
+    ///
+    /// ```text
+    /// 256 SHA256:UCUiLr7Pjs9wFFJMDByLgc3NrtdU344OgUM45wZPcIQ user@example.com (ED25519)
+    /// ```
+    ///
+    /// Use the [`Display`][`fmt::Display`] impl on [`Fingerprint`] for just
+    /// the fingerprint.
+    #[cfg(feature = "alloc")]
+    pub fn summary(&self, hash_alg: HashAlg) -> String {
+        let (bits, key_type) = match &self.key_data {
+            KeyData::Dsa(key) => (mpint_bits(&key.p), "DSA"),
+            #[cfg(feature = "ecdsa")]
+            KeyData::Ecdsa(key) => (
+                match key.curve() {
+                    EcdsaCurve::NistP256 => 256,
+                    EcdsaCurve::NistP384 => 384,
+                    EcdsaCurve::NistP521 => 521,
+                },
+                "ECDSA",
+            ),
+            KeyData::Ed25519(_) => (256, "ED25519"),
+            KeyData::Rsa(key) => (mpint_bits(&key.n), "RSA"),
+            #[cfg(feature = "ecdsa")]
+            KeyData::SkEcdsaSha2NistP256(_) => (256, "ECDSA-SK"),
+            KeyData::SkEd25519(_) => (256, "ED25519-SK"),
+            #[cfg(feature = "xmss")]
+            KeyData::Xmss(_) => (256, "XMSS"),
+            KeyData::Other(_) => (0, "UNKNOWN"),
+        };
+
+        let comment = match self.comment() {
+            "" => "no comment",
+            comment => comment,
+        };
+
+        format!(
+            "{bits} {} {comment} ({key_type})",
+            self.fingerprint(hash_alg)
+        )
+    }
+
     /// Set the comment on the key.
+    ///
+    /// The comment isn't validated here, as comments are arbitrary strings in
+    /// binary encodings such as OpenSSH private keys and the agent protocol,
+    /// and keys decoded from them must be representable. Instead, comments
+    /// which could break out of a line-based format (i.e. which contain
+    /// newlines or other control characters) are rejected with
+    /// [`Error::CommentInvalid`] when encoding the key in the OpenSSH or
+    /// RFC4716 formats.
     #[cfg(feature = "alloc")]
     pub fn set_comment(&mut self, comment: impl Into<String>) {
         self.comment = comment.into();
     }
 
+    /// Get the comment on the key, first setting it to the given default
+    /// (e.g. `user@host`) if the key doesn't have one.
+    #[cfg(feature = "alloc")]
+    pub fn ensure_comment_or(&mut self, default: impl Into<String>) -> &str {
+        if self.comment.is_empty() {
+            self.comment = default.into();
+        }
+
+        &self.comment
+    }
+
     /// Decode comment (e.g. email address).
     ///
     /// This is a stub implementation that ignores the comment.
@@ -245,6 +550,12 @@ impl PublicKey {
     }
 }
 
+impl AsRef<PublicKey> for PublicKey {
+    fn as_ref(&self) -> &PublicKey {
+        self
+    }
+}
+
 impl From<KeyData> for PublicKey {
     fn from(key_data: KeyData) -> PublicKey {
         PublicKey {
@@ -316,9 +627,9 @@ impl FromStr for PublicKey {
 }
 
 #[cfg(feature = "alloc")]
-impl ToString for PublicKey {
-    fn to_string(&self) -> String {
-        self.to_openssh().expect("SSH public key encoding error")
+impl fmt::Display for PublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_openssh().map_err(|_| fmt::Error)?)
     }
 }
 
@@ -355,3 +666,55 @@ impl Serialize for PublicKey {
         }
     }
 }
+
+/// Get the identifier byte used for the given algorithm in key transparency
+/// leaves. See [`PublicKey::to_transparency_leaf`].
+#[cfg(feature = "alloc")]
+fn transparency_leaf_id(algorithm: &Algorithm) -> Option<u8> {
+    match algorithm {
+        Algorithm::Dsa => Some(0x01),
+        Algorithm::Ecdsa {
+            curve: EcdsaCurve::NistP256,
+        } => Some(0x02),
+        Algorithm::Ecdsa {
+            curve: EcdsaCurve::NistP384,
+        } => Some(0x03),
+        Algorithm::Ecdsa {
+            curve: EcdsaCurve::NistP521,
+        } => Some(0x04),
+        Algorithm::Ed25519 => Some(0x05),
+        Algorithm::Rsa { .. } => Some(0x06),
+        Algorithm::SkEcdsaSha2NistP256 => Some(0x07),
+        Algorithm::SkEd25519 => Some(0x08),
+        _ => None,
+    }
+}
+
+/// Ensure a cloud metadata username is non-empty and contains no `:`,
+/// whitespace or control characters.
+#[cfg(feature = "alloc")]
+fn validate_cloud_metadata_username(username: &str) -> Result<()> {
+    if username.is_empty()
+        || username
+            .chars()
+            .any(|c| c == ':' || c.is_whitespace() || c.is_control())
+    {
+        return Err(Error::FormatEncoding);
+    }
+
+    Ok(())
+}
+
+/// Size of the given (positive) integer in bits, e.g. of an RSA modulus.
+#[cfg(feature = "alloc")]
+pub(crate) fn mpint_bits(n: &Mpint) -> usize {
+    let bytes = n.as_positive_bytes().unwrap_or_default();
+
+    match bytes.first() {
+        Some(&first) => bytes
+            .len()
+            .saturating_mul(8)
+            .saturating_sub(first.leading_zeros() as usize),
+        None => 0,
+    }
+}
@@ -43,6 +43,11 @@ impl EcdsaPublicKey {
     /// Parse an ECDSA public key from a SEC1-encoded point.
     ///
     /// Determines the key type from the SEC1 tag byte and length.
+    ///
+    /// Compressed points are decompressed, since SSH requires the uncompressed
+    /// encoding. This requires the crate feature for the relevant curve (i.e.
+    /// `p256`, `p384` or `p521`), and fails with [`Error::Crypto`] if the point isn't
+    /// on the curve.
     pub fn from_sec1_bytes(bytes: &[u8]) -> Result<Self> {
         match bytes {
             [tag, rest @ ..] => {
@@ -54,17 +59,54 @@ impl EcdsaPublicKey {
                     _ => return Err(Error::AlgorithmUnknown),
                 };
 
-                match point_size {
-                    32 => Ok(Self::NistP256(EcdsaNistP256PublicKey::from_bytes(bytes)?)),
-                    48 => Ok(Self::NistP384(EcdsaNistP384PublicKey::from_bytes(bytes)?)),
-                    66 => Ok(Self::NistP521(EcdsaNistP521PublicKey::from_bytes(bytes)?)),
-                    _ => Err(encoding::Error::Length.into()),
-                }
+                let key = match point_size {
+                    32 => Self::NistP256(EcdsaNistP256PublicKey::from_bytes(bytes)?),
+                    48 => Self::NistP384(EcdsaNistP384PublicKey::from_bytes(bytes)?),
+                    66 => Self::NistP521(EcdsaNistP521PublicKey::from_bytes(bytes)?),
+                    _ => return Err(encoding::Error::Length.into()),
+                };
+
+                key.decompress()
             }
             _ => Err(encoding::Error::Length.into()),
         }
     }
 
+    /// Convert a compressed point to its uncompressed encoding.
+    fn decompress(self) -> Result<Self> {
+        match self {
+            #[cfg(feature = "p256")]
+            Self::NistP256(point) if point.is_compressed() => {
+                let key = p256::ecdsa::VerifyingKey::from_encoded_point(&point)
+                    .map_err(|_| Error::Crypto)?;
+                Ok(Self::from(key))
+            }
+            #[cfg(feature = "p384")]
+            Self::NistP384(point) if point.is_compressed() => {
+                let key = p384::ecdsa::VerifyingKey::from_encoded_point(&point)
+                    .map_err(|_| Error::Crypto)?;
+                Ok(Self::from(key))
+            }
+            #[cfg(feature = "p521")]
+            Self::NistP521(point) if point.is_compressed() => {
+                let key = p521::ecdsa::VerifyingKey::from_encoded_point(&point)
+                    .map_err(|_| Error::Crypto)?;
+                Ok(Self::from(key))
+            }
+            _ if self.is_compressed() => Err(self.algorithm().unsupported_error()),
+            _ => Ok(self),
+        }
+    }
+
+    /// Is this public key's SEC1 encoding compressed?
+    fn is_compressed(&self) -> bool {
+        match self {
+            EcdsaPublicKey::NistP256(point) => point.is_compressed(),
+            EcdsaPublicKey::NistP384(point) => point.is_compressed(),
+            EcdsaPublicKey::NistP521(point) => point.is_compressed(),
+        }
+    }
+
     /// Borrow the SEC1-encoded key data as bytes.
     pub fn as_sec1_bytes(&self) -> &[u8] {
         match self {
@@ -89,6 +131,24 @@ impl EcdsaPublicKey {
             EcdsaPublicKey::NistP521(_) => EcdsaCurve::NistP521,
         }
     }
+
+    /// Check that this key is a valid point on its curve.
+    ///
+    /// Only performed for curves whose crate features are enabled
+    /// (i.e. `p256`, `p384` and `p521`): other points are only checked to be
+    /// well-formed SEC1 encodings.
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            #[cfg(feature = "p256")]
+            EcdsaPublicKey::NistP256(_) => p256::ecdsa::VerifyingKey::try_from(self).map(drop),
+            #[cfg(feature = "p384")]
+            EcdsaPublicKey::NistP384(_) => p384::ecdsa::VerifyingKey::try_from(self).map(drop),
+            #[cfg(feature = "p521")]
+            EcdsaPublicKey::NistP521(_) => p521::ecdsa::VerifyingKey::try_from(self).map(drop),
+            #[allow(unreachable_patterns)]
+            _ => Ok(()),
+        }
+    }
 }
 
 impl AsRef<[u8]> for EcdsaPublicKey {
@@ -175,6 +235,15 @@ impl TryFrom<EcdsaPublicKey> for p384::ecdsa::VerifyingKey {
     }
 }
 
+#[cfg(feature = "p521")]
+impl TryFrom<EcdsaPublicKey> for p521::ecdsa::VerifyingKey {
+    type Error = Error;
+
+    fn try_from(key: EcdsaPublicKey) -> Result<p521::ecdsa::VerifyingKey> {
+        p521::ecdsa::VerifyingKey::try_from(&key)
+    }
+}
+
 #[cfg(feature = "p256")]
 impl TryFrom<&EcdsaPublicKey> for p256::ecdsa::VerifyingKey {
     type Error = Error;
@@ -203,6 +272,20 @@ impl TryFrom<&EcdsaPublicKey> for p384::ecdsa::VerifyingKey {
     }
 }
 
+#[cfg(feature = "p521")]
+impl TryFrom<&EcdsaPublicKey> for p521::ecdsa::VerifyingKey {
+    type Error = Error;
+
+    fn try_from(public_key: &EcdsaPublicKey) -> Result<p521::ecdsa::VerifyingKey> {
+        match public_key {
+            EcdsaPublicKey::NistP521(key) => {
+                p521::ecdsa::VerifyingKey::from_encoded_point(key).map_err(|_| Error::Crypto)
+            }
+            _ => Err(Error::AlgorithmUnknown),
+        }
+    }
+}
+
 #[cfg(feature = "p256")]
 impl From<p256::ecdsa::VerifyingKey> for EcdsaPublicKey {
     fn from(key: p256::ecdsa::VerifyingKey) -> EcdsaPublicKey {
@@ -230,3 +313,17 @@ impl From<&p384::ecdsa::VerifyingKey> for EcdsaPublicKey {
         EcdsaPublicKey::NistP384(key.to_encoded_point(false))
     }
 }
+
+#[cfg(feature = "p521")]
+impl From<p521::ecdsa::VerifyingKey> for EcdsaPublicKey {
+    fn from(key: p521::ecdsa::VerifyingKey) -> EcdsaPublicKey {
+        EcdsaPublicKey::from(&key)
+    }
+}
+
+#[cfg(feature = "p521")]
+impl From<&p521::ecdsa::VerifyingKey> for EcdsaPublicKey {
+    fn from(key: &p521::ecdsa::VerifyingKey) -> EcdsaPublicKey {
+        EcdsaPublicKey::NistP521(key.to_encoded_point(false))
+    }
+}
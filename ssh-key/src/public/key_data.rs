@@ -5,11 +5,14 @@ use crate::{Algorithm, Error, Fingerprint, HashAlg, Result};
 use encoding::{CheckedSum, Decode, Encode, Reader, Writer};
 
 #[cfg(feature = "alloc")]
-use super::{DsaPublicKey, RsaPublicKey};
+use super::{DsaPublicKey, OpaquePublicKey, RsaPublicKey};
 
 #[cfg(feature = "ecdsa")]
 use super::{EcdsaPublicKey, SkEcdsaSha2NistP256};
 
+#[cfg(feature = "xmss")]
+use super::XmssPublicKey;
+
 /// Public key data.
 #[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[non_exhaustive]
@@ -39,6 +42,18 @@ pub enum KeyData {
     ///
     /// [PROTOCOL.u2f]: https://cvsweb.openbsd.org/src/usr.bin/ssh/PROTOCOL.u2f?annotate=HEAD
     SkEd25519(SkEd25519),
+
+    /// eXtended Merkle Signature Scheme (XMSS) public key data.
+    #[cfg(feature = "xmss")]
+    Xmss(XmssPublicKey),
+
+    /// Opaque public key data for an [`Algorithm::Other`], e.g. ECDSA with
+    /// an unsupported curve.
+    ///
+    /// The length of opaque key data can't be determined from the data
+    /// itself, so decoding it consumes all of the reader's remaining input.
+    #[cfg(feature = "alloc")]
+    Other(OpaquePublicKey),
 }
 
 impl KeyData {
@@ -55,6 +70,10 @@ impl KeyData {
             #[cfg(feature = "ecdsa")]
             Self::SkEcdsaSha2NistP256(_) => Algorithm::SkEcdsaSha2NistP256,
             Self::SkEd25519(_) => Algorithm::SkEd25519,
+            #[cfg(feature = "xmss")]
+            Self::Xmss(_) => Algorithm::Xmss,
+            #[cfg(feature = "alloc")]
+            Self::Other(key) => Algorithm::Other(key.algorithm()),
         }
     }
 
@@ -118,6 +137,24 @@ impl KeyData {
         }
     }
 
+    /// Get XMSS public key if this key is the correct type.
+    #[cfg(feature = "xmss")]
+    pub fn xmss(&self) -> Option<&XmssPublicKey> {
+        match self {
+            Self::Xmss(key) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Get opaque public key data if this key is for an [`Algorithm::Other`].
+    #[cfg(feature = "alloc")]
+    pub fn other(&self) -> Option<&OpaquePublicKey> {
+        match self {
+            Self::Other(key) => Some(key),
+            _ => None,
+        }
+    }
+
     /// Is this key a DSA key?
     #[cfg(feature = "alloc")]
     pub fn is_dsa(&self) -> bool {
@@ -152,7 +189,46 @@ impl KeyData {
         matches!(self, Self::SkEd25519(_))
     }
 
+    /// Is this key an XMSS key?
+    #[cfg(feature = "xmss")]
+    pub fn is_xmss(&self) -> bool {
+        matches!(self, Self::Xmss(_))
+    }
+
+    /// Perform checks on the key data which are skipped when decoding, such
+    /// as checking that ECDSA public keys are valid curve points.
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            #[cfg(feature = "ecdsa")]
+            Self::Ecdsa(key) => key.validate(),
+            #[cfg(feature = "alloc")]
+            Self::Rsa(key) => key.validate(),
+            #[cfg(feature = "ecdsa")]
+            Self::SkEcdsaSha2NistP256(sk) => EcdsaPublicKey::NistP256(*sk.ec_point()).validate(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Is this key a FIDO/U2F Security Key?
+    pub fn is_sk(&self) -> bool {
+        self.algorithm().is_sk()
+    }
+
+    /// Get the FIDO/U2F application (typically `ssh:`) if this is a
+    /// Security Key.
+    pub fn application(&self) -> Option<&str> {
+        match self {
+            #[cfg(feature = "ecdsa")]
+            Self::SkEcdsaSha2NistP256(sk) => Some(sk.application()),
+            Self::SkEd25519(sk) => Some(sk.application()),
+            _ => None,
+        }
+    }
+
     /// Decode [`KeyData`] for the specified algorithm.
+    ///
+    /// The reader must be limited to the key blob for [`Algorithm::Other`],
+    /// as its key data consumes all of the remaining input.
     pub(crate) fn decode_as(reader: &mut impl Reader, algorithm: Algorithm) -> Result<Self> {
         match algorithm {
             #[cfg(feature = "alloc")]
@@ -170,6 +246,10 @@ impl KeyData {
                 SkEcdsaSha2NistP256::decode(reader).map(Self::SkEcdsaSha2NistP256)
             }
             Algorithm::SkEd25519 => SkEd25519::decode(reader).map(Self::SkEd25519),
+            #[cfg(feature = "xmss")]
+            Algorithm::Xmss => XmssPublicKey::decode(reader).map(Self::Xmss),
+            #[cfg(feature = "alloc")]
+            Algorithm::Other(name) => OpaquePublicKey::decode_as(reader, name).map(Self::Other),
             #[allow(unreachable_patterns)]
             _ => Err(Error::AlgorithmUnknown),
         }
@@ -189,6 +269,10 @@ impl KeyData {
             #[cfg(feature = "ecdsa")]
             Self::SkEcdsaSha2NistP256(sk) => sk.encoded_len(),
             Self::SkEd25519(sk) => sk.encoded_len(),
+            #[cfg(feature = "xmss")]
+            Self::Xmss(key) => key.encoded_len(),
+            #[cfg(feature = "alloc")]
+            Self::Other(key) => key.encoded_len(),
         }
     }
 
@@ -205,10 +289,17 @@ impl KeyData {
             #[cfg(feature = "ecdsa")]
             Self::SkEcdsaSha2NistP256(sk) => sk.encode(writer),
             Self::SkEd25519(sk) => sk.encode(writer),
+            #[cfg(feature = "xmss")]
+            Self::Xmss(key) => key.encode(writer),
+            #[cfg(feature = "alloc")]
+            Self::Other(key) => key.encode(writer),
         }
     }
 }
 
+/// Decoding [`KeyData::Other`] consumes all of the reader's remaining input,
+/// so the reader must be limited to the key blob, e.g. using
+/// [`Reader::read_prefixed`].
 impl Decode for KeyData {
     type Error = Error;
 
@@ -274,3 +365,10 @@ impl From<SkEd25519> for KeyData {
         Self::SkEd25519(public_key)
     }
 }
+
+#[cfg(feature = "xmss")]
+impl From<XmssPublicKey> for KeyData {
+    fn from(public_key: XmssPublicKey) -> KeyData {
+        Self::Xmss(public_key)
+    }
+}
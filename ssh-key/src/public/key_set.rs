@@ -0,0 +1,103 @@
+//! Sets of public keys.
+
+use super::{KeyData, PublicKey};
+use alloc::vec::{self, Vec};
+use core::{mem, slice};
+
+/// Set of SSH public keys which have been deduplicated on their key data.
+///
+/// Two public keys are considered the same if their [`KeyData`] (i.e. the
+/// canonical key blob) are equal, regardless of comment or serialization.
+///
+/// Keys are kept in insertion order. When extending or collecting into a
+/// set, later keys replace earlier keys with the same key data.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct KeySet {
+    /// Public keys in this set.
+    keys: Vec<PublicKey>,
+}
+
+impl KeySet {
+    /// Create a new empty key set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a public key to this set.
+    ///
+    /// If a key with the same key data is already present, it's replaced by
+    /// the given key (keeping its position in the set) and returned, which
+    /// allows callers to report the collision. Otherwise returns `None`.
+    pub fn insert(&mut self, public_key: PublicKey) -> Option<PublicKey> {
+        match self
+            .keys
+            .iter_mut()
+            .find(|key| key.key_data() == public_key.key_data())
+        {
+            Some(key) => Some(mem::replace(key, public_key)),
+            None => {
+                self.keys.push(public_key);
+                None
+            }
+        }
+    }
+
+    /// Does this set contain a key with the given key data?
+    pub fn contains(&self, key_data: &KeyData) -> bool {
+        self.get(key_data).is_some()
+    }
+
+    /// Get the public key in this set with the given key data, if present.
+    pub fn get(&self, key_data: &KeyData) -> Option<&PublicKey> {
+        self.keys.iter().find(|key| key.key_data() == key_data)
+    }
+
+    /// Iterate over the public keys in this set.
+    pub fn iter(&self) -> slice::Iter<'_, PublicKey> {
+        self.keys.iter()
+    }
+
+    /// Number of keys in this set.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Is this set empty?
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+impl Extend<PublicKey> for KeySet {
+    fn extend<I: IntoIterator<Item = PublicKey>>(&mut self, iter: I) {
+        for public_key in iter {
+            self.insert(public_key);
+        }
+    }
+}
+
+impl FromIterator<PublicKey> for KeySet {
+    fn from_iter<I: IntoIterator<Item = PublicKey>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl IntoIterator for KeySet {
+    type Item = PublicKey;
+    type IntoIter = vec::IntoIter<PublicKey>;
+
+    fn into_iter(self) -> vec::IntoIter<PublicKey> {
+        self.keys.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a KeySet {
+    type Item = &'a PublicKey;
+    type IntoIter = slice::Iter<'a, PublicKey>;
+
+    fn into_iter(self) -> slice::Iter<'a, PublicKey> {
+        self.iter()
+    }
+}
@@ -0,0 +1,98 @@
+//! Public key metadata which can be parsed without decoding the key itself.
+
+use super::{PublicKey, SshFormat};
+use crate::{Algorithm, Error, Fingerprint, HashAlg, Result};
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use core::str::{self, FromStr};
+use encoding::{
+    base64::{Base64, Encoding},
+    Decode,
+};
+
+/// Metadata for an OpenSSH-formatted public key, i.e. its algorithm, key blob
+/// and comment.
+///
+/// Parsing metadata only decodes the Base64 key blob and checks the algorithm
+/// identifier at its start. The key data itself is not decoded or validated,
+/// which makes it cheap to e.g. list the fingerprints of a large number of
+/// keys. Use [`KeyMetadata::validate`] to obtain a fully-checked
+/// [`PublicKey`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeyMetadata {
+    /// Public key algorithm.
+    algorithm: Algorithm,
+
+    /// Binary public key blob.
+    key_blob: Vec<u8>,
+
+    /// Comment on the key.
+    comment: String,
+}
+
+impl KeyMetadata {
+    /// Parse the metadata of an OpenSSH-formatted public key.
+    pub fn from_openssh(public_key: &str) -> Result<Self> {
+        let encapsulation = SshFormat::decode(public_key.trim_end().as_bytes())?;
+        let algorithm = Algorithm::new(encapsulation.algorithm_id)?;
+        let key_blob = Base64::decode_vec(str::from_utf8(encapsulation.base64_data)?)?;
+
+        // Verify that the algorithm in the key blob matches the text
+        if Algorithm::decode(&mut key_blob.as_slice())? != algorithm {
+            return Err(Error::AlgorithmUnknown);
+        }
+
+        Ok(Self {
+            algorithm,
+            key_blob,
+            comment: encapsulation.comment.to_owned(),
+        })
+    }
+
+    /// Get the digital signature [`Algorithm`] used by this key.
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm.clone()
+    }
+
+    /// Get the binary public key blob.
+    pub fn key_blob(&self) -> &[u8] {
+        &self.key_blob
+    }
+
+    /// Comment on the key (e.g. email address).
+    pub fn comment(&self) -> &str {
+        &self.comment
+    }
+
+    /// Compute key fingerprint.
+    ///
+    /// Use [`Default::default()`] to use the default hash function (SHA-256).
+    pub fn fingerprint(&self, hash_alg: HashAlg) -> Fingerprint {
+        Fingerprint::from_key_blob(hash_alg, &self.key_blob)
+    }
+
+    /// Fully decode and validate the key.
+    ///
+    /// See [`PublicKey::validate`].
+    pub fn validate(&self) -> Result<PublicKey> {
+        let mut public_key = PublicKey::from_bytes(&self.key_blob)?;
+        public_key.validate()?;
+        public_key.set_comment(self.comment.as_str());
+        Ok(public_key)
+    }
+}
+
+impl FromStr for KeyMetadata {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::from_openssh(s)
+    }
+}
+
+impl TryFrom<&KeyMetadata> for PublicKey {
+    type Error = Error;
+
+    fn try_from(metadata: &KeyMetadata) -> Result<PublicKey> {
+        metadata.validate()
+    }
+}
@@ -0,0 +1,67 @@
+//! Opaque public keys for algorithms not otherwise supported by this crate.
+
+use crate::{AlgorithmName, Error, Result};
+use alloc::vec::Vec;
+use encoding::{Encode, Reader, Writer};
+
+/// Opaque public key for an [`Algorithm::Other`][`crate::Algorithm::Other`],
+/// e.g. ECDSA with an unsupported curve.
+///
+/// The key data following the algorithm identifier is stored as raw bytes,
+/// allowing such keys to be passed through unchanged.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+pub struct OpaquePublicKey {
+    /// Algorithm name.
+    algorithm: AlgorithmName,
+
+    /// Raw key data, i.e. the encoded key without the algorithm identifier.
+    key: Vec<u8>,
+}
+
+impl OpaquePublicKey {
+    /// Create a new opaque public key from the given algorithm name and raw
+    /// key data.
+    pub fn new(algorithm: AlgorithmName, key: impl Into<Vec<u8>>) -> Self {
+        Self {
+            algorithm,
+            key: key.into(),
+        }
+    }
+
+    /// Get the algorithm name.
+    pub fn algorithm(&self) -> AlgorithmName {
+        self.algorithm.clone()
+    }
+
+    /// Get the raw key data, i.e. the encoded key without the algorithm
+    /// identifier.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.key
+    }
+
+    /// Decode the key data for the given algorithm, consuming the rest of the
+    /// reader's input.
+    pub(super) fn decode_as(reader: &mut impl Reader, algorithm: AlgorithmName) -> Result<Self> {
+        let mut key = vec![0u8; reader.remaining_len()];
+        reader.read(&mut key)?;
+        Ok(Self { algorithm, key })
+    }
+}
+
+impl AsRef<[u8]> for OpaquePublicKey {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl Encode for OpaquePublicKey {
+    type Error = Error;
+
+    fn encoded_len(&self) -> Result<usize> {
+        Ok(self.key.len())
+    }
+
+    fn encode(&self, writer: &mut impl Writer) -> Result<()> {
+        Ok(writer.write(&self.key)?)
+    }
+}
@@ -0,0 +1,248 @@
+//! RFC4716 public key encoding, a.k.a. "SSH2" or SECSH public key format.
+//!
+//! Described in [RFC4716](https://datatracker.ietf.org/doc/html/rfc4716):
+//!
+//! ```text
+//! ---- BEGIN SSH2 PUBLIC KEY ----
+//! Subject: user
+//! Comment: "user@example.com"
+//! AAAAC3NzaC1lZDI1NTE5AAAAILM+rvN+ot98qgEN796jTiQfZfG1KaT0PtFDJ/XFSqti
+//! ---- END SSH2 PUBLIC KEY ----
+//! ```
+
+use super::PublicKey;
+use crate::{Error, LineEnding, Result};
+use alloc::string::String;
+use core::str;
+use encoding::base64::{Base64, Encoding};
+
+/// Begin marker line.
+const BEGIN_MARKER: &str = "---- BEGIN SSH2 PUBLIC KEY ----";
+
+/// End marker line.
+const END_MARKER: &str = "---- END SSH2 PUBLIC KEY ----";
+
+/// Header tag used for the key's comment.
+const COMMENT_HEADER: &str = "Comment";
+
+/// Header tag used for the login name of the key's owner.
+const SUBJECT_HEADER: &str = "Subject";
+
+/// Maximum length of a line in bytes, excluding the line ending.
+const MAX_LINE_LEN: usize = 72;
+
+/// Maximum length of a header value in bytes.
+const MAX_HEADER_VALUE_LEN: usize = 1024;
+
+/// Line width used for the Base64-encoded key. Matches `ssh-keygen -e`.
+const BASE64_LINE_WIDTH: usize = 70;
+
+impl PublicKey {
+    /// Parse an RFC4716-formatted public key, i.e. one which begins with
+    /// `---- BEGIN SSH2 PUBLIC KEY ----` as output by `ssh-keygen -e`.
+    ///
+    /// A `Comment` header is used as the key's comment, with any surrounding
+    /// quotes removed and backslash escapes (i.e. `\"` and `\\`) unescaped.
+    /// Other headers are ignored: see [`PublicKey::from_rfc4716_with_subject`]
+    /// to also read the `Subject` header.
+    pub fn from_rfc4716(public_key: &str) -> Result<Self> {
+        Self::from_rfc4716_with_subject(public_key).map(|(public_key, _)| public_key)
+    }
+
+    /// Parse an RFC4716-formatted public key along with the value of its
+    /// `Subject` header (i.e. the login name of the key's owner) if present.
+    ///
+    /// The subject is unquoted and unescaped in the same way as the comment.
+    /// See [`PublicKey::from_rfc4716`].
+    pub fn from_rfc4716_with_subject(public_key: &str) -> Result<(Self, Option<String>)> {
+        let mut lines = public_key.lines().skip_while(|line| line.trim().is_empty());
+
+        if lines.next() != Some(BEGIN_MARKER) {
+            return Err(Error::FormatEncoding);
+        }
+
+        let mut comment = String::new();
+        let mut subject = None;
+        let mut base64 = String::new();
+
+        loop {
+            let mut line = lines.next().ok_or(Error::FormatEncoding)?;
+
+            if line == END_MARKER {
+                break;
+            }
+
+            // Base64 never contains `:`, so any such line is a header
+            if base64.is_empty() && line.contains(':') {
+                let mut header = String::new();
+
+                // A trailing backslash continues the header on the next line
+                while let Some(continued) = line.strip_suffix('\\') {
+                    header.push_str(continued);
+                    line = lines.next().ok_or(Error::FormatEncoding)?;
+                }
+
+                header.push_str(line);
+
+                let (tag, value) = header.split_once(':').ok_or(Error::FormatEncoding)?;
+
+                if tag.eq_ignore_ascii_case(COMMENT_HEADER) {
+                    comment = unescape_header_value(value.trim_start())?;
+                } else if tag.eq_ignore_ascii_case(SUBJECT_HEADER) {
+                    subject = Some(unescape_header_value(value.trim_start())?);
+                }
+            } else {
+                base64.push_str(line.trim());
+            }
+        }
+
+        if lines.any(|line| !line.trim().is_empty()) {
+            return Err(Error::FormatEncoding);
+        }
+
+        let mut public_key = Self::from_bytes(&Base64::decode_vec(&base64)?)?;
+        public_key.set_comment(comment);
+        Ok((public_key, subject))
+    }
+
+    /// Encode an RFC4716-formatted public key.
+    ///
+    /// A non-empty comment is written as a quoted `Comment` header, escaping
+    /// `"` and `\` with a backslash and splitting it across continuation
+    /// lines if required.
+    ///
+    /// Returns [`Error::CommentInvalid`] if the comment contains a line break
+    /// or exceeds the 1024-byte limit on header values once escaped.
+    pub fn to_rfc4716(&self, line_ending: LineEnding) -> Result<String> {
+        self.encode_rfc4716(None, line_ending)
+    }
+
+    /// Encode an RFC4716-formatted public key with a `Subject` header giving
+    /// the login name of the key's owner, as expected by some SSH servers
+    /// (e.g. Tectia).
+    ///
+    /// The subject is escaped in the same way as the comment.
+    /// See [`PublicKey::to_rfc4716`].
+    ///
+    /// Returns [`Error::CommentInvalid`] if the subject or comment contains a
+    /// line break or exceeds the 1024-byte limit on header values once
+    /// escaped.
+    pub fn to_rfc4716_with_subject(
+        &self,
+        subject: &str,
+        line_ending: LineEnding,
+    ) -> Result<String> {
+        self.encode_rfc4716(Some(subject), line_ending)
+    }
+
+    /// Encode an RFC4716-formatted public key with an optional `Subject`
+    /// header.
+    fn encode_rfc4716(&self, subject: Option<&str>, line_ending: LineEnding) -> Result<String> {
+        let eol = str::from_utf8(line_ending.as_bytes())?;
+        let mut out = String::new();
+        out.push_str(BEGIN_MARKER);
+        out.push_str(eol);
+
+        if let Some(subject) = subject {
+            write_header(&mut out, SUBJECT_HEADER, subject, eol)?;
+        }
+
+        if !self.comment().is_empty() {
+            write_header(&mut out, COMMENT_HEADER, self.comment(), eol)?;
+        }
+
+        let base64 = Base64::encode_string(&self.to_bytes()?);
+
+        // Base64 is ASCII, so every chunk is valid UTF-8
+        for line in base64.as_bytes().chunks(BASE64_LINE_WIDTH) {
+            out.push_str(str::from_utf8(line)?);
+            out.push_str(eol);
+        }
+
+        out.push_str(END_MARKER);
+        out.push_str(eol);
+        Ok(out)
+    }
+}
+
+/// Write a header with the given tag and (escaped) value, splitting it
+/// across continuation lines if required.
+fn write_header(out: &mut String, tag: &str, value: &str, eol: &str) -> Result<()> {
+    let value = escape_header_value(value)?;
+    let mut header = String::with_capacity(value.len());
+    header.push_str(tag);
+    header.push_str(": ");
+    header.push_str(&value);
+
+    let mut remaining = header.as_str();
+
+    while remaining.len() > MAX_LINE_LEN {
+        // Leave room for the continuation backslash
+        let mut split = MAX_LINE_LEN.saturating_sub(1);
+
+        while !remaining.is_char_boundary(split) {
+            split = split.saturating_sub(1);
+        }
+
+        let (line, rest) = remaining.split_at(split);
+        out.push_str(line);
+        out.push('\\');
+        out.push_str(eol);
+        remaining = rest;
+    }
+
+    out.push_str(remaining);
+    out.push_str(eol);
+    Ok(())
+}
+
+/// Quote and escape a comment or subject for use as a header value.
+fn escape_header_value(comment: &str) -> Result<String> {
+    let mut value = String::with_capacity(comment.len());
+    value.push('"');
+
+    for c in comment.chars() {
+        match c {
+            '\r' | '\n' => return Err(Error::CommentInvalid),
+            '"' | '\\' => value.push('\\'),
+            _ => (),
+        }
+
+        value.push(c);
+    }
+
+    value.push('"');
+
+    if value.len() > MAX_HEADER_VALUE_LEN {
+        return Err(Error::CommentInvalid);
+    }
+
+    Ok(value)
+}
+
+/// Remove the quotes and backslash escapes from a header value.
+///
+/// Unquoted values are returned verbatim.
+fn unescape_header_value(value: &str) -> Result<String> {
+    let quoted = match value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        Some(quoted) => quoted,
+        None => return Ok(value.into()),
+    };
+
+    let mut unescaped = String::with_capacity(quoted.len());
+    let mut chars = quoted.chars();
+
+    while let Some(c) = chars.next() {
+        let c = match c {
+            '\\' => chars.next().ok_or(Error::FormatEncoding)?,
+            _ => c,
+        };
+
+        unescaped.push(c);
+    }
+
+    Ok(unescaped)
+}
@@ -26,14 +26,73 @@ impl RsaPublicKey {
     /// Minimum allowed RSA key size.
     #[cfg(feature = "rsa")]
     pub(crate) const MIN_KEY_SIZE: usize = RsaKeypair::MIN_KEY_SIZE;
+
+    /// Default maximum public exponent allowed when verifying signatures,
+    /// i.e. `2^33 - 1`.
+    ///
+    /// This allows 65537 and other common exponents, while rejecting large
+    /// exponents which would make verification slow.
+    pub const DEFAULT_MAX_EXPONENT: u64 = (1 << 33) - 1;
+
+    /// Create a new RSA public key from the given public exponent and
+    /// modulus, encoded as big endian unsigned integers (e.g. as found in
+    /// PKCS#1 or a JWK).
+    ///
+    /// Any leading zeroes are stripped, and a single leading zero is added
+    /// where the most significant bit is set, as required for `mpint` by
+    /// [RFC4251 § 5]. This ensures the encoded key blob, and therefore the
+    /// fingerprint, matches OpenSSH's regardless of how the inputs were
+    /// padded.
+    ///
+    /// [RFC4251 § 5]: https://datatracker.ietf.org/doc/html/rfc4251#section-5
+    pub fn new(e: &[u8], n: &[u8]) -> Result<Self> {
+        let strip = |bytes: &[u8]| {
+            let leading_zeroes = bytes.iter().take_while(|&&byte| byte == 0).count();
+            Mpint::from_positive_bytes(&bytes[leading_zeroes..])
+        };
+
+        let key = Self {
+            e: strip(e)?,
+            n: strip(n)?,
+        };
+
+        key.validate()?;
+        Ok(key)
+    }
+
+    /// Is the public exponent less than or equal to `max_exponent`?
+    pub fn exponent_at_most(&self, max_exponent: u64) -> bool {
+        match self.e.as_positive_bytes() {
+            Some(bytes) if bytes.len() <= 8 => {
+                let mut buf = [0u8; 8];
+                let offset = buf.len().saturating_sub(bytes.len());
+                buf[offset..].copy_from_slice(bytes);
+                u64::from_be_bytes(buf) <= max_exponent
+            }
+            _ => false,
+        }
+    }
+
+    /// Check that the public exponent and modulus are well-formed, i.e.
+    /// positive odd integers.
+    pub fn validate(&self) -> Result<()> {
+        for n in [&self.e, &self.n] {
+            match n.as_positive_bytes() {
+                Some([.., last]) if last & 1 == 1 => (),
+                _ => return Err(Error::Crypto),
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Decode for RsaPublicKey {
     type Error = Error;
 
     fn decode(reader: &mut impl Reader) -> Result<Self> {
-        let e = Mpint::decode(reader)?;
-        let n = Mpint::decode(reader)?;
+        let e = Mpint::decode_positive(reader)?;
+        let n = Mpint::decode_positive(reader)?;
         Ok(Self { e, n })
     }
 }
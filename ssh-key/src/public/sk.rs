@@ -15,6 +15,14 @@ use crate::{public::ecdsa::EcdsaNistP256PublicKey, EcdsaCurve};
 /// Default FIDO/U2F Security Key application string.
 const DEFAULT_APPLICATION_STRING: &str = "ssh:";
 
+/// Flag indicating user presence (i.e. a touch of the Security Key).
+#[cfg(feature = "alloc")]
+pub(crate) const SK_USER_PRESENCE_FLAG: u8 = 0x01;
+
+/// Flag indicating user verification (e.g. a PIN or biometric).
+#[cfg(feature = "alloc")]
+pub(crate) const SK_USER_VERIFICATION_FLAG: u8 = 0x04;
+
 /// Security Key (FIDO/U2F) ECDSA/NIST P-256 public key as specified in
 /// [PROTOCOL.u2f](https://cvsweb.openbsd.org/src/usr.bin/ssh/PROTOCOL.u2f?annotate=HEAD).
 #[cfg(feature = "ecdsa")]
@@ -0,0 +1,176 @@
+//! X.509 `SubjectPublicKeyInfo` (SPKI) public key support, i.e. the
+//! `-----BEGIN PUBLIC KEY-----` format used by OpenSSL and X.509 tooling.
+//!
+//! Described in [RFC5280] § 4.1.2.7.
+//!
+//! [RFC5280]: https://datatracker.ietf.org/doc/html/rfc5280#section-4.1.2.7
+
+use super::{Ed25519PublicKey, KeyData, PublicKey, RsaPublicKey};
+use crate::{Error, LineEnding, Mpint, Result};
+use alloc::{string::String, vec::Vec};
+use encoding::pem;
+use pkcs8::{
+    der::{
+        asn1::{AnyRef, BitStringRef, UintRef},
+        Decode, Encode,
+    },
+    spki::{AlgorithmIdentifierRef, SubjectPublicKeyInfoRef},
+    ObjectIdentifier,
+};
+
+#[cfg(feature = "ecdsa")]
+use {super::EcdsaPublicKey, crate::EcdsaCurve};
+
+/// PEM type label for `SubjectPublicKeyInfo`.
+const PUBLIC_KEY_LABEL: &str = "PUBLIC KEY";
+
+/// Elliptic curve public key algorithm.
+pub(crate) const EC_PUBLIC_KEY_OID: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.2.840.10045.2.1");
+
+/// NIST P-256 elliptic curve.
+pub(crate) const NIST_P256_OID: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.2.840.10045.3.1.7");
+
+/// NIST P-384 elliptic curve.
+pub(crate) const NIST_P384_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.132.0.34");
+
+/// NIST P-521 elliptic curve.
+pub(crate) const NIST_P521_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.132.0.35");
+
+/// Ed25519 public key algorithm.
+pub(crate) const ED25519_OID: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.101.112");
+
+/// RSA public key algorithm.
+pub(crate) const RSA_ENCRYPTION_OID: ObjectIdentifier =
+    ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.1");
+
+impl PublicKey {
+    /// Parse a DER-encoded X.509 `SubjectPublicKeyInfo`.
+    ///
+    /// Supports ECDSA (NIST P-256, P-384 and P-521), Ed25519 and RSA keys.
+    /// SPKI has no place to store a comment, so the resulting key's comment
+    /// is empty.
+    pub fn from_spki_der(der: &[u8]) -> Result<Self> {
+        let spki = SubjectPublicKeyInfoRef::from_der(der)?;
+        let public_key = spki
+            .subject_public_key
+            .as_bytes()
+            .ok_or(Error::FormatEncoding)?;
+
+        let key_data = match spki.algorithm.oid {
+            #[cfg(feature = "ecdsa")]
+            EC_PUBLIC_KEY_OID => {
+                let curve = match spki.algorithm.parameters_oid()? {
+                    NIST_P256_OID => EcdsaCurve::NistP256,
+                    NIST_P384_OID => EcdsaCurve::NistP384,
+                    NIST_P521_OID => EcdsaCurve::NistP521,
+                    _ => return Err(Error::AlgorithmUnknown),
+                };
+
+                let key = EcdsaPublicKey::from_sec1_bytes(public_key)?;
+
+                if key.curve() != curve {
+                    return Err(Error::FormatEncoding);
+                }
+
+                KeyData::Ecdsa(key)
+            }
+            ED25519_OID => KeyData::Ed25519(Ed25519PublicKey::try_from(public_key)?),
+            RSA_ENCRYPTION_OID => {
+                let [n, e] = <[UintRef<'_>; 2]>::from_der(public_key)?;
+
+                KeyData::Rsa(RsaPublicKey {
+                    e: Mpint::from_positive_bytes(e.as_bytes())?,
+                    n: Mpint::from_positive_bytes(n.as_bytes())?,
+                })
+            }
+            _ => return Err(Error::AlgorithmUnknown),
+        };
+
+        Ok(key_data.into())
+    }
+
+    /// Parse a PEM-encoded X.509 `SubjectPublicKeyInfo`, i.e. one which
+    /// begins with `-----BEGIN PUBLIC KEY-----`.
+    ///
+    /// See [`PublicKey::from_spki_der`] for the supported key types.
+    pub fn from_spki_pem(pem: impl AsRef<[u8]>) -> Result<Self> {
+        let (label, der) = pem::decode_vec(pem.as_ref())?;
+
+        if label != PUBLIC_KEY_LABEL {
+            return Err(Error::FormatEncoding);
+        }
+
+        Self::from_spki_der(&der)
+    }
+
+    /// Encode this public key as a DER-encoded X.509 `SubjectPublicKeyInfo`.
+    ///
+    /// The comment is not included, as SPKI has no place to store it.
+    ///
+    /// Returns [`Error::AlgorithmUnsupported`] for key types other than
+    /// ECDSA, Ed25519 and RSA.
+    pub fn to_spki_der(&self) -> Result<Vec<u8>> {
+        let rsa_public_key;
+
+        let (algorithm, public_key) = match self.key_data() {
+            #[cfg(feature = "ecdsa")]
+            KeyData::Ecdsa(key) => {
+                let curve_oid = match key.curve() {
+                    EcdsaCurve::NistP256 => &NIST_P256_OID,
+                    EcdsaCurve::NistP384 => &NIST_P384_OID,
+                    EcdsaCurve::NistP521 => &NIST_P521_OID,
+                };
+
+                let algorithm = AlgorithmIdentifierRef {
+                    oid: EC_PUBLIC_KEY_OID,
+                    parameters: Some(AnyRef::from(curve_oid)),
+                };
+
+                (algorithm, key.as_sec1_bytes())
+            }
+            KeyData::Ed25519(key) => {
+                let algorithm = AlgorithmIdentifierRef {
+                    oid: ED25519_OID,
+                    parameters: None,
+                };
+
+                (algorithm, key.as_ref().as_slice())
+            }
+            KeyData::Rsa(key) => {
+                rsa_public_key = [uint_ref(&key.n)?, uint_ref(&key.e)?].to_der()?;
+
+                let algorithm = AlgorithmIdentifierRef {
+                    oid: RSA_ENCRYPTION_OID,
+                    parameters: Some(AnyRef::NULL),
+                };
+
+                (algorithm, rsa_public_key.as_slice())
+            }
+            _ => return Err(self.algorithm().unsupported_error()),
+        };
+
+        let spki = SubjectPublicKeyInfoRef {
+            algorithm,
+            subject_public_key: BitStringRef::from_bytes(public_key)?,
+        };
+
+        Ok(spki.to_der()?)
+    }
+
+    /// Encode this public key as a PEM-encoded X.509 `SubjectPublicKeyInfo`,
+    /// i.e. one which begins with `-----BEGIN PUBLIC KEY-----`.
+    ///
+    /// See [`PublicKey::to_spki_der`] for the supported key types.
+    pub fn to_spki_pem(&self, line_ending: LineEnding) -> Result<String> {
+        let der = self.to_spki_der()?;
+        Ok(pem::encode_string(PUBLIC_KEY_LABEL, line_ending, &der)?)
+    }
+}
+
+/// Borrow a positive [`Mpint`] as an ASN.1 `INTEGER`.
+fn uint_ref(n: &Mpint) -> Result<UintRef<'_>> {
+    let bytes = n.as_positive_bytes().ok_or(Error::FormatEncoding)?;
+    Ok(UintRef::new(bytes)?)
+}
@@ -75,6 +75,7 @@ impl<'a> SshFormat<'a> {
             .ok_or(encoding::Error::Length)?;
 
         if !comment.is_empty() {
+            validate_comment(comment)?;
             encode_str(out, &mut offset, " ")?;
             encode_str(out, &mut offset, comment)?;
         }
@@ -112,10 +113,20 @@ impl<'a> SshFormat<'a> {
 #[cfg(feature = "alloc")]
 fn base64_len_approx(input_len: usize) -> usize {
     // TODO(tarcieri): checked arithmetic
-    #[allow(clippy::integer_arithmetic)]
+    #[allow(clippy::arithmetic_side_effects)]
     ((((input_len * 4) / 3) + 3) & !3)
 }
 
+/// Ensure a comment can't break out of the single-line format, i.e. that it
+/// contains no newlines or other control characters.
+fn validate_comment(comment: &str) -> Result<()> {
+    if comment.chars().any(char::is_control) {
+        return Err(Error::CommentInvalid);
+    }
+
+    Ok(())
+}
+
 /// Parse a segment of the public key.
 fn decode_segment<'a>(bytes: &mut &'a [u8]) -> Result<&'a [u8]> {
     let start = *bytes;
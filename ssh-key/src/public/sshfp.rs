@@ -0,0 +1,220 @@
+//! SSHFP DNS resource records.
+//!
+//! Described in [RFC4255](https://datatracker.ietf.org/doc/html/rfc4255),
+//! with the ECDSA and Ed25519 algorithm numbers assigned by
+//! [RFC6594](https://datatracker.ietf.org/doc/html/rfc6594) and
+//! [RFC7479](https://datatracker.ietf.org/doc/html/rfc7479):
+//!
+//! ```text
+//! example.com IN SSHFP 4 2 5025222ebecf8ecf7014524c0c1c8b81cdcdaed754df8e0e814338e7064f7084
+//! ```
+
+use super::{KeyData, PublicKey};
+use crate::{Error, HashAlg, Result};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt,
+    str::{self, FromStr},
+};
+use encoding::Encode;
+use sha1::{Digest, Sha1};
+
+/// SSHFP algorithm number for RSA keys.
+const ALGORITHM_RSA: u8 = 1;
+
+/// SSHFP algorithm number for DSA keys.
+const ALGORITHM_DSA: u8 = 2;
+
+/// SSHFP algorithm number for ECDSA keys.
+#[cfg(feature = "ecdsa")]
+const ALGORITHM_ECDSA: u8 = 3;
+
+/// SSHFP algorithm number for Ed25519 keys.
+const ALGORITHM_ED25519: u8 = 4;
+
+/// SSHFP fingerprint type for SHA-1.
+const FINGERPRINT_TYPE_SHA1: u8 = 1;
+
+/// SSHFP fingerprint type for SHA-256.
+const FINGERPRINT_TYPE_SHA256: u8 = 2;
+
+/// Resource record type which precedes the RDATA in a zone file entry.
+const RECORD_TYPE: &str = "SSHFP";
+
+/// SSHFP DNS resource record, which publishes the fingerprint of a host key
+/// so clients can verify it using (DNSSEC-validated) DNS.
+///
+/// Parses and serializes the record data (RDATA) in its presentation format,
+/// i.e. `<algorithm> <fingerprint type> <hex fingerprint>`. Parsing also
+/// accepts whole zone file entries as printed by `ssh-keygen -r`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct SshfpRecord {
+    /// Algorithm number of the key.
+    algorithm: u8,
+
+    /// Hash function used to compute the fingerprint.
+    fingerprint_type: u8,
+
+    /// Fingerprint of the key.
+    fingerprint: Vec<u8>,
+}
+
+impl SshfpRecord {
+    /// Create a new record from its algorithm number, fingerprint type and
+    /// fingerprint.
+    pub fn new(algorithm: u8, fingerprint_type: u8, fingerprint: impl Into<Vec<u8>>) -> Self {
+        Self {
+            algorithm,
+            fingerprint_type,
+            fingerprint: fingerprint.into(),
+        }
+    }
+
+    /// Algorithm number of the key, e.g. `4` for Ed25519.
+    pub fn algorithm(&self) -> u8 {
+        self.algorithm
+    }
+
+    /// Fingerprint type, i.e. `1` for SHA-1 or `2` for SHA-256.
+    pub fn fingerprint_type(&self) -> u8 {
+        self.fingerprint_type
+    }
+
+    /// Fingerprint of the key.
+    pub fn fingerprint(&self) -> &[u8] {
+        &self.fingerprint
+    }
+
+    /// Does this record match the given key?
+    pub fn matches(&self, key: &KeyData) -> bool {
+        if sshfp_algorithm(key) != Some(self.algorithm) {
+            return false;
+        }
+
+        match self.fingerprint_type {
+            FINGERPRINT_TYPE_SHA256 => {
+                key.fingerprint(HashAlg::Sha256).as_bytes() == self.fingerprint.as_slice()
+            }
+            FINGERPRINT_TYPE_SHA1 => {
+                let mut blob = Vec::new();
+                key.encode(&mut blob).is_ok() && Sha1::digest(blob)[..] == self.fingerprint[..]
+            }
+            _ => false,
+        }
+    }
+
+    /// Encode this record as a zone file entry for the given host name, as
+    /// printed by `ssh-keygen -r`.
+    pub fn to_zone_entry(&self, name: &str) -> String {
+        let mut out = String::from(name);
+        out.push_str(" IN ");
+        out.push_str(RECORD_TYPE);
+        out.push(' ');
+        out.push_str(&self.to_string());
+        out
+    }
+}
+
+impl FromStr for SshfpRecord {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut fields = s.split_whitespace();
+
+        // Skip the owner name, TTL and class of a zone file entry
+        if s.split_whitespace().any(|field| field == RECORD_TYPE) {
+            fields.find(|&field| field == RECORD_TYPE);
+        }
+
+        let algorithm = fields
+            .next()
+            .and_then(|field| field.parse().ok())
+            .ok_or(Error::FormatEncoding)?;
+
+        let fingerprint_type = fields
+            .next()
+            .and_then(|field| field.parse().ok())
+            .ok_or(Error::FormatEncoding)?;
+
+        // Zone files may split the fingerprint across whitespace
+        let fingerprint = decode_hex(&fields.collect::<String>())?;
+
+        Ok(Self::new(algorithm, fingerprint_type, fingerprint))
+    }
+}
+
+impl fmt::Display for SshfpRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} ", self.algorithm, self.fingerprint_type)?;
+
+        for byte in &self.fingerprint {
+            write!(f, "{byte:02x}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl PublicKey {
+    /// Compute the SSHFP record for this key using the given hash function.
+    ///
+    /// Returns [`Error::AlgorithmUnsupported`] for algorithms which have no
+    /// SSHFP algorithm number (e.g. FIDO/U2F Security Keys), and
+    /// [`Error::FormatEncoding`] for [`HashAlg::Sha512`], which has no SSHFP
+    /// fingerprint type.
+    pub fn to_sshfp(&self, hash_alg: HashAlg) -> Result<SshfpRecord> {
+        let algorithm = sshfp_algorithm(self.key_data()).ok_or(Error::AlgorithmUnsupported {
+            algorithm: self.algorithm(),
+        })?;
+
+        if hash_alg != HashAlg::Sha256 {
+            return Err(Error::FormatEncoding);
+        }
+
+        Ok(SshfpRecord::new(
+            algorithm,
+            FINGERPRINT_TYPE_SHA256,
+            self.fingerprint(hash_alg).as_bytes(),
+        ))
+    }
+
+    /// Does this key match any of the given SSHFP records, e.g. the records
+    /// published for the host it was received from?
+    pub fn matches_sshfp<'a>(&self, records: impl IntoIterator<Item = &'a SshfpRecord>) -> bool {
+        records
+            .into_iter()
+            .any(|record| record.matches(self.key_data()))
+    }
+}
+
+/// Get the SSHFP algorithm number for the given key.
+fn sshfp_algorithm(key: &KeyData) -> Option<u8> {
+    match key {
+        KeyData::Rsa(_) => Some(ALGORITHM_RSA),
+        KeyData::Dsa(_) => Some(ALGORITHM_DSA),
+        #[cfg(feature = "ecdsa")]
+        KeyData::Ecdsa(_) => Some(ALGORITHM_ECDSA),
+        KeyData::Ed25519(_) => Some(ALGORITHM_ED25519),
+        _ => None,
+    }
+}
+
+/// Decode the given (non-empty) hexadecimal string.
+pub(crate) fn decode_hex(hex: &str) -> Result<Vec<u8>> {
+    if hex.is_empty() || hex.len() % 2 != 0 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(Error::FormatEncoding);
+    }
+
+    hex.as_bytes()
+        .chunks_exact(2)
+        .map(|pair| {
+            str::from_utf8(pair)
+                .ok()
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or(Error::FormatEncoding)
+        })
+        .collect()
+}
@@ -0,0 +1,60 @@
+//! Public keys hashed together with their comments.
+
+use super::PublicKey;
+use core::hash::{Hash, Hasher};
+use encoding::{Encode, Writer};
+
+/// Wrapper around a [`PublicKey`] which implements [`Hash`], taking both the
+/// key data and the comment into account.
+///
+/// [`PublicKey`]'s own [`Eq`] impl already compares the comment, which this
+/// wrapper's [`Eq`] impl delegates to, but [`PublicKey`] doesn't implement
+/// [`Hash`]. This is useful as a key in hash maps and sets when keys which
+/// differ only in their comment need to be treated as distinct. To
+/// deduplicate keys on their key data alone, see [`KeySet`][`super::KeySet`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WithComment(pub PublicKey);
+
+impl WithComment {
+    /// Get the inner [`PublicKey`].
+    pub fn into_inner(self) -> PublicKey {
+        self.0
+    }
+}
+
+impl AsRef<PublicKey> for WithComment {
+    fn as_ref(&self) -> &PublicKey {
+        &self.0
+    }
+}
+
+impl From<PublicKey> for WithComment {
+    fn from(public_key: PublicKey) -> WithComment {
+        WithComment(public_key)
+    }
+}
+
+impl From<WithComment> for PublicKey {
+    fn from(with_comment: WithComment) -> PublicKey {
+        with_comment.0
+    }
+}
+
+impl Hash for WithComment {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // `HashWriter` is infallible, so encoding can only fail on length
+        // overflow, which is impossible for keys which were decoded
+        let _ = self.0.key_data().encode(&mut HashWriter(state));
+        self.0.comment().hash(state);
+    }
+}
+
+/// Adapter for encoding key data directly into a [`Hasher`].
+struct HashWriter<'a, H: Hasher>(&'a mut H);
+
+impl<H: Hasher> Writer for HashWriter<'_, H> {
+    fn write(&mut self, bytes: &[u8]) -> encoding::Result<()> {
+        self.0.write(bytes);
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+//! eXtended Merkle Signature Scheme (XMSS) public keys.
+//!
+//! Stateful hash-based signatures as described in [RFC8391], which OpenSSH
+//! supports experimentally when built with `XMSS=1`.
+//!
+//! [RFC8391]: https://datatracker.ietf.org/doc/html/rfc8391
+
+use crate::{Error, Result};
+use alloc::{string::String, vec::Vec};
+use encoding::{CheckedSum, Decode, Encode, Reader, Writer};
+
+/// XMSS parameter sets supported by OpenSSH.
+const XMSS_PARAMS: [&str; 3] = [
+    "XMSS_SHA2-256_W16_H10",
+    "XMSS_SHA2-256_W16_H16",
+    "XMSS_SHA2-256_W16_H20",
+];
+
+/// XMSS public key.
+///
+/// Encoded as the name of the XMSS parameter set (e.g.
+/// `XMSS_SHA2-256_W16_H10`) followed by the public key, i.e. the root of the
+/// Merkle tree and the public seed.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct XmssPublicKey {
+    /// Name of the XMSS parameter set.
+    params: String,
+
+    /// Public key, i.e. `root || PUB_SEED`.
+    key: Vec<u8>,
+}
+
+impl XmssPublicKey {
+    /// Size of an XMSS public key in bytes, for all parameter sets supported
+    /// by OpenSSH.
+    pub const BYTE_SIZE: usize = 64;
+
+    /// Create a new XMSS public key for the given parameter set.
+    ///
+    /// Returns [`Error::AlgorithmUnknown`] if the parameter set isn't one
+    /// supported by OpenSSH, or [`encoding::Error::Length`] if the key isn't
+    /// [`XmssPublicKey::BYTE_SIZE`] bytes.
+    pub fn new(params: &str, key: impl Into<Vec<u8>>) -> Result<Self> {
+        if !XMSS_PARAMS.contains(&params) {
+            return Err(Error::AlgorithmUnknown);
+        }
+
+        let key = key.into();
+
+        if key.len() != Self::BYTE_SIZE {
+            return Err(encoding::Error::Length.into());
+        }
+
+        Ok(Self {
+            params: params.into(),
+            key,
+        })
+    }
+
+    /// Get the name of the XMSS parameter set, e.g. `XMSS_SHA2-256_W16_H10`.
+    pub fn params(&self) -> &str {
+        &self.params
+    }
+
+    /// Get the public key as bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.key
+    }
+}
+
+impl AsRef<[u8]> for XmssPublicKey {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl Decode for XmssPublicKey {
+    type Error = Error;
+
+    fn decode(reader: &mut impl Reader) -> Result<Self> {
+        let params = String::decode(reader)?;
+        let key = Vec::decode(reader)?;
+        Self::new(&params, key)
+    }
+}
+
+impl Encode for XmssPublicKey {
+    type Error = Error;
+
+    fn encoded_len(&self) -> Result<usize> {
+        Ok([self.params.encoded_len()?, self.key.encoded_len()?].checked_sum()?)
+    }
+
+    fn encode(&self, writer: &mut impl Writer) -> Result<()> {
+        self.params.encode(writer)?;
+        Ok(self.key.encode(writer)?)
+    }
+}
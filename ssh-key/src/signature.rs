@@ -1,6 +1,10 @@
 //! Signatures (e.g. CA signatures over SSH certificates)
 
-use crate::{private, public, Algorithm, Error, Mpint, PrivateKey, PublicKey, Result};
+use crate::{
+    private,
+    public::{self, SK_USER_PRESENCE_FLAG, SK_USER_VERIFICATION_FLAG},
+    Algorithm, EcdsaCurve, Error, Mpint, PrivateKey, PublicKey, Result, VerificationConfig,
+};
 use alloc::vec::Vec;
 use core::fmt;
 use encoding::{CheckedSum, Decode, Encode, Reader, Writer};
@@ -12,53 +16,129 @@ use crate::{private::Ed25519Keypair, public::Ed25519PublicKey};
 #[cfg(feature = "dsa")]
 use {
     crate::{private::DsaKeypair, public::DsaPublicKey},
-    bigint::BigUint,
-    sha1::Sha1,
     signature::{DigestSigner, DigestVerifier},
 };
 
-#[cfg(any(feature = "p256", feature = "p384"))]
+#[cfg(any(feature = "dsa", feature = "rsa"))]
+use {bigint::BigUint, sha1::Sha1};
+
+#[cfg(any(feature = "p256", feature = "p384", feature = "p521"))]
 use crate::{
     private::{EcdsaKeypair, EcdsaPrivateKey},
     public::EcdsaPublicKey,
-    EcdsaCurve,
 };
 
+#[cfg(any(feature = "p256", feature = "p384", feature = "p521", feature = "rsa"))]
+use signature::hazmat::PrehashVerifier;
+
 #[cfg(feature = "rsa")]
 use {
-    crate::{private::RsaKeypair, public::RsaPublicKey, HashAlg},
+    crate::{
+        private::RsaKeypair,
+        public::{mpint_bits, RsaPublicKey},
+        HashAlg,
+    },
+    rsa::{traits::PublicKeyParts, Pkcs1v15Sign},
     sha2::Sha512,
 };
 
-#[cfg(any(feature = "ed25519", feature = "rsa"))]
+#[cfg(any(feature = "ed25519", feature = "p256", feature = "rsa"))]
 use sha2::Sha256;
 
-#[cfg(any(feature = "dsa", feature = "ed25519"))]
+#[cfg(any(
+    feature = "dsa",
+    feature = "ed25519",
+    feature = "p256",
+    feature = "rsa"
+))]
 use sha2::Digest;
 
 const DSA_SIGNATURE_SIZE: usize = 40;
 const ED25519_SIGNATURE_SIZE: usize = 64;
-const SK_ED25519_SIGNATURE_TRAILER_SIZE: usize = 5; // flags(u8), counter(u32)
-const SK_ED25519_SIGNATURE_SIZE: usize = ED25519_SIGNATURE_SIZE + SK_ED25519_SIGNATURE_TRAILER_SIZE;
+const SK_SIGNATURE_TRAILER_SIZE: usize = 5; // flags(u8), counter(u32)
+const SK_ED25519_SIGNATURE_SIZE: usize = ED25519_SIGNATURE_SIZE + SK_SIGNATURE_TRAILER_SIZE;
+
+/// Size of the data signed by a FIDO/U2F Security Key, i.e.
+/// SHA-256(application) || flags || counter || SHA-256(message).
+#[cfg(any(feature = "ed25519", feature = "p256"))]
+const SK_SIGNED_DATA_SIZE: usize = 32 + SK_SIGNATURE_TRAILER_SIZE + 32;
 
 /// Trait for signing keys which produce a [`Signature`].
 ///
-/// This trait is automatically impl'd for any types which impl the
-/// [`Signer`] trait for the SSH [`Signature`] type and also support a [`From`]
-/// conversion for [`public::KeyData`].
+/// This trait is impl'd for [`PrivateKey`], and can be impl'd for other types
+/// which impl the [`Signer`] trait for the SSH [`Signature`] type, such as
+/// hardware or agent-backed keys (e.g. the `AgentSigner` type). This
+/// allows [`certificate::Builder::sign`][`crate::certificate::Builder::sign`]
+/// and [`SshSig::sign`][`crate::SshSig::sign`] to delegate signing to e.g. an
+/// HSM, PKCS#11 token or cloud KMS, without the private key entering the
+/// process.
+///
+/// ## Example
+///
+#[cfg_attr(all(feature = "ed25519", feature = "std"), doc = " ```")]
+#[cfg_attr(not(all(feature = "ed25519", feature = "std")), doc = " ```ignore")]
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use ssh_key::{public::KeyData, HashAlg, PrivateKey, Signature, SigningKey, SshSig};
+///
+/// /// Key held by a remote signing service.
+/// struct RemoteKey {
+///     public_key: KeyData,
+/// }
+///
+/// impl signature::Signer<Signature> for RemoteKey {
+///     fn try_sign(&self, msg: &[u8]) -> signature::Result<Signature> {
+///         // Send `msg` to the signing service and decode the signature it
+///         // returns, e.g. using `Signature::new`
+///         # let key = PrivateKey::from_openssh(include_str!("../tests/examples/id_ed25519"))?;
+///         # key.try_sign(msg)
+///     }
+/// }
+///
+/// impl SigningKey for RemoteKey {
+///     fn public_key(&self) -> KeyData {
+///         self.public_key.clone()
+///     }
+/// }
+///
+/// let public_key = ssh_key::PublicKey::from_openssh(include_str!("../tests/examples/id_ed25519.pub"))?;
+/// let remote_key = RemoteKey {
+///     public_key: public_key.key_data().clone(),
+/// };
+///
+/// let sshsig = SshSig::sign(&remote_key, "file", HashAlg::Sha512, b"message")?;
+/// public_key.verify("file", b"message", &sshsig)?;
+/// # Ok(())
+/// # }
+/// ```
 pub trait SigningKey: Signer<Signature> {
     /// Get the [`public::KeyData`] for this signing key.
     fn public_key(&self) -> public::KeyData;
+
+    /// Get the [`Algorithm`] of this signing key, i.e. of its public key.
+    fn algorithm(&self) -> Algorithm {
+        self.public_key().algorithm()
+    }
+
+    /// Is private key material available to produce signatures locally?
+    ///
+    /// Defaults to `true`.
+    fn can_sign(&self) -> bool {
+        true
+    }
 }
 
-impl<T> SigningKey for T
-where
-    T: Signer<Signature>,
-    public::KeyData: for<'a> From<&'a T>,
-{
+impl SigningKey for PrivateKey {
     fn public_key(&self) -> public::KeyData {
         self.into()
     }
+
+    fn algorithm(&self) -> Algorithm {
+        PrivateKey::algorithm(self)
+    }
+
+    fn can_sign(&self) -> bool {
+        PrivateKey::can_sign(self)
+    }
 }
 
 /// Low-level digital signature (e.g. DSA, ECDSA, Ed25519).
@@ -97,32 +177,37 @@ impl Signature {
     /// See specifications in toplevel [`Signature`] documentation for how to
     /// format the raw signature data for a given algorithm.
     ///
+    /// Signatures for XMSS and [`Algorithm::Other`] are opaque: their data is
+    /// accepted as-is, so that they can be decoded and re-encoded, but they
+    /// can't be verified.
+    ///
+    /// `ssh-rsa` (SHA-1) signatures are rejected, but can still be decoded so
+    /// that they can be verified when allowed by a [`VerificationConfig`].
+    ///
     /// # Returns
-    /// - [`Error::Encoding`] if the signature is not the correct length.
+    /// - [`Error::Encoding`] if the signature is not the correct length, or
+    ///   the algorithm has no identifier (i.e. RSA with SHA-384).
     pub fn new(algorithm: Algorithm, data: impl Into<Vec<u8>>) -> Result<Self> {
         let data = data.into();
+        algorithm.check_identifier()?;
 
         // Validate signature is well-formed per OpensSH encoding
         match algorithm {
             Algorithm::Dsa if data.len() == DSA_SIGNATURE_SIZE => (),
-            Algorithm::Ecdsa { curve } => {
-                let reader = &mut data.as_slice();
-
-                for _ in 0..2 {
-                    let component = Mpint::decode(reader)?;
-
-                    if component.as_positive_bytes().ok_or(Error::Crypto)?.len()
-                        != curve.field_size()
-                    {
-                        return Err(encoding::Error::Length.into());
-                    }
-                }
-
-                reader.finish(())?;
-            }
+            Algorithm::Ecdsa { curve } => check_ecdsa_components(curve, &data)?,
             Algorithm::Ed25519 if data.len() == ED25519_SIGNATURE_SIZE => (),
+            Algorithm::SkEcdsaSha2NistP256 => {
+                let len = data
+                    .len()
+                    .checked_sub(SK_SIGNATURE_TRAILER_SIZE)
+                    .ok_or(encoding::Error::Length)?;
+                check_ecdsa_components(EcdsaCurve::NistP256, &data[..len])?;
+            }
             Algorithm::SkEd25519 if data.len() == SK_ED25519_SIGNATURE_SIZE => (),
             Algorithm::Rsa { hash: Some(_) } => (),
+            #[cfg(feature = "xmss")]
+            Algorithm::Xmss => (),
+            Algorithm::Other(_) => (),
             _ => return Err(encoding::Error::Length.into()),
         }
 
@@ -131,7 +216,7 @@ impl Signature {
 
     /// Get the [`Algorithm`] associated with this signature.
     pub fn algorithm(&self) -> Algorithm {
-        self.algorithm
+        self.algorithm.clone()
     }
 
     /// Get the raw signature as bytes.
@@ -139,6 +224,137 @@ impl Signature {
         &self.data
     }
 
+    /// Get the flags byte of a FIDO/U2F Security Key signature.
+    pub fn sk_flags(&self) -> Option<u8> {
+        self.sk_trailer().map(|trailer| trailer[0])
+    }
+
+    /// Get the signature counter of a FIDO/U2F Security Key signature.
+    pub fn sk_counter(&self) -> Option<u32> {
+        self.sk_trailer()
+            .map(|trailer| u32::from_be_bytes([trailer[1], trailer[2], trailer[3], trailer[4]]))
+    }
+
+    /// Was user presence (i.e. a touch) asserted by the Security Key?
+    ///
+    /// Always `false` for non-Security Key signatures.
+    pub fn sk_user_present(&self) -> bool {
+        self.sk_flags()
+            .map_or(false, |flags| flags & SK_USER_PRESENCE_FLAG != 0)
+    }
+
+    /// Was the user verified (e.g. with a PIN) by the Security Key?
+    ///
+    /// Always `false` for non-Security Key signatures.
+    pub fn sk_user_verified(&self) -> bool {
+        self.sk_flags()
+            .map_or(false, |flags| flags & SK_USER_VERIFICATION_FLAG != 0)
+    }
+
+    /// Get the flags and counter trailing a Security Key signature.
+    fn sk_trailer(&self) -> Option<&[u8]> {
+        if !self.algorithm.is_sk() {
+            return None;
+        }
+
+        let offset = self.data.len().checked_sub(SK_SIGNATURE_TRAILER_SIZE)?;
+        self.data.get(offset..)
+    }
+
+    /// Create an ECDSA signature from the big endian-encoded `r` and `s`
+    /// scalars for the given curve.
+    ///
+    /// Leading zeroes are stripped from the scalars as required by the
+    /// `mpint` encoding.
+    pub fn from_ecdsa_components(curve: EcdsaCurve, r: &[u8], s: &[u8]) -> Result<Self> {
+        let mut data = Vec::new();
+
+        for component in [r, s] {
+            let leading_zeroes = component.iter().take_while(|&&b| b == 0).count();
+            Mpint::from_positive_bytes(&component[leading_zeroes..])?.encode(&mut data)?;
+        }
+
+        Self::new(Algorithm::Ecdsa { curve }, data)
+    }
+
+    /// Get the `r` and `s` components of an ECDSA signature.
+    ///
+    /// Returns [`Error::AlgorithmUnsupported`] if this is not an ECDSA signature.
+    pub fn ecdsa_components(&self) -> Result<(Mpint, Mpint)> {
+        match self.algorithm {
+            Algorithm::Ecdsa { .. } => {
+                let reader = &mut self.as_bytes();
+                let r = Mpint::decode(reader)?;
+                let s = Mpint::decode(reader)?;
+                Ok(reader.finish((r, s))?)
+            }
+            _ => Err(self.algorithm.unsupported_error()),
+        }
+    }
+
+    /// Decode an ECDSA signature from its ASN.1 DER encoding, i.e.
+    /// `SEQUENCE { r INTEGER, s INTEGER }`.
+    pub fn from_ecdsa_der(curve: EcdsaCurve, der: &[u8]) -> Result<Self> {
+        let mut body = der_decode_sequence(der)?;
+        let r = der_decode_integer(&mut body)?;
+        let s = der_decode_integer(&mut body)?;
+
+        if !body.is_empty() {
+            return Err(Error::FormatEncoding);
+        }
+
+        let mut data = Vec::new();
+        r.encode(&mut data)?;
+        s.encode(&mut data)?;
+        Self::new(Algorithm::Ecdsa { curve }, data)
+    }
+
+    /// Encode an ECDSA signature as ASN.1 DER, i.e.
+    /// `SEQUENCE { r INTEGER, s INTEGER }`.
+    pub fn to_ecdsa_der(&self) -> Result<Vec<u8>> {
+        let (r, s) = self.ecdsa_components()?;
+        let mut body = Vec::new();
+        der_encode_integer(&r, &mut body)?;
+        der_encode_integer(&s, &mut body)?;
+
+        let mut der = Vec::new();
+        der_encode_tlv(DER_SEQUENCE_TAG, &body, &mut der)?;
+        Ok(der)
+    }
+
+    /// Decode ECDSA `r` and `s` into fixed-size field element buffers.
+    ///
+    /// The `mpint`s are read directly from the signature data rather than
+    /// via [`Mpint`], so this doesn't allocate.
+    #[cfg(any(feature = "p256", feature = "p384", feature = "p521"))]
+    fn ecdsa_field_bytes(&self, r_out: &mut [u8], s_out: &mut [u8]) -> Result<()> {
+        let reader = &mut self.as_bytes();
+
+        for out in [r_out, s_out] {
+            let len = usize::decode(reader)?;
+
+            if reader.len() < len {
+                return Err(encoding::Error::Length.into());
+            }
+
+            let (mpint, rest) = reader.split_at(len);
+            *reader = rest;
+
+            let bytes = match mpint {
+                // Unnecessary leading 0
+                [0x00] | [0x00, 0x00..=0x7F, ..] => return Err(Error::FormatEncoding),
+                [0x00, bytes @ ..] => bytes,
+                [byte, ..] if *byte < 0x80 => mpint,
+                _ => return Err(Error::Crypto),
+            };
+
+            let offset = out.len().checked_sub(bytes.len()).ok_or(Error::Crypto)?;
+            out[offset..].copy_from_slice(bytes);
+        }
+
+        Ok(reader.finish(())?)
+    }
+
     /// Placeholder signature used by the certificate builder.
     ///
     /// This is guaranteed generate an error if anything attempts to encode it.
@@ -168,7 +384,7 @@ impl Decode for Signature {
         let algorithm = Algorithm::decode(reader)?;
         let mut data = Vec::decode(reader)?;
 
-        if algorithm == Algorithm::SkEd25519 {
+        if algorithm.is_sk() {
             let flags = u8::decode(reader)?;
             let counter = u32::decode(reader)?;
 
@@ -176,6 +392,11 @@ impl Decode for Signature {
             data.extend(counter.to_be_bytes());
         }
 
+        // Whether SHA-1 is acceptable is decided when verifying
+        if algorithm == (Algorithm::Rsa { hash: None }) {
+            return Ok(Self { algorithm, data });
+        }
+
         Self::new(algorithm, data)
     }
 }
@@ -198,11 +419,11 @@ impl Encode for Signature {
 
         self.algorithm().encode(writer)?;
 
-        if self.algorithm == Algorithm::SkEd25519 {
+        if self.algorithm.is_sk() {
             let signature_length = self
                 .as_bytes()
                 .len()
-                .checked_sub(SK_ED25519_SIGNATURE_TRAILER_SIZE)
+                .checked_sub(SK_SIGNATURE_TRAILER_SIZE)
                 .ok_or(encoding::Error::Length)?;
             self.as_bytes()[..signature_length].encode(writer)?;
             writer.write(&self.as_bytes()[signature_length..])?;
@@ -271,13 +492,43 @@ impl Signer<Signature> for PrivateKey {
     }
 }
 
+impl PrivateKey {
+    /// Sign the given message using the given signature algorithm.
+    ///
+    /// This allows the hash used by RSA keys to be chosen, i.e. either
+    /// `rsa-sha2-256` or `rsa-sha2-512`, whereas [`Signer::try_sign`] always
+    /// uses `rsa-sha2-512`. Other keys only support their own algorithm.
+    ///
+    /// # Returns
+    /// - [`Error::AlgorithmUnsupported`] if the algorithm isn't compatible
+    ///   with this key, or is `ssh-rsa` (i.e. SHA-1).
+    /// - [`Error::Encoding`] for RSA with SHA-384, which has no identifier.
+    /// - [`Error::Encrypted`] if this key is encrypted.
+    pub fn sign_with_algorithm(&self, message: &[u8], algorithm: Algorithm) -> Result<Signature> {
+        if self.is_encrypted() {
+            return Err(Error::Encrypted);
+        }
+
+        match (self.key_data(), &algorithm) {
+            #[cfg(feature = "rsa")]
+            (private::KeypairData::Rsa(keypair), Algorithm::Rsa { hash: Some(hash) }) => {
+                keypair.try_sign_with_hash(message, *hash)
+            }
+            _ if algorithm == self.algorithm() && !algorithm.is_rsa() => {
+                Ok(self.try_sign(message)?)
+            }
+            _ => Err(algorithm.unsupported_error()),
+        }
+    }
+}
+
 impl Signer<Signature> for private::KeypairData {
     #[allow(unused_variables)]
     fn try_sign(&self, message: &[u8]) -> signature::Result<Signature> {
         match self {
             #[cfg(feature = "dsa")]
             Self::Dsa(keypair) => keypair.try_sign(message),
-            #[cfg(any(feature = "p256", feature = "p384"))]
+            #[cfg(any(feature = "p256", feature = "p384", feature = "p521"))]
             Self::Ecdsa(keypair) => keypair.try_sign(message),
             #[cfg(feature = "ed25519")]
             Self::Ed25519(keypair) => keypair.try_sign(message),
@@ -294,16 +545,204 @@ impl Verifier<Signature> for PublicKey {
     }
 }
 
+impl PublicKey {
+    /// Verify a signature against each of the given candidate keys, returning
+    /// the first key which successfully verifies it.
+    ///
+    /// Keys whose algorithm doesn't match the signature's (e.g. an Ed25519
+    /// key for an ECDSA signature) are skipped. RSA keys match RSA signatures
+    /// using any hash, i.e. `ssh-rsa`, `rsa-sha2-256` or `rsa-sha2-512`.
+    ///
+    /// This is not constant-time, since the candidate keys are public.
+    pub fn verify_any<'a>(
+        keys: impl IntoIterator<Item = &'a PublicKey>,
+        message: &[u8],
+        signature: &Signature,
+    ) -> Option<&'a PublicKey> {
+        let algorithm = signature.algorithm();
+
+        keys.into_iter()
+            .filter(|key| match key.algorithm() {
+                Algorithm::Rsa { .. } => algorithm.is_rsa(),
+                key_algorithm => key_algorithm == algorithm,
+            })
+            .find(|key| key.key_data().verify(message, signature).is_ok())
+    }
+
+    /// Verify a signature, returning the signature algorithm it was made
+    /// with on success, e.g. for audit logging.
+    ///
+    /// For RSA keys this distinguishes `ssh-rsa`, `rsa-sha2-256` and
+    /// `rsa-sha2-512` signatures.
+    pub fn verify_returning_algorithm(
+        &self,
+        message: &[u8],
+        signature: &Signature,
+    ) -> Result<Algorithm> {
+        self.key_data().verify(message, signature)?;
+        Ok(signature.algorithm())
+    }
+
+    /// Verify a signature over a message which has already been hashed.
+    ///
+    /// See [`KeyData::verify_prehashed`][`public::KeyData::verify_prehashed`].
+    pub fn verify_prehashed(&self, digest: &[u8], signature: &Signature) -> Result<()> {
+        self.key_data().verify_prehashed(digest, signature)
+    }
+}
+
+impl public::KeyData {
+    /// Decode an SSH public key blob and an SSH signature blob, as found on
+    /// the wire (e.g. in `SSH_MSG_USERAUTH_REQUEST`), and verify the
+    /// signature over the given message.
+    ///
+    /// Both blobs must be consumed entirely. RSA keys match RSA signatures
+    /// using any hash, i.e. `ssh-rsa`, `rsa-sha2-256` or `rsa-sha2-512`.
+    ///
+    /// # Returns
+    /// - [`Error::PublicKey`] if the algorithms of the key and signature
+    ///   don't agree.
+    /// - [`Error::Crypto`] if the signature is invalid.
+    pub fn verify_raw(
+        mut key_blob: &[u8],
+        message: &[u8],
+        mut signature_blob: &[u8],
+    ) -> Result<()> {
+        let reader = &mut key_blob;
+        let key = Self::decode(reader)?;
+        reader.finish(())?;
+
+        let reader = &mut signature_blob;
+        let signature = Signature::decode(reader)?;
+        reader.finish(())?;
+
+        let algorithms_agree = match key.algorithm() {
+            Algorithm::Rsa { .. } => signature.algorithm().is_rsa(),
+            algorithm => algorithm == signature.algorithm(),
+        };
+
+        if !algorithms_agree {
+            return Err(Error::PublicKey);
+        }
+
+        Ok(key.verify(message, &signature)?)
+    }
+
+    /// Verify a signature, rejecting signature algorithms and keys which
+    /// aren't allowed by the given [`VerificationConfig`].
+    ///
+    /// Unlike [`Verifier::verify`], this can accept `ssh-rsa` (SHA-1)
+    /// signatures and RSA keys smaller than 2048 bits if `config` allows
+    /// them.
+    ///
+    /// # Returns
+    /// - [`Error::AlgorithmUnsupported`] if the signature algorithm isn't
+    ///   allowed by `config`, or is unsupported.
+    /// - [`Error::Crypto`] if the signature is invalid, or the key isn't
+    ///   allowed by `config` (e.g. an RSA key which is too small).
+    pub fn verify_with(
+        &self,
+        message: &[u8],
+        signature: &Signature,
+        config: &VerificationConfig,
+    ) -> Result<()> {
+        if !config.allows(signature.algorithm()) {
+            return Err(signature.algorithm().unsupported_error());
+        }
+
+        match self {
+            #[cfg(feature = "rsa")]
+            Self::Rsa(pk) => pk.verify_with_config(message, signature, config),
+            _ => Ok(self.verify(message, signature)?),
+        }
+    }
+
+    /// Verify a signature over a message which has already been hashed.
+    ///
+    /// The `digest` must have been computed using the hash function of the
+    /// signature algorithm, i.e. SHA-256 for `ecdsa-sha2-nistp256`, SHA-384
+    /// for `ecdsa-sha2-nistp384` and the hash of `rsa-sha2-256`/`rsa-sha2-512`.
+    ///
+    /// # Returns
+    /// - [`Error::AlgorithmUnsupported`] for algorithms which don't hash the
+    ///   message before signing it (e.g. Ed25519), or which are unsupported.
+    /// - [`Error::Crypto`] if the signature is invalid.
+    #[allow(unused_variables)]
+    pub fn verify_prehashed(&self, digest: &[u8], signature: &Signature) -> Result<()> {
+        match (self, &signature.algorithm) {
+            #[cfg(feature = "p256")]
+            (
+                Self::Ecdsa(pk),
+                Algorithm::Ecdsa {
+                    curve: EcdsaCurve::NistP256,
+                },
+            ) => {
+                let verifying_key = p256::ecdsa::VerifyingKey::try_from(pk)?;
+                let signature = p256::ecdsa::Signature::try_from(signature)?;
+                verifying_key
+                    .verify_prehash(digest, &signature)
+                    .map_err(|_| Error::Crypto)
+            }
+            #[cfg(feature = "p384")]
+            (
+                Self::Ecdsa(pk),
+                Algorithm::Ecdsa {
+                    curve: EcdsaCurve::NistP384,
+                },
+            ) => {
+                let verifying_key = p384::ecdsa::VerifyingKey::try_from(pk)?;
+                let signature = p384::ecdsa::Signature::try_from(signature)?;
+                verifying_key
+                    .verify_prehash(digest, &signature)
+                    .map_err(|_| Error::Crypto)
+            }
+            #[cfg(feature = "p521")]
+            (
+                Self::Ecdsa(pk),
+                Algorithm::Ecdsa {
+                    curve: EcdsaCurve::NistP521,
+                },
+            ) => {
+                let verifying_key = p521::ecdsa::VerifyingKey::try_from(pk)?;
+                let signature = p521::ecdsa::Signature::try_from(signature)?;
+                verifying_key
+                    .verify_prehash(digest, &signature)
+                    .map_err(|_| Error::Crypto)
+            }
+            #[cfg(feature = "rsa")]
+            (Self::Rsa(pk), Algorithm::Rsa { hash: Some(hash) }) => {
+                let data = pk.padded_signature_data(signature)?;
+                let signature = rsa::pkcs1v15::Signature::try_from(data.as_slice())?;
+
+                match hash {
+                    HashAlg::Sha256 => rsa::pkcs1v15::VerifyingKey::<Sha256>::try_from(pk)?
+                        .verify_prehash(digest, &signature)
+                        .map_err(|_| Error::Crypto),
+                    HashAlg::Sha512 => rsa::pkcs1v15::VerifyingKey::<Sha512>::try_from(pk)?
+                        .verify_prehash(digest, &signature)
+                        .map_err(|_| Error::Crypto),
+                    HashAlg::Sha384 => {
+                        Err(Algorithm::Rsa { hash: Some(*hash) }.unsupported_error())
+                    }
+                }
+            }
+            _ => Err(signature.algorithm().unsupported_error()),
+        }
+    }
+}
+
 impl Verifier<Signature> for public::KeyData {
     #[allow(unused_variables)]
     fn verify(&self, message: &[u8], signature: &Signature) -> signature::Result<()> {
         match self {
             #[cfg(feature = "dsa")]
             Self::Dsa(pk) => pk.verify(message, signature),
-            #[cfg(any(feature = "p256", feature = "p384"))]
+            #[cfg(any(feature = "p256", feature = "p384", feature = "p521"))]
             Self::Ecdsa(pk) => pk.verify(message, signature),
             #[cfg(feature = "ed25519")]
             Self::Ed25519(pk) => pk.verify(message, signature),
+            #[cfg(feature = "p256")]
+            Self::SkEcdsaSha2NistP256(pk) => pk.verify(message, signature),
             #[cfg(feature = "ed25519")]
             Self::SkEd25519(pk) => pk.verify(message, signature),
             #[cfg(feature = "rsa")]
@@ -408,26 +847,84 @@ impl Verifier<Signature> for Ed25519PublicKey {
 #[cfg(feature = "ed25519")]
 impl Verifier<Signature> for public::SkEd25519 {
     fn verify(&self, message: &[u8], signature: &Signature) -> signature::Result<()> {
-        let signature_len = signature
-            .as_bytes()
-            .len()
-            .checked_sub(SK_ED25519_SIGNATURE_TRAILER_SIZE)
-            .ok_or(Error::Encoding(encoding::Error::Length))?;
-        let signature_bytes = &signature.as_bytes()[..signature_len];
-        let flags_and_counter = &signature.as_bytes()[signature_len..];
-
-        #[allow(clippy::integer_arithmetic)]
-        let mut signed_data =
-            Vec::with_capacity((2 * Sha256::output_size()) + SK_ED25519_SIGNATURE_TRAILER_SIZE);
-        signed_data.extend(Sha256::digest(self.application()));
-        signed_data.extend(flags_and_counter);
-        signed_data.extend(Sha256::digest(message));
-
+        let (signature_bytes, signed_data) =
+            sk_signed_data(self.application(), message, signature)?;
         let signature = ed25519_dalek::Signature::try_from(signature_bytes)?;
         ed25519_dalek::VerifyingKey::try_from(self.public_key())?.verify(&signed_data, &signature)
     }
 }
 
+#[cfg(feature = "p256")]
+impl Verifier<Signature> for public::SkEcdsaSha2NistP256 {
+    fn verify(&self, message: &[u8], signature: &Signature) -> signature::Result<()> {
+        if signature.algorithm() != Algorithm::SkEcdsaSha2NistP256 {
+            return Err(signature.algorithm().unsupported_error().into());
+        }
+
+        let (signature_bytes, signed_data) =
+            sk_signed_data(self.application(), message, signature)?;
+        let signature = Signature {
+            algorithm: Algorithm::Ecdsa {
+                curve: EcdsaCurve::NistP256,
+            },
+            data: signature_bytes.to_vec(),
+        };
+
+        let verifying_key = p256::ecdsa::VerifyingKey::from_encoded_point(self.ec_point())
+            .map_err(|_| Error::Crypto)?;
+        let signature = p256::ecdsa::Signature::try_from(&signature)?;
+        verifying_key.verify(&signed_data, &signature)
+    }
+}
+
+/// Split a FIDO/U2F Security Key signature into the underlying signature and
+/// the data it was computed over, as described in [PROTOCOL.u2f].
+///
+/// The signed data is assembled on the stack, so verification doesn't
+/// allocate.
+///
+/// [PROTOCOL.u2f]: https://cvsweb.openbsd.org/src/usr.bin/ssh/PROTOCOL.u2f?annotate=HEAD
+#[cfg(any(feature = "ed25519", feature = "p256"))]
+fn sk_signed_data<'a>(
+    application: &str,
+    message: &[u8],
+    signature: &'a Signature,
+) -> Result<(&'a [u8], [u8; SK_SIGNED_DATA_SIZE])> {
+    let signature_len = signature
+        .as_bytes()
+        .len()
+        .checked_sub(SK_SIGNATURE_TRAILER_SIZE)
+        .ok_or(Error::Encoding(encoding::Error::Length))?;
+    let (signature_bytes, flags_and_counter) = signature.as_bytes().split_at(signature_len);
+
+    let mut signed_data = [0u8; SK_SIGNED_DATA_SIZE];
+    let (application_hash, rest) = signed_data.split_at_mut(32);
+    let (trailer, message_hash) = rest.split_at_mut(SK_SIGNATURE_TRAILER_SIZE);
+    application_hash.copy_from_slice(&Sha256::digest(application));
+    trailer.copy_from_slice(flags_and_counter);
+    message_hash.copy_from_slice(&Sha256::digest(message));
+
+    Ok((signature_bytes, signed_data))
+}
+
+/// Check that the given data is a pair of `mpint`s (i.e. `r` and `s`) which
+/// fit in the field of the given curve.
+fn check_ecdsa_components(curve: EcdsaCurve, data: &[u8]) -> Result<()> {
+    let reader = &mut &*data;
+
+    // Components are `mpint`s with leading zeroes stripped, so they may be
+    // shorter than the field size.
+    for _ in 0..2 {
+        let component = Mpint::decode(reader)?;
+
+        if component.as_positive_bytes().ok_or(Error::Crypto)?.len() > curve.field_size() {
+            return Err(encoding::Error::Length.into());
+        }
+    }
+
+    Ok(reader.finish(())?)
+}
+
 #[cfg(feature = "p256")]
 impl TryFrom<p256::ecdsa::Signature> for Signature {
     type Error = Error;
@@ -446,25 +943,22 @@ impl TryFrom<p384::ecdsa::Signature> for Signature {
     }
 }
 
+#[cfg(feature = "p521")]
+impl TryFrom<p521::ecdsa::Signature> for Signature {
+    type Error = Error;
+
+    fn try_from(signature: p521::ecdsa::Signature) -> Result<Signature> {
+        Signature::try_from(&signature)
+    }
+}
+
 #[cfg(feature = "p256")]
 impl TryFrom<&p256::ecdsa::Signature> for Signature {
     type Error = Error;
 
     fn try_from(signature: &p256::ecdsa::Signature) -> Result<Signature> {
         let (r, s) = signature.split_bytes();
-
-        #[allow(clippy::integer_arithmetic)]
-        let mut data = Vec::with_capacity(32 * 2 + 4 * 2 + 2);
-
-        Mpint::from_positive_bytes(&r)?.encode(&mut data)?;
-        Mpint::from_positive_bytes(&s)?.encode(&mut data)?;
-
-        Ok(Signature {
-            algorithm: Algorithm::Ecdsa {
-                curve: EcdsaCurve::NistP256,
-            },
-            data,
-        })
+        Signature::from_ecdsa_components(EcdsaCurve::NistP256, &r, &s)
     }
 }
 
@@ -474,19 +968,17 @@ impl TryFrom<&p384::ecdsa::Signature> for Signature {
 
     fn try_from(signature: &p384::ecdsa::Signature) -> Result<Signature> {
         let (r, s) = signature.split_bytes();
+        Signature::from_ecdsa_components(EcdsaCurve::NistP384, &r, &s)
+    }
+}
 
-        #[allow(clippy::integer_arithmetic)]
-        let mut data = Vec::with_capacity(48 * 2 + 4 * 2 + 2);
-
-        Mpint::from_positive_bytes(&r)?.encode(&mut data)?;
-        Mpint::from_positive_bytes(&s)?.encode(&mut data)?;
+#[cfg(feature = "p521")]
+impl TryFrom<&p521::ecdsa::Signature> for Signature {
+    type Error = Error;
 
-        Ok(Signature {
-            algorithm: Algorithm::Ecdsa {
-                curve: EcdsaCurve::NistP384,
-            },
-            data,
-        })
+    fn try_from(signature: &p521::ecdsa::Signature) -> Result<Signature> {
+        let (r, s) = signature.split_bytes();
+        Signature::from_ecdsa_components(EcdsaCurve::NistP521, &r, &s)
     }
 }
 
@@ -508,30 +1000,28 @@ impl TryFrom<Signature> for p384::ecdsa::Signature {
     }
 }
 
+#[cfg(feature = "p521")]
+impl TryFrom<Signature> for p521::ecdsa::Signature {
+    type Error = Error;
+
+    fn try_from(signature: Signature) -> Result<p521::ecdsa::Signature> {
+        p521::ecdsa::Signature::try_from(&signature)
+    }
+}
+
 #[cfg(feature = "p256")]
 impl TryFrom<&Signature> for p256::ecdsa::Signature {
     type Error = Error;
 
     fn try_from(signature: &Signature) -> Result<p256::ecdsa::Signature> {
-        const FIELD_SIZE: usize = 32;
-
         match signature.algorithm {
             Algorithm::Ecdsa {
                 curve: EcdsaCurve::NistP256,
             } => {
-                let reader = &mut signature.as_bytes();
-                let r = Mpint::decode(reader)?;
-                let s = Mpint::decode(reader)?;
-
-                match (r.as_positive_bytes(), s.as_positive_bytes()) {
-                    (Some(r), Some(s)) if r.len() == FIELD_SIZE && s.len() == FIELD_SIZE => {
-                        Ok(p256::ecdsa::Signature::from_scalars(
-                            *p256::FieldBytes::from_slice(r),
-                            *p256::FieldBytes::from_slice(s),
-                        )?)
-                    }
-                    _ => Err(Error::Crypto),
-                }
+                let mut r = p256::FieldBytes::default();
+                let mut s = p256::FieldBytes::default();
+                signature.ecdsa_field_bytes(&mut r, &mut s)?;
+                Ok(p256::ecdsa::Signature::from_scalars(r, s)?)
             }
             _ => Err(signature.algorithm.unsupported_error()),
         }
@@ -543,32 +1033,40 @@ impl TryFrom<&Signature> for p384::ecdsa::Signature {
     type Error = Error;
 
     fn try_from(signature: &Signature) -> Result<p384::ecdsa::Signature> {
-        const FIELD_SIZE: usize = 48;
-
         match signature.algorithm {
             Algorithm::Ecdsa {
-                curve: EcdsaCurve::NistP256,
+                curve: EcdsaCurve::NistP384,
             } => {
-                let reader = &mut signature.as_bytes();
-                let r = Mpint::decode(reader)?;
-                let s = Mpint::decode(reader)?;
+                let mut r = p384::FieldBytes::default();
+                let mut s = p384::FieldBytes::default();
+                signature.ecdsa_field_bytes(&mut r, &mut s)?;
+                Ok(p384::ecdsa::Signature::from_scalars(r, s)?)
+            }
+            _ => Err(signature.algorithm.unsupported_error()),
+        }
+    }
+}
 
-                match (r.as_positive_bytes(), s.as_positive_bytes()) {
-                    (Some(r), Some(s)) if r.len() == FIELD_SIZE && s.len() == FIELD_SIZE => {
-                        Ok(p384::ecdsa::Signature::from_scalars(
-                            *p384::FieldBytes::from_slice(r),
-                            *p384::FieldBytes::from_slice(s),
-                        )?)
-                    }
-                    _ => Err(Error::Crypto),
-                }
+#[cfg(feature = "p521")]
+impl TryFrom<&Signature> for p521::ecdsa::Signature {
+    type Error = Error;
+
+    fn try_from(signature: &Signature) -> Result<p521::ecdsa::Signature> {
+        match signature.algorithm {
+            Algorithm::Ecdsa {
+                curve: EcdsaCurve::NistP521,
+            } => {
+                let mut r = p521::FieldBytes::default();
+                let mut s = p521::FieldBytes::default();
+                signature.ecdsa_field_bytes(&mut r, &mut s)?;
+                Ok(p521::ecdsa::Signature::from_scalars(r, s)?)
             }
             _ => Err(signature.algorithm.unsupported_error()),
         }
     }
 }
 
-#[cfg(any(feature = "p256", feature = "p384"))]
+#[cfg(any(feature = "p256", feature = "p384", feature = "p521"))]
 impl Signer<Signature> for EcdsaKeypair {
     fn try_sign(&self, message: &[u8]) -> signature::Result<Signature> {
         match self {
@@ -576,6 +1074,9 @@ impl Signer<Signature> for EcdsaKeypair {
             Self::NistP256 { private, .. } => private.try_sign(message),
             #[cfg(feature = "p384")]
             Self::NistP384 { private, .. } => private.try_sign(message),
+            #[cfg(feature = "p521")]
+            Self::NistP521 { private, .. } => private.try_sign(message),
+            #[cfg(not(all(feature = "p256", feature = "p384", feature = "p521")))]
             _ => Err(self.algorithm().unsupported_error().into()),
         }
     }
@@ -599,7 +1100,16 @@ impl Signer<Signature> for EcdsaPrivateKey<48> {
     }
 }
 
-#[cfg(any(feature = "p256", feature = "p384"))]
+#[cfg(feature = "p521")]
+impl Signer<Signature> for EcdsaPrivateKey<66> {
+    fn try_sign(&self, message: &[u8]) -> signature::Result<Signature> {
+        let signing_key = p521::ecdsa::SigningKey::from_slice(self.as_ref())?;
+        let signature: p521::ecdsa::Signature = signing_key.try_sign(message)?;
+        Ok(signature.try_into()?)
+    }
+}
+
+#[cfg(any(feature = "p256", feature = "p384", feature = "p521"))]
 impl Verifier<Signature> for EcdsaPublicKey {
     fn verify(&self, message: &[u8], signature: &Signature) -> signature::Result<()> {
         match signature.algorithm {
@@ -618,6 +1128,14 @@ impl Verifier<Signature> for EcdsaPublicKey {
                     verifying_key.verify(message, &signature)
                 }
 
+                #[cfg(feature = "p521")]
+                EcdsaCurve::NistP521 => {
+                    let verifying_key = p521::ecdsa::VerifyingKey::try_from(self)?;
+                    let signature = p521::ecdsa::Signature::try_from(signature)?;
+                    verifying_key.verify(message, &signature)
+                }
+
+                #[cfg(not(all(feature = "p256", feature = "p384", feature = "p521")))]
                 _ => Err(signature.algorithm().unsupported_error().into()),
             },
             _ => Err(signature.algorithm().unsupported_error().into()),
@@ -628,40 +1146,417 @@ impl Verifier<Signature> for EcdsaPublicKey {
 #[cfg(feature = "rsa")]
 impl Signer<Signature> for RsaKeypair {
     fn try_sign(&self, message: &[u8]) -> signature::Result<Signature> {
-        let data = rsa::pkcs1v15::SigningKey::<Sha512>::try_from(self)?
-            .try_sign(message)
-            .map_err(|_| signature::Error::new())?;
+        Ok(self.try_sign_with_hash(message, HashAlg::Sha512)?)
+    }
+}
 
-        Ok(Signature {
-            algorithm: Algorithm::Rsa {
-                hash: Some(HashAlg::Sha512),
-            },
-            data: data.to_vec(),
-        })
+#[cfg(feature = "rsa")]
+impl RsaKeypair {
+    /// Sign the given message using PKCS#1 v1.5 padding with the given hash,
+    /// i.e. `rsa-sha2-256` or `rsa-sha2-512`.
+    fn try_sign_with_hash(&self, message: &[u8], hash: HashAlg) -> Result<Signature> {
+        let algorithm = Algorithm::Rsa { hash: Some(hash) };
+
+        let data = match hash {
+            HashAlg::Sha256 => rsa::pkcs1v15::SigningKey::<Sha256>::try_from(self)?
+                .try_sign(message)
+                .map(|signature| signature.to_vec()),
+            HashAlg::Sha512 => rsa::pkcs1v15::SigningKey::<Sha512>::try_from(self)?
+                .try_sign(message)
+                .map(|signature| signature.to_vec()),
+            // There's no `rsa-sha2-384` signature algorithm
+            HashAlg::Sha384 => return Err(encoding::Error::Discriminant.into()),
+        }
+        .map_err(|_| Error::Crypto)?;
+
+        Ok(Signature { algorithm, data })
     }
 }
 
+/// RSA signature padding schemes.
+///
+/// OpenSSH's `rsa-sha2-256` and `rsa-sha2-512` algorithms use PKCS#1 v1.5
+/// padding as described in [RFC8332]. RSASSA-PSS is not presently used by
+/// OpenSSH, but can be used when verifying signatures from other sources
+/// when the `rsa-pss` feature is enabled.
+///
+/// [RFC8332]: https://datatracker.ietf.org/doc/html/rfc8332
 #[cfg(feature = "rsa")]
-impl Verifier<Signature> for RsaPublicKey {
-    fn verify(&self, message: &[u8], signature: &Signature) -> signature::Result<()> {
-        match signature.algorithm {
-            Algorithm::Rsa { hash: Some(hash) } => {
-                let signature = rsa::pkcs1v15::Signature::try_from(signature.data.as_ref())?;
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum RsaPadding {
+    /// PKCS#1 v1.5 padding.
+    #[default]
+    Pkcs1v15,
+
+    /// RSASSA-PSS padding, using the MGF1 mask generation function.
+    Pss {
+        /// Hash function used by MGF1, or `None` to use the same hash
+        /// function as the message digest.
+        mgf1_hash: Option<HashAlg>,
+
+        /// Salt length in bytes.
+        salt_len: usize,
+    },
+}
+
+#[cfg(feature = "rsa")]
+impl RsaPadding {
+    /// RSASSA-PSS padding with the given salt length, using MGF1 with the
+    /// same hash function as the message digest.
+    pub fn pss(salt_len: usize) -> Self {
+        RsaPadding::Pss {
+            mgf1_hash: None,
+            salt_len,
+        }
+    }
+}
+
+#[cfg(feature = "rsa")]
+impl RsaPublicKey {
+    /// Verify an RSA signature using the given padding scheme.
+    ///
+    /// The message digest is determined by the signature's [`Algorithm`].
+    ///
+    /// # Returns
+    /// - [`Error::AlgorithmUnsupported`] if the padding scheme is unsupported,
+    ///   e.g. PSS without the `rsa-pss` feature enabled.
+    /// - [`Error::Encoding`] if the signature is longer than the modulus.
+    /// - [`Error::Crypto`] if the signature is invalid, or the public exponent
+    ///   exceeds [`RsaPublicKey::DEFAULT_MAX_EXPONENT`].
+    pub fn verify_with_padding(
+        &self,
+        message: &[u8],
+        signature: &Signature,
+        padding: RsaPadding,
+    ) -> Result<()> {
+        self.verify_with_max_exponent(message, signature, padding, Self::DEFAULT_MAX_EXPONENT)
+    }
+
+    /// Verify an RSA signature using the given padding scheme, rejecting keys
+    /// whose public exponent exceeds `max_exponent`.
+    ///
+    /// Bounds greater than [`RsaPublicKey::DEFAULT_MAX_EXPONENT`] have no
+    /// effect, as larger exponents are never accepted.
+    ///
+    /// See [`RsaPublicKey::verify_with_padding`] for the errors returned.
+    pub fn verify_with_max_exponent(
+        &self,
+        message: &[u8],
+        signature: &Signature,
+        padding: RsaPadding,
+        max_exponent: u64,
+    ) -> Result<()> {
+        let hash = match signature.algorithm {
+            Algorithm::Rsa { hash: Some(hash) } => hash,
+            _ => return Err(signature.algorithm().unsupported_error()),
+        };
+
+        // Ensure the exponent is small enough to verify with quickly
+        if !self.exponent_at_most(max_exponent.min(Self::DEFAULT_MAX_EXPONENT)) {
+            return Err(Error::Crypto);
+        }
+
+        let data = self.padded_signature_data(signature)?;
+
+        match padding {
+            // The `rsa` crate compares the decoded padding and digest in
+            // constant time using `subtle`, so no timing information about
+            // the expected encoding is leaked on failure
+            RsaPadding::Pkcs1v15 => {
+                let signature = rsa::pkcs1v15::Signature::try_from(data.as_slice())?;
 
                 match hash {
                     HashAlg::Sha256 => rsa::pkcs1v15::VerifyingKey::<Sha256>::try_from(self)?
                         .verify(message, &signature)
-                        .map_err(|_| signature::Error::new()),
+                        .map_err(|_| Error::Crypto),
                     HashAlg::Sha512 => rsa::pkcs1v15::VerifyingKey::<Sha512>::try_from(self)?
                         .verify(message, &signature)
-                        .map_err(|_| signature::Error::new()),
+                        .map_err(|_| Error::Crypto),
+                    HashAlg::Sha384 => Err(Algorithm::Rsa { hash: Some(hash) }.unsupported_error()),
                 }
             }
-            _ => Err(signature.algorithm().unsupported_error().into()),
+            #[cfg(feature = "rsa-pss")]
+            RsaPadding::Pss {
+                mgf1_hash,
+                salt_len,
+            } => {
+                // The `rsa` crate always uses the message hash for MGF1, so
+                // EMSA-PSS is implemented here to allow configuring it
+                let key = rsa::RsaPublicKey::try_from(self)?;
+                let em_bits = mpint_bits(&self.n).checked_sub(1).ok_or(Error::Crypto)?;
+                let em = rsa_pss_encoded_message(&key, em_bits, &data)?;
+                let mgf1_hash = mgf1_hash.unwrap_or(hash);
+
+                if emsa_pss_verify(message, &em, em_bits, hash, mgf1_hash, salt_len) {
+                    Ok(())
+                } else {
+                    Err(Error::Crypto)
+                }
+            }
+            #[allow(unreachable_patterns)]
+            _ => Err(signature.algorithm().unsupported_error()),
         }
     }
 }
 
+#[cfg(feature = "rsa")]
+impl RsaPublicKey {
+    /// Verify a PKCS#1 v1.5 signature according to the given
+    /// [`VerificationConfig`].
+    ///
+    /// This bypasses the minimum key size enforced when converting to
+    /// [`rsa::RsaPublicKey`], applying `config.min_rsa_bits` instead.
+    fn verify_with_config(
+        &self,
+        message: &[u8],
+        signature: &Signature,
+        config: &VerificationConfig,
+    ) -> Result<()> {
+        if !self.exponent_at_most(config.max_rsa_exponent.min(Self::DEFAULT_MAX_EXPONENT)) {
+            return Err(Error::Crypto);
+        }
+
+        let data = self.padded_signature_data(signature)?;
+        let key = rsa::RsaPublicKey::new(BigUint::try_from(&self.n)?, BigUint::try_from(&self.e)?)
+            .map_err(|_| Error::Crypto)?;
+
+        if mpint_bits(&self.n) < config.min_rsa_bits {
+            return Err(Error::Crypto);
+        }
+
+        let result = match signature.algorithm {
+            Algorithm::Rsa { hash: None } if config.allow_sha1 => {
+                key.verify(Pkcs1v15Sign::new::<Sha1>(), &Sha1::digest(message), &data)
+            }
+            Algorithm::Rsa {
+                hash: Some(HashAlg::Sha256),
+            } => key.verify(
+                Pkcs1v15Sign::new::<Sha256>(),
+                &Sha256::digest(message),
+                &data,
+            ),
+            Algorithm::Rsa {
+                hash: Some(HashAlg::Sha512),
+            } => key.verify(
+                Pkcs1v15Sign::new::<Sha512>(),
+                &Sha512::digest(message),
+                &data,
+            ),
+            _ => return Err(signature.algorithm().unsupported_error()),
+        };
+
+        result.map_err(|_| Error::Crypto)
+    }
+
+    /// Get the data of an RSA signature, left-padded with zeroes to the size
+    /// of the modulus.
+    ///
+    /// Like OpenSSH, this accepts signatures with their leading zero bytes
+    /// stripped, and only rejects those longer than the modulus.
+    fn padded_signature_data(&self, signature: &Signature) -> Result<Vec<u8>> {
+        let modulus_len = self
+            .n
+            .as_positive_bytes()
+            .map(<[u8]>::len)
+            .ok_or(encoding::Error::Length)?;
+
+        let padding_len = modulus_len
+            .checked_sub(signature.data.len())
+            .ok_or(encoding::Error::Length)?;
+
+        let mut data = Vec::with_capacity(modulus_len);
+        data.resize(padding_len, 0);
+        data.extend_from_slice(&signature.data);
+        Ok(data)
+    }
+}
+
+/// Apply the RSA verification primitive (RSAVP1) to a signature as described
+/// in [RFC8017 § 8.1.2], returning the encoded message of `em_bits` bits,
+/// which is one less than the size of the modulus.
+///
+/// [RFC8017 § 8.1.2]: https://datatracker.ietf.org/doc/html/rfc8017#section-8.1.2
+#[cfg(feature = "rsa-pss")]
+fn rsa_pss_encoded_message(
+    key: &rsa::RsaPublicKey,
+    em_bits: usize,
+    signature: &[u8],
+) -> Result<Vec<u8>> {
+    let s = BigUint::from_bytes_be(signature);
+
+    if &s >= key.n() {
+        return Err(Error::Crypto);
+    }
+
+    let m = s.modpow(key.e(), key.n()).to_bytes_be();
+    let em_len = em_bits.checked_add(7).ok_or(Error::Crypto)? / 8;
+    let padding_len = em_len.checked_sub(m.len()).ok_or(Error::Crypto)?;
+
+    let mut em = Vec::with_capacity(em_len);
+    em.resize(padding_len, 0);
+    em.extend_from_slice(&m);
+    Ok(em)
+}
+
+/// EMSA-PSS verification as described in [RFC8017 § 9.1.2], allowing the MGF1
+/// hash function to differ from the message hash function.
+///
+/// `em` must be `ceil(em_bits / 8)` bytes long.
+///
+/// [RFC8017 § 9.1.2]: https://datatracker.ietf.org/doc/html/rfc8017#section-9.1.2
+#[cfg(feature = "rsa-pss")]
+fn emsa_pss_verify(
+    message: &[u8],
+    em: &[u8],
+    em_bits: usize,
+    hash: HashAlg,
+    mgf1_hash: HashAlg,
+    salt_len: usize,
+) -> bool {
+    let h_len = hash.digest_size();
+
+    // Mask for the bits of the first byte which are within `em_bits`
+    let unused_bits = em.len().saturating_mul(8).saturating_sub(em_bits);
+    let first_byte_mask = u32::try_from(unused_bits)
+        .ok()
+        .and_then(|bits| 0xFFu8.checked_shr(bits))
+        .unwrap_or(0);
+
+    let min_len = h_len.checked_add(salt_len).and_then(|n| n.checked_add(2));
+    if min_len.map_or(true, |min_len| em.len() < min_len) || em.last() != Some(&0xbc) {
+        return false;
+    }
+
+    let db_len = em.len().saturating_sub(h_len).saturating_sub(1);
+    let (masked_db, rest) = em.split_at(db_len);
+    let h = rest.get(..h_len).unwrap_or_default();
+
+    if masked_db
+        .first()
+        .map_or(true, |&byte| byte & !first_byte_mask != 0)
+    {
+        return false;
+    }
+
+    let mut db = mgf1(mgf1_hash, h, db_len);
+    for (db_byte, masked_byte) in db.iter_mut().zip(masked_db) {
+        *db_byte ^= masked_byte;
+    }
+
+    // The bits above `em_bits` are zero in the encoded message, so clear
+    // them from the unmasked data too
+    if let Some(first) = db.first_mut() {
+        *first &= first_byte_mask;
+    }
+
+    let ps_len = db_len.saturating_sub(salt_len).saturating_sub(1);
+    let (ps, rest) = db.split_at(ps_len);
+    let salt = match rest.split_first() {
+        Some((0x01, salt)) if ps.iter().all(|&byte| byte == 0) => salt,
+        _ => return false,
+    };
+
+    let mut m_prime = Vec::with_capacity(8usize.saturating_add(h_len).saturating_add(salt_len));
+    m_prime.extend_from_slice(&[0; 8]);
+    m_prime.extend_from_slice(&hash.digest(message));
+    m_prime.extend_from_slice(salt);
+
+    hash.digest(&m_prime) == h
+}
+
+/// MGF1 mask generation function as described in [RFC8017 Appendix B.2.1].
+///
+/// [RFC8017 Appendix B.2.1]: https://datatracker.ietf.org/doc/html/rfc8017#appendix-B.2.1
+#[cfg(feature = "rsa-pss")]
+fn mgf1(hash: HashAlg, seed: &[u8], len: usize) -> Vec<u8> {
+    let mut mask = Vec::with_capacity(len.saturating_add(hash.digest_size()));
+    let mut counter = 0u32;
+
+    while mask.len() < len {
+        let mut input = Vec::with_capacity(seed.len().saturating_add(4));
+        input.extend_from_slice(seed);
+        input.extend_from_slice(&counter.to_be_bytes());
+        mask.extend_from_slice(&hash.digest(&input));
+        counter = counter.wrapping_add(1);
+    }
+
+    mask.truncate(len);
+    mask
+}
+
+#[cfg(feature = "rsa")]
+impl Verifier<Signature> for RsaPublicKey {
+    fn verify(&self, message: &[u8], signature: &Signature) -> signature::Result<()> {
+        Ok(self.verify_with_padding(message, signature, RsaPadding::Pkcs1v15)?)
+    }
+}
+
+/// ASN.1 DER `INTEGER` tag.
+const DER_INTEGER_TAG: u8 = 0x02;
+
+/// ASN.1 DER `SEQUENCE` tag.
+const DER_SEQUENCE_TAG: u8 = 0x30;
+
+/// Encode an ASN.1 DER tag-length-value triple.
+fn der_encode_tlv(tag: u8, value: &[u8], out: &mut Vec<u8>) -> Result<()> {
+    out.push(tag);
+
+    match value.len() {
+        len @ 0..=0x7F => out.push(len as u8),
+        len @ 0x80..=0xFF => out.extend_from_slice(&[0x81, len as u8]),
+        _ => return Err(encoding::Error::Length.into()),
+    }
+
+    out.extend_from_slice(value);
+    Ok(())
+}
+
+/// Encode an [`Mpint`] as an ASN.1 DER `INTEGER`.
+///
+/// Both encodings are minimal two's complement, so the only difference is
+/// that zero is the empty string as an `mpint` but `0x00` in DER.
+fn der_encode_integer(mpint: &Mpint, out: &mut Vec<u8>) -> Result<()> {
+    match mpint.as_bytes() {
+        [] => der_encode_tlv(DER_INTEGER_TAG, &[0], out),
+        bytes => der_encode_tlv(DER_INTEGER_TAG, bytes, out),
+    }
+}
+
+/// Decode an ASN.1 DER tag-length-value triple with the given tag, returning
+/// the value and advancing `input` past it.
+fn der_decode_tlv<'a>(tag: u8, input: &mut &'a [u8]) -> Result<&'a [u8]> {
+    let (len, header_len): (usize, usize) = match *input {
+        [t, len @ 0..=0x7F, ..] if *t == tag => (usize::from(*len), 2),
+        [t, 0x81, len @ 0x80..=0xFF, ..] if *t == tag => (usize::from(*len), 3),
+        _ => return Err(Error::FormatEncoding),
+    };
+
+    let end = header_len.checked_add(len).ok_or(Error::FormatEncoding)?;
+    let value = input.get(header_len..end).ok_or(Error::FormatEncoding)?;
+    *input = &input[end..];
+    Ok(value)
+}
+
+/// Decode an ASN.1 DER `SEQUENCE`, rejecting any trailing data.
+fn der_decode_sequence(mut input: &[u8]) -> Result<&[u8]> {
+    let body = der_decode_tlv(DER_SEQUENCE_TAG, &mut input)?;
+
+    if !input.is_empty() {
+        return Err(Error::FormatEncoding);
+    }
+
+    Ok(body)
+}
+
+/// Decode an ASN.1 DER `INTEGER` as an [`Mpint`].
+fn der_decode_integer(input: &mut &[u8]) -> Result<Mpint> {
+    match der_decode_tlv(DER_INTEGER_TAG, input)? {
+        [0] => Mpint::from_bytes(&[]),
+        bytes => Mpint::from_bytes(bytes),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Signature;
@@ -686,6 +1581,19 @@ mod tests {
     #[cfg(feature = "ed25519")]
     const EXAMPLE_MSG: &[u8] = b"Hello, world!";
 
+    #[test]
+    fn decode_opaque() {
+        let bytes =
+            hex!("00000017 7373682d6d6c647361363540 6578616d706c652e636f6d 00000004 01020304");
+        let signature = Signature::try_from(bytes.as_slice()).unwrap();
+        assert_eq!(signature.algorithm().as_str(), "ssh-mldsa65@example.com");
+        assert_eq!(signature.as_bytes(), hex!("01020304"));
+
+        let mut encoded = Vec::new();
+        signature.encode(&mut encoded).unwrap();
+        assert_eq!(encoded, bytes);
+    }
+
     #[test]
     fn decode_dsa() {
         let signature = Signature::try_from(DSA_SIGNATURE).unwrap();
@@ -703,6 +1611,75 @@ mod tests {
         );
     }
 
+    #[test]
+    fn ecdsa_der_round_trip() {
+        let signature = Signature::try_from(ECDSA_SHA2_P256_SIGNATURE).unwrap();
+        let der = signature.to_ecdsa_der().unwrap();
+        assert_eq!(
+            der,
+            hex!("304402201298ab320720a32139cda8a40c97a13dc54ce032ea3c6f09ea9e87501e48fa1d022046e4ac697a6424a9870b9ef04ca1182cd741965f989bd1f1f4a26fd83cf70348")
+        );
+
+        let decoded = Signature::from_ecdsa_der(EcdsaCurve::NistP256, &der).unwrap();
+        assert_eq!(signature, decoded);
+    }
+
+    #[test]
+    fn ecdsa_der_rejects_trailing_data() {
+        let signature = Signature::try_from(ECDSA_SHA2_P256_SIGNATURE).unwrap();
+        let mut der = signature.to_ecdsa_der().unwrap();
+        der.push(0);
+        assert!(Signature::from_ecdsa_der(EcdsaCurve::NistP256, &der).is_err());
+    }
+
+    #[test]
+    fn ecdsa_components_strip_leading_zeroes() {
+        let mut r = [0x42u8; 32];
+        r[0] = 0;
+        let s = [0x80u8; 32];
+
+        let signature = Signature::from_ecdsa_components(EcdsaCurve::NistP256, &r, &s).unwrap();
+        let (r_mpint, s_mpint) = signature.ecdsa_components().unwrap();
+        assert_eq!(r_mpint.as_positive_bytes().unwrap(), &r[1..]);
+        assert_eq!(s_mpint.as_positive_bytes().unwrap(), &s);
+
+        // DER keeps the `0x00` sign byte on `s` since its MSB is set.
+        let der = signature.to_ecdsa_der().unwrap();
+        assert_eq!(&der[..4], &[0x30, 0x44, 0x02, 0x1F]);
+        assert_eq!(&der[35..38], &[0x02, 0x21, 0x00]);
+    }
+
+    #[cfg(feature = "p256")]
+    #[test]
+    fn ecdsa_der_verify_with_p256() {
+        use p256::ecdsa::{signature::Verifier as _, SigningKey};
+
+        let signing_key = SigningKey::from_slice(&[0x01; 32]).unwrap();
+        let verifying_key = signing_key.verifying_key();
+
+        // Search for a signature with a short `r` or `s` to exercise the
+        // leading zero handling of both encodings.
+        for i in 0u32.. {
+            let msg = i.to_be_bytes();
+            let p256_sig: p256::ecdsa::Signature = signing_key.sign(&msg);
+            let ssh_sig = Signature::try_from(p256_sig).unwrap();
+
+            let der = ssh_sig.to_ecdsa_der().unwrap();
+            let der_sig = p256::ecdsa::Signature::from_der(&der).unwrap();
+            assert_eq!(der_sig, p256_sig);
+            verifying_key.verify(&msg, &der_sig).unwrap();
+            assert_eq!(
+                p256::ecdsa::Signature::try_from(&ssh_sig).unwrap(),
+                p256_sig
+            );
+
+            let (r, s) = p256_sig.split_bytes();
+            if r[0] == 0 || s[0] == 0 {
+                break;
+            }
+        }
+    }
+
     #[test]
     fn decode_ed25519() {
         let signature = Signature::try_from(ED25519_SIGNATURE).unwrap();
@@ -715,6 +1692,20 @@ mod tests {
         assert_eq!(Algorithm::SkEd25519, signature.algorithm());
     }
 
+    #[test]
+    fn sk_ed25519_flags_and_counter() {
+        let signature = Signature::try_from(SK_ED25519_SIGNATURE).unwrap();
+        assert_eq!(Some(0x01), signature.sk_flags());
+        assert_eq!(Some(9), signature.sk_counter());
+        assert!(signature.sk_user_present());
+        assert!(!signature.sk_user_verified());
+
+        let signature = Signature::try_from(ED25519_SIGNATURE).unwrap();
+        assert_eq!(None, signature.sk_flags());
+        assert_eq!(None, signature.sk_counter());
+        assert!(!signature.sk_user_present());
+    }
+
     #[test]
     fn decode_rsa() {
         let signature = Signature::try_from(RSA_SHA512_SIGNATURE).unwrap();
@@ -770,6 +1761,161 @@ mod tests {
         assert!(keypair.public.verify(EXAMPLE_MSG, &signature).is_ok());
     }
 
+    #[cfg(feature = "ed25519")]
+    #[test]
+    fn verify_any_ed25519() {
+        use crate::PublicKey;
+
+        let keypairs = [[1; 32], [2; 32], [3; 32]].map(|seed| Ed25519Keypair::from_seed(&seed));
+        let keys = keypairs
+            .iter()
+            .map(|keypair| PublicKey::from(keypair.public))
+            .collect::<Vec<_>>();
+
+        let signature = keypairs[1].sign(EXAMPLE_MSG);
+        assert_eq!(
+            PublicKey::verify_any(&keys, EXAMPLE_MSG, &signature),
+            Some(&keys[1])
+        );
+        assert_eq!(PublicKey::verify_any(&keys, b"other", &signature), None);
+        assert_eq!(
+            PublicKey::verify_any(&keys[..1], EXAMPLE_MSG, &signature),
+            None
+        );
+    }
+
+    #[cfg(all(feature = "p256", feature = "ed25519"))]
+    #[test]
+    fn verify_prehashed_ecdsa_p256() {
+        use crate::{Error, PrivateKey};
+        use sha2::{Digest, Sha256};
+
+        let private_key =
+            PrivateKey::from_openssh(include_str!("../tests/examples/id_ecdsa_p256")).unwrap();
+        let public_key = private_key.public_key();
+        let signature = private_key.try_sign(EXAMPLE_MSG).unwrap();
+        let digest = Sha256::digest(EXAMPLE_MSG);
+
+        assert!(public_key
+            .key_data()
+            .verify(EXAMPLE_MSG, &signature)
+            .is_ok());
+        assert_eq!(public_key.verify_prehashed(&digest, &signature), Ok(()));
+        assert_eq!(
+            public_key.verify_prehashed(&Sha256::digest(b"other"), &signature),
+            Err(Error::Crypto)
+        );
+
+        // Ed25519 doesn't sign a digest of the message
+        let ed25519_key = Ed25519Keypair::from_seed(&[1; 32]);
+        let ed25519_public = crate::PublicKey::from(ed25519_key.public);
+        assert!(matches!(
+            ed25519_public.verify_prehashed(&digest, &ed25519_key.sign(EXAMPLE_MSG)),
+            Err(Error::AlgorithmUnsupported { .. })
+        ));
+    }
+
+    #[cfg(all(feature = "rsa", not(feature = "rsa-pss")))]
+    #[test]
+    fn verify_rsa_pss_unsupported() {
+        use crate::{Error, PublicKey, RsaPadding};
+
+        let public_key =
+            PublicKey::from_openssh(include_str!("../tests/examples/id_rsa_3072.pub")).unwrap();
+        let rsa_key = public_key.key_data().rsa().unwrap();
+        let signature = Signature::try_from(RSA_SHA512_SIGNATURE).unwrap();
+        let algorithm = signature.algorithm();
+
+        let padding = RsaPadding::pss(64);
+        assert_eq!(
+            rsa_key.verify_with_padding(b"", &signature, padding),
+            Err(Error::AlgorithmUnsupported { algorithm })
+        );
+    }
+
+    #[cfg(feature = "rsa-pss")]
+    #[test]
+    fn verify_rsa_pss() {
+        use crate::{public::RsaPublicKey, Error, RsaPadding};
+        use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+        use rsa::{pss, signature::RandomizedSigner};
+        use sha2::Sha256;
+        use signature::SignatureEncoding;
+
+        const MSG: &[u8] = b"Hello, world!";
+        const SALT_LEN: usize = 20;
+
+        let mut rng = ChaCha8Rng::from_seed([42; 32]);
+        let private_key = rsa::RsaPrivateKey::new(&mut rng, 2048).unwrap();
+        let public_key = RsaPublicKey::try_from(private_key.to_public_key()).unwrap();
+
+        let pss_signature = pss::SigningKey::<Sha256>::new_with_salt_len(private_key, SALT_LEN)
+            .sign_with_rng(&mut rng, MSG);
+
+        let algorithm = Algorithm::Rsa {
+            hash: Some(HashAlg::Sha256),
+        };
+        let signature = Signature::new(algorithm, pss_signature.to_vec()).unwrap();
+
+        let padding = RsaPadding::pss(SALT_LEN);
+        assert!(public_key
+            .verify_with_padding(MSG, &signature, padding)
+            .is_ok());
+        assert_eq!(
+            public_key.verify_with_padding(b"bogus", &signature, padding),
+            Err(Error::Crypto)
+        );
+
+        // PKCS#1 v1.5 verification must fail for a PSS signature
+        assert!(public_key.verify(MSG, &signature).is_err());
+    }
+
+    #[cfg(feature = "rsa-pss")]
+    #[test]
+    fn verify_rsa_pss_mgf1_hash() {
+        use crate::{Error, PublicKey, RsaPadding};
+
+        // Generated with `openssl dgst -sha256 -sigopt rsa_padding_mode:pss
+        // -sigopt rsa_pss_saltlen:32 -sigopt rsa_mgf1_md:<hash>` using
+        // `id_rsa_3072.pkcs1.pem`
+        const MSG: &[u8] = b"Hello, world!";
+        const MGF1_SHA384_SIGNATURE: &[u8] = &hex!("17892a6d3a60e19a64f59578b54c511fe465d355f8cd113f8c6f1ba3b7524088a351ad12619c23a22581c9e7d7501c736eaeb0743277bc208ec3b6e553353d6442f5c1841caf04be140c8925473aaaec6204b12cd18ac6dd51a5ebe0aff7cd337bd75c542f394e7563a8a427ed9fe1ada6cf8952ff5b4b095c593ba16dfbafaefe58d528efd61732d94b8627987b3396dfd03f495b27a53bc3acd4a1718b2b2a8650224a2a9c7034b46c4221531375b6fa5d78e06737da4a276aa5f13d3e6fc5bcc15d71b6d89ff002dc7c91e52d57acdf087b3dc6d1afe655f28b8255044c9019ada8030f9b7b2ba39100a71e449b7223a512c958db6ad91ebd16dd26f743bb235818ee8f4976aabdcc7e6a5f8a895089a0fd175fc032e1357f568f8791b05be7a5b385cffe78d6ccdd5aaf488d05b47269f65d2db8dc828e4bb73129ba2486e4aeb4c4ca3c4f69112e61f6b92fafa59f3ade72d4e3f0c24a15d9bbfb68e40c2bf066416e6c975641d9f5281f3e0faca95acce3fb47b3740e05773f6ea09474");
+        const MGF1_SHA512_SIGNATURE: &[u8] = &hex!("5021b9ee8efadde1c18c43c2a86e1d42256c5cb34a8b138733f76e17eb918182d0608670367b651633c923bbec8dfb764ee7544c3db243022ec1848c4cd69a37c5dfde019d25a1c214769b49482758e7c02626cdbc2571fae46a267454ae2c6bd076c03dfa92ef5df384e003efa4f46f7686371131741bd1e5013a2f4db09695f0dccd8be72616ec681cf64c6dcd3be0394344b7cf6d61d060ab449e5c31d375d4862324f431c32be2b5523679a93d8965ad3ad273389084b8fb826de9ab429ca712ccf1c2bf5df1e6907771671efa9275ba6c625633df495693dc547a40408ad1213c1e26728c0e9f4548e3c4f49e0e532cb49c9de3ef121bc532b32d492b6cf8296d80b061100745f9e6b9ed93ea1ca0adda16bcb23645146629738c02a09745ace13e3280bf286baa237ebb00edb4a642ba9240a65246326126ada3f240d793dbefe73deaf56867d29c6d9775c2dba97d3297866bf7474986b0afe66ab9a9edf0cfabae76b9a5a1a213e2321ecf74e7d9a79723129cb16a7a2c4edadf855f");
+
+        let public_key =
+            PublicKey::from_openssh(include_str!("../tests/examples/id_rsa_3072.pub")).unwrap();
+        let rsa_key = public_key.key_data().rsa().unwrap();
+        let algorithm = Algorithm::Rsa {
+            hash: Some(HashAlg::Sha256),
+        };
+
+        for (mgf1_hash, data) in [
+            (HashAlg::Sha384, MGF1_SHA384_SIGNATURE),
+            (HashAlg::Sha512, MGF1_SHA512_SIGNATURE),
+        ] {
+            let signature = Signature::new(algorithm.clone(), data).unwrap();
+            let padding = RsaPadding::Pss {
+                mgf1_hash: Some(mgf1_hash),
+                salt_len: 32,
+            };
+
+            assert_eq!(
+                rsa_key.verify_with_padding(MSG, &signature, padding),
+                Ok(())
+            );
+            assert_eq!(
+                rsa_key.verify_with_padding(b"bogus", &signature, padding),
+                Err(Error::Crypto)
+            );
+
+            // MGF1 defaults to the message hash, which doesn't match
+            assert_eq!(
+                rsa_key.verify_with_padding(MSG, &signature, RsaPadding::pss(32)),
+                Err(Error::Crypto)
+            );
+        }
+    }
+
     #[test]
     fn placeholder() {
         assert!(!Signature::try_from(ED25519_SIGNATURE)
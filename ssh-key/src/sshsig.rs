@@ -1,8 +1,11 @@
 //! `sshsig` implementation.
 
-use crate::{public, Algorithm, Error, HashAlg, Result, Signature, SigningKey};
-use alloc::{string::String, string::ToString, vec::Vec};
-use core::str::FromStr;
+use crate::{
+    public, Algorithm, Certificate, Error, Fingerprint, HashAlg, PublicKey, Result, Signature,
+    SigningKey, VerificationConfig,
+};
+use alloc::{string::String, vec::Vec};
+use core::{fmt, str::FromStr};
 use encoding::{
     pem::{LineEnding, PemLabel},
     CheckedSum, Decode, DecodePem, Encode, EncodePem, Reader, Writer,
@@ -23,11 +26,17 @@ type Version = u32;
 ///
 /// See [PROTOCOL.sshsig] for more information.
 ///
+/// Signatures made using a certificate, e.g. `ssh-keygen -Y sign` with a
+/// certificate held by `ssh-agent`, embed the certificate in place of the
+/// public key. These can be verified against the certificate authority
+/// using [`SshSig::verify_with_ca`].
+///
 /// [PROTOCOL.sshsig]: https://cvsweb.openbsd.org/src/usr.bin/ssh/PROTOCOL.sshsig?annotate=HEAD
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct SshSig {
     version: Version,
     public_key: public::KeyData,
+    certificate: Option<Certificate>,
     namespace: String,
     reserved: Vec<u8>,
     hash_alg: HashAlg,
@@ -46,6 +55,11 @@ impl SshSig {
 
     /// Create a new signature with the given public key, namespace, hash
     /// algorithm, and signature.
+    ///
+    /// The hash algorithm must be SHA-256 or SHA-512, as required by
+    /// [PROTOCOL.sshsig], otherwise [`Error::AlgorithmUnknown`] is returned.
+    ///
+    /// [PROTOCOL.sshsig]: https://cvsweb.openbsd.org/src/usr.bin/ssh/PROTOCOL.sshsig?annotate=HEAD
     pub fn new(
         public_key: public::KeyData,
         namespace: impl Into<String>,
@@ -60,9 +74,12 @@ impl SshSig {
             return Err(Error::Namespace);
         }
 
+        check_hash_alg(hash_alg)?;
+
         Ok(Self {
             version,
             public_key,
+            certificate: None,
             namespace,
             reserved,
             hash_alg,
@@ -79,6 +96,32 @@ impl SshSig {
         Self::decode_pem(pem)
     }
 
+    /// Decode a PEM-encoded signature and check that it's structurally
+    /// valid, without verifying it, like `ssh-keygen -Y check-novalidate`.
+    ///
+    /// In addition to the checks performed when decoding, this validates the
+    /// embedded public key and checks that the signature algorithm is one
+    /// that key can produce. The signature itself is not checked, so the
+    /// result must not be trusted until verified with [`PublicKey::verify`].
+    ///
+    /// Returns the signature along with the embedded public key.
+    pub fn parse_and_check(pem: &str) -> Result<(Self, PublicKey)> {
+        let sshsig = Self::from_pem(pem)?;
+        let public_key = PublicKey::from(sshsig.public_key.clone());
+        public_key.validate()?;
+
+        let algorithm_matches = match public_key.algorithm() {
+            Algorithm::Rsa { .. } => sshsig.algorithm().is_rsa(),
+            algorithm => algorithm == sshsig.algorithm(),
+        };
+
+        if !algorithm_matches {
+            return Err(Error::AlgorithmUnknown);
+        }
+
+        Ok((sshsig, public_key))
+    }
+
     /// Encode signature as PEM which begins with the following:
     ///
     /// ```text
@@ -99,16 +142,45 @@ impl SshSig {
             return Err(Error::Namespace);
         }
 
-        if signing_key.public_key().is_sk_ed25519() {
-            return Err(Algorithm::SkEd25519.unsupported_error());
-        }
+        Self::sign_digest(signing_key, namespace, hash_alg, &hash_alg.digest(msg))
+    }
 
-        #[cfg(feature = "ecdsa")]
-        if signing_key.public_key().is_sk_ecdsa_p256() {
-            return Err(Algorithm::SkEcdsaSha2NistP256.unsupported_error());
+    /// Sign the data read from the given reader with the provided signing
+    /// key.
+    ///
+    /// The message is hashed as it's read rather than being loaded into
+    /// memory, making this suitable for signing large files. The resulting
+    /// signature is identical to one produced by [`SshSig::sign`] over the
+    /// same data.
+    #[cfg(feature = "std")]
+    pub fn sign_reader<S: SigningKey>(
+        signing_key: &S,
+        namespace: &str,
+        hash_alg: HashAlg,
+        reader: impl std::io::Read,
+    ) -> Result<Self> {
+        if namespace.is_empty() {
+            return Err(Error::Namespace);
         }
 
-        let signed_data = Self::signed_data(namespace, hash_alg, msg)?;
+        let hash = hash_alg.digest_reader(reader)?;
+        Self::sign_digest(signing_key, namespace, hash_alg, &hash)
+    }
+
+    /// Sign the given message digest, computed using `hash_alg`.
+    fn sign_digest<S: SigningKey>(
+        signing_key: &S,
+        namespace: &str,
+        hash_alg: HashAlg,
+        hash: &[u8],
+    ) -> Result<Self> {
+        let signed_data = SignedData {
+            namespace,
+            reserved: &[],
+            hash_alg,
+            hash,
+        }
+        .to_bytes()?;
         let signature = signing_key.try_sign(&signed_data)?;
         Self::new(signing_key.public_key(), namespace, hash_alg, signature)
     }
@@ -142,15 +214,112 @@ impl SshSig {
     /// are correct and thus is crate-private so as to ensure these parameters
     /// are always authenticated by users of the public API.
     pub(crate) fn verify(&self, msg: &[u8]) -> Result<()> {
-        let signed_data = SignedData {
+        Ok(self
+            .public_key
+            .verify(&self.signed_data_for(msg)?, &self.signature)?)
+    }
+
+    /// Verify the given message against this signature according to the
+    /// given [`VerificationConfig`].
+    ///
+    /// Crate-private for the same reasons as [`SshSig::verify`].
+    pub(crate) fn verify_with(&self, msg: &[u8], config: &VerificationConfig) -> Result<()> {
+        self.public_key
+            .verify_with(&self.signed_data_for(msg)?, &self.signature, config)
+    }
+
+    /// Verify the data read from the given reader against this signature,
+    /// hashing it with this signature's hash algorithm as it's read.
+    ///
+    /// Crate-private for the same reasons as [`SshSig::verify`].
+    #[cfg(feature = "std")]
+    pub(crate) fn verify_reader(&self, reader: impl std::io::Read) -> Result<()> {
+        let signed_data = self.signed_data_for_hash(&self.hash_alg.digest_reader(reader)?)?;
+        Ok(self.public_key.verify(&signed_data, &self.signature)?)
+    }
+
+    /// Get the data signed for the given message, i.e. using this
+    /// signature's namespace, reserved field and hash algorithm.
+    fn signed_data_for(&self, msg: &[u8]) -> Result<Vec<u8>> {
+        self.signed_data_for_hash(&self.hash_alg.digest(msg))
+    }
+
+    /// Get the data signed for the given message digest, computed using
+    /// this signature's hash algorithm.
+    fn signed_data_for_hash(&self, hash: &[u8]) -> Result<Vec<u8>> {
+        SignedData {
             namespace: self.namespace.as_str(),
             reserved: self.reserved.as_slice(),
             hash_alg: self.hash_alg,
-            hash: self.hash_alg.digest(msg).as_slice(),
+            hash,
         }
-        .to_bytes()?;
+        .to_bytes()
+    }
 
-        Ok(self.public_key.verify(&signed_data, &self.signature)?)
+    /// Verify a signature made using a certificate issued by a trusted
+    /// certificate authority (CA), using the system clock to check that the
+    /// current time is within the certificate's validity window.
+    ///
+    /// See [`SshSig::verify_with_ca_at`] for more information.
+    #[cfg(feature = "std")]
+    pub fn verify_with_ca<'a, I>(
+        &self,
+        ca_fingerprints: I,
+        namespace: &str,
+        msg: &[u8],
+    ) -> Result<&Certificate>
+    where
+        I: IntoIterator<Item = &'a Fingerprint>,
+    {
+        let certificate = self.certificate_for(namespace)?;
+        certificate.validate(ca_fingerprints)?;
+        self.verify(msg)?;
+        Ok(certificate)
+    }
+
+    /// Verify a signature made using a certificate issued by a trusted
+    /// certificate authority (CA).
+    ///
+    /// The embedded certificate is validated using [`Certificate::validate_at`]
+    /// against the given CA fingerprints, and the signature is verified
+    /// against the certificate's subject public key. This allows signatures
+    /// to be verified when only the CA key is known, as with the
+    /// `cert-authority` option in an `allowed_signers` file.
+    ///
+    /// On success, returns the certificate so that e.g. its principals can be
+    /// checked by the caller, which this method does not do.
+    ///
+    /// # Returns
+    /// - [`Error::PublicKey`] if the signature wasn't made using a certificate.
+    /// - [`Error::Namespace`] if the namespace doesn't match.
+    /// - [`Error::CertificateValidation`] if the certificate failed to
+    ///   validate.
+    /// - [`Error::Crypto`] if the signature is invalid.
+    pub fn verify_with_ca_at<'a, I>(
+        &self,
+        unix_timestamp: u64,
+        ca_fingerprints: I,
+        namespace: &str,
+        msg: &[u8],
+    ) -> Result<&Certificate>
+    where
+        I: IntoIterator<Item = &'a Fingerprint>,
+    {
+        let certificate = self.certificate_for(namespace)?;
+        certificate.validate_at(unix_timestamp, ca_fingerprints)?;
+        self.verify(msg)?;
+        Ok(certificate)
+    }
+
+    /// Get the embedded certificate, checking the namespace matches.
+    fn certificate_for(&self, namespace: &str) -> Result<&Certificate> {
+        let certificate = self.certificate.as_ref().ok_or(Error::PublicKey)?;
+
+        if namespace != self.namespace {
+            return Err(Error::Namespace);
+        }
+
+        Ok(certificate)
     }
 
     /// Get the signature algorithm.
@@ -168,10 +337,19 @@ impl SshSig {
 
     /// Get public key which corresponds to the signing key that produced
     /// this signature.
+    ///
+    /// For signatures made using a certificate, this is the certificate's
+    /// subject public key.
     pub fn public_key(&self) -> &public::KeyData {
         &self.public_key
     }
 
+    /// Get the certificate embedded in this signature, if it was made using
+    /// a certificate.
+    pub fn certificate(&self) -> Option<&Certificate> {
+        self.certificate.as_ref()
+    }
+
     /// Get the namespace (i.e. domain identifier) for this signature.
     ///
     /// The purpose of the namespace value is to specify a unambiguous
@@ -231,7 +409,7 @@ impl Decode for SshSig {
             return Err(Error::Version { number: version });
         }
 
-        let public_key = reader.read_prefixed(public::KeyData::decode)?;
+        let (public_key, certificate) = decode_public_key_or_certificate(&Vec::decode(reader)?)?;
         let namespace = String::decode(reader)?;
 
         if namespace.is_empty() {
@@ -240,11 +418,13 @@ impl Decode for SshSig {
 
         let reserved = Vec::decode(reader)?;
         let hash_alg = HashAlg::decode(reader)?;
+        check_hash_alg(hash_alg)?;
         let signature = reader.read_prefixed(Signature::decode)?;
 
         Ok(Self {
             version,
             public_key,
+            certificate,
             namespace,
             reserved,
             hash_alg,
@@ -260,7 +440,10 @@ impl Encode for SshSig {
         Ok([
             Self::MAGIC_PREAMBLE.len(),
             self.version.encoded_len()?,
-            self.public_key.encoded_len_prefixed()?,
+            match &self.certificate {
+                Some(certificate) => certificate.encoded_len_prefixed()?,
+                None => self.public_key.encoded_len_prefixed()?,
+            },
             self.namespace.encoded_len()?,
             self.reserved.encoded_len()?,
             self.hash_alg.encoded_len()?,
@@ -272,7 +455,12 @@ impl Encode for SshSig {
     fn encode(&self, writer: &mut impl Writer) -> Result<()> {
         writer.write(Self::MAGIC_PREAMBLE)?;
         self.version.encode(writer)?;
-        self.public_key.encode_prefixed(writer)?;
+
+        match &self.certificate {
+            Some(certificate) => certificate.encode_prefixed(writer)?,
+            None => self.public_key.encode_prefixed(writer)?,
+        }
+
         self.namespace.encode(writer)?;
         self.reserved.encode(writer)?;
         self.hash_alg.encode(writer)?;
@@ -293,10 +481,34 @@ impl PemLabel for SshSig {
     const PEM_LABEL: &'static str = "SSH SIGNATURE";
 }
 
-impl ToString for SshSig {
-    fn to_string(&self) -> String {
-        self.to_pem(LineEnding::default())
-            .expect("SSH signature encoding error")
+impl fmt::Display for SshSig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pem = self.to_pem(LineEnding::default()).map_err(|_| fmt::Error)?;
+
+        f.write_str(&pem)
+    }
+}
+
+/// Ensure the given hash algorithm is one allowed by `PROTOCOL.sshsig`.
+fn check_hash_alg(hash_alg: HashAlg) -> Result<()> {
+    match hash_alg {
+        HashAlg::Sha256 | HashAlg::Sha512 => Ok(()),
+        _ => Err(Error::AlgorithmUnknown),
+    }
+}
+
+/// Decode the public key blob of a signature, which may be a certificate.
+fn decode_public_key_or_certificate(
+    mut blob: &[u8],
+) -> Result<(public::KeyData, Option<Certificate>)> {
+    let (_, is_certificate) = Algorithm::new_maybe_certificate(&String::decode(&mut &*blob)?)?;
+
+    if is_certificate {
+        let certificate = Certificate::from_bytes(blob)?;
+        Ok((certificate.public_key().clone(), Some(certificate)))
+    } else {
+        let public_key = public::KeyData::decode(&mut blob)?;
+        Ok((blob.finish(public_key)?, None))
     }
 }
 
@@ -0,0 +1,106 @@
+//! Policy for verifying signatures and certificates.
+
+use crate::Algorithm;
+use alloc::vec::Vec;
+
+/// Policy applied when verifying signatures and certificates, e.g. via
+/// [`KeyData::verify_with`][`crate::public::KeyData::verify_with`],
+/// [`PublicKey::verify_with`][`crate::PublicKey::verify_with`] and
+/// [`Certificate::validate_at_with`][`crate::Certificate::validate_at_with`].
+///
+/// The [`Default`] policy is secure: SHA-1 and deprecated algorithms are
+/// rejected, and RSA keys must be at least 2048 bits. Fields can be modified
+/// to loosen or tighten it:
+///
+/// ```
+/// use ssh_key::{Algorithm, VerificationConfig};
+///
+/// let mut config = VerificationConfig::default();
+/// config.min_rsa_bits = 3072;
+/// config.allowed_algorithms = Some(vec![Algorithm::Ed25519]);
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct VerificationConfig {
+    /// Allow signature algorithms based on SHA-1, i.e. `ssh-rsa` and
+    /// `ssh-dss`.
+    ///
+    /// Default: `false`.
+    pub allow_sha1: bool,
+
+    /// Minimum RSA modulus size in bits.
+    ///
+    /// Default: 2048.
+    pub min_rsa_bits: usize,
+
+    /// Maximum RSA public exponent.
+    ///
+    /// Bounds greater than
+    /// [`RsaPublicKey::DEFAULT_MAX_EXPONENT`][`crate::public::RsaPublicKey::DEFAULT_MAX_EXPONENT`]
+    /// have no effect, as larger exponents are never accepted.
+    ///
+    /// Default: `RsaPublicKey::DEFAULT_MAX_EXPONENT`.
+    pub max_rsa_exponent: u64,
+
+    /// Signature algorithms to accept, or `None` to accept any algorithm
+    /// (subject to the other options).
+    ///
+    /// For RSA, each of `ssh-rsa`, `rsa-sha2-256` and `rsa-sha2-512` needs
+    /// to be listed to be accepted.
+    ///
+    /// Default: `None`.
+    pub allowed_algorithms: Option<Vec<Algorithm>>,
+
+    /// Reject deprecated algorithms, i.e. DSA (`ssh-dss`), which OpenSSH no
+    /// longer supports.
+    ///
+    /// Default: `true`.
+    pub reject_deprecated: bool,
+}
+
+impl VerificationConfig {
+    /// Minimum RSA modulus size in bits used by the [`Default`] policy.
+    pub const DEFAULT_MIN_RSA_BITS: usize = 2048;
+
+    /// Permissive policy which accepts SHA-1 and deprecated algorithms, and
+    /// RSA keys of at least 1024 bits, e.g. for interoperating with legacy
+    /// systems.
+    pub fn permissive() -> Self {
+        Self {
+            allow_sha1: true,
+            min_rsa_bits: 1024,
+            reject_deprecated: false,
+            ..Self::default()
+        }
+    }
+
+    /// Does this policy allow signatures using the given algorithm?
+    ///
+    /// Key-specific requirements (e.g. the RSA modulus size) are checked
+    /// separately when verifying.
+    pub fn allows(&self, algorithm: Algorithm) -> bool {
+        if let Some(allowed) = &self.allowed_algorithms {
+            if !allowed.contains(&algorithm) {
+                return false;
+            }
+        }
+
+        match algorithm {
+            Algorithm::Dsa => self.allow_sha1 && !self.reject_deprecated,
+            Algorithm::Rsa { hash: None } => self.allow_sha1,
+            _ => true,
+        }
+    }
+}
+
+impl Default for VerificationConfig {
+    fn default() -> Self {
+        Self {
+            allow_sha1: false,
+            min_rsa_bits: Self::DEFAULT_MIN_RSA_BITS,
+            max_rsa_exponent: crate::public::RsaPublicKey::DEFAULT_MAX_EXPONENT,
+            allowed_algorithms: None,
+            reject_deprecated: true,
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! ssh-agent client tests.
+
+#![cfg(feature = "std")]
+
+use encoding::Encode;
+use signature::Signer;
+use ssh_key::{
+    agent::{Agent, Constraint},
+    Algorithm, Certificate, Error, Identity, PrivateKey, PublicKey, Signature, SigningKey,
+};
+use std::io::{self, Cursor, Read, Write};
+
+/// Ed25519 OpenSSH-formatted private key.
+const ED25519_PRIVATE_KEY: &str = include_str!("examples/id_ed25519");
+
+/// Ed25519 OpenSSH-formatted public key.
+const ED25519_PUBLIC_KEY: &str = include_str!("examples/id_ed25519.pub");
+
+/// Ed25519 OpenSSH certificate.
+const ED25519_CERTIFICATE: &str = include_str!("examples/id_ed25519-cert.pub");
+
+/// Stream which records requests and replays canned responses.
+#[derive(Default)]
+struct MockStream {
+    requests: Vec<u8>,
+    responses: Cursor<Vec<u8>>,
+}
+
+impl MockStream {
+    /// Create a stream which replays the given responses, which are framed
+    /// with their length.
+    fn new(responses: &[&[u8]]) -> Self {
+        let mut framed = Vec::new();
+
+        for response in responses {
+            response.encode(&mut framed).unwrap();
+        }
+
+        Self {
+            requests: Vec::new(),
+            responses: Cursor::new(framed),
+        }
+    }
+}
+
+impl Read for MockStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.responses.read(buf)
+    }
+}
+
+impl Write for MockStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.requests.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn identities() {
+    let public_key = PublicKey::from_openssh(ED25519_PUBLIC_KEY).unwrap();
+
+    let mut response = vec![12];
+    1u32.encode(&mut response).unwrap();
+    public_key
+        .to_bytes()
+        .unwrap()
+        .encode(&mut response)
+        .unwrap();
+    "user@example.com".encode(&mut response).unwrap();
+
+    let agent = Agent::new(MockStream::new(&[&response]));
+    assert_eq!(agent.identities().unwrap(), vec![public_key.into()]);
+    assert_eq!(agent.into_inner().requests, [0, 0, 0, 1, 11]);
+}
+
+#[test]
+fn identities_with_certificate() {
+    let public_key = PublicKey::from_openssh(ED25519_PUBLIC_KEY).unwrap();
+    let certificate = Certificate::from_openssh(ED25519_CERTIFICATE).unwrap();
+
+    let mut response = vec![12];
+    2u32.encode(&mut response).unwrap();
+    certificate
+        .to_bytes()
+        .unwrap()
+        .encode(&mut response)
+        .unwrap();
+    "cert@example.com".encode(&mut response).unwrap();
+    public_key
+        .to_bytes()
+        .unwrap()
+        .encode(&mut response)
+        .unwrap();
+    "user@example.com".encode(&mut response).unwrap();
+
+    let agent = Agent::new(MockStream::new(&[&response]));
+    let identities = agent.identities().unwrap();
+    assert_eq!(identities.len(), 2);
+
+    let cert_identity = &identities[0];
+    assert_eq!(cert_identity.comment(), "cert@example.com");
+    assert_eq!(
+        cert_identity.public_key().key_data(),
+        certificate.public_key()
+    );
+    assert_eq!(
+        cert_identity.certificate().unwrap().to_bytes().unwrap(),
+        certificate.to_bytes().unwrap()
+    );
+
+    assert_eq!(identities[1], Identity::from(public_key));
+}
+
+#[test]
+fn sign() {
+    let public_key = PublicKey::from_openssh(ED25519_PUBLIC_KEY).unwrap();
+    let signature = Signature::new(Algorithm::Ed25519, [1u8; 64]).unwrap();
+
+    let mut response = vec![14];
+    signature.encode_prefixed(&mut response).unwrap();
+
+    let agent = Agent::new(MockStream::new(&[&response, &response]));
+    assert_eq!(
+        agent.sign(public_key.key_data(), b"message").unwrap(),
+        signature
+    );
+
+    let signer = agent.signer(public_key.key_data().clone());
+    assert_eq!(&signer.public_key(), public_key.key_data());
+    assert_eq!(signer.try_sign(b"message").unwrap(), signature);
+
+    let mut request = vec![13];
+    public_key.key_data().encode_prefixed(&mut request).unwrap();
+    b"message".encode(&mut request).unwrap();
+    0u32.encode(&mut request).unwrap();
+
+    let mut expected = Vec::new();
+    request.encode(&mut expected).unwrap();
+    expected.extend_from_within(..);
+    assert_eq!(agent.into_inner().requests, expected);
+}
+
+#[test]
+fn add_identity_with_constraints() {
+    let private_key = PrivateKey::from_openssh(ED25519_PRIVATE_KEY).unwrap();
+    let agent = Agent::new(MockStream::new(&[&[6]]));
+
+    agent
+        .add_identity(
+            &private_key,
+            &[Constraint::Lifetime(60), Constraint::Confirm],
+        )
+        .unwrap();
+
+    let mut request = vec![25];
+    private_key.key_data().encode(&mut request).unwrap();
+    "user@example.com".encode(&mut request).unwrap();
+    request.extend_from_slice(&[1, 0, 0, 0, 60, 2]);
+
+    let mut expected = Vec::new();
+    request.encode(&mut expected).unwrap();
+    assert_eq!(agent.into_inner().requests, expected);
+}
+
+#[test]
+fn failure() {
+    let public_key = PublicKey::from_openssh(ED25519_PUBLIC_KEY).unwrap();
+    let agent = Agent::new(MockStream::new(&[&[5], &[30], &[6]]));
+
+    assert_eq!(
+        agent.remove_identity(public_key.key_data()),
+        Err(Error::AgentFailure)
+    );
+    assert_eq!(
+        agent.sign(public_key.key_data(), b"message"),
+        Err(Error::AgentFailure)
+    );
+    assert!(agent.remove_all_identities().is_ok());
+
+    // The connection was closed
+    assert_eq!(
+        agent.remove_all_identities(),
+        Err(Error::Io(io::ErrorKind::UnexpectedEof))
+    );
+}
@@ -0,0 +1,271 @@
+//! Algorithm tests.
+
+use core::str::FromStr;
+use ssh_key::{Algorithm, EcdsaCurve, Error, HashAlg};
+
+#[cfg(any(
+    all(feature = "alloc", feature = "ed25519"),
+    all(feature = "alloc", feature = "p256", feature = "rand_core")
+))]
+use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+
+#[test]
+fn parse_lenient() {
+    assert_eq!(
+        Algorithm::from_str_lenient("SSH-ED25519"),
+        Ok(Algorithm::Ed25519)
+    );
+    assert_eq!(
+        Algorithm::from_str_lenient("Sk-Ssh-Ed25519@OpenSSH.com"),
+        Ok(Algorithm::SkEd25519)
+    );
+    assert_eq!(
+        Algorithm::from_str_lenient("ssh-foobar"),
+        Err(Error::AlgorithmUnknown)
+    );
+}
+
+#[test]
+fn parse_strict() {
+    assert_eq!(Algorithm::from_str("ssh-ed25519"), Ok(Algorithm::Ed25519));
+    assert_eq!(
+        Algorithm::from_str("SSH-ED25519"),
+        Err(Error::AlgorithmUnknown)
+    );
+}
+
+#[test]
+fn certificate_name_round_trip() {
+    for algorithm in [
+        Algorithm::Dsa,
+        Algorithm::Ecdsa {
+            curve: EcdsaCurve::NistP256,
+        },
+        Algorithm::Ecdsa {
+            curve: EcdsaCurve::NistP384,
+        },
+        Algorithm::Ecdsa {
+            curve: EcdsaCurve::NistP521,
+        },
+        Algorithm::Ed25519,
+        Algorithm::Rsa { hash: None },
+        Algorithm::Rsa {
+            hash: Some(HashAlg::Sha256),
+        },
+        Algorithm::Rsa {
+            hash: Some(HashAlg::Sha512),
+        },
+        Algorithm::SkEcdsaSha2NistP256,
+        Algorithm::SkEd25519,
+    ] {
+        let cert_name = algorithm.as_certificate_str();
+        assert!(cert_name.ends_with("-cert-v01@openssh.com"));
+        assert_eq!(Algorithm::new_certificate(cert_name), Ok(algorithm.clone()));
+        assert_eq!(
+            Algorithm::new_maybe_certificate(cert_name),
+            Ok((algorithm.clone(), true))
+        );
+        assert_eq!(
+            Algorithm::new_maybe_certificate(algorithm.as_str()),
+            Ok((algorithm, false))
+        );
+    }
+
+    assert_eq!(
+        Algorithm::new_maybe_certificate("ssh-foobar-cert-v01@openssh.com"),
+        Err(Error::AlgorithmUnknown)
+    );
+}
+
+#[test]
+fn try_from_certificate_str() {
+    let p256 = Algorithm::Ecdsa {
+        curve: EcdsaCurve::NistP256,
+    };
+    assert_eq!(
+        Algorithm::try_from("ecdsa-sha2-nistp256-cert-v01@openssh.com"),
+        Ok(p256.clone())
+    );
+    assert_eq!(Algorithm::try_from("ecdsa-sha2-nistp256"), Ok(p256));
+    assert_eq!(
+        Algorithm::from_str("ecdsa-sha2-nistp256-cert-v01@openssh.com"),
+        Err(Error::AlgorithmUnknown)
+    );
+    assert_eq!(
+        Algorithm::try_from("ssh-foobar-cert-v01@openssh.com"),
+        Err(Error::AlgorithmUnknown)
+    );
+}
+
+#[test]
+fn hash_alg() {
+    let ecdsa = |curve| Algorithm::Ecdsa { curve };
+    assert_eq!(
+        ecdsa(EcdsaCurve::NistP256).hash_alg(),
+        Some(HashAlg::Sha256)
+    );
+    assert_eq!(
+        ecdsa(EcdsaCurve::NistP384).hash_alg(),
+        Some(HashAlg::Sha384)
+    );
+    assert_eq!(
+        ecdsa(EcdsaCurve::NistP521).hash_alg(),
+        Some(HashAlg::Sha512)
+    );
+    assert_eq!(
+        Algorithm::SkEcdsaSha2NistP256.hash_alg(),
+        Some(HashAlg::Sha256)
+    );
+
+    for hash in [HashAlg::Sha256, HashAlg::Sha512] {
+        assert_eq!(Algorithm::Rsa { hash: Some(hash) }.hash_alg(), Some(hash));
+    }
+
+    assert_eq!(Algorithm::Rsa { hash: None }.hash_alg(), None);
+    assert_eq!(Algorithm::Ed25519.hash_alg(), None);
+    assert_eq!(Algorithm::SkEd25519.hash_alg(), None);
+}
+
+#[test]
+fn hash_alg_round_trip() {
+    for hash in [HashAlg::Sha256, HashAlg::Sha384, HashAlg::Sha512] {
+        assert_eq!(hash.as_str().parse::<HashAlg>(), Ok(hash));
+        assert_eq!(hash.to_string().parse::<HashAlg>(), Ok(hash));
+    }
+
+    assert_eq!("sha512".parse::<HashAlg>(), Ok(HashAlg::Sha512));
+    assert_eq!(HashAlg::Sha512.to_string(), "sha512");
+    assert_eq!("md5".parse::<HashAlg>(), Err(Error::AlgorithmUnknown));
+    assert_eq!("SHA512".parse::<HashAlg>(), Err(Error::AlgorithmUnknown));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn hash_alg_digest_size() {
+    assert_eq!(HashAlg::Sha256.digest_size(), 32);
+    assert_eq!(HashAlg::Sha512.digest_size(), 64);
+
+    for hash in [HashAlg::Sha256, HashAlg::Sha512] {
+        assert_eq!(hash.digest(b"example").len(), hash.digest_size());
+    }
+}
+
+/// There's no `rsa-sha2-384` identifier, so RSA with SHA-384 can't be encoded
+/// or used in signatures.
+#[cfg(feature = "alloc")]
+#[test]
+fn rsa_sha384_unencodable() {
+    use ssh_key::{encoding::Encode, Signature};
+
+    let algorithm = Algorithm::Rsa {
+        hash: Some(HashAlg::Sha384),
+    };
+
+    assert_eq!(algorithm.as_str(), "");
+    assert_eq!(
+        algorithm.encode(&mut Vec::new()),
+        Err(ssh_key::encoding::Error::Discriminant.into())
+    );
+    assert_eq!(
+        Signature::new(algorithm, vec![0; 256]),
+        Err(ssh_key::encoding::Error::Discriminant.into())
+    );
+}
+
+#[test]
+fn signature_size_hint() {
+    assert_eq!(Algorithm::Ed25519.signature_size_hint(), Some(83));
+    assert_eq!(Algorithm::Rsa { hash: None }.signature_size_hint(), None);
+}
+
+#[cfg(all(feature = "alloc", feature = "ed25519"))]
+#[test]
+fn signature_size_hint_ed25519() {
+    let key = ssh_key::PrivateKey::random(&mut ChaCha8Rng::from_seed([1; 32]), Algorithm::Ed25519)
+        .unwrap();
+    let signature = key
+        .sign("example", Default::default(), b"message")
+        .unwrap()
+        .signature()
+        .clone();
+
+    let encoded = Vec::<u8>::try_from(signature).unwrap();
+    assert_eq!(
+        Algorithm::Ed25519.signature_size_hint(),
+        Some(encoded.len())
+    );
+}
+
+#[cfg(all(feature = "alloc", feature = "p256", feature = "rand_core"))]
+#[test]
+fn signature_size_hint_p256() {
+    let algorithm = Algorithm::Ecdsa {
+        curve: EcdsaCurve::NistP256,
+    };
+    assert_eq!(algorithm.signature_size_hint(), Some(101));
+
+    let mut rng = ChaCha8Rng::from_seed([2; 32]);
+    let key = ssh_key::PrivateKey::random(&mut rng, algorithm.clone()).unwrap();
+    let mut max_len = 0;
+
+    for i in 0..64u32 {
+        let signature = key
+            .sign("example", Default::default(), &i.to_be_bytes())
+            .unwrap()
+            .signature()
+            .clone();
+
+        let encoded_len = Vec::<u8>::try_from(signature).unwrap().len();
+        assert!(encoded_len <= algorithm.signature_size_hint().unwrap());
+        max_len = max_len.max(encoded_len);
+    }
+
+    assert_eq!(algorithm.signature_size_hint(), Some(max_len));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn other_algorithm_round_trip() {
+    use ssh_key::AlgorithmName;
+
+    let id = "sntrup761x25519-sha512@openssh.com";
+    let algorithm = Algorithm::new(id).unwrap();
+    assert_eq!(algorithm, Algorithm::Other(AlgorithmName::new(id).unwrap()));
+    assert_eq!(algorithm.as_str(), id);
+    assert_eq!(algorithm.to_string(), id);
+    assert_eq!(algorithm.hash_alg(), None);
+
+    for invalid in [
+        "sntrup761x25519-sha512",
+        "@openssh.com",
+        "kex@",
+        "a@b@c",
+        "kex name@openssh.com",
+        "ssh-foobar-cert-v01@openssh.com",
+        &format!("{}@openssh.com", "x".repeat(53)),
+    ] {
+        assert_eq!(Algorithm::new(invalid), Err(Error::AlgorithmUnknown));
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn unknown_ecdsa_curve() {
+    use ssh_key::AlgorithmName;
+
+    let id = "ecdsa-sha2-1.3.132.0.10";
+    assert_eq!(
+        Algorithm::new(id),
+        Ok(Algorithm::Other(AlgorithmName::new(id).unwrap()))
+    );
+
+    // Supported curves are never `Other`
+    assert_eq!(
+        AlgorithmName::new("ecdsa-sha2-nistp256"),
+        Err(Error::AlgorithmUnknown)
+    );
+    assert_eq!(
+        AlgorithmName::new("ecdsa-sha2-"),
+        Err(Error::AlgorithmUnknown)
+    );
+}
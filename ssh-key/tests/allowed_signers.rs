@@ -0,0 +1,314 @@
+//! Tests for parsing `allowed_signers` files.
+
+#![cfg(all(feature = "ecdsa", feature = "std"))]
+
+use ssh_key::{Algorithm, AllowedSigners};
+
+#[cfg(feature = "ed25519")]
+use ssh_key::{allowed_signers::verify_git_signature, Error, SshSig};
+
+/// Example `allowed_signers` file.
+#[cfg(feature = "ed25519")]
+const ALLOWED_SIGNERS_EXAMPLE: &str = include_str!("examples/allowed_signers");
+
+/// Commit object signed using `gpg.format=ssh`, as output by `git cat-file`.
+#[cfg(feature = "ed25519")]
+const GIT_COMMIT_EXAMPLE: &str = include_str!("examples/git_commit_ed25519");
+
+/// Split a signed git commit object into its signed payload and signature.
+#[cfg(feature = "ed25519")]
+fn split_git_commit(commit: &str) -> (String, SshSig) {
+    let mut payload = String::new();
+    let mut signature = String::new();
+    let mut in_signature = false;
+
+    for line in commit.split_inclusive('\n') {
+        if let Some(pem) = line.strip_prefix("gpgsig ") {
+            signature.push_str(pem);
+            in_signature = true;
+        } else if let (true, Some(pem)) = (in_signature, line.strip_prefix(' ')) {
+            signature.push_str(pem);
+        } else {
+            payload.push_str(line);
+            in_signature = false;
+        }
+    }
+
+    (payload, SshSig::from_pem(signature).unwrap())
+}
+
+#[test]
+fn read_example_file() {
+    let allowed_signers = AllowedSigners::read_file("./tests/examples/allowed_signers").unwrap();
+    assert_eq!(allowed_signers.len(), 3);
+
+    assert_eq!(
+        allowed_signers[0].principals(),
+        ["dev@example.org", "*@example.net"]
+    );
+    assert!(allowed_signers[0].config_opts().is_empty());
+    assert!(allowed_signers[0].allows_namespace("git"));
+    assert!(!allowed_signers[0].is_cert_authority());
+    assert!(matches!(
+        allowed_signers[0].public_key().algorithm(),
+        Algorithm::Ecdsa { .. }
+    ));
+
+    assert_eq!(allowed_signers[1].principals(), ["user@example.com"]);
+    assert_eq!(
+        allowed_signers[1].config_opts().as_str(),
+        "namespaces=\"git\""
+    );
+    assert!(allowed_signers[1].allows_namespace("git"));
+    assert!(!allowed_signers[1].allows_namespace("file"));
+    assert_eq!(
+        allowed_signers[1].public_key().algorithm(),
+        Algorithm::Ed25519
+    );
+    assert_eq!(
+        allowed_signers[1].public_key().comment(),
+        "user@example.com"
+    );
+
+    assert_eq!(allowed_signers[2].principals(), ["file@example.com"]);
+    assert!(!allowed_signers[2].allows_namespace("git"));
+}
+
+#[test]
+fn entry_round_trip() {
+    let line = "user@example.com,*@example.net cert-authority,namespaces=\"git,file\" ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILM+rvN+ot98qgEN796jTiQfZfG1KaT0PtFDJ/XFSqti user@example.com";
+    let entry = line.parse::<ssh_key::allowed_signers::Entry>().unwrap();
+    assert!(entry.is_cert_authority());
+    assert!(entry.allows_namespace("file"));
+    assert_eq!(entry.to_string(), line);
+}
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn verify_git_commit() {
+    let (payload, signature) = split_git_commit(GIT_COMMIT_EXAMPLE);
+    assert_eq!(signature.namespace(), "git");
+
+    let principal =
+        verify_git_signature(ALLOWED_SIGNERS_EXAMPLE, payload.as_bytes(), &signature).unwrap();
+    assert_eq!(principal, "user@example.com");
+}
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn verify_git_commit_tampered() {
+    let (payload, signature) = split_git_commit(GIT_COMMIT_EXAMPLE);
+    let payload = payload.replace("Initial commit", "Malicious commit");
+
+    assert_eq!(
+        verify_git_signature(ALLOWED_SIGNERS_EXAMPLE, payload.as_bytes(), &signature),
+        Err(Error::Crypto)
+    );
+}
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn verify_git_commit_unknown_signer() {
+    let (payload, signature) = split_git_commit(GIT_COMMIT_EXAMPLE);
+
+    // Only the ECDSA entry, which has a different key
+    let ecdsa_entry = ALLOWED_SIGNERS_EXAMPLE.lines().nth(1).unwrap();
+    assert_eq!(
+        verify_git_signature(ecdsa_entry, payload.as_bytes(), &signature),
+        Err(Error::PublicKey)
+    );
+
+    // Matching key, but not allowed to sign in the `git` namespace
+    let file_only = ALLOWED_SIGNERS_EXAMPLE.replace("namespaces=\"git\"", "namespaces=\"file\"");
+    assert_eq!(
+        verify_git_signature(&file_only, payload.as_bytes(), &signature),
+        Err(Error::Namespace)
+    );
+}
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn verify_git_commit_expired_signer() {
+    let (payload, signature) = split_git_commit(GIT_COMMIT_EXAMPLE);
+
+    let expired = ALLOWED_SIGNERS_EXAMPLE.replace(
+        "namespaces=\"git\"",
+        "namespaces=\"git\",valid-before=\"20000101\"",
+    );
+    assert_eq!(
+        verify_git_signature(&expired, payload.as_bytes(), &signature),
+        Err(Error::Time)
+    );
+}
+
+#[test]
+fn entry_principals_and_validity() {
+    use ssh_key::allowed_signers::Entry;
+
+    let line = "*@example.com,!mallory@example.com valid-after=\"20240101\",valid-before=\"202501011230Z\",namespaces=\"git,ex*\" ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILM+rvN+ot98qgEN796jTiQfZfG1KaT0PtFDJ/XFSqti";
+    let entry = line.parse::<Entry>().unwrap();
+
+    assert!(entry.matches_principal("alice@example.com"));
+    assert!(!entry.matches_principal("mallory@example.com"));
+    assert!(!entry.matches_principal("alice@example.org"));
+
+    assert!(entry.allows_namespace("git"));
+    assert!(entry.allows_namespace("example"));
+    assert!(!entry.allows_namespace("file"));
+
+    assert_eq!(entry.valid_after(), Ok(Some(1704067200)));
+    assert_eq!(entry.valid_before(), Ok(Some(1735734600)));
+    assert_eq!(entry.is_valid_at(1704067199), Ok(false));
+    assert_eq!(entry.is_valid_at(1704067200), Ok(true));
+    assert_eq!(entry.is_valid_at(1735734600), Ok(true));
+    assert_eq!(entry.is_valid_at(1735734601), Ok(false));
+
+    for invalid in [
+        "2024010",
+        "20240230",
+        "20241301",
+        "202401012460",
+        "1969123123",
+    ] {
+        let line = line.replace("20240101", invalid);
+        assert_eq!(
+            line.parse::<Entry>().unwrap().valid_after(),
+            Err(ssh_key::Error::Time)
+        );
+    }
+}
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn allowed_signers_file_verify() {
+    use ssh_key::{
+        allowed_signers::{AllowedSignersFile, Entry},
+        authorized_keys::ConfigOpts,
+        PublicKey,
+    };
+
+    let signature = SshSig::from_pem(include_str!("examples/sshsig_ed25519")).unwrap();
+    let public_key = PublicKey::from_openssh(include_str!("examples/id_ed25519.pub")).unwrap();
+
+    let mut file = ALLOWED_SIGNERS_EXAMPLE
+        .parse::<AllowedSignersFile>()
+        .unwrap();
+    assert_eq!(file.len(), 3);
+
+    // Only allowed to sign in the `git` namespace
+    assert_eq!(
+        file.verify("user@example.com", "example", &signature, b"testing"),
+        Err(Error::Namespace)
+    );
+
+    file.push(
+        Entry::new(
+            vec!["*@example.com".to_string(), "!user@example.com".to_string()],
+            ConfigOpts::new("valid-before=\"21000101\"").unwrap(),
+            public_key.clone(),
+        )
+        .unwrap(),
+    );
+    file.push(
+        Entry::new(
+            vec!["alice@example.com".to_string()],
+            ConfigOpts::default(),
+            public_key,
+        )
+        .unwrap(),
+    );
+
+    let entry = file
+        .verify("alice@example.com", "example", &signature, b"testing")
+        .unwrap();
+    assert_eq!(entry.principals(), ["*@example.com", "!user@example.com"]);
+
+    assert_eq!(
+        file.verify("alice@example.com", "example", &signature, b"tampered"),
+        Err(Error::Crypto)
+    );
+    assert_eq!(
+        file.verify("alice@example.com", "file", &signature, b"testing"),
+        Err(Error::Namespace)
+    );
+    assert_eq!(
+        file.verify("bob@example.org", "example", &signature, b"testing"),
+        Err(Error::PublicKey)
+    );
+
+    // Only the entry which has expired matches
+    assert_eq!(
+        file.verify_at(
+            4102444801,
+            "bob@example.com",
+            "example",
+            &signature,
+            b"testing"
+        ),
+        Err(Error::Time)
+    );
+
+    assert_eq!(file.to_string().parse::<AllowedSignersFile>(), Ok(file));
+    assert!(Entry::new(
+        Vec::new(),
+        ConfigOpts::default(),
+        signature.public_key().clone().into()
+    )
+    .is_err());
+}
+
+#[cfg(all(feature = "ed25519", feature = "p256"))]
+#[test]
+fn allowed_signers_file_verify_cert_authority() {
+    use ssh_key::allowed_signers::AllowedSignersFile;
+
+    let ca_key = include_str!("examples/id_ecdsa_p256.pub");
+    let signature =
+        SshSig::from_pem(include_str!("examples/sshsig_ed25519_cert_with_principal")).unwrap();
+
+    let file = format!("*@example.com cert-authority {ca_key}")
+        .parse::<AllowedSignersFile>()
+        .unwrap();
+
+    let entry = file
+        .verify("alice@example.com", "example", &signature, b"testing")
+        .unwrap();
+    assert!(entry.is_cert_authority());
+
+    // Principal allowed by the file, but not the certificate
+    assert_eq!(
+        file.verify("bob@example.com", "example", &signature, b"testing"),
+        Err(Error::CertificateValidation)
+    );
+
+    // Certificate has expired
+    assert_eq!(
+        file.verify_at(
+            4102444801,
+            "alice@example.com",
+            "example",
+            &signature,
+            b"testing"
+        ),
+        Err(Error::CertificateValidation)
+    );
+
+    // CA keys must have the `cert-authority` option
+    let file = format!("*@example.com {ca_key}")
+        .parse::<AllowedSignersFile>()
+        .unwrap();
+    assert_eq!(
+        file.verify("alice@example.com", "example", &signature, b"testing"),
+        Err(Error::PublicKey)
+    );
+
+    // Certificate lacks principals
+    let signature = SshSig::from_pem(include_str!("examples/sshsig_ed25519_cert")).unwrap();
+    let file = format!("* cert-authority {ca_key}")
+        .parse::<AllowedSignersFile>()
+        .unwrap();
+    assert_eq!(
+        file.verify("alice@example.com", "example", &signature, b"testing"),
+        Err(Error::CertificateValidation)
+    );
+}
@@ -47,3 +47,402 @@ fn read_example_file() {
         "user4@example.com"
     );
 }
+
+#[test]
+fn collect_authorized_keys_file() {
+    use ssh_key::{
+        authorized_keys::{AuthorizedKeysFile, ConfigOpts, Entry},
+        PublicKey,
+    };
+
+    let ed25519_key = PublicKey::from_openssh(
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILM+rvN+ot98qgEN796jTiQfZfG1KaT0PtFDJ/XFSqti",
+    )
+    .unwrap();
+    let ecdsa_key = PublicKey::from_openssh("ecdsa-sha2-nistp256 AAAAE2VjZHNhLXNoYTItbmlzdHAyNTYAAAAIbmlzdHAyNTYAAABBBHwf2HMM5TRXvo2SQJjsNkiDD5KqiiNjrGVv3UUh+mMT5RHxiRtOnlqvjhQtBq0VpmpCV/PwUdhOig4vkbqAcEc= user2@example.com").unwrap();
+
+    let file = [ed25519_key.clone(), ecdsa_key.clone()]
+        .into_iter()
+        .collect::<AuthorizedKeysFile>();
+
+    assert_eq!(file.len(), 2);
+    assert_eq!(
+        file.to_string(),
+        format!("{}\n{}\n", ed25519_key, ecdsa_key)
+    );
+
+    let opts = ConfigOpts::new("command=\"/usr/bin/date\"").unwrap();
+    let mut file = [
+        Entry::from(ed25519_key.clone()),
+        Entry::new(opts, ecdsa_key.clone()),
+    ]
+    .into_iter()
+    .collect::<AuthorizedKeysFile>();
+    file.push(ed25519_key.clone());
+
+    let expected = format!(
+        "{}\ncommand=\"/usr/bin/date\" {}\n{}\n",
+        ed25519_key, ecdsa_key, ed25519_key
+    );
+    assert_eq!(file.to_string(), expected);
+
+    // Ensure output round-trips
+    let parsed = expected.parse::<AuthorizedKeysFile>().unwrap();
+    assert_eq!(parsed, file);
+    assert_eq!(parsed.to_string(), expected);
+
+    assert_eq!(AuthorizedKeysFile::new().to_string(), "");
+}
+
+#[test]
+fn certificate_entry_round_trip() {
+    use ssh_key::{authorized_keys::Entry, Certificate};
+
+    let cert_str = include_str!("examples/id_ed25519-cert.pub").trim_end();
+    let cert = Certificate::from_openssh(cert_str).unwrap();
+
+    let line = format!("no-pty,command=\"/usr/bin/date\" {cert_str}");
+    let entry = line.parse::<Entry>().unwrap();
+    assert_eq!(
+        entry.config_opts().iter().collect::<Vec<_>>(),
+        ["no-pty", "command=\"/usr/bin/date\""]
+    );
+    assert_eq!(entry.certificate(), Some(&cert));
+    assert_eq!(entry.public_key().key_data(), cert.public_key());
+    assert_eq!(entry.public_key().comment(), cert.comment());
+    assert_eq!(entry.to_string(), line);
+
+    let entry = Entry::from(cert);
+    assert_eq!(entry.to_string(), cert_str);
+    assert_eq!(cert_str.parse::<Entry>().unwrap(), entry);
+}
+
+#[test]
+fn parse_crlf_line_endings() {
+    use ssh_key::authorized_keys::Entry;
+
+    const KEY: &str =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILM+rvN+ot98qgEN796jTiQfZfG1KaT0PtFDJ/XFSqti";
+    let input = format!("{KEY} user@example.com\r\n{KEY}\r\n");
+
+    let entries = AuthorizedKeys::new(&input)
+        .collect::<ssh_key::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].public_key().comment(), "user@example.com");
+    assert_eq!(entries[1].public_key().comment(), "");
+
+    // Parsing an individual line which retains its `\r`
+    let entry = format!("{KEY} user@example.com\r")
+        .parse::<Entry>()
+        .unwrap();
+    assert_eq!(entry.public_key().comment(), "user@example.com");
+    let entry = format!("{KEY}\r").parse::<Entry>().unwrap();
+    assert_eq!(entry.public_key().comment(), "");
+}
+
+#[test]
+fn fingerprints() {
+    use ssh_key::{authorized_keys, Error, HashAlg};
+
+    let ed25519_key = include_str!("examples/id_ed25519.pub")
+        .rsplit_once(' ')
+        .unwrap()
+        .0;
+    let ecdsa_key = format!(
+        "no-pty {}",
+        include_str!("examples/id_ecdsa_p256.pub").trim_end()
+    );
+    let rsa_key = include_str!("examples/id_rsa_3072.pub").trim_end();
+
+    let input = format!("# Example\n{ed25519_key}\n\n{ecdsa_key}\n{rsa_key}\n");
+    let fingerprints = authorized_keys::fingerprints(input.as_bytes(), HashAlg::Sha256).unwrap();
+    let fingerprints = fingerprints
+        .iter()
+        .map(|(comment, fingerprint)| (comment.as_deref(), fingerprint.to_string()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        fingerprints,
+        [
+            (
+                None,
+                "SHA256:UCUiLr7Pjs9wFFJMDByLgc3NrtdU344OgUM45wZPcIQ".to_string()
+            ),
+            (
+                Some("user@example.com"),
+                "SHA256:JQ6FV0rf7qqJHZqIj4zNH8eV0oB8KLKh9Pph3FTD98g".to_string()
+            ),
+            (
+                Some("user@example.com"),
+                "SHA256:Fmxts/GcV77PakFnf1Ueki5mpU4ZjUQWGRjZGAo3n/I".to_string()
+            ),
+        ]
+    );
+
+    let malformed = format!(
+        "# Example\n{ed25519_key}\n\n{ecdsa_key}\nssh-ed25519 AAAA-not-base64\n{rsa_key}\n"
+    );
+    assert_eq!(
+        authorized_keys::fingerprints(malformed.as_bytes(), HashAlg::Sha256),
+        Err(Error::LineInvalid { number: 5 })
+    );
+}
+
+#[test]
+fn canonicalize() {
+    use ssh_key::{authorized_keys, Error};
+
+    let ed25519_key = include_str!("examples/id_ed25519.pub")
+        .rsplit_once(' ')
+        .unwrap()
+        .0;
+    let ecdsa_key = include_str!("examples/id_ecdsa_p256.pub").trim_end();
+    let rsa_key = include_str!("examples/id_rsa_3072.pub").trim_end();
+
+    let input = format!(
+        "# Example\n\
+         \n\
+         no-pty,no-pty,restrict {ecdsa_key}\n\
+         {ed25519_key}\n\
+         {rsa_key}\n\
+         {ed25519_key} user@example.com\n\
+         no-pty,restrict {ecdsa_key}\n"
+    );
+
+    assert_eq!(
+        authorized_keys::canonicalize(&input).unwrap(),
+        format!(
+            "{rsa_key}\n\
+             {ed25519_key} user@example.com\n\
+             no-pty,restrict {ecdsa_key}\n"
+        )
+    );
+
+    // Options are applied from left to right, so their order is preserved
+    for (options, expected) in [
+        ("pty,restrict", "pty,restrict"),
+        ("restrict,pty", "restrict,pty"),
+        ("pty,restrict,pty", "restrict,pty"),
+        ("no-pty,pty,no-pty", "pty,no-pty"),
+    ] {
+        assert_eq!(
+            authorized_keys::canonicalize(&format!("{options} {rsa_key}")).unwrap(),
+            format!("{expected} {rsa_key}\n")
+        );
+    }
+
+    let conflicting = format!("pty,restrict {ecdsa_key}\n{rsa_key}\nrestrict,pty {ecdsa_key}\n");
+    assert_eq!(
+        authorized_keys::canonicalize(&conflicting),
+        Err(Error::LineInvalid { number: 3 })
+    );
+}
+
+#[test]
+fn parse_options() {
+    use ssh_key::authorized_keys::Options;
+
+    let authorized_keys = AuthorizedKeys::read_file("./tests/examples/authorized_keys").unwrap();
+
+    assert_eq!(authorized_keys[0].options().unwrap(), Options::default());
+    assert_eq!(
+        authorized_keys[1].options().unwrap().command.as_deref(),
+        Some("/usr/bin/date")
+    );
+    assert_eq!(
+        authorized_keys[2].options().unwrap().environment,
+        ["PATH=/bin:/usr/bin"]
+    );
+
+    let options = authorized_keys[3].options().unwrap();
+    assert_eq!(options.from, ["10.0.0.?", "*.example.com"]);
+    assert_eq!(options.x11_forwarding, Some(false));
+    assert!(!options.restrict);
+
+    let options = "no-pty,restrict,port-forwarding,cert-authority,principals=\"alice,bob\",\
+                   permitopen=\"localhost:80\",permitopen=\"localhost:443\",\
+                   expiry-time=\"20350101\",no-touch-required,tunnel=\"0\",x-custom"
+        .parse::<Options>()
+        .unwrap();
+
+    assert!(options.restrict);
+    assert!(options.cert_authority);
+    assert!(options.no_touch_required);
+    assert_eq!(options.pty, None);
+    assert_eq!(options.port_forwarding, Some(true));
+    assert_eq!(options.principals, ["alice", "bob"]);
+    assert_eq!(options.permitopen, ["localhost:80", "localhost:443"]);
+    assert_eq!(options.expiry_time.as_deref(), Some("20350101"));
+    assert_eq!(options.tunnel.as_deref(), Some("0"));
+    assert_eq!(options.other, ["x-custom"]);
+    assert_eq!(
+        options.to_string(),
+        "restrict,cert-authority,port-forwarding,no-touch-required,\
+         expiry-time=\"20350101\",permitopen=\"localhost:80\",permitopen=\"localhost:443\",\
+         tunnel=\"0\",principals=\"alice,bob\",x-custom"
+    );
+
+    // Options which can only be given once
+    assert!("command=\"true\",command=\"false\""
+        .parse::<Options>()
+        .is_err());
+    assert!("from=\"a\",from=\"b\"".parse::<Options>().is_err());
+
+    // Unterminated quoted value
+    assert!("command=\"true\\\"".parse::<Options>().is_err());
+}
+
+#[test]
+fn options_escaping_round_trip() {
+    use ssh_key::authorized_keys::{ConfigOpts, Options};
+
+    for command in ["C:\\Users\\", "echo \"\\\"\"", "a\\nb", "\\"] {
+        let mut options = Options::default();
+        options.command = Some(command.to_string());
+
+        let config_opts = ConfigOpts::try_from(&options).unwrap();
+        assert_eq!(config_opts.options().unwrap(), options);
+    }
+
+    let options = "command=\"a\\\\\",no-pty".parse::<Options>().unwrap();
+    assert_eq!(options.command.as_deref(), Some("a\\"));
+    assert_eq!(options.pty, Some(false));
+
+    // Backslashes which don't escape anything are kept
+    let options = "command=\"a\\nb\"".parse::<Options>().unwrap();
+    assert_eq!(options.command.as_deref(), Some("a\\nb"));
+}
+
+#[test]
+fn parse_hash_in_quoted_option() {
+    use ssh_key::authorized_keys::{self, Entry};
+    use ssh_key::{AuthorizedKeysReader, HashAlg};
+
+    let key = include_str!("examples/id_ed25519.pub").trim_end();
+    let line = format!("command=\"echo #hi\" {key}");
+    let input = format!("# comment\n  # indented comment\n{line}\n");
+
+    let entry = line.parse::<Entry>().unwrap();
+    assert_eq!(
+        entry.options().unwrap().command.as_deref(),
+        Some("echo #hi")
+    );
+
+    // The file parsers agree with the line parser
+    assert_eq!(
+        AuthorizedKeys::new(&input).collect::<Vec<_>>(),
+        [Ok(entry.clone())]
+    );
+    assert_eq!(
+        AuthorizedKeysReader::new(input.as_bytes()).collect::<Vec<_>>(),
+        [Ok(entry)]
+    );
+    assert_eq!(
+        authorized_keys::canonicalize(&input).unwrap(),
+        format!("{line}\n")
+    );
+    assert_eq!(
+        authorized_keys::fingerprints(input.as_bytes(), HashAlg::Sha256)
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn parse_options_with_at_sign() {
+    use ssh_key::authorized_keys::{Entry, EntryRef};
+
+    // Options can also be valid (unknown) algorithm identifiers
+    let key = include_str!("examples/id_ed25519.pub").trim_end();
+    let line = format!("x-custom@example.com {key}");
+
+    let entry = EntryRef::parse(&line).unwrap();
+    assert_eq!(
+        entry.config_opts().collect::<Vec<_>>(),
+        ["x-custom@example.com"]
+    );
+    assert_eq!(entry.key_type(), "ssh-ed25519");
+
+    let entry = line.parse::<Entry>().unwrap();
+    assert_eq!(entry.config_opts().as_str(), "x-custom@example.com");
+    assert_eq!(entry.public_key().to_string(), key);
+}
+
+#[test]
+fn modify_entry_options() {
+    use ssh_key::authorized_keys::{ConfigOpts, Entry};
+
+    let key = include_str!("examples/id_ed25519.pub").trim_end();
+    let line = format!("command=\"echo \\\"hello, world\\\"\",no-pty {key}");
+
+    let mut entry = line.parse::<Entry>().unwrap();
+    assert_eq!(entry.public_key().comment(), "user@example.com");
+
+    let mut options = entry.options().unwrap();
+    assert_eq!(options.command.as_deref(), Some("echo \"hello, world\""));
+    assert_eq!(options.pty, Some(false));
+
+    // Enforce `restrict` on the entry
+    options.restrict = true;
+    options.pty = None;
+    entry.set_config_opts(ConfigOpts::try_from(&options).unwrap());
+
+    assert_eq!(
+        entry.to_string(),
+        format!("restrict,command=\"echo \\\"hello, world\\\"\" {key}")
+    );
+    assert_eq!(entry.to_string().parse::<Entry>().unwrap(), entry);
+}
+
+#[test]
+fn stream_example_file() {
+    use ssh_key::AuthorizedKeysReader;
+    use std::{fs::File, io::BufReader};
+
+    let file = File::open("./tests/examples/authorized_keys").unwrap();
+    let entries = AuthorizedKeysReader::new(BufReader::new(file))
+        .collect::<ssh_key::Result<Vec<_>>>()
+        .unwrap();
+
+    assert_eq!(
+        entries,
+        AuthorizedKeys::read_file("./tests/examples/authorized_keys").unwrap()
+    );
+}
+
+#[test]
+fn stream_invalid_lines() {
+    use ssh_key::{authorized_keys::EntryRef, AuthorizedKeysReader, Error};
+
+    let key = include_str!("examples/id_ed25519.pub").trim_end();
+    let mut input = format!("# comment\n{key}\n\nssh-ed25519 !!!\n").into_bytes();
+    input.extend_from_slice(b"\xFF\n");
+    input.extend_from_slice(format!("no-pty {key}\r\n").as_bytes());
+
+    let mut reader = AuthorizedKeysReader::new(input.as_slice());
+    assert_eq!(
+        reader.next_ref().unwrap().unwrap(),
+        EntryRef::parse(key).unwrap()
+    );
+    assert_eq!(reader.line_number(), 2);
+
+    // Invalid lines are reported without ending the iteration
+    assert_eq!(
+        reader.next().unwrap(),
+        Err(Error::LineInvalid { number: 4 })
+    );
+    assert_eq!(
+        reader.next_ref().unwrap(),
+        Err(Error::LineInvalid { number: 5 })
+    );
+
+    let entry = reader.next_ref().unwrap().unwrap();
+    assert_eq!(entry.config_opts().collect::<Vec<_>>(), ["no-pty"]);
+    assert_eq!(entry.key_type(), "ssh-ed25519");
+    assert_eq!(entry.comment(), "user@example.com");
+    assert_eq!(entry.to_entry().unwrap().public_key().to_string(), key);
+
+    assert!(reader.next().is_none());
+}
@@ -3,7 +3,7 @@
 #![cfg(feature = "alloc")]
 
 use hex_literal::hex;
-use ssh_key::{Algorithm, Certificate};
+use ssh_key::{Algorithm, Certificate, Error};
 use std::str::FromStr;
 
 #[cfg(feature = "ecdsa")]
@@ -19,6 +19,17 @@ const DSA_CERT_EXAMPLE: &str = include_str!("examples/id_dsa_1024-cert.pub");
 #[cfg(feature = "ecdsa")]
 const ECDSA_P256_CERT_EXAMPLE: &str = include_str!("examples/id_ecdsa_p256-cert.pub");
 
+/// ECDSA/P-256 OpenSSH Certificate which never expires, with the maximum
+/// serial number
+#[cfg(feature = "ecdsa")]
+const ECDSA_P256_CERT_FOREVER_EXAMPLE: &str =
+    include_str!("examples/id_ecdsa_p256-cert-forever.pub");
+
+/// ECDSA/P-256 OpenSSH Certificate with a `source-address` critical option
+#[cfg(all(feature = "ecdsa", feature = "std"))]
+const ECDSA_P256_CERT_WITH_SOURCE_ADDRESS_EXAMPLE: &str =
+    include_str!("examples/id_ecdsa_p256-cert-with-source-address.pub");
+
 /// Ed25519 OpenSSH Certificate
 const ED25519_CERT_EXAMPLE: &str = include_str!("examples/id_ed25519-cert.pub");
 
@@ -36,6 +47,11 @@ const ED25519_CERT_WITH_P256_CA_EXAMPLE: &str =
 const ED25519_CERT_WITH_RSA_CA_EXAMPLE: &str =
     include_str!("examples/id_ed25519-cert-with-rsa-ca.pub");
 
+/// RSA (3072-bit) OpenSSH Certificate using the `rsa-sha2-512` certificate
+/// type name, signed by an RSA certificate authority
+#[cfg(feature = "rsa")]
+const RSA_3072_SHA2_512_CERT_EXAMPLE: &str = include_str!("examples/id_rsa_3072-cert-sha2-512.pub");
+
 /// RSA (4096-bit) OpenSSH Certificate
 const RSA_4096_CERT_EXAMPLE: &str = include_str!("examples/id_rsa_4096-cert.pub");
 
@@ -43,6 +59,11 @@ const RSA_4096_CERT_EXAMPLE: &str = include_str!("examples/id_rsa_4096-cert.pub"
 #[cfg(feature = "ecdsa")]
 const SK_ECDSA_P256_CERT_EXAMPLE: &str = include_str!("examples/id_sk_ecdsa_p256-cert.pub");
 
+/// Security Key (FIDO/U2F) ECDSA/NIST P-256 OpenSSH Certificate with principals
+#[cfg(feature = "ecdsa")]
+const SK_ECDSA_P256_CERT_WITH_PRINCIPALS_EXAMPLE: &str =
+    include_str!("examples/id_sk_ecdsa_p256-cert-with-principals.pub");
+
 /// Security Key (FIDO/U2F) Ed25519 OpenSSH Certificate
 const SK_ED25519_CERT_EXAMPLE: &str = include_str!("examples/id_sk_ed25519-cert.pub");
 
@@ -124,6 +145,63 @@ fn decode_ecdsa_p256_openssh() {
     assert_eq!("user@example.com", cert.comment());
 }
 
+#[cfg(feature = "ecdsa")]
+#[test]
+fn decode_ecdsa_p256_forever_openssh() {
+    let cert = Certificate::from_str(ECDSA_P256_CERT_FOREVER_EXAMPLE).unwrap();
+    assert_eq!(cert.serial(), u64::MAX);
+    assert_eq!(cert.valid_after(), 0);
+    assert_eq!(cert.valid_before(), u64::MAX);
+    assert_eq!(
+        cert.to_openssh().unwrap(),
+        ECDSA_P256_CERT_FOREVER_EXAMPLE.trim_end()
+    );
+
+    #[cfg(feature = "ed25519")]
+    {
+        let ca = ssh_key::Fingerprint::from_str(CA_FINGERPRINT).unwrap();
+        assert!(cert.validate_at(VALID_TIMESTAMP, &[ca]).is_ok());
+        assert!(cert.validate_at(EXPIRED_TIMESTAMP, &[ca]).is_ok());
+        assert!(cert.validate_at(u64::MAX, &[ca]).is_err());
+    }
+}
+
+#[cfg(all(feature = "ecdsa", feature = "std"))]
+#[test]
+fn decode_source_address() {
+    use ssh_key::certificate::SourceAddress;
+
+    let cert = Certificate::from_str(ECDSA_P256_CERT_WITH_SOURCE_ADDRESS_EXAMPLE).unwrap();
+    let source_address = cert.source_address().unwrap().unwrap();
+    assert_eq!(source_address.to_string(), "192.0.2.0/24");
+    assert!(source_address.matches("192.0.2.42".parse().unwrap()));
+    assert!(!source_address.matches("192.0.3.1".parse().unwrap()));
+    assert!(!source_address.matches("2001:db8::1".parse().unwrap()));
+
+    let cert = Certificate::from_str(ECDSA_P256_CERT_EXAMPLE).unwrap();
+    assert_eq!(cert.source_address(), Ok(None));
+
+    let source_address = "10.0.0.1,2001:db8::/32".parse::<SourceAddress>().unwrap();
+    assert!(source_address.matches("10.0.0.1".parse().unwrap()));
+    assert!(!source_address.matches("10.0.0.2".parse().unwrap()));
+    assert!(source_address.matches("2001:db8:ffff::1".parse().unwrap()));
+
+    for invalid in [
+        "192.0.2.0/33",
+        "192.0.2.1/24",
+        "192.0.2.0/",
+        "example.com",
+        "",
+    ] {
+        assert_eq!(
+            invalid.parse::<SourceAddress>(),
+            Err(Error::CertificateFieldInvalid(
+                ssh_key::certificate::Field::CriticalOptions
+            ))
+        );
+    }
+}
+
 #[test]
 fn decode_ed25519_openssh() {
     let cert = Certificate::from_str(ED25519_CERT_EXAMPLE).unwrap();
@@ -189,6 +267,28 @@ fn decode_sk_ecdsa_p256_openssh() {
     assert_eq!("user@example.com", cert.comment());
 }
 
+#[cfg(feature = "ecdsa")]
+#[test]
+fn decode_sk_ecdsa_p256_with_principals() {
+    let cert = Certificate::from_str(SK_ECDSA_P256_CERT_WITH_PRINCIPALS_EXAMPLE).unwrap();
+    assert_eq!(
+        Algorithm::SkEcdsaSha2NistP256,
+        cert.public_key().algorithm()
+    );
+    assert_eq!("ssh:", cert.public_key().application().unwrap());
+    assert_eq!(cert.valid_principals(), ["alice", "bob"]);
+    assert_eq!(
+        SK_ECDSA_P256_CERT_WITH_PRINCIPALS_EXAMPLE.trim_end(),
+        &cert.to_openssh().unwrap()
+    );
+
+    #[cfg(feature = "ed25519")]
+    {
+        let ca = CA_FINGERPRINT.parse().unwrap();
+        assert!(cert.validate_at(VALID_TIMESTAMP, &[ca]).is_ok());
+    }
+}
+
 #[test]
 fn decode_sk_ed25519_openssh() {
     let cert = Certificate::from_str(SK_ED25519_CERT_EXAMPLE).unwrap();
@@ -303,6 +403,38 @@ fn reject_certificate_with_future_valid_after() {
     assert!(cert.validate_at(PAST_TIMESTAMP, &[ca]).is_err())
 }
 
+#[cfg(all(feature = "ecdsa", feature = "ed25519", feature = "std"))]
+#[test]
+fn validator_source_address() {
+    use ssh_key::certificate::{ValidationError, Validator};
+
+    let cert = Certificate::from_str(ECDSA_P256_CERT_WITH_SOURCE_ADDRESS_EXAMPLE).unwrap();
+    let mut validator = Validator::new();
+    validator
+        .trust_ca(CA_FINGERPRINT.parse().unwrap())
+        .unix_timestamp(VALID_TIMESTAMP);
+
+    // Without a client address, the critical option can't be enforced
+    assert_eq!(
+        validator.validate(&cert),
+        Err(ValidationError::SourceAddress)
+    );
+    assert_eq!(
+        validator
+            .clone()
+            .client_address("192.0.2.1".parse().unwrap())
+            .validate(&cert),
+        Ok(())
+    );
+    assert_eq!(
+        validator
+            .clone()
+            .client_address("198.51.100.1".parse().unwrap())
+            .validate(&cert),
+        Err(ValidationError::SourceAddress)
+    );
+}
+
 #[cfg(feature = "p256")]
 #[test]
 fn verify_p256_certificate_signature() {
@@ -332,3 +464,73 @@ fn verify_rsa_certificate_signature() {
         cert.signature().algorithm()
     );
 }
+
+#[cfg(feature = "rsa")]
+#[test]
+fn verify_rsa_sha2_512_certificate() {
+    let cert = Certificate::from_str(RSA_3072_SHA2_512_CERT_EXAMPLE).unwrap();
+    let sha512 = Algorithm::Rsa {
+        hash: Some(HashAlg::Sha512),
+    };
+    assert_eq!(sha512, cert.algorithm());
+    assert_eq!(Algorithm::Rsa { hash: None }, cert.public_key().algorithm());
+    assert_eq!(sha512, cert.signature().algorithm());
+    assert!(cert.verify_signature().is_ok());
+    assert_eq!(
+        RSA_3072_SHA2_512_CERT_EXAMPLE.trim_end(),
+        &cert.to_openssh().unwrap()
+    );
+}
+
+#[test]
+fn reject_trailing_data_in_signature() {
+    let cert = Certificate::from_str(ED25519_CERT_EXAMPLE).unwrap();
+    let mut bytes = cert.to_bytes().unwrap();
+
+    // Add a trailing byte inside the length-prefixed signature field
+    let signature_len = Vec::<u8>::try_from(cert.signature().clone()).unwrap().len();
+    let prefix_offset = bytes.len() - signature_len - 4;
+    let new_len = u32::try_from(signature_len + 1).unwrap();
+    bytes[prefix_offset..prefix_offset + 4].copy_from_slice(&new_len.to_be_bytes());
+    bytes.push(0);
+
+    let err = Certificate::from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err, Error::Encoding(_)));
+    assert_eq!(
+        err.to_string(),
+        "unexpected trailing data at end of message (1 bytes)"
+    );
+}
+
+#[test]
+fn reject_trailing_data_after_signature() {
+    let cert = Certificate::from_str(ED25519_CERT_EXAMPLE).unwrap();
+    let mut bytes = cert.to_bytes().unwrap();
+    bytes.push(0);
+
+    let err = Certificate::from_bytes(&bytes).unwrap_err();
+    assert!(matches!(err, Error::Encoding(_)));
+    assert_eq!(
+        err.to_string(),
+        "unexpected trailing data at end of message (1 bytes)"
+    );
+}
+
+#[test]
+fn certificate_fingerprints() {
+    use ssh_key::sha2::{Digest, Sha256};
+
+    let cert = Certificate::from_str(ED25519_CERT_EXAMPLE).unwrap();
+    let key_fingerprint = cert.key_fingerprint(Default::default());
+    let cert_fingerprint = cert.fingerprint(Default::default());
+
+    // Same as the fingerprint of `id_ed25519.pub`
+    assert_eq!(
+        key_fingerprint.to_string(),
+        "SHA256:UCUiLr7Pjs9wFFJMDByLgc3NrtdU344OgUM45wZPcIQ"
+    );
+
+    let digest: [u8; 32] = Sha256::digest(cert.to_bytes().unwrap()).into();
+    assert_eq!(cert_fingerprint.sha256(), Some(digest));
+    assert_ne!(key_fingerprint, cert_fingerprint);
+}
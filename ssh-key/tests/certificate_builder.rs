@@ -13,6 +13,9 @@ use ssh_key::{certificate, Algorithm, PrivateKey};
 #[cfg(feature = "p256")]
 use ssh_key::EcdsaCurve;
 
+#[cfg(all(feature = "ed25519", feature = "p256"))]
+use ssh_key::{Certificate, PublicKey};
+
 #[cfg(all(feature = "ed25519", feature = "rsa"))]
 use std::str::FromStr;
 
@@ -105,8 +108,8 @@ fn ecdsa_nistp256_sign_and_verify() {
     let algorithm = Algorithm::Ecdsa {
         curve: EcdsaCurve::NistP256,
     };
-    let ca_key = PrivateKey::random(&mut rng, algorithm).unwrap();
-    let subject_key = PrivateKey::random(&mut rng, algorithm).unwrap();
+    let ca_key = PrivateKey::random(&mut rng, algorithm.clone()).unwrap();
+    let subject_key = PrivateKey::random(&mut rng, algorithm.clone()).unwrap();
     let mut cert_builder = certificate::Builder::new_with_random_nonce(
         &mut rng,
         subject_key.public_key(),
@@ -126,6 +129,68 @@ fn ecdsa_nistp256_sign_and_verify() {
     assert!(cert.validate_at(VALID_AT, &[ca_fingerprint]).is_ok());
 }
 
+#[cfg(feature = "p256")]
+#[test]
+fn ca_key() {
+    let mut rng = ChaCha8Rng::from_seed(PRNG_SEED);
+
+    let algorithm = Algorithm::Ecdsa {
+        curve: EcdsaCurve::NistP256,
+    };
+    let ca_key = PrivateKey::random(&mut rng, algorithm.clone()).unwrap();
+    let subject_key = PrivateKey::random(&mut rng, algorithm.clone()).unwrap();
+    let mut cert_builder = certificate::Builder::new_with_random_nonce(
+        &mut rng,
+        subject_key.public_key(),
+        ISSUED_AT,
+        EXPIRES_AT,
+    )
+    .unwrap();
+    cert_builder.all_principals_valid().unwrap();
+    let cert = cert_builder.sign(&ca_key).unwrap();
+
+    let cert_ca_key = cert.ca_key();
+    assert_eq!(cert_ca_key.algorithm(), algorithm);
+    assert_eq!(
+        cert_ca_key.fingerprint(Default::default()),
+        ca_key.fingerprint(Default::default())
+    );
+    assert_eq!(cert_ca_key.comment(), "");
+}
+
+#[cfg(all(feature = "ed25519", feature = "p256"))]
+#[test]
+fn sk_sign_and_verify() {
+    let mut rng = ChaCha8Rng::from_seed(PRNG_SEED);
+    let ca_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+    let ca_fingerprint = ca_key.fingerprint(Default::default());
+
+    for subject_key in [
+        include_str!("examples/id_sk_ecdsa_p256.pub"),
+        include_str!("examples/id_sk_ed25519.pub"),
+    ] {
+        let subject_key = PublicKey::from_openssh(subject_key).unwrap();
+        let mut cert_builder = certificate::Builder::new_with_random_nonce(
+            &mut rng,
+            subject_key.clone(),
+            ISSUED_AT,
+            EXPIRES_AT,
+        )
+        .unwrap();
+        cert_builder.valid_principal("nobody").unwrap();
+        let cert = cert_builder.sign(&ca_key).unwrap();
+
+        assert_eq!(cert.algorithm(), subject_key.algorithm());
+        assert!(cert.algorithm().is_sk());
+        assert_eq!(cert.public_key(), subject_key.key_data());
+        assert!(cert.validate_at(VALID_AT, &[ca_fingerprint]).is_ok());
+
+        let encoded = cert.to_openssh().unwrap();
+        assert!(encoded.starts_with(cert.algorithm().as_certificate_str()));
+        assert_eq!(Certificate::from_openssh(&encoded).unwrap(), cert);
+    }
+}
+
 #[cfg(all(feature = "ed25519", feature = "rsa"))]
 #[test]
 fn rsa_sign_and_verify() {
@@ -183,3 +248,465 @@ R6qbyo6hPuCiV9cAAAAAAQID
     let ca_fingerprint = ca_key.fingerprint(Default::default());
     assert!(cert.validate_at(VALID_AT, &[ca_fingerprint]).is_ok());
 }
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn remove_extension_and_resign() {
+    let mut rng = ChaCha8Rng::from_seed(PRNG_SEED);
+    let ca_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+    let subject_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+
+    let mut cert_builder = certificate::Builder::new_with_random_nonce(
+        &mut rng,
+        subject_key.public_key(),
+        ISSUED_AT,
+        EXPIRES_AT,
+    )
+    .unwrap();
+
+    cert_builder.valid_principal("nobody").unwrap();
+    cert_builder.add_extension("permit-pty", "").unwrap();
+    cert_builder
+        .add_extension("permit-X11-forwarding", "")
+        .unwrap();
+    assert!(cert_builder.add_extension("permit-pty", "").is_err());
+    cert_builder
+        .add_critical_option("force-command", "/bin/true")
+        .unwrap();
+    assert!(cert_builder
+        .add_critical_option("unknown-option", "")
+        .is_err());
+
+    cert_builder.remove_extension("permit-pty").unwrap();
+    cert_builder.clear_critical_options().unwrap();
+
+    let cert = cert_builder.sign(&ca_key).unwrap();
+    assert!(cert.extensions().get("permit-pty").is_none());
+    assert!(cert.extensions().get("permit-X11-forwarding").is_some());
+    assert!(cert.critical_options().is_empty());
+
+    let ca_fingerprint = ca_key.fingerprint(Default::default());
+    assert!(cert.validate_at(VALID_AT, &[ca_fingerprint]).is_ok());
+}
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn clear_extensions() {
+    let mut rng = ChaCha8Rng::from_seed(PRNG_SEED);
+    let ca_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+    let subject_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+
+    let mut cert_builder = certificate::Builder::new_with_random_nonce(
+        &mut rng,
+        subject_key.public_key(),
+        ISSUED_AT,
+        EXPIRES_AT,
+    )
+    .unwrap();
+
+    cert_builder.all_principals_valid().unwrap();
+    cert_builder.add_extension("permit-pty", "").unwrap();
+    cert_builder.clear_extensions().unwrap();
+
+    let cert = cert_builder.sign(&ca_key).unwrap();
+    assert!(cert.extensions().is_empty());
+}
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn semantically_eq_ignores_nonce_and_signature() {
+    let mut rng = ChaCha8Rng::from_seed(PRNG_SEED);
+    let ca_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+    let subject_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+
+    let mut issue = |serial, principal| {
+        let mut cert_builder = certificate::Builder::new_with_random_nonce(
+            &mut rng,
+            subject_key.public_key(),
+            ISSUED_AT,
+            EXPIRES_AT,
+        )
+        .unwrap();
+        cert_builder.serial(serial).unwrap();
+        cert_builder.valid_principal(principal).unwrap();
+        cert_builder.extension("permit-pty", "").unwrap();
+        cert_builder.sign(&ca_key).unwrap()
+    };
+
+    let cert1 = issue(1, "nobody");
+    let cert2 = issue(2, "nobody");
+    assert_ne!(cert1.nonce(), cert2.nonce());
+    assert_ne!(cert1, cert2);
+    assert!(cert1.semantically_eq(&cert2));
+
+    let cert3 = issue(1, "somebody");
+    assert!(!cert1.semantically_eq(&cert3));
+}
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn host_principal_wildcards() {
+    let mut rng = ChaCha8Rng::from_seed(PRNG_SEED);
+    let ca_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+    let subject_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+
+    let mut issue = |cert_type| {
+        let mut cert_builder = certificate::Builder::new_with_random_nonce(
+            &mut rng,
+            subject_key.public_key(),
+            ISSUED_AT,
+            EXPIRES_AT,
+        )
+        .unwrap();
+        cert_builder.cert_type(cert_type).unwrap();
+        cert_builder.valid_principal("*.example.com").unwrap();
+        cert_builder.valid_principal("db?.example.net").unwrap();
+        cert_builder.sign(&ca_key).unwrap()
+    };
+
+    let host_cert = issue(certificate::CertType::Host);
+    assert!(host_cert.is_valid_for_principal("web.example.com"));
+    assert!(host_cert.is_valid_for_principal("a.b.example.com"));
+    assert!(host_cert.is_valid_for_principal("db1.example.net"));
+    assert!(!host_cert.is_valid_for_principal("db10.example.net"));
+    assert!(!host_cert.is_valid_for_principal("example.com"));
+    assert!(!host_cert.is_valid_for_principal("example.org"));
+    assert!(!host_cert.is_valid_for_principal("web.example.com.evil"));
+
+    // Wildcards aren't expanded for user certificates
+    let user_cert = issue(certificate::CertType::User);
+    assert!(user_cert.is_valid_for_principal("*.example.com"));
+    assert!(!user_cert.is_valid_for_principal("web.example.com"));
+}
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn revocation_list() {
+    let mut rng = ChaCha8Rng::from_seed(PRNG_SEED);
+    let ca_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+    let subject_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+    let other_subject_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+
+    let mut issue = |subject_key: &PrivateKey, serial| {
+        let mut cert_builder = certificate::Builder::new_with_random_nonce(
+            &mut rng,
+            subject_key.public_key(),
+            ISSUED_AT,
+            EXPIRES_AT,
+        )
+        .unwrap();
+        cert_builder.serial(serial).unwrap();
+        cert_builder.valid_principal("nobody").unwrap();
+        cert_builder.sign(&ca_key).unwrap()
+    };
+
+    let mut revocation_list = certificate::RevocationList::new();
+    revocation_list.revoke_serial(7);
+    revocation_list.revoke_serial_range(100..=199);
+    assert!(revocation_list.is_revoked(&issue(&subject_key, 7)));
+    assert!(revocation_list.is_revoked(&issue(&subject_key, 150)));
+    assert!(!revocation_list.is_revoked(&issue(&subject_key, 8)));
+    assert!(!revocation_list.is_revoked(&issue(&subject_key, 200)));
+
+    // Revocation by key blob
+    let mut revocation_list = certificate::RevocationList::new();
+    revocation_list.revoke_key(subject_key.public_key().key_data().clone());
+    assert!(revocation_list.is_revoked(&issue(&subject_key, 1)));
+    assert!(!revocation_list.is_revoked(&issue(&other_subject_key, 1)));
+
+    // Revocation by key fingerprint
+    let mut revocation_list = certificate::RevocationList::new();
+    revocation_list.revoke_fingerprint(other_subject_key.fingerprint(Default::default()));
+    assert!(!revocation_list.is_revoked(&issue(&subject_key, 1)));
+    assert!(revocation_list.is_revoked(&issue(&other_subject_key, 1)));
+}
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn check_principal() {
+    use certificate::{CertType, PrincipalError};
+
+    let mut rng = ChaCha8Rng::from_seed(PRNG_SEED);
+    let ca_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+    let subject_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+
+    let mut issue = |cert_type, principals: &[&str]| {
+        let mut cert_builder = certificate::Builder::new_with_random_nonce(
+            &mut rng,
+            subject_key.public_key(),
+            ISSUED_AT,
+            EXPIRES_AT,
+        )
+        .unwrap();
+        cert_builder.cert_type(cert_type).unwrap();
+
+        if principals.is_empty() {
+            cert_builder.all_principals_valid().unwrap();
+        }
+
+        for principal in principals {
+            cert_builder.valid_principal(*principal).unwrap();
+        }
+
+        cert_builder.sign(&ca_key).unwrap()
+    };
+
+    let user_cert = issue(CertType::User, &["alice", "bob"]);
+    assert_eq!(user_cert.check_principal("alice"), Ok(()));
+    assert_eq!(
+        user_cert.check_principal("carol"),
+        Err(PrincipalError::Mismatch)
+    );
+
+    let user_cert = issue(CertType::User, &["*"]);
+    assert_eq!(
+        user_cert.check_principal("alice"),
+        Err(PrincipalError::Mismatch)
+    );
+
+    let user_cert = issue(CertType::User, &[]);
+    assert!(!user_cert.is_valid_for_principal("alice"));
+    assert_eq!(
+        user_cert.check_principal("alice"),
+        Err(PrincipalError::Empty)
+    );
+
+    let host_cert = issue(CertType::Host, &["*.example.com"]);
+    assert_eq!(host_cert.check_principal("web.example.com"), Ok(()));
+    assert_eq!(
+        host_cert.check_principal("example.org"),
+        Err(PrincipalError::PatternMismatch)
+    );
+
+    let host_cert = issue(CertType::Host, &[]);
+    assert_eq!(host_cert.check_principal("example.org"), Ok(()));
+}
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn host_cert_with_no_principals() {
+    use certificate::{CertType, Certificate, PrincipalError};
+
+    let mut rng = ChaCha8Rng::from_seed(PRNG_SEED);
+    let ca_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+    let subject_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+
+    let mut new_builder = |cert_type| {
+        let mut cert_builder = certificate::Builder::new_with_random_nonce(
+            &mut rng,
+            subject_key.public_key(),
+            ISSUED_AT,
+            EXPIRES_AT,
+        )
+        .unwrap();
+        cert_builder.cert_type(cert_type).unwrap();
+        cert_builder
+    };
+
+    // An empty principal list must still be requested explicitly
+    assert_eq!(
+        new_builder(CertType::Host).sign(&ca_key),
+        Err(certificate::Field::ValidPrincipals.invalid_error())
+    );
+
+    let mut cert_builder = new_builder(CertType::Host);
+    cert_builder.all_principals_valid().unwrap();
+    let cert = cert_builder.sign(&ca_key).unwrap();
+
+    // The "valid principals" field is encoded as an empty string
+    let cert = Certificate::from_bytes(&cert.to_bytes().unwrap()).unwrap();
+    assert!(cert.valid_principals().is_empty());
+    assert!(cert
+        .validate_at(VALID_AT, [&ca_key.fingerprint(Default::default())])
+        .is_ok());
+
+    for hostname in ["example.com", "web.example.net", "localhost"] {
+        assert_eq!(cert.check_principal(hostname), Ok(()));
+    }
+
+    let mut cert_builder = new_builder(CertType::User);
+    cert_builder.all_principals_valid().unwrap();
+    let cert = cert_builder.sign(&ca_key).unwrap();
+    assert_eq!(cert.check_principal("nobody"), Err(PrincipalError::Empty));
+}
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn verify_chain() {
+    let mut rng = ChaCha8Rng::from_seed(PRNG_SEED);
+    let mut random_key = || PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+    let root_key = random_key();
+    let intermediate_key = random_key();
+    let other_key = random_key();
+    let leaf_key = random_key();
+
+    let issue = |subject_key: &PrivateKey, ca_key: &PrivateKey| {
+        let mut cert_builder =
+            certificate::Builder::new([0; 16], subject_key.public_key(), ISSUED_AT, EXPIRES_AT)
+                .unwrap();
+        cert_builder.all_principals_valid().unwrap();
+        cert_builder.sign(ca_key).unwrap()
+    };
+
+    let root = root_key.fingerprint(Default::default());
+    let intermediate = issue(&intermediate_key, &root_key);
+    let leaf = issue(&leaf_key, &intermediate_key);
+
+    assert!(leaf
+        .verify_chain_at(VALID_AT, std::slice::from_ref(&intermediate), &root)
+        .is_ok());
+    assert!(intermediate.verify_chain_at(VALID_AT, &[], &root).is_ok());
+
+    // Expired links and missing intermediates are rejected
+    for (unix_timestamp, intermediates) in
+        [(EXPIRES_AT, vec![intermediate.clone()]), (VALID_AT, vec![])]
+    {
+        assert!(leaf
+            .verify_chain_at(unix_timestamp, &intermediates, &root)
+            .is_err());
+    }
+
+    // Broken link, i.e. an intermediate which wasn't issued by the root
+    let broken = issue(&intermediate_key, &other_key);
+    assert!(leaf.verify_chain_at(VALID_AT, &[broken], &root).is_err());
+
+    // Circular chain, i.e. two CAs which issued certificates to each other
+    let circular = [
+        issue(&intermediate_key, &other_key),
+        issue(&other_key, &intermediate_key),
+    ];
+    assert!(leaf.verify_chain_at(VALID_AT, &circular, &root).is_err());
+}
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn validator() {
+    use certificate::{CertType, PrincipalError, ValidationError, Validator};
+    use ssh_key::VerificationConfig;
+
+    let mut rng = ChaCha8Rng::from_seed(PRNG_SEED);
+    let ca_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+    let subject_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+
+    let mut issue = |cert_type, critical_option: Option<(&str, &str)>| {
+        let mut cert_builder = certificate::Builder::new_with_random_nonce(
+            &mut rng,
+            subject_key.public_key(),
+            ISSUED_AT,
+            EXPIRES_AT,
+        )
+        .unwrap();
+        cert_builder.cert_type(cert_type).unwrap();
+        cert_builder.valid_principal("alice").unwrap();
+
+        if let Some((name, data)) = critical_option {
+            cert_builder.critical_option(name, data).unwrap();
+        }
+
+        cert_builder.sign(&ca_key).unwrap()
+    };
+
+    let cert = issue(CertType::User, None);
+    let mut validator = Validator::new();
+    assert_eq!(validator.validate(&cert), Err(ValidationError::UntrustedCa));
+
+    validator
+        .trust_ca(ca_key.fingerprint(Default::default()))
+        .cert_type(CertType::User)
+        .principal("alice")
+        .unix_timestamp(VALID_AT);
+    assert_eq!(validator.validate(&cert), Ok(()));
+
+    assert_eq!(
+        validator
+            .clone()
+            .unix_timestamp(ISSUED_AT - 1)
+            .validate(&cert),
+        Err(ValidationError::NotYetValid)
+    );
+    assert_eq!(
+        validator.clone().unix_timestamp(EXPIRES_AT).validate(&cert),
+        Err(ValidationError::Expired)
+    );
+    assert_eq!(
+        validator.clone().principal("bob").validate(&cert),
+        Err(ValidationError::Principal(PrincipalError::Mismatch))
+    );
+    assert_eq!(
+        validator.clone().cert_type(CertType::Host).validate(&cert),
+        Err(ValidationError::CertType(CertType::User))
+    );
+
+    let mut config = VerificationConfig::default();
+    config.allowed_algorithms = Some(vec![Algorithm::Rsa { hash: None }]);
+    assert_eq!(
+        validator
+            .clone()
+            .verification_config(config)
+            .validate(&cert),
+        Err(ValidationError::SignatureAlgorithm(Algorithm::Ed25519))
+    );
+
+    // Critical options recognized by OpenSSH are only allowed in user
+    // certificates
+    let cert = issue(CertType::User, Some(("verify-required", "")));
+    assert_eq!(validator.validate(&cert), Ok(()));
+
+    let cert = issue(CertType::Host, Some(("verify-required", "")));
+    assert_eq!(
+        validator.clone().cert_type(CertType::Host).validate(&cert),
+        Err(ValidationError::UnrecognizedCriticalOption(
+            "verify-required".to_owned()
+        ))
+    );
+
+    let cert = issue(CertType::User, Some(("example@example.com", "")));
+    assert_eq!(
+        validator.validate(&cert),
+        Err(ValidationError::UnrecognizedCriticalOption(
+            "example@example.com".to_owned()
+        ))
+    );
+    assert_eq!(
+        validator
+            .clone()
+            .recognize_critical_option("example@example.com")
+            .validate(&cert),
+        Ok(())
+    );
+}
+
+#[cfg(all(feature = "ed25519", feature = "std"))]
+#[test]
+fn validator_rejects_invalid_source_address() {
+    use certificate::{ValidationError, Validator};
+
+    let mut rng = ChaCha8Rng::from_seed(PRNG_SEED);
+    let ca_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+    let subject_key = PrivateKey::random(&mut rng, Algorithm::Ed25519).unwrap();
+
+    let mut cert_builder = certificate::Builder::new_with_random_nonce(
+        &mut rng,
+        subject_key.public_key(),
+        ISSUED_AT,
+        EXPIRES_AT,
+    )
+    .unwrap();
+    cert_builder.all_principals_valid().unwrap();
+    cert_builder
+        .add_critical_option("source-address", "bogus")
+        .unwrap();
+    let cert = cert_builder.sign(&ca_key).unwrap();
+
+    let mut validator = Validator::new();
+    validator
+        .trust_ca(ca_key.fingerprint(Default::default()))
+        .unix_timestamp(VALID_AT);
+
+    assert_eq!(
+        validator.validate(&cert),
+        Err(ValidationError::InvalidCriticalOption(
+            "source-address".to_owned()
+        ))
+    );
+}
@@ -0,0 +1,78 @@
+//! Tests for decoding and encoding custom types using the re-exported
+//! `ssh-encoding` traits.
+
+#![cfg(feature = "alloc")]
+
+use hex_literal::hex;
+use ssh_key::{
+    encoding::{CheckedSum, Decode, Encode, Reader, Writer},
+    Error, Mpint, Result,
+};
+
+/// Example of a third-party SSH-formatted structure.
+#[derive(Debug, Eq, PartialEq)]
+struct Extension {
+    name: String,
+    value: Mpint,
+    critical: bool,
+}
+
+impl Decode for Extension {
+    type Error = Error;
+
+    fn decode(reader: &mut impl Reader) -> Result<Self> {
+        Ok(Self {
+            name: String::decode(reader)?,
+            value: Mpint::decode(reader)?,
+            critical: bool::decode(reader)?,
+        })
+    }
+}
+
+impl Encode for Extension {
+    type Error = Error;
+
+    fn encoded_len(&self) -> Result<usize> {
+        Ok([
+            self.name.encoded_len()?,
+            self.value.encoded_len()?,
+            self.critical.encoded_len()?,
+        ]
+        .checked_sum()?)
+    }
+
+    fn encode(&self, writer: &mut impl Writer) -> Result<()> {
+        self.name.encode(writer)?;
+        self.value.encode(writer)?;
+        self.critical.encode(writer)?;
+        Ok(())
+    }
+}
+
+/// `string "example"`, `mpint 0x80`, `boolean TRUE`
+const EXTENSION_BYTES: [u8; 18] = hex!("000000076578616d706c65 000000020080 01");
+
+#[test]
+fn decode_custom_struct() {
+    let mut reader = EXTENSION_BYTES.as_slice();
+    let extension = Extension::decode(&mut reader).unwrap();
+    assert!(reader.is_finished());
+
+    assert_eq!(extension.name, "example");
+    assert_eq!(extension.value.as_positive_bytes(), Some([0x80].as_slice()));
+    assert!(extension.critical);
+
+    let mut out = Vec::new();
+    extension.encode(&mut out).unwrap();
+    assert_eq!(extension.encoded_len().unwrap(), out.len());
+    assert_eq!(out, EXTENSION_BYTES);
+}
+
+#[test]
+fn reject_truncated_custom_struct() {
+    let mut reader = &EXTENSION_BYTES[..EXTENSION_BYTES.len() - 1];
+    assert_eq!(
+        Extension::decode(&mut reader),
+        Err(Error::Encoding(ssh_key::encoding::Error::Length))
+    );
+}
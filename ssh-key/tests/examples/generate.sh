@@ -12,3 +12,114 @@ ssh-keygen -t ecdsa -b 521 -f id_ecdsa_p521 -C user@example.com
 ssh-keygen -t ed25519 -f id_ed25519 -C user@example.com
 ssh-keygen -t rsa -b 3072 -f id_rsa_3072 -C user@example.com
 ssh-keygen -t rsa -b 4096 -f id_rsa_4096 -C user@example.com
+
+# `id_ed25519_extra_padding` is `id_ed25519` with its private section padding
+# extended by an extra block (i.e. `1, 2, ..., 13`), as Dropbear may emit
+
+# `id_rsa_3072-cert-sha2-512.pub` is a certificate for `id_rsa_3072.pub` signed
+# by `id_rsa_4096` using the `rsa-sha2-512-cert-v01@openssh.com` type name,
+# which `ssh-keygen` doesn't emit. It was assembled by hand and checked using
+# `ssh-keygen -L -f id_rsa_3072-cert-sha2-512.pub`
+
+# `id_ecdsa_p256.pkcs8.enc` is `id_ecdsa_p256` converted to PKCS#8 and
+# encrypted using PBES2 with the password `hunter42`:
+#   ssh-keygen -p -N "" -m PKCS8 -f id_ecdsa_p256 (on a copy)
+#   openssl pkcs8 -topk8 -v2 aes-256-cbc -v2prf hmacWithSHA256 \
+#     -passout pass:hunter42 -in <copy> -out id_ecdsa_p256.pkcs8.enc
+
+# `id_ed25519.rfc4716.pub` is `id_ed25519.pub` in RFC4716 format
+ssh-keygen -e -f id_ed25519.pub > id_ed25519.rfc4716.pub
+
+# `git_commit_ed25519` is a commit object signed by `id_ed25519` using
+# `git commit -S` with `gpg.format=ssh`, as output by `git cat-file commit`.
+# `allowed_signers` was assembled by hand and checked with `git verify-commit`
+
+# `id_ed25519_mismatched_algorithm` is `id_ecdsa_p256` with its outer public
+# key replaced by the one from `id_ed25519`, i.e. the outer and inner
+# algorithms disagree. It was assembled by hand.
+
+# `id_ecdsa_p256.sec1.pem` is `id_ecdsa_p256` in the traditional SEC1 format,
+# and `id_ecdsa_p256.pkcs8.pem` is the same key as unencrypted PKCS#8
+#   ssh-keygen -p -N "" -m PEM -f id_ecdsa_p256 (on a copy)
+#   openssl pkcs8 -topk8 -nocrypt -in <copy> -out id_ecdsa_p256.pkcs8.pem
+
+# `id_rsa_3072.pkcs1.pem` is `id_rsa_3072` in the traditional PKCS#1 format
+#   ssh-keygen -p -N "" -m PEM -f id_rsa_3072 (on a copy)
+
+# `krl_ed25519` is a KRL revoking certificates issued by `id_ed25519` with
+# serials 1-10, 42, 100, 102 and 1000-1003 or key ID `ed25519`, as well as
+# `id_rsa_3072.pub` explicitly and `id_ecdsa_p256.pub` by SHA-256 hash:
+#   ssh-keygen -k -f krl_ed25519 -s id_ed25519.pub -z 1 <spec>
+#   ssh-keygen -k -u -f krl_ed25519 -z 2 id_rsa_3072.pub
+# where `<spec>` contains the `serial:`, `id:` and `hash:` lines for the above
+
+# `id_ecdsa_p256.spki.pem` and `id_rsa_3072.spki.pem` are the public keys in
+# X.509 `SubjectPublicKeyInfo` format
+ssh-keygen -e -m PKCS8 -f id_ecdsa_p256.pub > id_ecdsa_p256.spki.pem
+ssh-keygen -e -m PKCS8 -f id_rsa_3072.pub > id_rsa_3072.spki.pem
+
+# `sshsig_ed25519_cert` is a signature over `testing` made using
+# `id_ed25519-cert-with-p256-ca.pub`, with `id_ed25519` loaded into ssh-agent:
+#   ssh-keygen -Y sign -f id_ed25519-cert-with-p256-ca.pub -n example <msg>
+
+# `sshsig_ed25519_cert_with_principal` is a signature over `testing` made the
+# same way, using a user certificate for `id_ed25519.pub` with principal
+# `alice@example.com`, signed by `id_ecdsa_p256` (on a copy of the key):
+#   ssh-keygen -s id_ecdsa_p256 -I ed25519-with-principal -n alice@example.com -V 20220101:21000101 -z 1 id_ed25519.pub
+#   ssh-keygen -Y sign -f id_ed25519-cert.pub -n example <msg>
+
+# `id_sk_ecdsa_p256-cert-with-principals.pub` is a user certificate for
+# `id_sk_ecdsa_p256.pub` with principals, signed by `id_ed25519` (on a copy of
+# the public key, so as not to overwrite `id_sk_ecdsa_p256-cert.pub`):
+#   ssh-keygen -s id_ed25519 -I sk-ecdsa-with-principals -n alice,bob \
+#     -V 20220101:21000101 <copy of id_sk_ecdsa_p256.pub>
+
+# `id_ecdsa_p256-cert-with-source-address.pub` is a user certificate for
+# `id_ecdsa_p256.pub` restricted to `192.0.2.0/24`, signed by `id_ed25519` (on
+# a copy of the public key, so as not to overwrite `id_ecdsa_p256-cert.pub`):
+#   ssh-keygen -s id_ed25519 -I test -z 1 -O clear \
+#     -O source-address=192.0.2.0/24 -V 20220101:21000101 \
+#     <copy of id_ecdsa_p256.pub>
+
+# `id_ecdsa_p256.pub` which never expires, with the maximum serial number,
+# signed by `id_ed25519` (on a copy of the public key, as above):
+#   ssh-keygen -s id_ed25519 -I forever -z 18446744073709551615 -O clear \
+#     <copy of id_ecdsa_p256.pub>
+
+# `id_*.aes-ctr.enc` (other than `id_ed25519.aes-ctr.enc`) are the respective
+# keys encrypted with the password `hunter42`:
+#   ssh-keygen -p -P "" -N hunter42 -f <copy of key>
+
+# `id_ed25519.<cipher>.enc` (other than `aes-ctr` and `aes-gcm`) are
+# `id_ed25519` encrypted with the password `hunter42` using the respective
+# cipher, i.e. `aes128-cbc`, `aes256-cbc`, `3des-cbc` and
+# `chacha20-poly1305@openssh.com`:
+#   ssh-keygen -p -P "" -N hunter42 -Z <cipher> -f <copy of id_ed25519>
+
+# `id_rsa_1024.pub` is an undersized RSA key (its private key is not kept), and
+# `sig_rsa_*` are raw PKCS#1 v1.5 signatures over the message
+# `example message`, i.e. `rsa-sha2-256` by it and `ssh-rsa` (SHA-1) by
+# `id_rsa_3072`:
+#   ssh-keygen -t rsa -b 1024 -f id_rsa_1024 -C user@example.com
+#   openssl dgst -sha256 -sign <id_rsa_1024 as PEM> -out sig_rsa_1024_sha256
+#   openssl dgst -sha1 -sign id_rsa_3072.pkcs1.pem -out sig_rsa_3072_sha1
+
+# `sig_rsa_3072_sha512_short` is a raw `rsa-sha2-512` signature by `id_rsa_3072`
+# over `example message 90`, whose first byte is zero, with that byte removed:
+#   openssl dgst -sha512 -sign id_rsa_3072.pkcs1.pem <message> | tail -c +2
+
+# `*.ppk` are PuTTY private keys of the respective keys. The file format
+# version is 3 except for `id_rsa_3072.ppk` and the `v2-aes-cbc` keys.
+#
+# `id_rsa_3072.v2-aes-cbc.ppk` and `id_ed25519.v3-argon2.ppk` were generated
+# by PuTTYgen with the password `123`, and are taken from the RustCrypto
+# `ssh-key` test vectors:
+#   puttygen <key> -O private --ppk-param version=2 -P -o id_rsa_3072.v2-aes-cbc.ppk
+#   puttygen <key> -O private -P -o id_ed25519.v3-argon2.ppk
+#
+# The others were written following the PPK format specification (since PuTTY
+# is unavailable) to match the output of the `puttygen` commands below, with
+# `id_ed25519.v2-aes-cbc.ppk` using the password `hunter42`:
+#   puttygen <key> -O private -o id_ed25519.ppk
+#   puttygen <key> -O private --ppk-param version=2 -o id_rsa_3072.ppk
+#   puttygen <key> -O private --ppk-param version=2 -P -o id_ed25519.v2-aes-cbc.ppk
@@ -0,0 +1,81 @@
+//! FIPS policy tests.
+
+#![cfg(feature = "alloc")]
+
+use ssh_key::{Algorithm, EcdsaCurve, FipsPolicy, HashAlg, PublicKey};
+
+/// DSA OpenSSH-formatted public key.
+const DSA_PUBLIC_KEY: &str = include_str!("examples/id_dsa_1024.pub");
+
+/// RSA (1024-bit) OpenSSH-formatted public key.
+const RSA_1024_PUBLIC_KEY: &str = include_str!("examples/id_rsa_1024.pub");
+
+/// RSA (3072-bit) OpenSSH-formatted public key.
+const RSA_3072_PUBLIC_KEY: &str = include_str!("examples/id_rsa_3072.pub");
+
+/// Ed25519 OpenSSH-formatted public key.
+const ED25519_PUBLIC_KEY: &str = include_str!("examples/id_ed25519.pub");
+
+#[cfg(all(feature = "rand_core", feature = "ed25519", feature = "p256"))]
+#[test]
+fn random_private_key() {
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+
+    let mut rng = ChaCha8Rng::from_seed([1; 32]);
+    let policy = FipsPolicy::default();
+
+    assert_eq!(
+        policy
+            .random_private_key(&mut rng, Algorithm::Ed25519)
+            .err(),
+        Some(ssh_key::Error::AlgorithmUnsupported {
+            algorithm: Algorithm::Ed25519
+        })
+    );
+
+    let algorithm = Algorithm::Ecdsa {
+        curve: EcdsaCurve::NistP256,
+    };
+    let key = policy
+        .random_private_key(&mut rng, algorithm.clone())
+        .unwrap();
+    assert_eq!(key.algorithm(), algorithm);
+
+    let mut policy = FipsPolicy::default();
+    policy.allow_ed25519 = true;
+    assert!(policy
+        .random_private_key(&mut rng, Algorithm::Ed25519)
+        .is_ok());
+}
+
+#[test]
+fn check_key() {
+    let policy = FipsPolicy::default();
+    let check = |key: &str| policy.check_key(PublicKey::from_openssh(key).unwrap().key_data());
+
+    assert!(check(RSA_3072_PUBLIC_KEY).is_ok());
+    assert!(check(RSA_1024_PUBLIC_KEY).is_err());
+    assert!(check(DSA_PUBLIC_KEY).is_err());
+    assert!(check(ED25519_PUBLIC_KEY).is_err());
+}
+
+#[test]
+fn verification_config() {
+    let config = FipsPolicy::default().verification_config();
+    assert_eq!(config.min_rsa_bits, FipsPolicy::MIN_RSA_BITS);
+
+    for curve in [
+        EcdsaCurve::NistP256,
+        EcdsaCurve::NistP384,
+        EcdsaCurve::NistP521,
+    ] {
+        assert!(config.allows(Algorithm::Ecdsa { curve }));
+    }
+
+    assert!(config.allows(Algorithm::Rsa {
+        hash: Some(HashAlg::Sha512)
+    }));
+    assert!(!config.allows(Algorithm::Rsa { hash: None }));
+    assert!(!config.allows(Algorithm::Ed25519));
+    assert!(!config.allows(Algorithm::SkEcdsaSha2NistP256));
+}
@@ -0,0 +1,84 @@
+//! Key format detection tests.
+
+use ssh_key::{detect_format, KeyFormat};
+
+#[test]
+fn detect_openssh_private_key() {
+    assert_eq!(
+        detect_format(include_str!("examples/id_ed25519")),
+        KeyFormat::OpensshPrivateKey
+    );
+    assert_eq!(
+        detect_format(include_str!("examples/id_ed25519.aes-ctr.enc")),
+        KeyFormat::OpensshPrivateKey
+    );
+}
+
+#[test]
+fn detect_pem_documents() {
+    assert_eq!(
+        detect_format(include_str!("examples/id_rsa_3072.pkcs1.pem")),
+        KeyFormat::Pkcs1PrivateKey
+    );
+    assert_eq!(
+        detect_format(include_str!("examples/id_ecdsa_p256.sec1.pem")),
+        KeyFormat::Sec1PrivateKey
+    );
+    assert_eq!(
+        detect_format(include_str!("examples/id_ecdsa_p256.pkcs8.pem")),
+        KeyFormat::Pkcs8PrivateKey
+    );
+    assert_eq!(
+        detect_format(include_str!("examples/id_ecdsa_p256.pkcs8.enc")),
+        KeyFormat::Pkcs8EncryptedPrivateKey
+    );
+    assert_eq!(
+        detect_format(include_str!("examples/id_rsa_3072.spki.pem")),
+        KeyFormat::SpkiPublicKey
+    );
+    assert_eq!(
+        detect_format(include_str!("examples/sshsig_ed25519")),
+        KeyFormat::SshSig
+    );
+}
+
+#[test]
+fn detect_public_keys() {
+    assert_eq!(
+        detect_format(include_str!("examples/id_ed25519.pub")),
+        KeyFormat::OpensshPublicKey
+    );
+    assert_eq!(
+        detect_format(include_str!("examples/id_ecdsa_p256-cert.pub")),
+        KeyFormat::OpensshCertificate
+    );
+    assert_eq!(
+        detect_format(include_str!("examples/id_ed25519.rfc4716.pub")),
+        KeyFormat::Rfc4716PublicKey
+    );
+
+    // Pasted with surrounding whitespace and a byte order mark
+    let pasted = ["\u{feff}\n  ", include_str!("examples/id_ed25519.pub")].concat();
+    assert_eq!(detect_format(&pasted), KeyFormat::OpensshPublicKey);
+}
+
+#[test]
+fn detect_putty_private_key() {
+    assert_eq!(
+        detect_format("PuTTY-User-Key-File-3: ssh-ed25519\nEncryption: none\n"),
+        KeyFormat::PuttyPrivateKey
+    );
+}
+
+#[test]
+fn detect_unknown() {
+    for input in [
+        "",
+        "garbage",
+        "ssh-ed25519",
+        "-----BEGIN CERTIFICATE-----\n",
+        "user@example.com ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILM+rvN+ot98qgEN796jTiQfZfG1KaT0PtFDJ/XFSqti",
+    ] {
+        assert_eq!(detect_format(input), KeyFormat::Unknown, "{input:?}");
+    }
+}
@@ -0,0 +1,103 @@
+//! Key and certificate inventory tests.
+
+#![cfg(all(feature = "alloc", feature = "ecdsa"))]
+
+use ssh_key::{Algorithm, Identity};
+
+/// Mix of keys and a certificate, as in e.g. a concatenation of `.pub` files.
+const INVENTORY_EXAMPLE: &str = concat!(
+    "# Fleet keys\n",
+    include_str!("examples/id_ed25519.pub"),
+    "\n",
+    include_str!("examples/id_rsa_3072.pub"),
+    include_str!("examples/id_sk_ecdsa_p256-cert-with-principals.pub"),
+);
+
+#[test]
+fn parse_keys_and_certificate() {
+    let identities = Identity::parse(INVENTORY_EXAMPLE).unwrap();
+    assert_eq!(identities.len(), 3);
+
+    for (identity, algorithm, fingerprint) in [
+        (
+            &identities[0],
+            Algorithm::Ed25519,
+            "SHA256:UCUiLr7Pjs9wFFJMDByLgc3NrtdU344OgUM45wZPcIQ",
+        ),
+        (
+            &identities[1],
+            Algorithm::Rsa { hash: None },
+            "SHA256:Fmxts/GcV77PakFnf1Ueki5mpU4ZjUQWGRjZGAo3n/I",
+        ),
+    ] {
+        assert_eq!(identity.public_key().algorithm(), algorithm);
+        assert_eq!(identity.comment(), "user@example.com");
+        assert_eq!(
+            identity.fingerprint(Default::default()).to_string(),
+            fingerprint
+        );
+        assert!(identity.certificate().is_none());
+        assert_eq!(identity.key_id(), None);
+        assert!(identity.valid_principals().is_empty());
+        assert_eq!(identity.validity(), None);
+    }
+
+    let cert = &identities[2];
+    assert!(cert.certificate().is_some());
+    assert_eq!(cert.comment(), "user@example.com");
+    assert_eq!(
+        cert.fingerprint(Default::default()).to_string(),
+        "SHA256:UINe2WXFh3SiqwLxsBv34fBO2ei+g7uOeJJXVEK95iE"
+    );
+    assert_eq!(cert.key_id(), Some("sk-ecdsa-with-principals"));
+    assert_eq!(cert.valid_principals(), ["alice", "bob"]);
+
+    // 2022-01-01T00:00:00Z to 2100-01-01T00:00:00Z
+    assert_eq!(cert.validity(), Some(1640995200..4102444800));
+}
+
+#[test]
+fn display_name() {
+    let identities = Identity::parse(INVENTORY_EXAMPLE).unwrap();
+    assert_eq!(identities[0].display_name(), "user@example.com");
+    assert_eq!(identities[2].display_name(), "sk-ecdsa-with-principals");
+
+    // Certificates are written with their comment, not their key ID
+    let cert = identities[2].certificate().unwrap();
+    assert!(cert.to_openssh().unwrap().ends_with(" user@example.com"));
+}
+
+#[test]
+fn parse_rfc4716() {
+    let identities = Identity::parse(include_str!("examples/id_ed25519.rfc4716.pub")).unwrap();
+    assert_eq!(identities.len(), 1);
+    assert_eq!(
+        identities[0].fingerprint(Default::default()).to_string(),
+        "SHA256:UCUiLr7Pjs9wFFJMDByLgc3NrtdU344OgUM45wZPcIQ"
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn read_dir() {
+    let identities = Identity::read_dir("tests/examples").unwrap();
+
+    // Every `.pub` file holds exactly one key or certificate
+    assert!(identities
+        .iter()
+        .all(|(path, _)| path.extension().unwrap() == "pub"));
+    assert!(identities.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+    let (_, cert) = identities
+        .iter()
+        .find(|(path, _)| path.ends_with("id_sk_ecdsa_p256-cert-with-principals.pub"))
+        .unwrap();
+    assert_eq!(cert.key_id(), Some("sk-ecdsa-with-principals"));
+
+    let (_, key) = identities
+        .iter()
+        .find(|(path, _)| path.ends_with("id_ed25519.pub"))
+        .unwrap();
+    assert_eq!(key.comment(), "user@example.com");
+    assert!(key.certificate().is_none());
+}
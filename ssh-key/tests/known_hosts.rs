@@ -61,3 +61,89 @@ fn read_example_file() {
         "authority@example.com"
     );
 }
+
+#[test]
+fn match_host_patterns() {
+    let patterns: HostPatterns =
+        "cvs.example.net,!test.example.???,*.example.???,[*.example.net]:999"
+            .parse()
+            .unwrap();
+
+    assert!(patterns.matches("cvs.example.net", 22));
+    assert!(patterns.matches("CVS.Example.NET", 22));
+    assert!(patterns.matches("www.example.org", 22));
+    assert!(!patterns.matches("test.example.org", 22));
+    assert!(!patterns.matches("cvs.example.net", 2222));
+    assert!(patterns.matches("cvs.example.net", 999));
+    assert!(!patterns.matches("example.com", 22));
+}
+
+#[cfg(feature = "hashed-hosts")]
+#[test]
+fn match_hashed_host() {
+    use ssh_key::known_hosts::Entry;
+
+    // Generated with `ssh-keygen -H`
+    let entry: Entry = "|1|0ERdzQeIxXtF2FfbM0zef6reP3U=|gBT2TfYReTDkAsMAkWoP7bQfIwM= ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILM+rvN+ot98qgEN796jTiQfZfG1KaT0PtFDJ/XFSqti user@example.com"
+        .parse()
+        .unwrap();
+
+    assert!(entry.matches("example.com", 2222));
+    assert!(entry.matches("Example.com", 2222));
+    assert!(!entry.matches("example.com", 22));
+    assert!(!entry.matches("example.org", 2222));
+
+    let salt = match entry.host_patterns() {
+        HostPatterns::HashedName { salt, .. } => salt.clone(),
+        other => panic!("unexpected host patterns: {other:?}"),
+    };
+    assert_eq!(
+        &HostPatterns::hash_with_salt("example.com", 2222, salt),
+        entry.host_patterns()
+    );
+}
+
+#[cfg(all(feature = "hashed-hosts", feature = "rand_core"))]
+#[test]
+fn hash_host() {
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use ssh_key::known_hosts::Entry;
+
+    let mut rng = ChaCha8Rng::from_seed([1; 32]);
+    let host_patterns = HostPatterns::hash(&mut rng, "example.com", 22);
+    assert!(host_patterns.matches("example.com", 22));
+    assert!(!host_patterns.matches("example.com", 2222));
+    assert_ne!(
+        HostPatterns::hash(&mut rng, "example.com", 22),
+        host_patterns
+    );
+
+    let public_key =
+        "ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAILM+rvN+ot98qgEN796jTiQfZfG1KaT0PtFDJ/XFSqti"
+            .parse()
+            .unwrap();
+    let entry = Entry::new(None, host_patterns, public_key);
+    assert_eq!(entry.to_string().parse::<Entry>().unwrap(), entry);
+}
+
+#[test]
+fn stream_example_file() {
+    use ssh_key::KnownHostsReader;
+    use std::{fs::File, io::BufReader};
+
+    let file = File::open("./tests/examples/known_hosts").unwrap();
+    let mut reader = KnownHostsReader::new(BufReader::new(file));
+
+    let entry = reader.next_ref().unwrap().unwrap();
+    assert_eq!(entry.marker(), None);
+    assert_eq!(entry.host_patterns(), "test.example.com");
+    assert_eq!(entry.key_type(), "ssh-ed25519");
+    assert_eq!(entry.comment(), "");
+
+    let entries = reader.collect::<ssh_key::Result<Vec<_>>>().unwrap();
+    assert_eq!(
+        entries,
+        KnownHosts::read_file("./tests/examples/known_hosts").unwrap()[1..]
+    );
+    assert_eq!(entries[1].marker(), Some(&Marker::Revoked));
+}
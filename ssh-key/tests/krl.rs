@@ -0,0 +1,208 @@
+//! OpenSSH Key Revocation List (KRL) tests.
+
+#![cfg(all(feature = "alloc", feature = "ecdsa"))]
+
+use ssh_key::{Certificate, Error, HashAlg, Krl, KrlBuilder, PublicKey};
+use std::str::FromStr;
+
+/// KRL for certificates issued by `id_ed25519`, generated by `ssh-keygen -k`.
+const KRL_EXAMPLE: &[u8] = include_bytes!("examples/krl_ed25519");
+
+/// KRL revoking the SHA-1 hash of `id_ecdsa_p256`, generated by `ssh-keygen -k`.
+const KRL_SHA1_EXAMPLE: &[u8] = include_bytes!("examples/krl_sha1");
+
+/// Ed25519 OpenSSH Certificate, with key ID `ed25519` and serial 0.
+const ED25519_CERT_EXAMPLE: &str = include_str!("examples/id_ed25519-cert.pub");
+
+#[test]
+fn decode_krl() {
+    let krl = Krl::from_bytes(KRL_EXAMPLE).unwrap();
+    assert_eq!(krl.version(), 2);
+    assert_eq!(krl.comment(), "");
+}
+
+#[test]
+fn query_serial() {
+    let krl = Krl::from_bytes(KRL_EXAMPLE).unwrap();
+    let ca_key = PublicKey::from_openssh(include_str!("examples/id_ed25519.pub")).unwrap();
+    let other_ca_key = PublicKey::from_openssh(include_str!("examples/id_rsa_4096.pub")).unwrap();
+
+    for serial in [1, 5, 10, 42, 100, 102, 1000, 1003] {
+        assert!(krl.is_serial_revoked(ca_key.key_data(), serial));
+        assert!(!krl.is_serial_revoked(other_ca_key.key_data(), serial));
+    }
+
+    for serial in [0, 11, 41, 101, 999, 1004, u64::MAX] {
+        assert!(!krl.is_serial_revoked(ca_key.key_data(), serial));
+    }
+}
+
+#[test]
+fn query_key() {
+    let krl = Krl::from_bytes(KRL_EXAMPLE).unwrap();
+
+    // Revoked explicitly
+    let rsa_key = PublicKey::from_openssh(include_str!("examples/id_rsa_3072.pub")).unwrap();
+    assert!(krl.is_key_revoked(rsa_key.key_data()));
+
+    // Revoked by SHA-256 hash
+    let ecdsa_key = PublicKey::from_openssh(include_str!("examples/id_ecdsa_p256.pub")).unwrap();
+    assert!(krl.is_key_revoked(ecdsa_key.key_data()));
+
+    let ed25519_key = PublicKey::from_openssh(include_str!("examples/id_ed25519.pub")).unwrap();
+    assert!(!krl.is_key_revoked(ed25519_key.key_data()));
+}
+
+#[test]
+fn query_key_sha1() {
+    let krl = Krl::from_bytes(KRL_SHA1_EXAMPLE).unwrap();
+
+    let ecdsa_key = PublicKey::from_openssh(include_str!("examples/id_ecdsa_p256.pub")).unwrap();
+    assert!(krl.is_key_revoked(ecdsa_key.key_data()));
+
+    let ed25519_key = PublicKey::from_openssh(include_str!("examples/id_ed25519.pub")).unwrap();
+    assert!(!krl.is_key_revoked(ed25519_key.key_data()));
+}
+
+#[test]
+fn query_certificate() {
+    let krl = Krl::from_bytes(KRL_EXAMPLE).unwrap();
+    let cert = Certificate::from_str(ED25519_CERT_EXAMPLE).unwrap();
+
+    // Serial 0 can't be revoked, but the key ID is
+    assert!(!krl.is_serial_revoked(cert.signature_key(), cert.serial()));
+    assert!(krl.is_key_id_revoked(cert.signature_key(), cert.key_id()));
+    assert!(krl.is_revoked_cert(&cert));
+}
+
+#[test]
+fn query_public_key() {
+    let krl = Krl::from_bytes(KRL_EXAMPLE).unwrap();
+    let rsa_key = PublicKey::from_openssh(include_str!("examples/id_rsa_3072.pub")).unwrap();
+    let ed25519_key = PublicKey::from_openssh(include_str!("examples/id_ed25519.pub")).unwrap();
+    assert!(krl.is_revoked(&rsa_key));
+    assert!(!krl.is_revoked(&ed25519_key));
+}
+
+#[test]
+fn encode_krl() {
+    let krl = Krl::from_bytes(KRL_EXAMPLE).unwrap();
+    assert_eq!(Krl::from_bytes(&krl.to_bytes().unwrap()).unwrap(), krl);
+}
+
+#[test]
+fn build_krl() {
+    let ca_key = PublicKey::from_openssh(include_str!("examples/id_ed25519.pub")).unwrap();
+    let rsa_key = PublicKey::from_openssh(include_str!("examples/id_rsa_3072.pub")).unwrap();
+    let ecdsa_key = PublicKey::from_openssh(include_str!("examples/id_ecdsa_p256.pub")).unwrap();
+    let cert = Certificate::from_str(ED25519_CERT_EXAMPLE).unwrap();
+
+    let mut builder = KrlBuilder::new(3, 1700000000);
+    builder
+        .comment("example")
+        .unwrap()
+        .revoke_key(rsa_key.key_data().clone())
+        .unwrap()
+        .revoke_fingerprint(ecdsa_key.fingerprint(HashAlg::Sha256))
+        .unwrap()
+        .revoke_serial(Some(ca_key.key_data()), 42)
+        .unwrap()
+        .revoke_serials(Some(ca_key.key_data()), 100..=199)
+        .unwrap()
+        .revoke_key_id(None, cert.key_id())
+        .unwrap();
+
+    assert!(builder.revoke_serial(None, 0).is_err());
+    assert!(builder
+        .revoke_fingerprint(ecdsa_key.fingerprint(HashAlg::Sha512))
+        .is_err());
+
+    let krl = Krl::from_bytes(&builder.build().to_bytes().unwrap()).unwrap();
+    assert_eq!(krl.version(), 3);
+    assert_eq!(krl.generated_date(), 1700000000);
+    assert_eq!(krl.comment(), "example");
+    assert!(krl.is_revoked(&rsa_key));
+    assert!(krl.is_revoked(&ecdsa_key));
+    assert!(!krl.is_revoked(&ca_key));
+
+    for serial in [42, 100, 150, 199] {
+        assert!(krl.is_serial_revoked(ca_key.key_data(), serial));
+        assert!(!krl.is_serial_revoked(rsa_key.key_data(), serial));
+    }
+
+    for serial in [41, 43, 99, 200] {
+        assert!(!krl.is_serial_revoked(ca_key.key_data(), serial));
+    }
+
+    // Key IDs revoked for any CA
+    assert!(krl.is_key_id_revoked(rsa_key.key_data(), cert.key_id()));
+    assert!(krl.is_revoked_cert(&cert));
+}
+
+/// Signature sections aren't enclosed in a section string, as described in
+/// `PROTOCOL.krl`.
+#[test]
+fn decode_signed_krl() {
+    use ssh_key::encoding::Encode;
+
+    let ca_key = PublicKey::from_openssh(include_str!("examples/id_ed25519.pub")).unwrap();
+    let mut signed = KRL_EXAMPLE.to_vec();
+    4u8.encode(&mut signed).unwrap();
+    ca_key.key_data().encode_prefixed(&mut signed).unwrap();
+    [0x42u8; 83].encode(&mut signed).unwrap();
+
+    assert_eq!(
+        Krl::from_bytes(&signed).unwrap(),
+        Krl::from_bytes(KRL_EXAMPLE).unwrap()
+    );
+}
+
+/// Serial number 0 is ignored in serial sections, as in OpenSSH.
+#[test]
+fn ignore_serial_zero() {
+    use ssh_key::encoding::Encode;
+
+    let mut serials = Vec::new();
+    0u64.encode(&mut serials).unwrap();
+    5u64.encode(&mut serials).unwrap();
+
+    let mut range = Vec::new();
+    0u64.encode(&mut range).unwrap();
+    2u64.encode(&mut range).unwrap();
+
+    let mut section = Vec::new();
+    [0u8; 0].encode(&mut section).unwrap(); // any CA
+    [0u8; 0].encode(&mut section).unwrap(); // reserved
+    0x20u8.encode(&mut section).unwrap();
+    serials.encode(&mut section).unwrap();
+    0x21u8.encode(&mut section).unwrap();
+    range.encode(&mut section).unwrap();
+
+    let mut bytes = b"SSHKRL\n\0".to_vec();
+    1u32.encode(&mut bytes).unwrap(); // format version
+    1u64.encode(&mut bytes).unwrap(); // KRL version
+    0u64.encode(&mut bytes).unwrap(); // generated date
+    0u64.encode(&mut bytes).unwrap(); // flags
+    [0u8; 0].encode(&mut bytes).unwrap(); // reserved
+    "".encode(&mut bytes).unwrap(); // comment
+    1u8.encode(&mut bytes).unwrap();
+    section.encode(&mut bytes).unwrap();
+
+    let krl = Krl::from_bytes(&bytes).unwrap();
+    let ca_key = PublicKey::from_openssh(include_str!("examples/id_ed25519.pub")).unwrap();
+
+    assert!(!krl.is_serial_revoked(ca_key.key_data(), 0));
+
+    for serial in [1, 2, 5] {
+        assert!(krl.is_serial_revoked(ca_key.key_data(), serial));
+    }
+
+    assert!(!krl.is_serial_revoked(ca_key.key_data(), 3));
+}
+
+#[test]
+fn reject_invalid_magic() {
+    let mut krl = KRL_EXAMPLE.to_vec();
+    krl[0] ^= 1;
+    assert_eq!(Krl::from_bytes(&krl), Err(Error::FormatEncoding));
+}
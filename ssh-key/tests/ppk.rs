@@ -0,0 +1,187 @@
+//! PuTTY private key (`.ppk`) tests.
+
+#![cfg(feature = "ppk")]
+
+use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+use ssh_key::{Error, LineEnding, PpkVersion, PrivateKey};
+
+/// Unencrypted Ed25519 OpenSSH-formatted private key.
+const OPENSSH_ED25519_EXAMPLE: &str = include_str!("examples/id_ed25519");
+
+/// Unencrypted RSA (3072-bit) OpenSSH-formatted private key.
+const OPENSSH_RSA_3072_EXAMPLE: &str = include_str!("examples/id_rsa_3072");
+
+/// Unencrypted ECDSA/P-256 OpenSSH-formatted private key.
+#[cfg(feature = "ecdsa")]
+const OPENSSH_ECDSA_P256_EXAMPLE: &str = include_str!("examples/id_ecdsa_p256");
+
+/// Unencrypted version 3 PuTTY Ed25519 private key.
+const PPK_ED25519_EXAMPLE: &str = include_str!("examples/id_ed25519.ppk");
+
+/// Unencrypted version 2 PuTTY RSA (3072-bit) private key.
+const PPK_RSA_3072_EXAMPLE: &str = include_str!("examples/id_rsa_3072.ppk");
+
+/// Unencrypted version 3 PuTTY ECDSA/P-256 private key.
+#[cfg(feature = "ecdsa")]
+const PPK_ECDSA_P256_EXAMPLE: &str = include_str!("examples/id_ecdsa_p256.ppk");
+
+/// `aes256-cbc` encrypted version 2 PuTTY Ed25519 private key.
+const PPK_V2_AES_CBC_ED25519_EXAMPLE: &str = include_str!("examples/id_ed25519.v2-aes-cbc.ppk");
+
+/// `aes256-cbc` encrypted version 2 PuTTY RSA (3072-bit) private key,
+/// generated by PuTTYgen.
+const PPK_V2_AES_CBC_RSA_3072_EXAMPLE: &str = include_str!("examples/id_rsa_3072.v2-aes-cbc.ppk");
+
+/// Argon2id encrypted version 3 PuTTY Ed25519 private key, generated by
+/// PuTTYgen.
+const PPK_V3_ARGON2_ED25519_EXAMPLE: &str = include_str!("examples/id_ed25519.v3-argon2.ppk");
+
+/// Password used by the encrypted examples.
+const PASSWORD: &[u8] = b"hunter42";
+
+/// Password used by the encrypted examples generated by PuTTYgen.
+const PUTTYGEN_PASSWORD: &[u8] = b"123";
+
+/// Decode the given PuTTY key, check it matches the given OpenSSH key, and
+/// check it re-encodes identically.
+fn check_ppk(ppk: &str, password: &[u8], openssh: &str, version: PpkVersion) {
+    let key = PrivateKey::from_ppk(ppk, password).unwrap();
+    let expected = PrivateKey::from_openssh(openssh).unwrap();
+    assert_eq!(key.key_data(), expected.key_data());
+    assert_eq!(key.comment(), expected.comment());
+
+    let encoded = expected.to_ppk(version, password, LineEnding::LF).unwrap();
+    assert_eq!(&*encoded, ppk);
+}
+
+#[test]
+fn ed25519_v3() {
+    check_ppk(
+        PPK_ED25519_EXAMPLE,
+        b"",
+        OPENSSH_ED25519_EXAMPLE,
+        PpkVersion::V3,
+    );
+}
+
+#[test]
+fn rsa_3072_v2() {
+    check_ppk(
+        PPK_RSA_3072_EXAMPLE,
+        b"",
+        OPENSSH_RSA_3072_EXAMPLE,
+        PpkVersion::V2,
+    );
+}
+
+#[cfg(feature = "ecdsa")]
+#[test]
+fn ecdsa_p256_v3() {
+    check_ppk(
+        PPK_ECDSA_P256_EXAMPLE,
+        b"",
+        OPENSSH_ECDSA_P256_EXAMPLE,
+        PpkVersion::V3,
+    );
+}
+
+#[test]
+fn ed25519_v2_aes_cbc() {
+    check_ppk(
+        PPK_V2_AES_CBC_ED25519_EXAMPLE,
+        PASSWORD,
+        OPENSSH_ED25519_EXAMPLE,
+        PpkVersion::V2,
+    );
+
+    assert_eq!(
+        PrivateKey::from_ppk(PPK_V2_AES_CBC_ED25519_EXAMPLE, "hunter43").err(),
+        Some(Error::WrongPassword)
+    );
+}
+
+#[test]
+fn rsa_3072_v2_aes_cbc() {
+    check_ppk(
+        PPK_V2_AES_CBC_RSA_3072_EXAMPLE,
+        PUTTYGEN_PASSWORD,
+        OPENSSH_RSA_3072_EXAMPLE,
+        PpkVersion::V2,
+    );
+}
+
+#[test]
+fn ed25519_v3_argon2() {
+    let key = PrivateKey::from_ppk(PPK_V3_ARGON2_ED25519_EXAMPLE, PUTTYGEN_PASSWORD).unwrap();
+    let expected = PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    assert_eq!(key.key_data(), expected.key_data());
+    assert_eq!(key.comment(), expected.comment());
+
+    assert_eq!(
+        PrivateKey::from_ppk(PPK_V3_ARGON2_ED25519_EXAMPLE, "124").err(),
+        Some(Error::WrongPassword)
+    );
+}
+
+#[test]
+fn ed25519_v3_argon2_round_trip() {
+    let key = PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let mut rng = ChaCha8Rng::from_seed([42; 32]);
+    let ppk = key
+        .to_ppk_with_rng(&mut rng, PpkVersion::V3, PASSWORD, LineEnding::LF)
+        .unwrap();
+
+    assert!(ppk.contains("Key-Derivation: Argon2id\n"));
+    assert_eq!(
+        PrivateKey::from_ppk(&ppk, PASSWORD).unwrap().key_data(),
+        key.key_data()
+    );
+}
+
+#[test]
+fn argon2_parameter_limits() {
+    for (header, value) in [
+        ("Argon2-Memory", "4294967295"),
+        ("Argon2-Passes", "4294967295"),
+        ("Argon2-Parallelism", "4294967295"),
+    ] {
+        let ppk = PPK_V3_ARGON2_ED25519_EXAMPLE
+            .lines()
+            .map(|line| match line.split_once(": ") {
+                Some((name, _)) if name == header => format!("{name}: {value}\n"),
+                _ => format!("{line}\n"),
+            })
+            .collect::<String>();
+
+        assert_ne!(ppk, PPK_V3_ARGON2_ED25519_EXAMPLE);
+        assert_eq!(
+            PrivateKey::from_ppk(&ppk, PUTTYGEN_PASSWORD).err(),
+            Some(Error::FormatEncoding)
+        );
+    }
+}
+
+#[test]
+fn v3_encryption_requires_rng() {
+    let key = PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    assert_eq!(
+        key.to_ppk(PpkVersion::V3, PASSWORD, LineEnding::LF).err(),
+        Some(Error::AlgorithmUnknown)
+    );
+}
+
+#[test]
+fn modified_comment() {
+    let ppk = PPK_ED25519_EXAMPLE.replace("user@example.com", "mallory@example.com");
+    assert_eq!(PrivateKey::from_ppk(&ppk, "").err(), Some(Error::Crypto));
+}
+
+#[test]
+fn crlf_line_endings() {
+    let ppk = PPK_ED25519_EXAMPLE.replace('\n', "\r\n");
+    let key = PrivateKey::from_ppk(&ppk, "").unwrap();
+    assert_eq!(
+        *key.to_ppk(PpkVersion::V3, "", LineEnding::CRLF).unwrap(),
+        ppk
+    );
+}
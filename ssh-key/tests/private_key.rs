@@ -1,19 +1,16 @@
 //! SSH private key tests.
 
 use hex_literal::hex;
-use ssh_key::{Algorithm, Cipher, KdfAlg, PrivateKey};
+use ssh_key::{Algorithm, Cipher, KdfAlg, PrivateKey, PublicKey};
 
 #[cfg(feature = "ecdsa")]
 use ssh_key::EcdsaCurve;
 
-#[cfg(all(feature = "alloc"))]
+#[cfg(feature = "alloc")]
 use ssh_key::LineEnding;
 
-#[cfg(all(feature = "std"))]
-use {
-    ssh_key::PublicKey,
-    std::{io, process},
-};
+#[cfg(feature = "std")]
+use std::{io, process};
 
 /// DSA OpenSSH-formatted public key
 #[cfg(feature = "alloc")]
@@ -34,6 +31,16 @@ const OPENSSH_ECDSA_P521_EXAMPLE: &str = include_str!("examples/id_ecdsa_p521");
 /// Ed25519 OpenSSH-formatted private key
 const OPENSSH_ED25519_EXAMPLE: &str = include_str!("examples/id_ed25519");
 
+/// Ed25519 OpenSSH-formatted private key with more padding than necessary
+const OPENSSH_ED25519_EXTRA_PADDING_EXAMPLE: &str =
+    include_str!("examples/id_ed25519_extra_padding");
+
+/// OpenSSH-formatted private key whose outer (Ed25519) and inner (ECDSA/P-256)
+/// algorithms disagree
+#[cfg(feature = "ecdsa")]
+const OPENSSH_MISMATCHED_ALGORITHM_EXAMPLE: &str =
+    include_str!("examples/id_ed25519_mismatched_algorithm");
+
 /// RSA (3072-bit) OpenSSH-formatted public key
 #[cfg(feature = "alloc")]
 const OPENSSH_RSA_3072_EXAMPLE: &str = include_str!("examples/id_rsa_3072");
@@ -214,6 +221,197 @@ fn decode_ed25519_openssh() {
     assert_eq!(key.comment(), "user@example.com");
 }
 
+#[cfg(feature = "alloc")]
+#[test]
+fn decode_openssh_with_bom() {
+    let key = PrivateKey::from_openssh(format!("\u{feff}{OPENSSH_ED25519_EXAMPLE}")).unwrap();
+    assert_eq!(
+        key,
+        PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap()
+    );
+
+    let bom_inside = OPENSSH_ED25519_EXAMPLE.replacen('\n', "\n\u{feff}", 1);
+    assert!(PrivateKey::from_openssh(bom_inside).is_err());
+}
+
+#[test]
+fn keypair_public_key_data() {
+    let key = PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let public_key_data = key.key_data().public_key_data().unwrap();
+    assert_eq!(&public_key_data, key.public_key().key_data());
+
+    #[cfg(feature = "ed25519")]
+    {
+        let derived =
+            ssh_key::public::Ed25519PublicKey::from(&key.key_data().ed25519().unwrap().private);
+        assert_eq!(public_key_data.ed25519(), Some(&derived));
+    }
+}
+
+#[test]
+fn public_key_conversions() {
+    fn public_key_of(key: &impl AsRef<PublicKey>) -> &PublicKey {
+        key.as_ref()
+    }
+
+    let key = PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    assert_eq!(&PublicKey::from(&key), key.public_key());
+    assert_eq!(public_key_of(&key), key.public_key());
+    assert_eq!(public_key_of(key.public_key()), key.public_key());
+}
+
+#[cfg(all(feature = "alloc", feature = "ed25519"))]
+#[test]
+fn new_from_keypair_data() {
+    use ssh_key::{
+        private::{Ed25519Keypair, Ed25519PrivateKey, KeypairData},
+        public::Ed25519PublicKey,
+        Error, HashAlg,
+    };
+
+    let public = Ed25519PublicKey(hex!(
+        "b33eaef37ea2df7caa010defdea34e241f65f1b529a4f43ed14327f5c54aab62"
+    ));
+    let private = Ed25519PrivateKey::from_bytes(&hex!(
+        "b606c222d10c16dae16c70a4d45173472ec617e05c656920d26e56c08fb591ed"
+    ));
+
+    let key_data = KeypairData::from(Ed25519Keypair {
+        public,
+        private: private.clone(),
+    });
+    let key = PrivateKey::new(key_data, "user@example.com").unwrap();
+    assert_eq!(
+        key,
+        PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap()
+    );
+
+    let signature = key.sign("example", HashAlg::Sha512, b"testing").unwrap();
+    key.public_key()
+        .verify("example", b"testing", &signature)
+        .unwrap();
+
+    // Public key doesn't match the private key
+    let mismatched = KeypairData::from(Ed25519Keypair {
+        public: Ed25519PublicKey::from(&Ed25519PrivateKey::from_bytes(&[0x42; 32])),
+        private,
+    });
+    assert_eq!(
+        PrivateKey::new(mismatched, "user@example.com"),
+        Err(Error::PublicKey)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn opaque_keypair_round_trip() {
+    use ssh_key::{
+        private::{KeypairData, OpaqueKeypair},
+        public::OpaquePublicKey,
+        AlgorithmName,
+    };
+
+    let algorithm = AlgorithmName::new("ssh-mldsa65@example.com").unwrap();
+    let public_key = OpaquePublicKey::new(algorithm.clone(), hex!("00000004 11223344"));
+
+    // Private key data which ends in what looks like an empty `string`
+    for comment in ["user@example.com", ""] {
+        let keypair = OpaqueKeypair::new(public_key.clone(), hex!("00000008 5566778800000000"));
+        let key = PrivateKey::new(KeypairData::Other(keypair.clone()), comment).unwrap();
+
+        let decoded = PrivateKey::from_openssh(key.to_openssh(LineEnding::LF).unwrap()).unwrap();
+        assert_eq!(decoded, key);
+        assert_eq!(decoded.algorithm(), Algorithm::Other(algorithm.clone()));
+        assert_eq!(decoded.comment(), comment);
+        assert_eq!(decoded.key_data().other(), Some(&keypair));
+        assert_eq!(
+            decoded.fingerprint(Default::default()),
+            key.public_key().fingerprint(Default::default())
+        );
+    }
+}
+
+#[cfg(feature = "xmss")]
+#[test]
+fn xmss_keypair_round_trip() {
+    use ssh_key::{private::XmssKeypair, public::XmssPublicKey};
+
+    let public_key = XmssPublicKey::new("XMSS_SHA2-256_W16_H10", [0x42; 64]).unwrap();
+
+    // Signer state: format, magic, index, `stack`, `stackoffset`,
+    // `stacklevels`, `auth`, `keep`, `th_nodes`, `retain`, and one treehash
+    // instance
+    let state = hex!(
+        "01"
+        "00000007 584d53532d4b32"
+        "00000005"
+        "00000002 1111"
+        "00000000"
+        "00000001 22"
+        "00000002 3333"
+        "00000000"
+        "00000001 44"
+        "00000000"
+        "00000001"
+        "00000003 00000004 00000000 01 00000000"
+    );
+
+    let keypair = XmssKeypair::new(public_key.clone(), [0x24; 132], state).unwrap();
+    assert_eq!(keypair.index(), Some(5));
+    assert_eq!(keypair.state_bytes(), state);
+    let key = PrivateKey::from(keypair.clone());
+    assert_eq!(key.algorithm(), Algorithm::Xmss);
+    assert_eq!(key.public_key().key_data().xmss(), Some(&public_key));
+
+    let decoded = PrivateKey::from_openssh(key.to_openssh(LineEnding::LF).unwrap()).unwrap();
+    assert_eq!(decoded, key);
+    assert_eq!(decoded.key_data().xmss(), Some(&keypair));
+    assert!(decoded
+        .public_key()
+        .to_openssh()
+        .unwrap()
+        .starts_with("ssh-xmss@openssh.com AAAAFHNzaC14bXNzQG9wZW5zc2guY29t"));
+
+    // Truncated and unknown signer states are rejected
+    assert!(XmssKeypair::new(public_key.clone(), [0x24; 132], &state[..state.len() - 1]).is_err());
+    assert_eq!(
+        XmssKeypair::new(public_key, [0x24; 132], [3]).err(),
+        Some(ssh_key::Error::FormatEncoding)
+    );
+}
+
+#[cfg(feature = "rsa")]
+#[test]
+fn rsa_3072_pkcs1_round_trip() {
+    let key = PrivateKey::from_openssh(OPENSSH_RSA_3072_EXAMPLE).unwrap();
+    let pkcs1_pem = key.to_pkcs1_pem(LineEnding::LF).unwrap();
+    assert_eq!(&*pkcs1_pem, include_str!("examples/id_rsa_3072.pkcs1.pem"));
+
+    let mut decoded = PrivateKey::from_pkcs1_pem(&*pkcs1_pem).unwrap();
+    assert_eq!(decoded.comment(), "");
+    decoded.set_comment(key.comment());
+
+    let (expected, actual) = (
+        key.key_data().rsa().unwrap(),
+        decoded.key_data().rsa().unwrap(),
+    );
+    assert_eq!(expected.public, actual.public);
+    assert_eq!(expected.private.d, actual.private.d);
+    assert_eq!(expected.private.iqmp, actual.private.iqmp);
+    assert_eq!(expected.private.p, actual.private.p);
+    assert_eq!(expected.private.q, actual.private.q);
+    assert_eq!(decoded.public_key(), key.public_key());
+
+    // Only RSA keys can be encoded as PKCS#1
+    let ed25519_key = PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    assert_eq!(
+        ed25519_key.to_pkcs1_pem(LineEnding::LF).err(),
+        Some(ssh_key::Error::AlgorithmUnsupported {
+            algorithm: Algorithm::Ed25519
+        })
+    );
+}
+
 #[cfg(feature = "alloc")]
 #[test]
 fn decode_rsa_3072_openssh() {
@@ -367,7 +565,7 @@ fn decode_rsa_4096_openssh() {
     assert_eq!("user@example.com", key.comment());
 }
 
-#[cfg(all(feature = "alloc"))]
+#[cfg(feature = "alloc")]
 #[test]
 fn encode_dsa_openssh() {
     encoding_test(OPENSSH_DSA_EXAMPLE)
@@ -391,26 +589,429 @@ fn encode_ecdsa_p521_openssh() {
     encoding_test(OPENSSH_ECDSA_P521_EXAMPLE)
 }
 
-#[cfg(all(feature = "alloc"))]
+#[cfg(feature = "alloc")]
 #[test]
 fn encode_ed25519_openssh() {
     encoding_test(OPENSSH_ED25519_EXAMPLE)
 }
 
-#[cfg(all(feature = "alloc"))]
+#[cfg(feature = "alloc")]
 #[test]
 fn encode_rsa_3072_openssh() {
     encoding_test(OPENSSH_RSA_3072_EXAMPLE)
 }
 
-#[cfg(all(feature = "alloc"))]
+#[cfg(feature = "alloc")]
 #[test]
 fn encode_rsa_4096_openssh() {
     encoding_test(OPENSSH_RSA_4096_EXAMPLE)
 }
 
+#[cfg(feature = "alloc")]
+#[test]
+fn encode_openssh_with_checkint() {
+    let key = PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let rebuilt = PrivateKey::new(key.key_data().clone(), key.comment()).unwrap();
+    assert_ne!(
+        key.to_openssh(LineEnding::LF).unwrap(),
+        rebuilt.to_openssh(LineEnding::LF).unwrap()
+    );
+
+    let encoded = key.to_openssh_with_checkint(LineEnding::LF, 0).unwrap();
+    assert_eq!(
+        encoded,
+        rebuilt.to_openssh_with_checkint(LineEnding::LF, 0).unwrap()
+    );
+    assert_eq!(
+        encoded,
+        key.to_openssh_with_checkint(LineEnding::LF, 0).unwrap()
+    );
+    assert_eq!(PrivateKey::from_openssh(&*encoded).unwrap(), key);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn encode_unencrypted_header() {
+    let key = PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let bytes = key.to_bytes().unwrap();
+
+    // `openssh-key-v1\0`, cipher `none`, KDF `none`, and empty `kdfoptions`
+    assert_eq!(
+        &bytes[..35],
+        &hex!("6f70656e7373682d6b65792d763100 000000046e6f6e65 000000046e6f6e65 00000000")
+    );
+
+    // Ensure output matches the original `ssh-keygen` output byte-for-byte
+    let pem = key.to_openssh(LineEnding::LF).unwrap();
+    assert_eq!(OPENSSH_ED25519_EXAMPLE, pem.as_str());
+}
+
+/// Importing the secret key of the first [RFC8032 § 7.1] test vector as a seed.
+///
+/// [RFC8032 § 7.1]: https://datatracker.ietf.org/doc/html/rfc8032#section-7.1
+#[cfg(feature = "ed25519")]
+#[test]
+fn from_ed25519_seed() {
+    let key = PrivateKey::from_ed25519_seed(&hex!(
+        "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60"
+    ));
+    assert_eq!(key.algorithm(), Algorithm::Ed25519);
+    assert_eq!(
+        key.public_key().key_data().ed25519().unwrap().as_ref(),
+        &hex!("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a")
+    );
+    assert_eq!(
+        key.fingerprint(Default::default()).to_string(),
+        "SHA256:bbXpuKG6zhzdmnxq256TlqzFBzRl2f6OOg722cYNbU8"
+    );
+}
+
+#[test]
+fn decode_ed25519_extra_padding() {
+    let key = PrivateKey::from_openssh(OPENSSH_ED25519_EXTRA_PADDING_EXAMPLE).unwrap();
+    assert_eq!(
+        key,
+        PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap()
+    );
+}
+
+#[cfg(feature = "ecdsa")]
+#[test]
+fn reject_mismatched_algorithm() {
+    assert_eq!(
+        PrivateKey::from_openssh(OPENSSH_MISMATCHED_ALGORITHM_EXAMPLE),
+        Err(ssh_key::Error::AlgorithmMismatch {
+            public: Algorithm::Ed25519,
+            private: Algorithm::Ecdsa {
+                curve: EcdsaCurve::NistP256
+            },
+        })
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn reject_invalid_padding() {
+    let mut bytes = PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE)
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+
+    // Padding is `1, 2, 3, 4, 5`
+    *bytes.last_mut().unwrap() = 6;
+    assert_eq!(
+        PrivateKey::from_bytes(&bytes),
+        Err(ssh_key::Error::FormatEncoding)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn decode_seven_byte_padding() {
+    let mut key = PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+
+    // Lengthen the comment so exactly 7 bytes of padding are needed
+    key.set_comment("user@example.com.local");
+    let mut bytes = key.to_bytes().unwrap();
+    assert!(bytes.ends_with(&[0x6c, 1, 2, 3, 4, 5, 6, 7]));
+    assert_eq!(PrivateKey::from_bytes(&bytes).unwrap(), key);
+
+    // Padding out of sequence
+    let len = bytes.len();
+    bytes.swap(len - 2, len - 1);
+    assert_eq!(
+        PrivateKey::from_bytes(&bytes),
+        Err(ssh_key::Error::FormatEncoding)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn reject_mismatched_checkints() {
+    let mut bytes = PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE)
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+
+    // Corrupt the first checkint, which follows the private section's length
+    bytes[98] ^= 1;
+    assert_eq!(PrivateKey::from_bytes(&bytes), Err(ssh_key::Error::Crypto));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn decode_unencrypted_with_kdfoptions() {
+    let mut bytes = PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE)
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+
+    // Set the length of `kdfoptions` to be non-zero
+    bytes[34] = 1;
+    assert_eq!(
+        PrivateKey::from_bytes(&bytes),
+        Err(ssh_key::Error::FormatEncoding)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn decode_encrypted_without_kdf() {
+    let bytes = PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE)
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+
+    // Replace the `none` cipher following the magic, keeping the `none` KDF
+    let mut modified = bytes[..15].to_vec();
+    modified.extend_from_slice(&[0, 0, 0, 10]);
+    modified.extend_from_slice(b"aes256-ctr");
+    modified.extend_from_slice(&bytes[23..]);
+    assert_eq!(
+        PrivateKey::from_bytes(&modified),
+        Err(ssh_key::Error::FormatEncoding)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn decode_binary_ed25519() {
+    use base64ct::{Base64, Encoding};
+
+    let base64 = OPENSSH_ED25519_EXAMPLE
+        .lines()
+        .filter(|line| !line.starts_with("-----"))
+        .collect::<String>();
+    let bytes = Base64::decode_vec(&base64).unwrap();
+
+    let key = PrivateKey::try_from(bytes.as_slice()).unwrap();
+    assert_eq!(
+        key,
+        PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap()
+    );
+    assert_eq!(key.comment(), "user@example.com");
+
+    // Reject data without the `openssh-key-v1\0` magic
+    assert_eq!(
+        PrivateKey::try_from(&bytes[1..]),
+        Err(ssh_key::Error::FormatVersion {
+            magic: *b"penssh-key-v1\0\0"
+        })
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn reject_unknown_format_version() {
+    let mut bytes = PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE)
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+
+    // Change the magic to `openssh-key-v2\0`
+    bytes[13] = b'2';
+    let err = PrivateKey::from_bytes(&bytes).unwrap_err();
+    assert_eq!(
+        err,
+        ssh_key::Error::FormatVersion {
+            magic: *b"openssh-key-v2\0"
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "unsupported private key format: \"openssh-key-v2\\x00\""
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn encode_ssh_keygen_generated_ed25519() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("unencrypted_ed25519");
+
+    match process::Command::new("ssh-keygen")
+        .args([
+            "-q",
+            "-t",
+            "ed25519",
+            "-N",
+            "",
+            "-C",
+            "user@example.com",
+            "-f",
+        ])
+        .arg(&path)
+        .output()
+    {
+        Ok(output) => assert_eq!(output.status.code().unwrap(), 0),
+        Err(err) => {
+            if err.kind() == io::ErrorKind::NotFound {
+                eprintln!("couldn't find 'ssh-keygen'! skipping test");
+                return;
+            } else {
+                panic!("error invoking ssh-keygen: {}", err)
+            }
+        }
+    }
+
+    let ssh_keygen_output = std::fs::read_to_string(&path).unwrap();
+    let key = PrivateKey::from_openssh(&ssh_keygen_output).unwrap();
+    assert!(!key.is_encrypted());
+    assert_eq!(
+        ssh_keygen_output,
+        key.to_openssh(LineEnding::LF).unwrap().as_str()
+    );
+}
+
+#[cfg(all(feature = "ed25519", feature = "p256", feature = "rsa"))]
+#[test]
+fn repair_public_key() {
+    use ssh_key::{
+        private::{EcdsaKeypair, KeypairData},
+        public::Ed25519PublicKey,
+        Mpint,
+    };
+
+    for example in [
+        OPENSSH_ECDSA_P256_EXAMPLE,
+        OPENSSH_ED25519_EXAMPLE,
+        OPENSSH_RSA_3072_EXAMPLE,
+        #[cfg(feature = "dsa")]
+        OPENSSH_DSA_EXAMPLE,
+    ] {
+        let expected = PrivateKey::from_openssh(example).unwrap();
+
+        // Zero out the public half of the keypair
+        let zeroed = match expected.key_data().clone() {
+            KeypairData::Dsa(mut keypair) => {
+                keypair.public.y = Mpint::from_bytes(&[]).unwrap();
+                KeypairData::Dsa(keypair)
+            }
+            KeypairData::Ecdsa(EcdsaKeypair::NistP256 { private, .. }) => {
+                KeypairData::Ecdsa(EcdsaKeypair::NistP256 {
+                    public: ssh_key::sec1::EncodedPoint::identity(),
+                    private,
+                })
+            }
+            KeypairData::Ed25519(mut keypair) => {
+                keypair.public = Ed25519PublicKey([0; Ed25519PublicKey::BYTE_SIZE]);
+                KeypairData::Ed25519(keypair)
+            }
+            KeypairData::Rsa(mut keypair) => {
+                keypair.public.e = Mpint::from_bytes(&[]).unwrap();
+                keypair.public.n = Mpint::from_bytes(&[]).unwrap();
+                KeypairData::Rsa(keypair)
+            }
+            other => panic!("unexpected key type: {:?}", other.algorithm()),
+        };
+
+        let mut key = PrivateKey::try_from(zeroed).unwrap();
+        key.set_comment(expected.comment());
+        assert_ne!(key.public_key(), expected.public_key());
+
+        key.repair_public_key().unwrap();
+        assert_eq!(key.public_key(), expected.public_key());
+        assert_eq!(key.key_data(), expected.key_data());
+    }
+}
+
+#[cfg(all(feature = "dsa", feature = "rsa"))]
+#[test]
+fn validate_dsa_and_rsa() {
+    use ssh_key::{private::KeypairData, Error, Mpint};
+
+    let dsa_key = PrivateKey::from_openssh(OPENSSH_DSA_EXAMPLE).unwrap();
+    let rsa_key = PrivateKey::from_openssh(OPENSSH_RSA_3072_EXAMPLE).unwrap();
+    let other_rsa_key = PrivateKey::from_openssh(OPENSSH_RSA_4096_EXAMPLE).unwrap();
+    assert_eq!(dsa_key.key_data().validate(), Ok(()));
+    assert_eq!(rsa_key.key_data().validate(), Ok(()));
+
+    let mut dsa_keypair = dsa_key.key_data().dsa().unwrap().clone();
+    dsa_keypair.public.y = Mpint::from_positive_bytes(&[2]).unwrap();
+    assert_eq!(
+        KeypairData::Dsa(dsa_keypair).validate(),
+        Err(Error::PublicKey)
+    );
+
+    // Modulus which isn't `pq`
+    let mut rsa_keypair = rsa_key.key_data().rsa().unwrap().clone();
+    rsa_keypair.public.n = other_rsa_key.key_data().rsa().unwrap().public.n.clone();
+    assert_eq!(
+        KeypairData::Rsa(rsa_keypair).validate(),
+        Err(Error::PublicKey)
+    );
+
+    // Public exponent which doesn't match `d`
+    let mut rsa_keypair = rsa_key.key_data().rsa().unwrap().clone();
+    rsa_keypair.public.e = Mpint::from_positive_bytes(&[3]).unwrap();
+    assert_eq!(
+        KeypairData::Rsa(rsa_keypair).validate(),
+        Err(Error::PublicKey)
+    );
+}
+
+#[cfg(all(feature = "pkcs8", feature = "p256"))]
+#[test]
+fn ecdsa_p256_sec1_round_trip() {
+    let key = PrivateKey::from_openssh(OPENSSH_ECDSA_P256_EXAMPLE).unwrap();
+    let sec1_pem = key.to_sec1_pem(LineEnding::LF).unwrap();
+    assert_eq!(&*sec1_pem, include_str!("examples/id_ecdsa_p256.sec1.pem"));
+
+    let decoded = PrivateKey::from_sec1_pem(&*sec1_pem).unwrap();
+    assert_eq!(decoded.comment(), "");
+    assert_eq!(decoded.key_data(), key.key_data());
+
+    // As output by `openssl ecparam -genkey`
+    let with_params = format!(
+        "-----BEGIN EC PARAMETERS-----\nBggqhkjOPQMBBw==\n-----END EC PARAMETERS-----\n{}",
+        &*sec1_pem
+    );
+    let decoded = PrivateKey::from_sec1_pem(with_params).unwrap();
+    assert_eq!(decoded.key_data(), key.key_data());
+
+    // Only ECDSA keys can be encoded as SEC1
+    let ed25519_key = PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    assert_eq!(
+        ed25519_key.to_sec1_pem(LineEnding::LF).err(),
+        Some(ssh_key::Error::AlgorithmUnsupported {
+            algorithm: Algorithm::Ed25519
+        })
+    );
+}
+
+#[cfg(all(feature = "ed25519", feature = "pkcs8", feature = "p256"))]
+#[test]
+fn pkcs8_round_trip() {
+    let key = PrivateKey::from_openssh(OPENSSH_ECDSA_P256_EXAMPLE).unwrap();
+    let pkcs8_pem = key.to_pkcs8_pem(LineEnding::LF).unwrap();
+    assert_eq!(
+        &*pkcs8_pem,
+        include_str!("examples/id_ecdsa_p256.pkcs8.pem")
+    );
+
+    let decoded = PrivateKey::from_pkcs8_pem(&*pkcs8_pem).unwrap();
+    assert_eq!(decoded.comment(), "");
+    assert_eq!(decoded.key_data(), key.key_data());
+
+    let ed25519_key = PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let pkcs8_pem = ed25519_key.to_pkcs8_pem(LineEnding::LF).unwrap();
+    let decoded = PrivateKey::from_pkcs8_pem(&*pkcs8_pem).unwrap();
+    assert_eq!(decoded.key_data(), ed25519_key.key_data());
+
+    // The encrypted format is rejected
+    assert!(PrivateKey::from_pkcs8_pem(include_str!("examples/id_ecdsa_p256.pkcs8.enc")).is_err());
+}
+
+#[test]
+fn try_clone() {
+    let key = PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let cloned = key.try_clone().unwrap();
+    assert_eq!(key, cloned);
+    assert_eq!(key.comment(), cloned.comment());
+}
+
 /// Common behavior of all encoding tests
-#[cfg(all(feature = "alloc"))]
+#[cfg(feature = "alloc")]
 fn encoding_test(private_key: &str) {
     let key = PrivateKey::from_openssh(private_key).unwrap();
 
@@ -423,8 +1024,73 @@ fn encoding_test(private_key: &str) {
     encoding_integration_test(key)
 }
 
+#[cfg(feature = "std")]
+#[test]
+fn read_openssh_file_with_public() {
+    let mut key = PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    key.set_comment("");
+
+    let dir = tempfile::tempdir().unwrap();
+    let private_path = &dir.path().join("id_ed25519_no_comment");
+    key.write_openssh_file(private_path, LineEnding::LF)
+        .unwrap();
+    assert_eq!(
+        "",
+        PrivateKey::read_openssh_file(private_path)
+            .unwrap()
+            .comment()
+    );
+
+    let public_path = std::path::Path::new("tests/examples/id_ed25519.pub");
+    let key = PrivateKey::read_openssh_file_with_public(private_path, public_path).unwrap();
+    assert_eq!(Algorithm::Ed25519, key.algorithm());
+    assert_eq!("user@example.com", key.comment());
+
+    let mismatched_path = std::path::Path::new("tests/examples/id_rsa_3072.pub");
+    assert_eq!(
+        PrivateKey::read_openssh_file_with_public(private_path, mismatched_path),
+        Err(ssh_key::Error::PublicKey)
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn from_openssh_env() {
+    const VAR_NAME: &str = "SSH_KEY_TEST_FROM_OPENSSH_ENV";
+
+    std::env::set_var(VAR_NAME, OPENSSH_ED25519_EXAMPLE.replace('\n', "\\n"));
+    assert_eq!(
+        PrivateKey::from_openssh_env(VAR_NAME).unwrap(),
+        PrivateKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap()
+    );
+
+    std::env::set_var(VAR_NAME, "not a private key");
+    assert!(!matches!(
+        PrivateKey::from_openssh_env(VAR_NAME),
+        Ok(_) | Err(ssh_key::Error::Io(_))
+    ));
+
+    std::env::remove_var(VAR_NAME);
+    assert_eq!(
+        PrivateKey::from_openssh_env(VAR_NAME),
+        Err(ssh_key::Error::Io(io::ErrorKind::NotFound))
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn read_nonexistent_file() {
+    let path = std::path::Path::new("tests/examples/nonexistent");
+    let expected = Some(ssh_key::Error::Io(io::ErrorKind::NotFound));
+
+    assert_eq!(PrivateKey::read_openssh_file(path).err(), expected);
+    assert_eq!(PublicKey::read_openssh_file(path).err(), expected);
+    assert_eq!(ssh_key::Certificate::read_file(path).err(), expected);
+    assert_eq!(ssh_key::AuthorizedKeys::read_file(path).err(), expected);
+}
+
 /// Parse PEM encoded using `PrivateKey::to_openssh` using the `ssh-keygen` utility.
-#[cfg(all(feature = "std"))]
+#[cfg(feature = "std")]
 fn encoding_integration_test(private_key: PrivateKey) {
     let fingerprint = private_key
         .fingerprint(Default::default())
@@ -432,7 +1098,8 @@ fn encoding_integration_test(private_key: PrivateKey) {
         .replace(':', "-")
         .replace('/', "_");
 
-    let path = std::path::PathBuf::from(&format!("tests/scratch/{}", fingerprint));
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join(fingerprint);
 
     private_key
         .write_openssh_file(&path, LineEnding::LF)
@@ -460,3 +1127,109 @@ fn encoding_integration_test(private_key: PrivateKey) {
     // Ensure ssh-keygen successfully parsed our public key
     assert_eq!(&public_key, private_key.public_key());
 }
+
+#[cfg(all(
+    feature = "rand_core",
+    feature = "ed25519",
+    feature = "p256",
+    feature = "rsa"
+))]
+#[test]
+fn generate_host_key_set() {
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use ssh_key::HashAlg;
+
+    let mut rng = ChaCha8Rng::from_seed([42; 32]);
+    let keys = PrivateKey::generate_host_key_set(&mut rng).unwrap();
+
+    let algorithms = keys.iter().map(|key| key.algorithm()).collect::<Vec<_>>();
+    assert_eq!(
+        algorithms,
+        [
+            Algorithm::Rsa { hash: None },
+            Algorithm::Ecdsa {
+                curve: EcdsaCurve::NistP256
+            },
+            Algorithm::Ed25519,
+        ]
+    );
+
+    let rsa_key = keys[0].key_data().rsa().unwrap();
+    assert_eq!(
+        rsa_key.public.n.as_positive_bytes().unwrap().len() * 8,
+        3072
+    );
+
+    for key in &keys {
+        assert_eq!(key.comment(), "");
+        assert!(key.public_key().validate().is_ok());
+
+        let signature = key.sign("example", HashAlg::Sha512, b"host key").unwrap();
+        key.public_key()
+            .verify("example", b"host key", &signature)
+            .unwrap();
+
+        let encoded = key.to_openssh(LineEnding::LF).unwrap();
+        let decoded = PrivateKey::from_openssh(&encoded).unwrap();
+        assert_eq!(key, &decoded);
+        assert_eq!(
+            key.fingerprint(HashAlg::Sha256),
+            decoded.fingerprint(HashAlg::Sha256)
+        );
+    }
+}
+
+#[cfg(all(
+    feature = "rand_core",
+    feature = "p256",
+    feature = "p384",
+    feature = "p521"
+))]
+#[test]
+fn random_ecdsa_with_comment() {
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+    use ssh_key::HashAlg;
+
+    let mut rng = ChaCha8Rng::from_seed([42; 32]);
+
+    for curve in [
+        EcdsaCurve::NistP256,
+        EcdsaCurve::NistP384,
+        EcdsaCurve::NistP521,
+    ] {
+        let key = PrivateKey::random(&mut rng, Algorithm::Ecdsa { curve })
+            .unwrap()
+            .with_comment("user@example.com");
+
+        assert_eq!(key.algorithm(), Algorithm::Ecdsa { curve });
+        assert_eq!(key.comment(), "user@example.com");
+        assert_eq!(key.public_key().comment(), "user@example.com");
+        assert!(key.key_data().validate().is_ok());
+
+        let signature = key.sign("example", HashAlg::Sha512, b"message").unwrap();
+        key.public_key()
+            .verify("example", b"message", &signature)
+            .unwrap();
+
+        let encoded = key.to_openssh(LineEnding::LF).unwrap();
+        assert_eq!(PrivateKey::from_openssh(&encoded).unwrap(), key);
+    }
+}
+
+#[cfg(all(feature = "rand_core", feature = "rsa"))]
+#[test]
+fn random_rsa() {
+    use rand_chacha::{rand_core::SeedableRng, ChaCha8Rng};
+
+    let mut rng = ChaCha8Rng::from_seed([42; 32]);
+    let key = PrivateKey::random_rsa(&mut rng, 2048).unwrap();
+
+    let rsa_key = key.key_data().rsa().unwrap();
+    assert_eq!(
+        rsa_key.public.n.as_positive_bytes().unwrap().len() * 8,
+        2048
+    );
+    assert!(key.public_key().validate().is_ok());
+
+    assert!(PrivateKey::random_rsa(&mut rng, 1024).is_err());
+}
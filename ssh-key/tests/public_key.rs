@@ -3,9 +3,18 @@
 use hex_literal::hex;
 use ssh_key::{Algorithm, PublicKey};
 
+#[cfg(feature = "alloc")]
+use ssh_key::public::KeyMetadata;
+
 #[cfg(feature = "ecdsa")]
 use ssh_key::EcdsaCurve;
 
+#[cfg(feature = "p256")]
+use ssh_key::{
+    public::{EcdsaPublicKey, KeyData},
+    Error,
+};
+
 /// DSA OpenSSH-formatted public key
 #[cfg(feature = "alloc")]
 const OPENSSH_DSA_EXAMPLE: &str = include_str!("examples/id_dsa_1024.pub");
@@ -25,6 +34,10 @@ const OPENSSH_ECDSA_P521_EXAMPLE: &str = include_str!("examples/id_ecdsa_p521.pu
 /// Ed25519 OpenSSH-formatted public key
 const OPENSSH_ED25519_EXAMPLE: &str = include_str!("examples/id_ed25519.pub");
 
+/// Ed25519 RFC4716-formatted public key
+#[cfg(feature = "alloc")]
+const RFC4716_ED25519_EXAMPLE: &str = include_str!("examples/id_ed25519.rfc4716.pub");
+
 /// RSA (3072-bit) OpenSSH-formatted public key
 #[cfg(feature = "alloc")]
 const OPENSSH_RSA_3072_EXAMPLE: &str = include_str!("examples/id_rsa_3072.pub");
@@ -195,6 +208,56 @@ fn decode_ed25519_openssh() {
     );
 }
 
+#[cfg(feature = "alloc")]
+#[test]
+fn ensure_comment_or() {
+    let mut key = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    assert_eq!(key.ensure_comment_or("root@localhost"), "user@example.com");
+    assert_eq!(key.comment(), "user@example.com");
+
+    key.set_comment("");
+    assert_eq!(key.ensure_comment_or("root@localhost"), "root@localhost");
+    assert_eq!(key.comment(), "root@localhost");
+}
+
+#[cfg(all(feature = "alloc", feature = "ecdsa"))]
+#[test]
+fn decode_openssh_multi() {
+    let input = format!(
+        "# Keys\n{OPENSSH_ED25519_EXAMPLE}\n{OPENSSH_ECDSA_P256_EXAMPLE}\n  \n{OPENSSH_RSA_3072_EXAMPLE}"
+    );
+    let keys = PublicKey::from_openssh_multi(&input).unwrap();
+    assert_eq!(
+        keys.iter().map(PublicKey::algorithm).collect::<Vec<_>>(),
+        [
+            Algorithm::Ed25519,
+            Algorithm::Ecdsa {
+                curve: EcdsaCurve::NistP256
+            },
+            Algorithm::Rsa { hash: None },
+        ]
+    );
+
+    let malformed = input.replacen("ecdsa-sha2-nistp256 AAAA", "ecdsa-sha2-nistp256 ????", 1);
+    assert_eq!(
+        PublicKey::from_openssh_multi(&malformed),
+        Err(ssh_key::Error::LineInvalid { number: 4 })
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn decode_openssh_with_bom() {
+    let key = PublicKey::from_openssh(&format!("\u{feff}{OPENSSH_ED25519_EXAMPLE}")).unwrap();
+    assert_eq!(
+        key,
+        PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap()
+    );
+
+    let bom_inside = OPENSSH_ED25519_EXAMPLE.replacen(' ', " \u{feff}", 1);
+    assert!(PublicKey::from_openssh(&bom_inside).is_err());
+}
+
 #[cfg(feature = "alloc")]
 #[test]
 fn decode_rsa_3072_openssh() {
@@ -224,6 +287,89 @@ fn decode_rsa_3072_openssh() {
     );
 }
 
+#[cfg(feature = "alloc")]
+#[test]
+fn rsa_from_unsigned_components() {
+    use ssh_key::public::{KeyData, RsaPublicKey};
+
+    let expected = PublicKey::from_openssh(OPENSSH_RSA_3072_EXAMPLE).unwrap();
+    let n = expected
+        .key_data()
+        .rsa()
+        .unwrap()
+        .n
+        .as_positive_bytes()
+        .unwrap();
+    assert!(n[0] >= 0x80, "modulus should have its MSB set");
+
+    // With and without superfluous leading zeroes, e.g. fixed-width inputs
+    for (e, n) in [
+        (hex!("010001").as_slice(), n.to_vec()),
+        (&hex!("00000000010001"), [&[0, 0][..], n].concat()),
+    ] {
+        let key = RsaPublicKey::new(e, &n).unwrap();
+        assert_eq!(&key, expected.key_data().rsa().unwrap());
+
+        // Matches `ssh-keygen -lf`
+        let key = PublicKey::new(KeyData::Rsa(key), "");
+        assert_eq!(
+            "SHA256:Fmxts/GcV77PakFnf1Ueki5mpU4ZjUQWGRjZGAo3n/I",
+            &key.fingerprint(Default::default()).to_string(),
+        );
+    }
+
+    // Even modulus
+    let mut even = n.to_vec();
+    *even.last_mut().unwrap() &= 0xfe;
+    assert_eq!(
+        RsaPublicKey::new(&hex!("010001"), &even),
+        Err(ssh_key::Error::Crypto)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn reject_non_canonical_rsa_modulus() {
+    use encoding::{Decode, Encode};
+
+    let key = PublicKey::from_openssh(OPENSSH_RSA_3072_EXAMPLE).unwrap();
+    let blob = key.to_bytes().unwrap();
+    let mut reader = blob.as_slice();
+    let algorithm = String::decode(&mut reader).unwrap();
+    let e = Vec::<u8>::decode(&mut reader).unwrap();
+    let n = Vec::<u8>::decode(&mut reader).unwrap();
+    assert_eq!(n[0], 0, "modulus should have its MSB set");
+
+    let encode_with_modulus = |n: &[u8]| {
+        let mut blob = Vec::new();
+        algorithm.encode(&mut blob).unwrap();
+        e.encode(&mut blob).unwrap();
+        n.encode(&mut blob).unwrap();
+        blob
+    };
+
+    // Canonical encoding round trips
+    assert_eq!(
+        PublicKey::from_bytes(&encode_with_modulus(&n))
+            .unwrap()
+            .key_data(),
+        key.key_data()
+    );
+
+    // Superfluous leading zero byte
+    let padded = [&[0], n.as_slice()].concat();
+    assert_eq!(
+        PublicKey::from_bytes(&encode_with_modulus(&padded)),
+        Err(ssh_key::Error::FormatEncoding)
+    );
+
+    // Leading zero removed, i.e. a negative modulus
+    assert_eq!(
+        PublicKey::from_bytes(&encode_with_modulus(&n[1..])),
+        Err(ssh_key::Error::FormatEncoding)
+    );
+}
+
 #[cfg(feature = "alloc")]
 #[test]
 fn decode_rsa_4096_openssh() {
@@ -256,6 +402,92 @@ fn decode_rsa_4096_openssh() {
     );
 }
 
+#[cfg(feature = "alloc")]
+#[test]
+fn transparency_leaf() {
+    let key = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let leaf = key.to_transparency_leaf().unwrap();
+    assert_eq!(leaf[0], 0x05);
+    assert_eq!(&leaf[1..], key.to_bytes().unwrap());
+
+    // Comments don't affect the leaf
+    let mut renamed = key.clone();
+    renamed.set_comment("other@example.com");
+    assert_eq!(renamed.to_transparency_leaf().unwrap(), leaf);
+
+    let other = PublicKey::from_openssh(OPENSSH_RSA_3072_EXAMPLE).unwrap();
+    let other_leaf = other.to_transparency_leaf().unwrap();
+    assert_eq!(other_leaf[0], 0x06);
+    assert_ne!(other_leaf, leaf);
+
+    let decoded = PublicKey::from_transparency_leaf(&leaf).unwrap();
+    assert_eq!(decoded.key_data(), key.key_data());
+    assert_eq!(decoded.comment(), "");
+
+    // Identifier byte which doesn't match the key
+    let mut mismatched = leaf.clone();
+    mismatched[0] = 0x06;
+    assert_eq!(
+        PublicKey::from_transparency_leaf(&mismatched),
+        Err(ssh_key::Error::FormatEncoding)
+    );
+    assert!(PublicKey::from_transparency_leaf(&[]).is_err());
+}
+
+#[test]
+fn fingerprint_ordering() {
+    use ssh_key::HashAlg;
+    use std::{cmp::Ordering, collections::BTreeSet};
+
+    let ed25519 = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let sk_ed25519 = PublicKey::from_openssh(OPENSSH_SK_ED25519_EXAMPLE).unwrap();
+
+    let a = ed25519.fingerprint(HashAlg::Sha256);
+    let b = sk_ed25519.fingerprint(HashAlg::Sha256);
+    let c = ed25519.fingerprint(HashAlg::Sha512);
+    assert_eq!(
+        a.cmp(&ed25519.fingerprint(HashAlg::Sha256)),
+        Ordering::Equal
+    );
+
+    // SHA-256 fingerprints sort before SHA-512 ones, then by digest
+    let sorted = if a < b { [a, b, c] } else { [b, a, c] };
+    assert!(sorted[0].sha256().unwrap() < sorted[1].sha256().unwrap());
+
+    let set = [c, b, a, b, c].into_iter().collect::<BTreeSet<_>>();
+    assert_eq!(set.into_iter().collect::<Vec<_>>(), sorted);
+    assert!(a < c && b < c);
+}
+
+/// The application string is part of the fingerprinted key blob, so changing
+/// it changes the fingerprint. The expected fingerprint is the output of
+/// `ssh-keygen -lf` for the modified key.
+#[cfg(all(feature = "alloc", feature = "ecdsa"))]
+#[test]
+fn fingerprint_sk_ecdsa_p256_application() {
+    use encoding::Encode;
+
+    let key = PublicKey::from_openssh(OPENSSH_SK_ECDSA_P256_EXAMPLE).unwrap();
+    let mut blob = key.to_bytes().unwrap();
+    assert!(blob.ends_with(b"\0\0\0\x04ssh:"));
+    blob.truncate(blob.len() - 8);
+    "ssh:git-test".encode(&mut blob).unwrap();
+
+    let other = PublicKey::from_bytes(&blob).unwrap();
+    assert_eq!(
+        other.key_data().sk_ecdsa_p256().unwrap().application(),
+        "ssh:git-test"
+    );
+    assert_eq!(
+        "SHA256:fKkZ7VuZsWTduV1fwR8E/Q07giq7cSvGFMt7dg6om1E",
+        &other.fingerprint(Default::default()).to_string(),
+    );
+    assert_ne!(
+        key.fingerprint(Default::default()),
+        other.fingerprint(Default::default())
+    );
+}
+
 #[cfg(feature = "ecdsa")]
 #[test]
 fn decode_sk_ecdsa_p256_openssh() {
@@ -304,6 +536,17 @@ fn decode_sk_ed25519_openssh() {
     );
 }
 
+#[test]
+fn sk_application() {
+    let key = PublicKey::from_openssh(OPENSSH_SK_ED25519_EXAMPLE).unwrap();
+    assert!(key.is_sk());
+    assert_eq!(Some("ssh:"), key.application());
+
+    let key = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    assert!(!key.is_sk());
+    assert_eq!(None, key.application());
+}
+
 #[cfg(feature = "alloc")]
 #[test]
 fn encode_dsa_openssh() {
@@ -352,3 +595,586 @@ fn encode_rsa_4096_openssh() {
     let key = PublicKey::from_openssh(OPENSSH_RSA_4096_EXAMPLE).unwrap();
     assert_eq!(OPENSSH_RSA_4096_EXAMPLE.trim_end(), &key.to_string());
 }
+
+#[cfg(feature = "alloc")]
+#[test]
+fn key_set_deduplicates_keys() {
+    let key = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let mut renamed = key.clone();
+    renamed.set_comment("other@example.com");
+
+    let mut set = ssh_key::public::KeySet::new();
+    assert_eq!(set.insert(key.clone()), None);
+    assert_eq!(set.insert(renamed.clone()), Some(key.clone()));
+    assert_eq!(set.len(), 1);
+    assert_eq!(
+        set.get(key.key_data()).unwrap().comment(),
+        renamed.comment()
+    );
+
+    let other = PublicKey::from_openssh(OPENSSH_RSA_3072_EXAMPLE).unwrap();
+    assert_eq!(set.insert(other.clone()), None);
+    assert_eq!(set.len(), 2);
+    assert!(set.contains(other.key_data()));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn with_comment_eq_and_hash() {
+    use ssh_key::public::WithComment;
+    use std::collections::HashSet;
+
+    let key = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let mut renamed = key.clone();
+    renamed.set_comment("other@example.com");
+
+    // Same key data, but different comments, which both `PublicKey` and
+    // `WithComment` compare
+    assert_eq!(key.key_data(), renamed.key_data());
+    assert_ne!(key, renamed);
+    assert_ne!(WithComment(key.clone()), WithComment(renamed.clone()));
+    assert_eq!(WithComment(key.clone()), WithComment(key.clone()));
+
+    let set = [key.clone(), renamed, key]
+        .into_iter()
+        .map(WithComment)
+        .collect::<HashSet<_>>();
+    assert_eq!(set.len(), 2);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn metadata_from_large_file() {
+    let examples = [
+        OPENSSH_DSA_EXAMPLE,
+        OPENSSH_ED25519_EXAMPLE,
+        OPENSSH_RSA_3072_EXAMPLE,
+        OPENSSH_RSA_4096_EXAMPLE,
+        OPENSSH_SK_ED25519_EXAMPLE,
+    ];
+
+    let file = examples.repeat(200).concat();
+    let mut count = 0;
+
+    for (line, example) in file.lines().zip(examples.iter().cycle()) {
+        let metadata = KeyMetadata::from_openssh(line).unwrap();
+        let public_key = PublicKey::from_openssh(example).unwrap();
+
+        assert_eq!(public_key.algorithm(), metadata.algorithm());
+        assert_eq!(public_key.comment(), metadata.comment());
+        assert_eq!(
+            public_key.fingerprint(Default::default()),
+            metadata.fingerprint(Default::default())
+        );
+        assert_eq!(public_key, metadata.validate().unwrap());
+        count += 1;
+    }
+
+    assert_eq!(count, examples.len() * 200);
+}
+
+#[cfg(feature = "p256")]
+#[test]
+fn decode_ecdsa_p256_compressed_point() {
+    let key = PublicKey::from_openssh(OPENSSH_ECDSA_P256_EXAMPLE).unwrap();
+    let uncompressed = key.key_data().ecdsa().unwrap().as_sec1_bytes();
+
+    // The y-coordinate of the example key is odd
+    let mut compressed = vec![0x03];
+    compressed.extend_from_slice(&uncompressed[1..33]);
+
+    let decompressed = EcdsaPublicKey::from_sec1_bytes(&compressed).unwrap();
+    assert_eq!(key.key_data().ecdsa(), Some(&decompressed));
+
+    // Key blob where the point is compressed re-encodes as uncompressed
+    let key_blob = key.to_bytes().unwrap();
+    let mut compressed_blob = key_blob[..key_blob.len() - 69].to_vec();
+    compressed_blob.extend_from_slice(&33u32.to_be_bytes());
+    compressed_blob.extend_from_slice(&compressed);
+
+    let decoded = PublicKey::from_bytes(&compressed_blob).unwrap();
+    assert_eq!(key.key_data(), decoded.key_data());
+    assert_eq!(key_blob, decoded.to_bytes().unwrap());
+
+    // x-coordinate isn't a valid field element
+    let mut invalid = vec![0x02];
+    invalid.extend_from_slice(&[0xFF; 32]);
+    assert_eq!(
+        EcdsaPublicKey::from_sec1_bytes(&invalid),
+        Err(Error::Crypto)
+    );
+}
+
+#[cfg(feature = "p256")]
+#[test]
+fn metadata_validate_rejects_invalid_point() {
+    let key = PublicKey::from_openssh(OPENSSH_ECDSA_P256_EXAMPLE).unwrap();
+    let mut point = key.key_data().ecdsa().unwrap().as_sec1_bytes().to_vec();
+    *point.last_mut().unwrap() ^= 1;
+
+    let invalid_key = PublicKey::new(
+        KeyData::Ecdsa(EcdsaPublicKey::from_sec1_bytes(&point).unwrap()),
+        key.comment(),
+    );
+    let metadata = KeyMetadata::from_openssh(&invalid_key.to_openssh().unwrap()).unwrap();
+    assert_eq!(invalid_key.algorithm(), metadata.algorithm());
+    assert_eq!(
+        invalid_key.fingerprint(Default::default()),
+        metadata.fingerprint(Default::default())
+    );
+    assert_eq!(metadata.validate(), Err(Error::Crypto));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn reject_comment_with_newline() {
+    let mut key = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    key.set_comment("user@example.com\nssh-ed25519 AAAA attacker");
+    assert_eq!(key.to_openssh(), Err(ssh_key::Error::CommentInvalid));
+
+    key.set_comment("Üser Námé <user@example.com>");
+    let encoded = key.to_openssh().unwrap();
+    assert!(encoded.ends_with(" Üser Námé <user@example.com>"));
+    assert_eq!(PublicKey::from_openssh(&encoded).unwrap(), key);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn canonical_bytes_fingerprint() {
+    use ssh_key::sha2::{Digest, Sha256};
+
+    let mut key = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let canonical_bytes = key.canonical_bytes().unwrap();
+
+    let digest: [u8; 32] = Sha256::digest(&canonical_bytes).into();
+    assert_eq!(Some(digest), key.fingerprint(Default::default()).sha256());
+
+    // Comment is not part of the canonical encoding
+    key.set_comment("other@example.com");
+    assert_eq!(canonical_bytes, key.canonical_bytes().unwrap());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn cloud_metadata_round_trip() {
+    let key = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let line = format!("alice:{}", OPENSSH_ED25519_EXAMPLE.trim_end());
+    assert_eq!(line, key.to_cloud_metadata("alice").unwrap());
+
+    let (username, decoded_key) = PublicKey::from_cloud_metadata(&line).unwrap();
+    assert_eq!("alice", username);
+    assert_eq!("user@example.com", decoded_key.comment());
+    assert_eq!(key, decoded_key);
+
+    for username in ["", "a:b", "a b"] {
+        assert_eq!(
+            key.to_cloud_metadata(username),
+            Err(ssh_key::Error::FormatEncoding)
+        );
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn decode_ed25519_rfc4716() {
+    let key = PublicKey::from_rfc4716(RFC4716_ED25519_EXAMPLE).unwrap();
+    let expected = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    assert_eq!(expected.key_data(), key.key_data());
+    assert_eq!(
+        "256-bit ED25519, converted by root@vm from OpenSSH",
+        key.comment()
+    );
+    assert_eq!(
+        RFC4716_ED25519_EXAMPLE,
+        key.to_rfc4716(ssh_key::LineEnding::LF).unwrap()
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn rfc4716_comment_round_trip() {
+    let mut key = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let long_comment = "a very long comment \\ with \"escapes\" ".repeat(8);
+
+    for comment in [r#"user "quoted" \ name"#, long_comment.as_str()] {
+        key.set_comment(comment);
+        let encoded = key.to_rfc4716(ssh_key::LineEnding::LF).unwrap();
+        assert!(encoded.lines().all(|line| line.len() <= 72));
+        assert_eq!(
+            comment,
+            PublicKey::from_rfc4716(&encoded).unwrap().comment()
+        );
+    }
+
+    // The long comment must have been split across continuation lines
+    let encoded = key.to_rfc4716(ssh_key::LineEnding::CRLF).unwrap();
+    assert!(encoded.lines().filter(|line| line.ends_with('\\')).count() > 1);
+    assert_eq!(key, PublicKey::from_rfc4716(&encoded).unwrap());
+
+    key.set_comment("line\nbreak");
+    assert_eq!(
+        key.to_rfc4716(ssh_key::LineEnding::LF),
+        Err(ssh_key::Error::CommentInvalid)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn rfc4716_subject() {
+    let (key, subject) = PublicKey::from_rfc4716_with_subject(RFC4716_ED25519_EXAMPLE).unwrap();
+    assert_eq!(subject, None);
+
+    let encoded = key
+        .to_rfc4716_with_subject("alice", ssh_key::LineEnding::LF)
+        .unwrap();
+    assert!(encoded.contains("\nSubject: \"alice\"\nComment: "));
+
+    let (decoded, subject) = PublicKey::from_rfc4716_with_subject(&encoded).unwrap();
+    assert_eq!(decoded, key);
+    assert_eq!(subject.as_deref(), Some("alice"));
+
+    // Unquoted values are accepted, as written by some implementations
+    let encoded = encoded.replace("\"alice\"", "bob");
+    let (_, subject) = PublicKey::from_rfc4716_with_subject(&encoded).unwrap();
+    assert_eq!(subject.as_deref(), Some("bob"));
+    assert_eq!(PublicKey::from_rfc4716(&encoded).unwrap(), key);
+
+    assert_eq!(
+        key.to_rfc4716_with_subject("line\nbreak", ssh_key::LineEnding::LF),
+        Err(ssh_key::Error::CommentInvalid)
+    );
+}
+
+#[test]
+fn infer_from_blob() {
+    let expected = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let (_, blob_and_comment) = OPENSSH_ED25519_EXAMPLE.split_once(' ').unwrap();
+    let (blob, _) = blob_and_comment.split_once(' ').unwrap();
+
+    let key = PublicKey::infer_from_blob(blob_and_comment).unwrap();
+    assert_eq!(key, expected);
+
+    let key = PublicKey::infer_from_blob(blob).unwrap();
+    assert_eq!(key.key_data(), expected.key_data());
+    assert_eq!(key.algorithm(), Algorithm::Ed25519);
+    #[cfg(feature = "alloc")]
+    assert_eq!(key.comment(), "");
+
+    // Input with the algorithm identifier is also accepted
+    assert_eq!(
+        PublicKey::infer_from_blob(OPENSSH_ED25519_EXAMPLE),
+        Ok(expected)
+    );
+
+    // A comment alone isn't mistaken for key data
+    assert!(PublicKey::infer_from_blob("user@example.com").is_err());
+}
+
+#[test]
+fn fingerprint_display() {
+    let key = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let fingerprint = key.fingerprint(Default::default()).to_string();
+    assert_eq!(
+        "SHA256:UCUiLr7Pjs9wFFJMDByLgc3NrtdU344OgUM45wZPcIQ",
+        fingerprint
+    );
+
+    let (prefix, base64) = fingerprint.split_once(':').unwrap();
+    assert_eq!("SHA256", prefix);
+    assert!(base64
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/'));
+}
+
+#[test]
+fn fingerprint_sha384() {
+    use ssh_key::{Fingerprint, HashAlg};
+
+    // Generated with `ssh-keygen -l -E sha384`
+    let example = "SHA384:/KXnJddZPbnWy+Fp6jEiD1DhU24DuF6srjB+KAOLhtS2IcC6tIMDcq4BNo7qjhR4";
+
+    let key = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let fingerprint = key.fingerprint(HashAlg::Sha384);
+    assert!(fingerprint.is_sha384());
+    assert_eq!(fingerprint.to_string(), example);
+    assert_eq!(example.parse::<Fingerprint>().unwrap(), fingerprint);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn fingerprint_masked() {
+    use ssh_key::{Fingerprint, HashAlg};
+
+    let key = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let fingerprint = key.fingerprint(HashAlg::Sha256);
+    assert_eq!(fingerprint.masked(), "SHA256:UCUi\u{2026}PcIQ");
+    assert_eq!(fingerprint.masked_with(2), "SHA256:UC\u{2026}IQ");
+
+    // Retained characters are capped at a quarter of the encoding
+    assert_eq!(
+        fingerprint.masked_with(100),
+        "SHA256:UCUiLr7Pjs\u{2026}UM45wZPcIQ"
+    );
+
+    let masked = key.fingerprint(HashAlg::Sha512).masked();
+    let (prefix, base64) = masked.split_once(':').unwrap();
+    let (head, tail) = base64.split_once('\u{2026}').unwrap();
+    assert_eq!(prefix, "SHA512");
+    assert_eq!(
+        (head.len(), tail.len()),
+        (
+            Fingerprint::DEFAULT_MASKED_CHARS,
+            Fingerprint::DEFAULT_MASKED_CHARS
+        )
+    );
+}
+
+#[test]
+fn reject_fingerprint_with_wrong_digest_size() {
+    use ssh_key::{Error, Fingerprint, HashAlg};
+
+    let key = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let sha256 = key.fingerprint(HashAlg::Sha256).to_string();
+    let sha512 = key.fingerprint(HashAlg::Sha512).to_string();
+    let (_, sha256_base64) = sha256.split_once(':').unwrap();
+    let (_, sha512_base64) = sha512.split_once(':').unwrap();
+
+    for (alg, base64) in [
+        ("SHA256", sha512_base64),
+        ("SHA512", sha256_base64),
+        ("SHA256", &sha256_base64[..40]),
+    ] {
+        assert_eq!(
+            format!("{alg}:{base64}").parse::<Fingerprint>(),
+            Err(Error::Encoding(ssh_key::encoding::Error::Length))
+        );
+    }
+}
+
+#[cfg(all(feature = "alloc", feature = "ecdsa"))]
+#[test]
+fn summary() {
+    // Expected output of `ssh-keygen -l`
+    for (key, expected) in [
+        (
+            OPENSSH_DSA_EXAMPLE,
+            "1024 SHA256:Nh0Me49Zh9fDw/VYUfq43IJmI1T+XrjiYONPND8GzaM user@example.com (DSA)",
+        ),
+        (
+            OPENSSH_ECDSA_P521_EXAMPLE,
+            "521 SHA256:l3AUUMK6Q2BbuiqvMx2fs97f8LUYq7sWCAx7q5m3S6M user@example.com (ECDSA)",
+        ),
+        (
+            OPENSSH_ED25519_EXAMPLE,
+            "256 SHA256:UCUiLr7Pjs9wFFJMDByLgc3NrtdU344OgUM45wZPcIQ user@example.com (ED25519)",
+        ),
+        (
+            OPENSSH_RSA_3072_EXAMPLE,
+            "3072 SHA256:Fmxts/GcV77PakFnf1Ueki5mpU4ZjUQWGRjZGAo3n/I user@example.com (RSA)",
+        ),
+        (
+            OPENSSH_SK_ECDSA_P256_EXAMPLE,
+            "256 SHA256:UINe2WXFh3SiqwLxsBv34fBO2ei+g7uOeJJXVEK95iE user@example.com (ECDSA-SK)",
+        ),
+        (
+            OPENSSH_SK_ED25519_EXAMPLE,
+            "256 SHA256:6WZVJ44bqhAWLVP4Ns0TDkoSQSsZo/h2K+mEvOaNFbw user@example.com (ED25519-SK)",
+        ),
+    ] {
+        let key = PublicKey::from_openssh(key).unwrap();
+        assert_eq!(expected, key.summary(Default::default()));
+    }
+
+    let mut key = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    key.set_comment("");
+    assert_eq!(
+        "256 SHA256:UCUiLr7Pjs9wFFJMDByLgc3NrtdU344OgUM45wZPcIQ no comment (ED25519)",
+        key.summary(Default::default())
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn decode_xmss() {
+    use ssh_key::sha2::{Digest, Sha256};
+
+    let mut blob = Vec::new();
+    for field in [
+        b"ssh-xmss@openssh.com".as_slice(),
+        b"XMSS_SHA2-256_W16_H10".as_slice(),
+        &[0x42; 64],
+    ] {
+        blob.extend_from_slice(&(field.len() as u32).to_be_bytes());
+        blob.extend_from_slice(field);
+    }
+
+    let key = PublicKey::from_bytes(&blob).unwrap();
+    assert_eq!(key.to_bytes().unwrap(), blob);
+    assert_eq!(
+        key.fingerprint(Default::default()).sha256(),
+        Some(Sha256::digest(&blob).into())
+    );
+
+    #[cfg(feature = "xmss")]
+    {
+        let xmss = key.key_data().xmss().unwrap();
+        assert_eq!(key.algorithm(), Algorithm::Xmss);
+        assert_eq!(xmss.params(), "XMSS_SHA2-256_W16_H10");
+        assert_eq!(xmss.as_bytes(), &[0x42; 64]);
+        assert!(key
+            .summary(Default::default())
+            .ends_with("no comment (XMSS)"));
+
+        // Unknown parameter set
+        let mut invalid = blob.clone();
+        invalid[47] = b'5';
+        assert!(PublicKey::from_bytes(&invalid).is_err());
+    }
+
+    // Without the `xmss` feature, XMSS keys are opaque
+    #[cfg(not(feature = "xmss"))]
+    assert_eq!(key.key_data().other().unwrap().as_bytes(), &blob[24..]);
+}
+
+#[cfg(all(feature = "alloc", feature = "ecdsa"))]
+#[test]
+fn decode_unknown_ecdsa_curve() {
+    use ssh_key::AlgorithmName;
+
+    // `ecdsa-sha2-1.3.132.0.10` (secp256k1) public key with a dummy point
+    let mut blob = Vec::new();
+    for field in [
+        b"ecdsa-sha2-1.3.132.0.10".as_slice(),
+        b"1.3.132.0.10".as_slice(),
+        &[4; 65],
+    ] {
+        blob.extend_from_slice(&(field.len() as u32).to_be_bytes());
+        blob.extend_from_slice(field);
+    }
+
+    let key = PublicKey::from_bytes(&blob).unwrap();
+    let algorithm = AlgorithmName::new("ecdsa-sha2-1.3.132.0.10").unwrap();
+    assert_eq!(key.algorithm(), Algorithm::Other(algorithm.clone()));
+
+    let opaque = key.key_data().other().unwrap();
+    assert_eq!(opaque.algorithm(), algorithm);
+    assert_eq!(opaque.as_bytes(), &blob[27..]);
+    assert_eq!(key.to_bytes().unwrap(), blob);
+
+    let openssh = key.to_openssh().unwrap();
+    assert!(openssh.starts_with("ecdsa-sha2-1.3.132.0.10 "));
+    assert_eq!(PublicKey::from_openssh(&openssh).unwrap(), key);
+
+    // Supported curves still decode as ECDSA keys
+    let key = PublicKey::from_openssh(OPENSSH_ECDSA_P256_EXAMPLE).unwrap();
+    assert_eq!(
+        key.algorithm(),
+        Algorithm::Ecdsa {
+            curve: EcdsaCurve::NistP256
+        }
+    );
+    assert!(key.key_data().other().is_none());
+}
+
+#[cfg(all(feature = "ecdsa", feature = "pkcs8"))]
+#[test]
+fn spki_round_trip() {
+    use ssh_key::LineEnding;
+
+    for (openssh, spki) in [
+        (
+            OPENSSH_ECDSA_P256_EXAMPLE,
+            include_str!("examples/id_ecdsa_p256.spki.pem"),
+        ),
+        (
+            OPENSSH_RSA_3072_EXAMPLE,
+            include_str!("examples/id_rsa_3072.spki.pem"),
+        ),
+    ] {
+        let key = PublicKey::from_openssh(openssh).unwrap();
+        assert_eq!(key.to_spki_pem(LineEnding::LF).unwrap(), spki);
+
+        let key2 = PublicKey::from_spki_pem(spki).unwrap();
+        assert_eq!(key.key_data(), key2.key_data());
+        assert_eq!(key2.comment(), "");
+    }
+
+    let key = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    let der = key.to_spki_der().unwrap();
+    assert_eq!(
+        der,
+        hex!(
+            "302a300506032b6570032100
+             b33eaef37ea2df7caa010defdea34e241f65f1b529a4f43ed14327f5c54aab62"
+        )
+    );
+
+    let pem = key.to_spki_pem(LineEnding::LF).unwrap();
+    let key2 = PublicKey::from_spki_pem(pem).unwrap();
+    assert_eq!(key.key_data(), key2.key_data());
+
+    let dsa_key = PublicKey::from_openssh(OPENSSH_DSA_EXAMPLE).unwrap();
+    assert_eq!(
+        dsa_key.to_spki_der(),
+        Err(ssh_key::Error::AlgorithmUnsupported {
+            algorithm: Algorithm::Dsa
+        })
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn sshfp() {
+    use ssh_key::{public::SshfpRecord, HashAlg};
+
+    // Generated with `ssh-keygen -r example.com`
+    let examples = [
+        (
+            OPENSSH_DSA_EXAMPLE,
+            "example.com IN SSHFP 2 2 361d0c7b8f5987d7c3c3f55851fab8dc82662354fe5eb8e260e34f343f06cda3",
+        ),
+        (
+            OPENSSH_ED25519_EXAMPLE,
+            "example.com IN SSHFP 4 2 5025222ebecf8ecf7014524c0c1c8b81cdcdaed754df8e0e814338e7064f7084",
+        ),
+        (
+            OPENSSH_RSA_3072_EXAMPLE,
+            "example.com IN SSHFP 1 2 166c6db3f19c57becf6a41677f551e922e66a54e198d44161918d9180a379ff2",
+        ),
+    ];
+
+    let mut records = Vec::new();
+
+    for (key, entry) in examples {
+        let key = PublicKey::from_openssh(key).unwrap();
+        let record = key.to_sshfp(HashAlg::Sha256).unwrap();
+        assert_eq!(record.to_zone_entry("example.com"), entry);
+        assert_eq!(entry.parse::<SshfpRecord>().unwrap(), record);
+        assert_eq!(record.to_string().parse::<SshfpRecord>().unwrap(), record);
+        records.push(record);
+    }
+
+    let ed25519_key = PublicKey::from_openssh(OPENSSH_ED25519_EXAMPLE).unwrap();
+    assert!(ed25519_key.matches_sshfp(&records));
+    assert!(!ed25519_key.matches_sshfp(&records[..1]));
+    assert!(ed25519_key.to_sshfp(HashAlg::Sha512).is_err());
+
+    // Fingerprint split across whitespace, with the algorithm of another key
+    let record = "4 2 5025222ebecf8ecf7014524c0c1c8b81 cdcdaed754df8e0e814338e7064f7084"
+        .parse::<SshfpRecord>()
+        .unwrap();
+    assert!(record.matches(ed25519_key.key_data()));
+    assert!(!SshfpRecord::new(1, 2, record.fingerprint()).matches(ed25519_key.key_data()));
+
+    assert!(
+        "example.com IN SSHFP 4 1 6753df136e39eee7ba1dfc527e95512eb1a278ef"
+            .parse::<SshfpRecord>()
+            .unwrap()
+            .matches(ed25519_key.key_data())
+    );
+
+    let sk_key = PublicKey::from_openssh(OPENSSH_SK_ED25519_EXAMPLE).unwrap();
+    assert!(sk_key.to_sshfp(HashAlg::Sha256).is_err());
+    assert!("4 2".parse::<SshfpRecord>().is_err());
+    assert!("4 2 50252".parse::<SshfpRecord>().is_err());
+}
@@ -0,0 +1,98 @@
+//! `serde` support tests.
+
+#![cfg(all(feature = "alloc", feature = "serde"))]
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use ssh_key::{Algorithm, Certificate, Fingerprint, HashAlg, PrivateKey, PublicKey};
+
+/// Ed25519 OpenSSH-formatted private key.
+const ED25519_PRIVATE_KEY: &str = include_str!("examples/id_ed25519");
+
+/// Ed25519 OpenSSH-formatted public key.
+const ED25519_PUBLIC_KEY: &str = include_str!("examples/id_ed25519.pub");
+
+/// Ed25519 OpenSSH certificate.
+const ED25519_CERT: &str = include_str!("examples/id_ed25519-cert.pub");
+
+/// Host key inventory entry.
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+struct HostKey {
+    algorithm: Algorithm,
+    fingerprint: Fingerprint,
+    public_key: PublicKey,
+
+    #[serde(with = "ssh_key::private::serde")]
+    private_key: PrivateKey,
+}
+
+impl HostKey {
+    fn example() -> Self {
+        let private_key = PrivateKey::from_openssh(ED25519_PRIVATE_KEY).unwrap();
+
+        Self {
+            algorithm: private_key.algorithm(),
+            fingerprint: private_key.fingerprint(HashAlg::Sha256),
+            public_key: private_key.public_key().clone(),
+            private_key,
+        }
+    }
+}
+
+/// Round trip the given value through CBOR, a binary format.
+fn cbor_round_trip<T: DeserializeOwned + Serialize>(value: &T) -> T {
+    let mut cbor = Vec::new();
+    ciborium::ser::into_writer(value, &mut cbor).unwrap();
+    ciborium::de::from_reader(cbor.as_slice()).unwrap()
+}
+
+#[test]
+fn json() {
+    let host_key = HostKey::example();
+    let json = serde_json::to_value(&host_key).unwrap();
+
+    assert_eq!(json["algorithm"], "ssh-ed25519");
+    assert_eq!(
+        json["fingerprint"],
+        "SHA256:UCUiLr7Pjs9wFFJMDByLgc3NrtdU344OgUM45wZPcIQ"
+    );
+    assert_eq!(json["public_key"], ED25519_PUBLIC_KEY.trim_end());
+    assert_eq!(json["private_key"], ED25519_PRIVATE_KEY);
+
+    assert_eq!(serde_json::from_value::<HostKey>(json).unwrap(), host_key);
+}
+
+#[test]
+fn cbor() {
+    let host_key = HostKey::example();
+    let decoded = cbor_round_trip(&host_key);
+
+    // Wire encodings of public keys don't include the comment
+    assert_eq!(decoded.algorithm, host_key.algorithm);
+    assert_eq!(decoded.fingerprint, host_key.fingerprint);
+    assert_eq!(
+        decoded.public_key.key_data(),
+        host_key.public_key.key_data()
+    );
+    assert_eq!(decoded.private_key, host_key.private_key);
+    assert_eq!(decoded.private_key.comment(), "user@example.com");
+}
+
+#[test]
+fn certificate() {
+    let cert = Certificate::from_openssh(ED25519_CERT).unwrap();
+
+    let json = serde_json::to_string(&cert).unwrap();
+    assert_eq!(
+        json,
+        serde_json::to_string(ED25519_CERT.trim_end()).unwrap()
+    );
+    assert_eq!(serde_json::from_str::<Certificate>(&json).unwrap(), cert);
+
+    let decoded = cbor_round_trip(&cert);
+    assert_eq!(decoded.to_bytes().unwrap(), cert.to_bytes().unwrap());
+}
+
+#[test]
+fn reject_unknown_algorithm() {
+    assert!(serde_json::from_str::<Algorithm>(r#""""#).is_err());
+}
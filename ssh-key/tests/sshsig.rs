@@ -14,7 +14,21 @@ use ssh_key::{Algorithm, HashAlg, LineEnding, PublicKey, SshSig};
 use {encoding::Decode, signature::Verifier, ssh_key::PrivateKey, ssh_key::Signature};
 
 #[cfg(feature = "ed25519")]
-use ssh_key::Error;
+use ssh_key::private::SkEd25519;
+
+#[cfg(feature = "p256")]
+use ssh_key::{private::SkEcdsaSha2NistP256, public::EcdsaPublicKey};
+
+#[cfg(any(feature = "ed25519", feature = "p256"))]
+use {
+    encoding::Encode,
+    signature::Signer,
+    ssh_key::{
+        private::{KeypairData, SkAssertion, SkAuthenticator},
+        sha2::{Digest, Sha256},
+        Error,
+    },
+};
 
 /// DSA OpenSSH-formatted private key.
 #[cfg(feature = "dsa")]
@@ -42,6 +56,10 @@ const ED25519_PUBLIC_KEY: &str = include_str!("examples/id_ed25519.pub");
 /// `sshsig`-encoded signature.
 const ED25519_SIGNATURE: &str = include_str!("examples/sshsig_ed25519");
 
+/// `sshsig`-encoded signature made using `id_ed25519-cert-with-p256-ca.pub`.
+#[cfg(feature = "ecdsa")]
+const ED25519_CERT_SIGNATURE: &str = include_str!("examples/sshsig_ed25519_cert");
+
 /// Bytes of the raw Ed25519 signature.
 const ED25519_SIGNATURE_BYTES: [u8; 64] = hex!(
     "4f11abfeb4c18d9e8c7832eccceeb947c9505a8c29fc074900ca2396c0f2a9ac"
@@ -69,6 +87,11 @@ const RSA_PRIVATE_KEY: &str = include_str!("examples/id_rsa_3072");
 #[cfg(feature = "rsa")]
 const RSA_PUBLIC_KEY: &str = include_str!("examples/id_rsa_3072.pub");
 
+/// Raw `rsa-sha2-512` signature over `example message 90` by `id_rsa_3072`,
+/// which is one byte shorter than the modulus as its leading zero is stripped.
+#[cfg(feature = "rsa")]
+const RSA_SHA512_SHORT_SIGNATURE: &[u8] = include_bytes!("examples/sig_rsa_3072_sha512_short");
+
 /// Example message to be signed/verified.
 #[allow(dead_code)]
 const MSG_EXAMPLE: &[u8] = b"testing";
@@ -160,6 +183,72 @@ fn encode_sk_ed25519() {
     assert_eq!(&sshsig_pem, SK_ED25519_SIGNATURE);
 }
 
+#[test]
+fn parse_and_check() {
+    let (sshsig, public_key) = SshSig::parse_and_check(ED25519_SIGNATURE).unwrap();
+    assert_eq!(sshsig, ED25519_SIGNATURE.parse::<SshSig>().unwrap());
+    assert_eq!(
+        public_key.key_data(),
+        ED25519_PUBLIC_KEY.parse::<PublicKey>().unwrap().key_data()
+    );
+
+    // Structurally valid, but the signature is garbage
+    let mut signature_bytes = ED25519_SIGNATURE_BYTES;
+    signature_bytes[0] ^= 0xFF;
+    let tampered = SshSig::new(
+        sshsig.public_key().clone(),
+        NAMESPACE_EXAMPLE,
+        sshsig.hash_alg(),
+        ssh_key::Signature::new(Algorithm::Ed25519, signature_bytes.as_slice()).unwrap(),
+    )
+    .unwrap()
+    .to_pem(LineEnding::LF)
+    .unwrap();
+    let (tampered, public_key) = SshSig::parse_and_check(&tampered).unwrap();
+    assert_eq!(tampered.signature_bytes(), signature_bytes);
+    assert_eq!(public_key.key_data(), sshsig.public_key());
+
+    #[cfg(feature = "ed25519")]
+    assert_eq!(
+        public_key.verify(NAMESPACE_EXAMPLE, MSG_EXAMPLE, &tampered),
+        Err(Error::Crypto)
+    );
+
+    // Signature algorithm the embedded key can't produce
+    let mismatched = SshSig::new(
+        sshsig.public_key().clone(),
+        NAMESPACE_EXAMPLE,
+        sshsig.hash_alg(),
+        ssh_key::Signature::new(Algorithm::SkEd25519, SK_ED25519_SIGNATURE_BYTES.as_slice())
+            .unwrap(),
+    )
+    .unwrap()
+    .to_pem(LineEnding::LF)
+    .unwrap();
+    assert_eq!(
+        SshSig::parse_and_check(&mismatched),
+        Err(ssh_key::Error::AlgorithmUnknown)
+    );
+
+    // SHA-384 isn't allowed by PROTOCOL.sshsig
+    assert_eq!(
+        SshSig::new(
+            sshsig.public_key().clone(),
+            NAMESPACE_EXAMPLE,
+            HashAlg::Sha384,
+            sshsig.signature().clone(),
+        ),
+        Err(ssh_key::Error::AlgorithmUnknown)
+    );
+
+    // Corrupt armor
+    let corrupt = ED25519_SIGNATURE.replacen("SSH SIGNATURE", "SSH SIGNATUR", 1);
+    assert!(SshSig::parse_and_check(&corrupt).is_err());
+
+    let corrupt = ED25519_SIGNATURE.replacen("U1NIU0lH", "U1NIU0lI", 1);
+    assert!(SshSig::parse_and_check(&corrupt).is_err());
+}
+
 #[test]
 #[cfg(feature = "dsa")]
 fn sign_dsa() {
@@ -214,6 +303,54 @@ fn verify_ecdsa_p256_openssh_wire_format() {
         .unwrap();
 }
 
+#[test]
+#[cfg(all(feature = "ed25519", feature = "p256"))]
+fn verify_raw_ed25519() {
+    use ssh_key::{public::KeyData, Error};
+
+    let key_blob = ED25519_PUBLIC_KEY
+        .parse::<PublicKey>()
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+
+    assert_eq!(
+        KeyData::verify_raw(&key_blob, MSG_EXAMPLE, &ED25519_SIGNATURE_OPENSSH_WIRE),
+        Ok(())
+    );
+    assert_eq!(
+        KeyData::verify_raw(&key_blob, b"other", &ED25519_SIGNATURE_OPENSSH_WIRE),
+        Err(Error::Crypto)
+    );
+
+    // Signature made with a different algorithm than the key's
+    assert_eq!(
+        KeyData::verify_raw(&key_blob, MSG_EXAMPLE, &ECDSA_P256_SIGNATURE_OPENSSH_WIRE),
+        Err(Error::PublicKey)
+    );
+
+    // Trailing data in either blob is rejected
+    let mut signature_blob = ED25519_SIGNATURE_OPENSSH_WIRE.to_vec();
+    signature_blob.push(0);
+    assert!(KeyData::verify_raw(&key_blob, MSG_EXAMPLE, &signature_blob).is_err());
+}
+
+#[test]
+#[cfg(feature = "rsa")]
+fn verify_raw_rsa() {
+    use ssh_key::public::KeyData;
+
+    let key_blob = RSA_PUBLIC_KEY
+        .parse::<PublicKey>()
+        .unwrap()
+        .to_bytes()
+        .unwrap();
+    assert_eq!(
+        KeyData::verify_raw(&key_blob, MSG_EXAMPLE, &RSA_SIGNATURE_OPENSSH_WIRE),
+        Ok(())
+    );
+}
+
 #[test]
 #[cfg(feature = "ed25519")]
 fn sign_ed25519() {
@@ -225,6 +362,58 @@ fn sign_ed25519() {
     assert_eq!(signature, ED25519_SIGNATURE.parse::<SshSig>().unwrap());
 }
 
+#[test]
+#[cfg(all(feature = "ed25519", feature = "std"))]
+fn sign_reader_ed25519() {
+    const FILE_PATH: &str = "./tests/examples/id_rsa_4096";
+    let signing_key = PrivateKey::from_openssh(ED25519_PRIVATE_KEY).unwrap();
+    let contents = std::fs::read(FILE_PATH).unwrap();
+
+    for hash_alg in [HashAlg::Sha256, HashAlg::Sha512] {
+        let file = std::fs::File::open(FILE_PATH).unwrap();
+        let signature =
+            SshSig::sign_reader(&signing_key, NAMESPACE_EXAMPLE, hash_alg, file).unwrap();
+
+        assert_eq!(
+            signature,
+            signing_key
+                .sign(NAMESPACE_EXAMPLE, hash_alg, &contents)
+                .unwrap()
+        );
+    }
+
+    // Signatures over files can be verified without buffering them
+    let signature = signing_key
+        .sign_reader(
+            NAMESPACE_EXAMPLE,
+            HashAlg::Sha256,
+            std::fs::File::open(FILE_PATH).unwrap(),
+        )
+        .unwrap();
+    let verifying_key = signing_key.public_key();
+    let file = std::fs::File::open(FILE_PATH).unwrap();
+    verifying_key
+        .verify_reader(NAMESPACE_EXAMPLE, file, &signature)
+        .unwrap();
+    assert!(verifying_key
+        .verify_reader(NAMESPACE_EXAMPLE, MSG_EXAMPLE, &signature)
+        .is_err());
+    assert_eq!(
+        verifying_key.verify_reader("other", &contents[..], &signature),
+        Err(Error::Namespace)
+    );
+
+    // The reader variant produces the same signature over the example message
+    let signature = SshSig::sign_reader(
+        &signing_key,
+        NAMESPACE_EXAMPLE,
+        HashAlg::Sha512,
+        MSG_EXAMPLE,
+    )
+    .unwrap();
+    assert_eq!(signature, ED25519_SIGNATURE.parse::<SshSig>().unwrap());
+}
+
 #[test]
 #[cfg(feature = "ed25519")]
 fn verify_ed25519_openssh_wire_format() {
@@ -252,6 +441,42 @@ fn sign_rsa() {
     );
 }
 
+#[test]
+#[cfg(feature = "rsa")]
+fn verify_rsa_tampered() {
+    let signing_key = PrivateKey::from_openssh(RSA_PRIVATE_KEY).unwrap();
+    let verifying_key = RSA_PUBLIC_KEY.parse::<PublicKey>().unwrap();
+    let signed_data = SshSig::signed_data(NAMESPACE_EXAMPLE, HashAlg::Sha512, MSG_EXAMPLE).unwrap();
+    let signature: Signature = signature::Signer::try_sign(&signing_key, &signed_data).unwrap();
+
+    // valid
+    verifying_key
+        .key_data()
+        .verify(&signed_data, &signature)
+        .unwrap();
+
+    // wrong message, i.e. digest mismatch
+    assert!(verifying_key
+        .key_data()
+        .verify(b"wrong message", &signature)
+        .is_err());
+
+    // corrupted signature, i.e. invalid padding once decrypted
+    for index in [
+        0,
+        signature.as_bytes().len() / 2,
+        signature.as_bytes().len() - 1,
+    ] {
+        let mut data = signature.as_bytes().to_vec();
+        data[index] ^= 1;
+        let tampered = Signature::new(signature.algorithm(), data).unwrap();
+        assert!(verifying_key
+            .key_data()
+            .verify(&signed_data, &tampered)
+            .is_err());
+    }
+}
+
 #[test]
 #[cfg(feature = "rsa")]
 fn verify_rsa_openssh_wire_format() {
@@ -263,6 +488,129 @@ fn verify_rsa_openssh_wire_format() {
         .unwrap();
 }
 
+#[test]
+#[cfg(feature = "rsa")]
+fn sign_rsa_with_algorithm() {
+    let signing_key = PrivateKey::from_openssh(RSA_PRIVATE_KEY).unwrap();
+    let verifying_key = RSA_PUBLIC_KEY.parse::<PublicKey>().unwrap();
+
+    for hash in [HashAlg::Sha256, HashAlg::Sha512] {
+        let algorithm = Algorithm::Rsa { hash: Some(hash) };
+        let signature = signing_key
+            .sign_with_algorithm(MSG_EXAMPLE, algorithm.clone())
+            .unwrap();
+        assert_eq!(signature.algorithm(), algorithm.clone());
+        assert_eq!(
+            verifying_key.verify_returning_algorithm(MSG_EXAMPLE, &signature),
+            Ok(algorithm)
+        );
+    }
+
+    // `ssh-rsa` (i.e. SHA-1) and non-RSA algorithms are rejected
+    for algorithm in [Algorithm::Rsa { hash: None }, Algorithm::Ed25519] {
+        assert_eq!(
+            signing_key.sign_with_algorithm(MSG_EXAMPLE, algorithm.clone()),
+            Err(ssh_key::Error::AlgorithmUnsupported { algorithm })
+        );
+    }
+}
+
+#[test]
+#[cfg(feature = "rsa")]
+fn verify_rsa_max_exponent() {
+    use ssh_key::{Mpint, RsaPadding};
+
+    let signature = Signature::decode(&mut RSA_SIGNATURE_OPENSSH_WIRE.as_ref()).unwrap();
+    let verifying_key = RSA_PUBLIC_KEY.parse::<PublicKey>().unwrap();
+    let rsa_key = verifying_key.key_data().rsa().unwrap();
+    assert_eq!(
+        rsa_key.e.as_positive_bytes(),
+        Some([0x01, 0x00, 0x01].as_slice())
+    );
+
+    assert_eq!(
+        rsa_key.verify_with_max_exponent(MSG_EXAMPLE, &signature, RsaPadding::Pkcs1v15, 65537),
+        Ok(())
+    );
+    assert_eq!(
+        rsa_key.verify_with_max_exponent(MSG_EXAMPLE, &signature, RsaPadding::Pkcs1v15, 65535),
+        Err(ssh_key::Error::Crypto)
+    );
+
+    // Absurdly large exponents are rejected regardless of the bound
+    let mut large_exponent = rsa_key.clone();
+    large_exponent.e = Mpint::from_positive_bytes(&[0xFF; 64]).unwrap();
+    assert_eq!(
+        large_exponent.verify_with_padding(MSG_EXAMPLE, &signature, RsaPadding::Pkcs1v15),
+        Err(ssh_key::Error::Crypto)
+    );
+    assert_eq!(
+        large_exponent.verify_with_max_exponent(
+            MSG_EXAMPLE,
+            &signature,
+            RsaPadding::Pkcs1v15,
+            u64::MAX
+        ),
+        Err(ssh_key::Error::Crypto)
+    );
+}
+
+#[test]
+#[cfg(feature = "rsa")]
+fn verify_returning_algorithm_rsa_sha2_512() {
+    let signature = Signature::decode(&mut RSA_SIGNATURE_OPENSSH_WIRE.as_ref()).unwrap();
+    let verifying_key = RSA_PUBLIC_KEY.parse::<PublicKey>().unwrap();
+    assert_eq!(
+        verifying_key.verify_returning_algorithm(MSG_EXAMPLE, &signature),
+        Ok(Algorithm::Rsa {
+            hash: Some(HashAlg::Sha512)
+        })
+    );
+    assert!(verifying_key
+        .verify_returning_algorithm(b"wrong message", &signature)
+        .is_err());
+}
+
+#[test]
+fn reject_truncated_ed25519_signature() {
+    assert_eq!(
+        ssh_key::Signature::new(Algorithm::Ed25519, &ED25519_SIGNATURE_BYTES[..63]),
+        Err(ssh_key::Error::Encoding(encoding::Error::Length))
+    );
+}
+
+#[test]
+#[cfg(all(feature = "rsa", feature = "std"))]
+fn reject_oversized_rsa_signature() {
+    let signature = Signature::decode(&mut RSA_SIGNATURE_OPENSSH_WIRE.as_ref()).unwrap();
+    let mut data = vec![0];
+    data.extend_from_slice(signature.as_bytes());
+    let oversized = Signature::new(signature.algorithm(), data).unwrap();
+    let verifying_key = RSA_PUBLIC_KEY.parse::<PublicKey>().unwrap();
+    assert_eq!(
+        verifying_key.verify_returning_algorithm(MSG_EXAMPLE, &oversized),
+        Err(ssh_key::Error::Encoding(encoding::Error::Length))
+    );
+}
+
+#[test]
+#[cfg(feature = "rsa")]
+fn verify_rsa_signature_without_leading_zero() {
+    let signature = Signature::new(
+        Algorithm::Rsa {
+            hash: Some(HashAlg::Sha512),
+        },
+        RSA_SHA512_SHORT_SIGNATURE,
+    )
+    .unwrap();
+
+    let verifying_key = RSA_PUBLIC_KEY.parse::<PublicKey>().unwrap();
+    assert!(verifying_key
+        .key_data()
+        .verify(b"example message 90", &signature)
+        .is_ok());
+}
+
 #[test]
 #[cfg(feature = "ed25519")]
 fn verify_ed25519() {
@@ -288,6 +636,47 @@ fn verify_ed25519() {
     );
 }
 
+#[test]
+#[cfg(feature = "ed25519")]
+fn reject_cross_namespace_ed25519() {
+    let signing_key = PrivateKey::from_openssh(ED25519_PRIVATE_KEY).unwrap();
+    let verifying_key = signing_key.public_key();
+    let signature = signing_key
+        .sign("email", HashAlg::Sha512, MSG_EXAMPLE)
+        .unwrap();
+
+    assert_eq!(
+        verifying_key.verify("email", MSG_EXAMPLE, &signature),
+        Ok(())
+    );
+    assert_eq!(
+        verifying_key.verify("file", MSG_EXAMPLE, &signature),
+        Err(Error::Namespace)
+    );
+
+    // Namespaces are compared exactly, without any normalization
+    for namespace in ["Email", "email ", " email", "email\0"] {
+        assert_eq!(
+            verifying_key.verify(namespace, MSG_EXAMPLE, &signature),
+            Err(Error::Namespace)
+        );
+    }
+
+    // Relabelling the signature with another namespace invalidates it, as the
+    // namespace is part of the signed data
+    let relabelled = SshSig::new(
+        signature.public_key().clone(),
+        "file",
+        signature.hash_alg(),
+        signature.signature().clone(),
+    )
+    .unwrap();
+    assert_eq!(
+        verifying_key.verify("file", MSG_EXAMPLE, &relabelled),
+        Err(Error::Crypto)
+    );
+}
+
 #[test]
 #[cfg(feature = "ed25519")]
 fn verify_sk_ed25519() {
@@ -312,3 +701,154 @@ fn verify_sk_ed25519() {
         Err(Error::Crypto)
     );
 }
+
+#[cfg(feature = "ecdsa")]
+#[test]
+fn decode_certificate_signature() {
+    let sshsig = SshSig::from_pem(ED25519_CERT_SIGNATURE).unwrap();
+    let certificate = sshsig.certificate().unwrap();
+    assert_eq!(certificate.key_id(), "ed25519-with-p256-ca");
+
+    let public_key = PublicKey::from_openssh(ED25519_PUBLIC_KEY).unwrap();
+    assert_eq!(sshsig.public_key(), public_key.key_data());
+    assert_eq!(certificate.public_key(), public_key.key_data());
+
+    // Ensure the certificate is re-encoded in place of the public key
+    assert_eq!(
+        sshsig.to_pem(LineEnding::LF).unwrap(),
+        ED25519_CERT_SIGNATURE
+    );
+}
+
+#[cfg(all(feature = "ed25519", feature = "p256", feature = "std"))]
+#[test]
+fn verify_with_ca() {
+    let sshsig = SshSig::from_pem(ED25519_CERT_SIGNATURE).unwrap();
+    let ca_key = PublicKey::from_openssh(ECDSA_P256_PUBLIC_KEY).unwrap();
+    let ca_fingerprint = ca_key.fingerprint(HashAlg::Sha256);
+
+    let certificate = sshsig
+        .verify_with_ca([&ca_fingerprint], NAMESPACE_EXAMPLE, MSG_EXAMPLE)
+        .unwrap();
+    assert_eq!(certificate.key_id(), "ed25519-with-p256-ca");
+
+    // Untrusted CA
+    let untrusted = PublicKey::from_openssh(ED25519_PUBLIC_KEY)
+        .unwrap()
+        .fingerprint(HashAlg::Sha256);
+    assert_eq!(
+        sshsig.verify_with_ca([&untrusted], NAMESPACE_EXAMPLE, MSG_EXAMPLE),
+        Err(Error::CertificateValidation)
+    );
+
+    assert_eq!(
+        sshsig.verify_with_ca([&ca_fingerprint], "file", MSG_EXAMPLE),
+        Err(Error::Namespace)
+    );
+
+    assert_eq!(
+        sshsig.verify_with_ca([&ca_fingerprint], NAMESPACE_EXAMPLE, b"tampered"),
+        Err(Error::Crypto)
+    );
+
+    // Signatures made using a plain key have no certificate
+    let plain = SshSig::from_pem(ED25519_SIGNATURE).unwrap();
+    assert_eq!(
+        plain.verify_with_ca([&ca_fingerprint], NAMESPACE_EXAMPLE, MSG_EXAMPLE),
+        Err(Error::PublicKey)
+    );
+}
+
+/// Software [`SkAuthenticator`] which signs using an ordinary private key.
+#[cfg(any(feature = "ed25519", feature = "p256"))]
+struct SoftAuthenticator(PrivateKey);
+
+#[cfg(any(feature = "ed25519", feature = "p256"))]
+impl SkAuthenticator for SoftAuthenticator {
+    fn sign(
+        &self,
+        application: &str,
+        key_handle: &[u8],
+        flags: u8,
+        message: &[u8],
+    ) -> Result<SkAssertion, Error> {
+        assert_eq!(application, "ssh:");
+        assert_eq!(key_handle, b"key handle");
+
+        let counter = 42u32;
+        let mut signed_data = Sha256::digest(application).to_vec();
+        signed_data.push(flags);
+        signed_data.extend_from_slice(&counter.to_be_bytes());
+        signed_data.extend_from_slice(&Sha256::digest(message));
+
+        Ok(SkAssertion {
+            flags,
+            counter,
+            signature: self.0.try_sign(&signed_data)?,
+        })
+    }
+}
+
+#[test]
+#[cfg(feature = "ed25519")]
+fn sign_sk_ed25519() {
+    let authenticator = SoftAuthenticator(PrivateKey::from_openssh(ED25519_PRIVATE_KEY).unwrap());
+    let public = authenticator.0.public_key().key_data().ed25519().unwrap();
+    let sk = SkEd25519::new((*public).into(), 0x01, *b"key handle");
+    let private_key = PrivateKey::try_from(KeypairData::SkEd25519(sk)).unwrap();
+    let public_key = private_key.public_key();
+
+    let signer = private_key.sk_signer(&authenticator).unwrap();
+    let sshsig = SshSig::sign(&signer, NAMESPACE_EXAMPLE, HashAlg::Sha512, MSG_EXAMPLE).unwrap();
+    assert_eq!(sshsig.algorithm(), Algorithm::SkEd25519);
+    assert_eq!(sshsig.signature().sk_counter(), Some(42));
+    assert!(sshsig.signature().sk_user_present());
+    public_key
+        .verify(NAMESPACE_EXAMPLE, MSG_EXAMPLE, &sshsig)
+        .unwrap();
+
+    let mut encoded = Vec::new();
+    sshsig.signature().encode(&mut encoded).unwrap();
+    assert_eq!(
+        &Signature::decode(&mut encoded.as_slice()).unwrap(),
+        sshsig.signature()
+    );
+    assert!(public_key
+        .verify(NAMESPACE_EXAMPLE, b"another message", &sshsig)
+        .is_err());
+
+    // Ordinary keys can't be used with an authenticator
+    assert!(authenticator.0.sk_signer(&authenticator).is_err());
+}
+
+#[test]
+#[cfg(feature = "p256")]
+fn sign_sk_ecdsa_p256() {
+    let authenticator =
+        SoftAuthenticator(PrivateKey::from_openssh(ECDSA_P256_PRIVATE_KEY).unwrap());
+    let public = match authenticator.0.public_key().key_data().ecdsa().unwrap() {
+        EcdsaPublicKey::NistP256(point) => *point,
+        _ => unreachable!(),
+    };
+    let sk = SkEcdsaSha2NistP256::new(public.into(), 0x01, *b"key handle");
+    let private_key = PrivateKey::try_from(KeypairData::SkEcdsaSha2NistP256(sk)).unwrap();
+    let public_key = private_key.public_key();
+
+    let signer = private_key.sk_signer(&authenticator).unwrap();
+    let sshsig = SshSig::sign(&signer, NAMESPACE_EXAMPLE, HashAlg::Sha512, MSG_EXAMPLE).unwrap();
+    assert_eq!(sshsig.algorithm(), Algorithm::SkEcdsaSha2NistP256);
+    assert_eq!(sshsig.signature().sk_counter(), Some(42));
+    public_key
+        .verify(NAMESPACE_EXAMPLE, MSG_EXAMPLE, &sshsig)
+        .unwrap();
+
+    let mut encoded = Vec::new();
+    sshsig.signature().encode(&mut encoded).unwrap();
+    assert_eq!(
+        &Signature::decode(&mut encoded.as_slice()).unwrap(),
+        sshsig.signature()
+    );
+    assert!(public_key
+        .verify(NAMESPACE_EXAMPLE, b"another message", &sshsig)
+        .is_err());
+}
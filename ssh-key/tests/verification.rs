@@ -0,0 +1,147 @@
+//! Verification policy tests.
+
+#![cfg(feature = "alloc")]
+
+use ssh_key::{Algorithm, VerificationConfig};
+
+#[cfg(feature = "ed25519")]
+use {core::str::FromStr, ssh_key::Certificate};
+
+#[cfg(any(feature = "ed25519", feature = "rsa"))]
+use ssh_key::{Error, HashAlg};
+
+#[cfg(feature = "rsa")]
+use {
+    encoding::{Decode, Encode},
+    ssh_key::{PublicKey, Signature},
+};
+
+/// RSA (1024-bit) OpenSSH-formatted public key.
+#[cfg(feature = "rsa")]
+const RSA_1024_PUBLIC_KEY: &str = include_str!("examples/id_rsa_1024.pub");
+
+/// RSA (3072-bit) OpenSSH-formatted public key.
+#[cfg(feature = "rsa")]
+const RSA_3072_PUBLIC_KEY: &str = include_str!("examples/id_rsa_3072.pub");
+
+/// `rsa-sha2-256` signature over [`MESSAGE`] by the 1024-bit RSA key.
+#[cfg(feature = "rsa")]
+const RSA_1024_SHA256_SIGNATURE: &[u8] = include_bytes!("examples/sig_rsa_1024_sha256");
+
+/// `ssh-rsa` (SHA-1) signature over [`MESSAGE`] by the 3072-bit RSA key.
+#[cfg(feature = "rsa")]
+const RSA_3072_SHA1_SIGNATURE: &[u8] = include_bytes!("examples/sig_rsa_3072_sha1");
+
+/// Message signed by the example signatures.
+#[cfg(feature = "rsa")]
+const MESSAGE: &[u8] = b"example message";
+
+/// Ed25519 OpenSSH Certificate, signed by an Ed25519 certificate authority.
+#[cfg(feature = "ed25519")]
+const ED25519_CERT_EXAMPLE: &str = include_str!("examples/id_ed25519-cert.pub");
+
+/// Fingerprint of the certificate authority.
+#[cfg(feature = "ed25519")]
+const CA_FINGERPRINT: &str = "SHA256:UCUiLr7Pjs9wFFJMDByLgc3NrtdU344OgUM45wZPcIQ";
+
+/// Unix timestamp within the validity window of the example certificate.
+#[cfg(feature = "ed25519")]
+const VALID_TIMESTAMP: u64 = 1750000000;
+
+#[test]
+fn default_is_strict() {
+    let config = VerificationConfig::default();
+    assert!(!config.allow_sha1);
+    assert!(config.reject_deprecated);
+    assert_eq!(config.min_rsa_bits, 2048);
+    assert!(!config.allows(Algorithm::Rsa { hash: None }));
+    assert!(!config.allows(Algorithm::Dsa));
+    assert!(config.allows(Algorithm::Ed25519));
+
+    let permissive = VerificationConfig::permissive();
+    assert!(permissive.allows(Algorithm::Rsa { hash: None }));
+    assert!(permissive.allows(Algorithm::Dsa));
+}
+
+#[cfg(feature = "rsa")]
+#[test]
+fn rsa_sha1_signature() {
+    let key = PublicKey::from_openssh(RSA_3072_PUBLIC_KEY).unwrap();
+    assert!(Signature::new(Algorithm::Rsa { hash: None }, RSA_3072_SHA1_SIGNATURE).is_err());
+
+    let mut blob = Vec::new();
+    "ssh-rsa".encode(&mut blob).unwrap();
+    RSA_3072_SHA1_SIGNATURE.encode(&mut blob).unwrap();
+    let signature = Signature::decode(&mut blob.as_slice()).unwrap();
+
+    assert_eq!(
+        key.key_data()
+            .verify_with(MESSAGE, &signature, &VerificationConfig::default()),
+        Err(Error::AlgorithmUnsupported {
+            algorithm: Algorithm::Rsa { hash: None }
+        })
+    );
+
+    let permissive = VerificationConfig::permissive();
+    assert!(key
+        .key_data()
+        .verify_with(MESSAGE, &signature, &permissive)
+        .is_ok());
+    assert_eq!(
+        key.key_data()
+            .verify_with(b"other message", &signature, &permissive),
+        Err(Error::Crypto)
+    );
+}
+
+#[cfg(feature = "rsa")]
+#[test]
+fn rsa_1024_bit_key() {
+    let key = PublicKey::from_openssh(RSA_1024_PUBLIC_KEY).unwrap();
+    let algorithm = Algorithm::Rsa {
+        hash: Some(HashAlg::Sha256),
+    };
+    let signature = Signature::new(algorithm.clone(), RSA_1024_SHA256_SIGNATURE).unwrap();
+
+    assert_eq!(
+        key.key_data()
+            .verify_with(MESSAGE, &signature, &VerificationConfig::default()),
+        Err(Error::Crypto)
+    );
+
+    let permissive = VerificationConfig::permissive();
+    assert!(key
+        .key_data()
+        .verify_with(MESSAGE, &signature, &permissive)
+        .is_ok());
+
+    // Algorithms not in the allow list are rejected
+    let mut config = permissive;
+    config.allowed_algorithms = Some(vec![Algorithm::Rsa {
+        hash: Some(HashAlg::Sha512),
+    }]);
+    assert_eq!(
+        key.key_data().verify_with(MESSAGE, &signature, &config),
+        Err(Error::AlgorithmUnsupported { algorithm })
+    );
+}
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn certificate_allowed_algorithms() {
+    let cert = Certificate::from_str(ED25519_CERT_EXAMPLE).unwrap();
+    let ca = CA_FINGERPRINT.parse().unwrap();
+
+    let mut config = VerificationConfig::default();
+    assert!(cert
+        .validate_at_with(VALID_TIMESTAMP, &[ca], &config)
+        .is_ok());
+
+    config.allowed_algorithms = Some(vec![Algorithm::Rsa {
+        hash: Some(HashAlg::Sha512),
+    }]);
+    assert_eq!(
+        cert.validate_at_with(VALID_TIMESTAMP, &[ca], &config),
+        Err(Error::CertificateValidation)
+    );
+}
@@ -0,0 +1,91 @@
+//! Tests that signature verification doesn't allocate.
+//!
+//! These install a global allocator which counts allocations made on the
+//! current thread, so they live in their own test binary.
+
+#![cfg(all(feature = "alloc", any(feature = "ed25519", feature = "p256")))]
+
+use signature::Verifier;
+use ssh_key::{PublicKey, SshSig};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+/// Example message which was signed.
+const MSG_EXAMPLE: &[u8] = b"testing";
+
+/// Namespace used for the example signatures.
+const NAMESPACE_EXAMPLE: &str = "example";
+
+/// Global allocator which counts allocations made by each thread.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get().saturating_add(1)));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Number of allocations made by the current thread while running `f`.
+fn count_allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get).saturating_sub(before)
+}
+
+/// Verify the given `sshsig` example's inner signature without allocating.
+fn assert_verify_no_alloc(public_key: &str, sshsig: &str) {
+    let public_key = PublicKey::from_openssh(public_key).unwrap();
+    let sshsig = SshSig::from_pem(sshsig).unwrap();
+    let signed_data =
+        SshSig::signed_data(NAMESPACE_EXAMPLE, sshsig.hash_alg(), MSG_EXAMPLE).unwrap();
+
+    let allocations = count_allocations(|| {
+        public_key
+            .key_data()
+            .verify(&signed_data, sshsig.signature())
+            .unwrap();
+    });
+
+    assert_eq!(allocations, 0);
+}
+
+#[cfg(feature = "p256")]
+#[test]
+fn verify_ecdsa_p256_without_allocating() {
+    assert_verify_no_alloc(
+        include_str!("examples/id_ecdsa_p256.pub"),
+        include_str!("examples/sshsig_ecdsa_p256"),
+    );
+}
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn verify_ed25519_without_allocating() {
+    assert_verify_no_alloc(
+        include_str!("examples/id_ed25519.pub"),
+        include_str!("examples/sshsig_ed25519"),
+    );
+}
+
+#[cfg(feature = "ed25519")]
+#[test]
+fn verify_sk_ed25519_without_allocating() {
+    assert_verify_no_alloc(
+        include_str!("examples/id_sk_ed25519_2.pub"),
+        include_str!("examples/sshsig_sk_ed25519"),
+    );
+}